@@ -7,6 +7,8 @@
  *
  * Security measures:
  * - HMAC-based request signing (timestamp + nonce + body hash)
+ * - Transcription requires the user's WorkOS access token (Bearer), checked
+ *   against WorkOS's JWKS; ALLOWED_USERS and USER_RATE_LIMITER apply per user
  * - Rate limiting via Cloudflare dashboard rules (Dashboard > Security > WAF)
  * - Request size limits (10MB max)
  * - Privacy-compliant logging (IPs are hashed, not stored raw)
@@ -16,9 +18,30 @@
 interface Env {
   GROQ_API_KEY: string;
   MURMUR_APP_SECRET: string; // HMAC signing secret shared between app and proxy
+  WORKOS_CLIENT_ID: string; // Access tokens must be issued for this client
+  ALLOWED_USERS?: string; // Optional comma-separated WorkOS user IDs
+  USER_RATE_LIMITER?: RateLimiter; // Optional per-user rate limit binding
   IP_HASH_SALT?: string; // Optional salt for IP hashing (rotatable)
 }
 
+// Cloudflare rate limiting binding (see wrangler.toml)
+interface RateLimiter {
+  limit(options: { key: string }): Promise<{ success: boolean }>;
+}
+
+// Machine-readable `code` in error bodies. The app signs the user in again
+// only for `login_required`; other 401s are signing problems, not sessions.
+const ERROR_CODES = {
+  loginRequired: 'login_required',
+  notEntitled: 'not_entitled',
+  invalidSignature: 'invalid_signature',
+  rateLimited: 'rate_limited',
+  upstreamAuth: 'upstream_auth',
+} as const;
+
+// How long fetched WorkOS signing keys are reused (ms)
+const JWKS_CACHE_MS = 60 * 60 * 1000;
+
 // Groq API endpoints we proxy
 const GROQ_ENDPOINTS = {
   whisper: 'https://api.groq.com/openai/v1/audio/transcriptions',
@@ -151,6 +174,106 @@ async function verifyHmacSignature(
   return { valid: true };
 }
 
+// Per-isolate cache of WorkOS signing keys, by key ID
+let jwksCache: { keys: Map<string, CryptoKey>; fetchedAt: number } | null = null;
+
+function base64UrlDecode(input: string): Uint8Array {
+  const base64 = input.replace(/-/g, '+').replace(/_/g, '/');
+  const padded = base64 + '='.repeat((4 - (base64.length % 4)) % 4);
+  const binary = atob(padded);
+  return Uint8Array.from(binary, c => c.charCodeAt(0));
+}
+
+async function getSigningKey(kid: string, env: Env): Promise<CryptoKey | null> {
+  const fresh = jwksCache && Date.now() - jwksCache.fetchedAt < JWKS_CACHE_MS;
+  if (!fresh || !jwksCache!.keys.has(kid)) {
+    const response = await fetch(`https://api.workos.com/sso/jwks/${env.WORKOS_CLIENT_ID}`);
+    if (!response.ok) {
+      throw new Error(`JWKS fetch failed: ${response.status}`);
+    }
+    const jwks = (await response.json()) as { keys: (JsonWebKey & { kid?: string })[] };
+    const keys = new Map<string, CryptoKey>();
+    for (const jwk of jwks.keys) {
+      if (!jwk.kid || jwk.kty !== 'RSA') continue;
+      keys.set(
+        jwk.kid,
+        await crypto.subtle.importKey(
+          'jwk',
+          jwk,
+          { name: 'RSASSA-PKCS1-v1_5', hash: 'SHA-256' },
+          false,
+          ['verify']
+        )
+      );
+    }
+    jwksCache = { keys, fetchedAt: Date.now() };
+  }
+  return jwksCache!.keys.get(kid) ?? null;
+}
+
+/**
+ * Verify the user's WorkOS access token (RS256 JWT) from the Authorization header.
+ * Returns the user ID (`sub`) when the signature is valid and the token hasn't expired.
+ */
+async function verifyAccessToken(
+  request: Request,
+  env: Env
+): Promise<{ valid: boolean; userId?: string; error?: string }> {
+  const header = request.headers.get('Authorization');
+  if (!header || !header.startsWith('Bearer ')) {
+    return { valid: false, error: 'Missing access token' };
+  }
+  const parts = header.slice('Bearer '.length).trim().split('.');
+  if (parts.length !== 3) {
+    return { valid: false, error: 'Malformed access token' };
+  }
+
+  try {
+    const decoder = new TextDecoder();
+    const jwtHeader = JSON.parse(decoder.decode(base64UrlDecode(parts[0])));
+    const claims = JSON.parse(decoder.decode(base64UrlDecode(parts[1])));
+    if (jwtHeader.alg !== 'RS256' || typeof jwtHeader.kid !== 'string') {
+      return { valid: false, error: 'Unsupported token algorithm' };
+    }
+
+    const key = await getSigningKey(jwtHeader.kid, env);
+    if (!key) {
+      return { valid: false, error: 'Unknown signing key' };
+    }
+    const signed = new TextEncoder().encode(`${parts[0]}.${parts[1]}`);
+    const verified = await crypto.subtle.verify(
+      'RSASSA-PKCS1-v1_5',
+      key,
+      base64UrlDecode(parts[2]),
+      signed
+    );
+    if (!verified) {
+      return { valid: false, error: 'Invalid token signature' };
+    }
+
+    const now = Math.floor(Date.now() / 1000);
+    if (typeof claims.exp !== 'number' || claims.exp <= now) {
+      return { valid: false, error: 'Token expired' };
+    }
+    if (typeof claims.sub !== 'string' || !claims.sub) {
+      return { valid: false, error: 'Token has no subject' };
+    }
+    return { valid: true, userId: claims.sub };
+  } catch (e) {
+    return { valid: false, error: 'Token verification failed' };
+  }
+}
+
+/**
+ * Whether the user may use the proxy (ALLOWED_USERS unset = everyone)
+ */
+function isEntitled(userId: string, env: Env): boolean {
+  if (!env.ALLOWED_USERS) {
+    return true;
+  }
+  return env.ALLOWED_USERS.split(',').map(u => u.trim()).includes(userId);
+}
+
 /**
  * Validate request content type and basic structure
  */
@@ -209,7 +332,8 @@ export default {
     const path = url.pathname;
 
     let targetUrl: string;
-    if (path === '/v1/audio/transcriptions' || path === '/whisper') {
+    const isTranscription = path === '/v1/audio/transcriptions' || path === '/whisper';
+    if (isTranscription) {
       targetUrl = GROQ_ENDPOINTS.whisper;
     } else if (path === '/v1/chat/completions' || path === '/chat') {
       targetUrl = GROQ_ENDPOINTS.chat;
@@ -233,7 +357,29 @@ export default {
     const signatureResult = await verifyHmacSignature(request, bodyBytes, env, isMultipart);
     if (!signatureResult.valid) {
       console.error(`Auth failed from ${hashedIP}: ${signatureResult.error}`);
-      return jsonError('Unauthorized', 401);
+      return jsonError('Unauthorized', 401, ERROR_CODES.invalidSignature);
+    }
+
+    // ===== SECURITY: Verify the user's session (transcription) =====
+    if (isTranscription) {
+      if (!env.WORKOS_CLIENT_ID) {
+        console.error('WORKOS_CLIENT_ID not configured');
+        return jsonError('Proxy not configured', 500);
+      }
+      const tokenResult = await verifyAccessToken(request, env);
+      if (!tokenResult.valid || !tokenResult.userId) {
+        console.error(`Session rejected from ${hashedIP}: ${tokenResult.error}`);
+        return jsonError('Login required', 401, ERROR_CODES.loginRequired);
+      }
+      if (!isEntitled(tokenResult.userId, env)) {
+        return jsonError('Not enabled for this account', 403, ERROR_CODES.notEntitled);
+      }
+      if (env.USER_RATE_LIMITER) {
+        const { success } = await env.USER_RATE_LIMITER.limit({ key: tokenResult.userId });
+        if (!success) {
+          return jsonError('Too many requests', 429, ERROR_CODES.rateLimited);
+        }
+      }
     }
 
     try {
@@ -247,6 +393,13 @@ export default {
         body: bodyBytes,
       });
 
+      // Groq rejecting our key is a proxy problem - don't let the app read it
+      // as the user's session being rejected
+      if (groqResponse.status === 401 || groqResponse.status === 403) {
+        console.error(`Groq rejected the proxy API key (${groqResponse.status})`);
+        return jsonError('Upstream authentication failed', 502, ERROR_CODES.upstreamAuth);
+      }
+
       // Return the response with CORS headers
       const responseBody = await groqResponse.text();
 
@@ -271,13 +424,14 @@ function corsHeaders(): HeadersInit {
   return {
     'Access-Control-Allow-Origin': '*',
     'Access-Control-Allow-Methods': 'POST, OPTIONS',
-    'Access-Control-Allow-Headers': 'Content-Type, X-Murmur-Timestamp, X-Murmur-Nonce, X-Murmur-Signature',
+    'Access-Control-Allow-Headers':
+      'Authorization, Content-Type, X-Murmur-Timestamp, X-Murmur-Nonce, X-Murmur-Signature',
     'Access-Control-Max-Age': '86400',
   };
 }
 
-function jsonError(message: string, status: number): Response {
-  return new Response(JSON.stringify({ error: message }), {
+function jsonError(message: string, status: number, code?: string): Response {
+  return new Response(JSON.stringify(code ? { error: message, code } : { error: message }), {
     status,
     headers: {
       ...corsHeaders(),
@@ -1,22 +1,203 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use cpal::{FromSample, Sample};
 use rubato::{FftFixedIn, Resampler};
-use std::sync::atomic::{AtomicBool, Ordering};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Manager};
 
-use crate::state::AudioLevelEvent;
+use crate::state::{AudioLevelEvent, MicrophoneFallbackEvent, RecordingStatsEvent};
 
 /// Whisper requires 16kHz audio
 const WHISPER_SAMPLE_RATE: u32 = 16000;
 
+/// dB range mapped to the 0-1 meter level by `calculate_levels`.
+/// Below MIN_DB = complete silence (noise floor), at MAX_DB = maximum level.
+pub const LEVEL_MIN_DB: f32 = -60.0;
+pub const LEVEL_MAX_DB: f32 = 0.0;
+
+/// Fraction of the held peak kept per meter frame (~33ms) with peak hold on,
+/// so a peak fades out over roughly half a second
+const PEAK_HOLD_DECAY: f32 = 0.9;
+
+/// Auto-stop never fires this early, so short utterances aren't cut off
+const AUTO_STOP_GRACE_MS: u64 = 500;
+
+/// How long `test_input_level` listens for (ms)
+pub const MIC_TEST_DURATION_MS: u64 = 2000;
+
+/// Window used to measure loudness when trimming silence (ms)
+const TRIM_WINDOW_MS: u32 = 20;
+
+/// Audio kept on each side of the detected speech when trimming silence (ms)
+const TRIM_PADDING_MS: u32 = 100;
+
+/// Set once the user has been told their microphone is missing, so
+/// `microphone-fallback` fires once per disconnection rather than every recording
+static MICROPHONE_FALLBACK_NOTIFIED: AtomicBool = AtomicBool::new(false);
+
+/// Speed/fidelity trade-off for resampling to 16kHz.
+///
+/// Controls the `FftFixedIn` chunk size and sub-chunk count: smaller FFTs finish
+/// sooner (good for quick dictations on older Macs), larger ones use a longer
+/// anti-aliasing filter (better for long recordings).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ResampleQuality {
+    Fast,
+    #[default]
+    Balanced,
+    High,
+}
+
+impl ResampleQuality {
+    /// (chunk size, sub-chunks) for `FftFixedIn`
+    fn fft_params(self) -> (usize, usize) {
+        match self {
+            ResampleQuality::Fast => (512, 4),
+            ResampleQuality::Balanced => (1024, 2),
+            ResampleQuality::High => (2048, 1),
+        }
+    }
+}
+
+/// How multi-channel input is reduced to mono: "mix" averages every channel,
+/// a number (0 = first channel) records just that channel, for interfaces
+/// where the microphone is on one input and the others carry only noise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MonoChannel {
+    #[default]
+    Mix,
+    Channel(u16),
+}
+
+impl MonoChannel {
+    /// Channel to record for a stream with `channels` channels, or None to mix.
+    /// An index the stream doesn't have falls back to mixing.
+    fn resolve(self, channels: usize) -> Option<usize> {
+        match self {
+            MonoChannel::Mix => None,
+            MonoChannel::Channel(index) if (index as usize) < channels => Some(index as usize),
+            MonoChannel::Channel(index) => {
+                log::warn!(
+                    "Mono channel {} not available on a {}-channel stream, mixing instead",
+                    index,
+                    channels
+                );
+                None
+            }
+        }
+    }
+}
+
+impl Serialize for MonoChannel {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            MonoChannel::Mix => serializer.serialize_str("mix"),
+            MonoChannel::Channel(index) => serializer.serialize_u16(*index),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for MonoChannel {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Repr {
+            Index(u16),
+            Name(String),
+        }
+        match Repr::deserialize(deserializer)? {
+            Repr::Index(index) => Ok(MonoChannel::Channel(index)),
+            Repr::Name(name) if name == "mix" => Ok(MonoChannel::Mix),
+            Repr::Name(name) => Err(serde::de::Error::custom(format!(
+                "invalid mono channel '{}' (expected \"mix\" or a channel index)",
+                name
+            ))),
+        }
+    }
+}
+
+/// Reduce one frame of samples to mono: the chosen channel, or the average
+fn downmix_frame(frame: &[f32], channel: Option<usize>) -> f32 {
+    match channel.and_then(|c| frame.get(c)) {
+        Some(&sample) => sample,
+        None => frame.iter().sum::<f32>() / frame.len() as f32,
+    }
+}
+
+/// Calibration of the overlay level meter. Display only: auto-stop and
+/// recording stats always use the default `LEVEL_MIN_DB..LEVEL_MAX_DB` range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeterSettings {
+    /// Level shown as empty (noise floor, dBFS)
+    pub min_db: f32,
+    /// Level shown as full (dBFS)
+    pub max_db: f32,
+    /// Let the peak fall back gradually instead of jumping with every buffer
+    pub peak_hold: bool,
+}
+
+impl Default for MeterSettings {
+    fn default() -> Self {
+        MeterSettings {
+            min_db: LEVEL_MIN_DB,
+            max_db: LEVEL_MAX_DB,
+            peak_hold: false,
+        }
+    }
+}
+
+/// Turns buffers of samples into `audio-level` values for one meter
+struct LevelMeter {
+    settings: MeterSettings,
+    held_peak: f32,
+}
+
+impl LevelMeter {
+    fn new(settings: MeterSettings) -> Self {
+        LevelMeter {
+            settings,
+            held_peak: 0.0,
+        }
+    }
+
+    /// (level, peak) for the latest buffer on the configured scale
+    fn measure(&mut self, samples: &[f32]) -> (f32, f32) {
+        let (level, peak) =
+            calculate_levels_in_range(samples, self.settings.min_db, self.settings.max_db);
+        if !self.settings.peak_hold {
+            return (level, peak);
+        }
+        self.held_peak = peak.max(self.held_peak * PEAK_HOLD_DECAY);
+        (level, self.held_peak)
+    }
+}
+
 pub struct AudioRecorder {
     audio_data: Arc<Mutex<Vec<f32>>>,
     sample_rate: u32,
     is_recording: Arc<AtomicBool>,
+    // Recording on hold: the stream keeps running but samples are dropped
+    is_paused: Arc<AtomicBool>,
     // For audio level metering
     recent_samples: Arc<Mutex<Vec<f32>>>,
+    // Voice-activity auto-stop (None = disabled)
+    silence_timeout_ms: Option<u32>,
+    silence_threshold_db: f32,
+    resample_quality: ResampleQuality,
+    mono_channel: MonoChannel,
+    meter_settings: MeterSettings,
+    // Gain applied to the recording before transcription (dB, 0 = none)
+    input_gain_db: f32,
+    // Pre-roll ring buffer, filled while not recording (see `start_prebuffer`)
+    preroll: Arc<Mutex<VecDeque<f32>>>,
+    preroll_sample_rate: Arc<AtomicU32>,
+    prebuffer_running: Arc<AtomicBool>,
+    // Stats of the last stopped recording (see `last_recording_stats`)
+    last_stats: Option<RecordingStatsEvent>,
 }
 
 impl AudioRecorder {
@@ -25,53 +206,175 @@ impl AudioRecorder {
             audio_data: Arc::new(Mutex::new(Vec::new())),
             sample_rate: 48000,
             is_recording: Arc::new(AtomicBool::new(false)),
+            is_paused: Arc::new(AtomicBool::new(false)),
             recent_samples: Arc::new(Mutex::new(Vec::with_capacity(4800))), // ~100ms at 48kHz
+            silence_timeout_ms: None,
+            silence_threshold_db: crate::config::DEFAULT_SILENCE_THRESHOLD_DB,
+            resample_quality: ResampleQuality::default(),
+            mono_channel: MonoChannel::default(),
+            meter_settings: MeterSettings::default(),
+            input_gain_db: 0.0,
+            preroll: Arc::new(Mutex::new(VecDeque::new())),
+            preroll_sample_rate: Arc::new(AtomicU32::new(0)),
+            prebuffer_running: Arc::new(AtomicBool::new(false)),
+            last_stats: None,
+        }
+    }
+
+    /// Set the resampling quality used when the recording is stopped
+    pub fn set_resample_quality(&mut self, quality: ResampleQuality) {
+        self.resample_quality = quality;
+    }
+
+    /// Set how multi-channel input is reduced to mono. Applies to the next recording.
+    pub fn set_mono_channel(&mut self, mono_channel: MonoChannel) {
+        self.mono_channel = mono_channel;
+    }
+
+    /// Calibrate the overlay level meter. Applies to the next recording.
+    pub fn set_meter_settings(&mut self, settings: MeterSettings) {
+        self.meter_settings = settings;
+    }
+
+    /// Amplify the recording (and the live meter) by `gain_db`, for quiet
+    /// microphones. Applies to the next recording.
+    pub fn set_input_gain(&mut self, gain_db: f32) {
+        self.input_gain_db = gain_db;
+    }
+
+    /// Continuously keep the last `preroll_ms` of microphone audio so it can be
+    /// prepended when recording starts - stream startup latency otherwise clips
+    /// the first syllable.
+    ///
+    /// Trade-off: the input stream stays open for the life of the app. That costs
+    /// a little CPU (every buffer is downmixed even when idle), `preroll_ms` worth
+    /// of samples in memory (~58KB for 300ms at 48kHz), and macOS shows the
+    /// microphone-in-use indicator the whole time. A `preroll_ms` of 0 disables it.
+    /// Input is downmixed with the current `set_mono_channel` setting.
+    pub fn start_prebuffer(
+        &mut self,
+        preroll_ms: u32,
+        device_name: Option<String>,
+    ) -> Result<(), String> {
+        if preroll_ms == 0 {
+            self.stop_prebuffer();
+            return Ok(());
+        }
+        if self.prebuffer_running.swap(true, Ordering::SeqCst) {
+            return Ok(()); // Already running
+        }
+
+        log::info!("Starting {}ms pre-roll buffer...", preroll_ms);
+
+        let preroll = self.preroll.clone();
+        let preroll_sample_rate = self.preroll_sample_rate.clone();
+        let running = self.prebuffer_running.clone();
+        let is_recording = self.is_recording.clone();
+        let mono_channel = self.mono_channel;
+
+        std::thread::spawn(move || {
+            if let Err(e) = Self::capture_prebuffer(
+                preroll,
+                preroll_sample_rate,
+                running.clone(),
+                is_recording,
+                preroll_ms,
+                device_name,
+                mono_channel,
+            ) {
+                log::error!("Pre-roll capture error: {}", e);
+            }
+            running.store(false, Ordering::SeqCst);
+        });
+
+        Ok(())
+    }
+
+    /// Stop filling the pre-roll buffer and release the input stream
+    pub fn stop_prebuffer(&mut self) {
+        self.prebuffer_running.store(false, Ordering::SeqCst);
+        if let Ok(mut preroll) = self.preroll.lock() {
+            preroll.clear();
         }
     }
 
+    /// Enable auto-stop after `timeout_ms` of trailing silence (None disables it).
+    /// Audio below `threshold_db` (dBFS) counts as silence. Applies to the next recording.
+    pub fn set_silence_timeout(&mut self, timeout_ms: Option<u32>, threshold_db: f32) {
+        self.silence_timeout_ms = timeout_ms;
+        self.silence_threshold_db = threshold_db;
+    }
+
     pub fn start_recording_with_device(
         &mut self,
         app_handle: AppHandle,
         device_name: Option<String>,
     ) -> Result<(), String> {
-        println!("Starting audio recording...");
+        log::info!("Starting audio recording...");
         if let Some(ref name) = device_name {
-            println!("Using selected device: {}", name);
+            log::info!("Using selected device: {}", name);
         }
 
-        // Clear previous audio data
+        // Clear previous audio data, then splice in the pre-roll (if it was
+        // captured at the rate we record at)
         if let Ok(mut data) = self.audio_data.lock() {
             data.clear();
+            let preroll_rate = self.preroll_sample_rate.load(Ordering::SeqCst);
+            if self.prebuffer_running.load(Ordering::SeqCst) && preroll_rate == self.sample_rate {
+                if let Ok(mut preroll) = self.preroll.lock() {
+                    splice_preroll(&mut data, &mut preroll);
+                    log::info!("Spliced {} pre-roll samples", data.len());
+                }
+            }
         }
         if let Ok(mut samples) = self.recent_samples.lock() {
             samples.clear();
         }
 
         // Set recording flag
+        self.is_paused.store(false, Ordering::SeqCst);
         self.is_recording.store(true, Ordering::SeqCst);
 
         let audio_data = self.audio_data.clone();
         let is_recording = self.is_recording.clone();
+        let is_paused = self.is_paused.clone();
         let recent_samples = self.recent_samples.clone();
+        let silence_detector = self
+            .silence_timeout_ms
+            .map(|timeout| SilenceDetector::new(timeout, self.silence_threshold_db));
+        let mono_channel = self.mono_channel;
+        let meter_settings = self.meter_settings;
+        let input_gain_db = self.input_gain_db;
 
         // Spawn audio capture thread
         std::thread::spawn(move || {
             if let Err(e) = Self::capture_audio(
                 audio_data,
                 is_recording,
+                is_paused,
                 recent_samples,
                 app_handle,
                 device_name,
+                silence_detector,
+                mono_channel,
+                meter_settings,
+                input_gain_db,
             ) {
-                eprintln!("Audio capture error: {}", e);
+                log::error!("Audio capture error: {}", e);
             }
         });
 
         Ok(())
     }
 
+    /// Pause or resume the current recording. While paused the input stream
+    /// stays open, but nothing is added to the recording and auto-stop waits.
+    pub fn set_paused(&self, paused: bool) {
+        self.is_paused.store(paused, Ordering::SeqCst);
+    }
+
     pub fn stop_recording(&mut self) -> Result<Vec<u8>, String> {
-        println!("Stopping audio recording...");
+        log::info!("Stopping audio recording...");
 
         // Set recording flag to false
         self.is_recording.store(false, Ordering::SeqCst);
@@ -85,7 +388,7 @@ impl AudioRecorder {
             .lock()
             .map_err(|e| format!("Failed to lock audio data: {}", e))?;
 
-        println!("Audio data samples: {}", audio_data.len());
+        log::info!("Audio data samples: {}", audio_data.len());
 
         if audio_data.is_empty() {
             return Err("No audio data recorded".to_string());
@@ -95,9 +398,16 @@ impl AudioRecorder {
         self.convert_to_wav(&audio_data)
     }
 
+    /// Duration and levels of the audio captured by the last
+    /// `stop_recording_for_whisper`, before silence trimming
+    pub fn last_recording_stats(&self) -> Option<RecordingStatsEvent> {
+        self.last_stats
+    }
+
     /// Stop recording and return resampled audio for Whisper (16kHz)
     pub fn stop_recording_for_whisper(&mut self) -> Result<Vec<f32>, String> {
-        println!("Stopping audio recording for Whisper...");
+        log::info!("Stopping audio recording for Whisper...");
+        self.last_stats = None;
 
         // Set recording flag to false
         self.is_recording.store(false, Ordering::SeqCst);
@@ -111,135 +421,89 @@ impl AudioRecorder {
             .lock()
             .map_err(|e| format!("Failed to lock audio data: {}", e))?;
 
-        println!(
+        log::info!(
             "Audio data samples: {} at {}Hz",
             audio_data.len(),
             self.sample_rate
         );
+        self.last_stats = Some(recording_stats(&audio_data, self.sample_rate));
 
         if audio_data.is_empty() {
             return Err("No audio data recorded".to_string());
         }
 
+        // Boost quiet microphones before trimming, so the silence threshold
+        // applies to what the meter showed
+        let amplified;
+        let samples: &[f32] = if self.input_gain_db == 0.0 {
+            &audio_data
+        } else {
+            let mut gained = audio_data.clone();
+            let clipped = apply_gain(&mut gained, self.input_gain_db);
+            if clipped > 0 {
+                log::warn!(
+                    "Input gain of {}dB clipped {} of {} samples ({:.1}%)",
+                    self.input_gain_db,
+                    clipped,
+                    gained.len(),
+                    clipped as f32 * 100.0 / gained.len() as f32
+                );
+            }
+            amplified = gained;
+            &amplified
+        };
+
+        // Drop leading/trailing silence so Whisper doesn't hallucinate on it
+        let trimmed = trim_silence_below(samples, self.sample_rate, self.silence_threshold_db);
+        log::info!(
+            "Trimmed silence: {} -> {} samples",
+            audio_data.len(),
+            trimmed.len()
+        );
+        if trimmed.is_empty() {
+            return Ok(Vec::new());
+        }
+
         // Resample to 16kHz for Whisper
-        let resampled = self.resample_to_16khz(&audio_data)?;
-        println!("Resampled to {} samples at 16kHz", resampled.len());
+        let resampled = self.resample_to_16khz(&trimmed)?;
+        log::info!("Resampled to {} samples at 16kHz", resampled.len());
 
         Ok(resampled)
     }
 
     /// Resample audio from current sample rate to 16kHz for Whisper
     fn resample_to_16khz(&self, samples: &[f32]) -> Result<Vec<f32>, String> {
-        if self.sample_rate == WHISPER_SAMPLE_RATE {
-            // Already at 16kHz, no resampling needed
-            return Ok(samples.to_vec());
-        }
-
-        let input_rate = self.sample_rate as usize;
-        let output_rate = WHISPER_SAMPLE_RATE as usize;
-
-        // Create resampler
-        // Using 1024 samples per chunk for good quality
-        let chunk_size = 1024;
-        let mut resampler = FftFixedIn::<f32>::new(
-            input_rate,
-            output_rate,
-            chunk_size,
-            2, // sub-chunks for quality
-            1, // mono
-        )
-        .map_err(|e| format!("Failed to create resampler: {}", e))?;
-
-        let mut output = Vec::new();
-
-        // Process in chunks
-        for chunk in samples.chunks(chunk_size) {
-            if chunk.len() == chunk_size {
-                let input = vec![chunk.to_vec()];
-                match resampler.process(&input, None) {
-                    Ok(resampled) => {
-                        if !resampled.is_empty() {
-                            output.extend_from_slice(&resampled[0]);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Resampling chunk failed: {}", e);
-                    }
-                }
-            } else if !chunk.is_empty() {
-                // Handle remaining samples (pad with zeros)
-                let mut padded = chunk.to_vec();
-                padded.resize(chunk_size, 0.0);
-                let input = vec![padded];
-                match resampler.process(&input, None) {
-                    Ok(resampled) => {
-                        if !resampled.is_empty() {
-                            // Only take the proportional amount
-                            let ratio = output_rate as f32 / input_rate as f32;
-                            let expected_len = (chunk.len() as f32 * ratio).ceil() as usize;
-                            let take_len = expected_len.min(resampled[0].len());
-                            output.extend_from_slice(&resampled[0][..take_len]);
-                        }
-                    }
-                    Err(e) => {
-                        eprintln!("Resampling final chunk failed: {}", e);
-                    }
-                }
-            }
-        }
-
-        Ok(output)
+        resample_to_16khz(samples, self.sample_rate, self.resample_quality)
     }
 
     fn capture_audio(
         audio_data: Arc<Mutex<Vec<f32>>>,
         is_recording: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
         recent_samples: Arc<Mutex<Vec<f32>>>,
         app_handle: AppHandle,
         device_name: Option<String>,
+        mut silence_detector: Option<SilenceDetector>,
+        mono_channel: MonoChannel,
+        meter_settings: MeterSettings,
+        input_gain_db: f32,
     ) -> Result<(), String> {
         let host = cpal::default_host();
 
-        // Find the device by name, or fall back to default
-        let device = if let Some(ref name) = device_name {
-            // Try to find the device by name
-            let found_device = host
-                .input_devices()
-                .ok()
-                .and_then(|mut devices| devices.find(|d| d.name().ok().as_ref() == Some(name)));
-
-            match found_device {
-                Some(d) => {
-                    println!("Found selected device: {}", name);
-                    d
-                }
-                None => {
-                    println!(
-                        "Selected device '{}' not found, falling back to default",
-                        name
-                    );
-                    host.default_input_device()
-                        .ok_or("No input device available")?
-                }
-            }
-        } else {
-            host.default_input_device()
-                .ok_or("No input device available")?
-        };
+        let device = select_recording_device(&host, device_name.as_deref(), &app_handle)?;
 
-        println!("Using input device: {}", device.name().unwrap_or_default());
+        log::info!("Using input device: {}", device.name().unwrap_or_default());
 
         let config = device
             .default_input_config()
             .map_err(|e| format!("Failed to get default input config: {}", e))?;
 
-        println!("Input config: {:?}", config);
-
-        let err_fn = |err| eprintln!("Audio stream error: {}", err);
+        log::info!("Input config: {:?}", config);
 
         // Clone for the audio level thread
         let recent_samples_for_meter = recent_samples.clone();
         let is_recording_for_meter = is_recording.clone();
+        let is_paused_for_meter = is_paused.clone();
         let app_handle_for_meter = app_handle.clone();
 
         // Spawn audio level metering thread (30fps)
@@ -247,21 +511,38 @@ impl AudioRecorder {
             let mut last_emit = Instant::now();
             let emit_interval = Duration::from_millis(33); // ~30fps
             let mut frame_count = 0u32;
+            let recording_start = Instant::now();
+            let mut meter = LevelMeter::new(meter_settings);
 
             while is_recording_for_meter.load(Ordering::SeqCst) {
                 if last_emit.elapsed() >= emit_interval {
-                    let (level, peak) = if let Ok(mut samples) = recent_samples_for_meter.lock() {
-                        let result = calculate_levels(&samples);
-                        samples.clear();
-                        result
-                    } else {
-                        (0.0, 0.0)
-                    };
+                    // Auto-stop measures on the fixed scale, whatever the meter shows
+                    let ((level, peak), silence_level) =
+                        if let Ok(mut samples) = recent_samples_for_meter.lock() {
+                            apply_gain(&mut samples, input_gain_db);
+                            let result = (meter.measure(&samples), calculate_levels(&samples).0);
+                            samples.clear();
+                            result
+                        } else {
+                            ((0.0, 0.0), 0.0)
+                        };
 
                     // Debug: log every 30 frames (~1 second)
                     frame_count += 1;
                     if frame_count % 30 == 0 {
-                        println!("Audio level: {:.3}, peak: {:.3}", level, peak);
+                        log::debug!("Audio level: {:.3}, peak: {:.3}", level, peak);
+                    }
+
+                    // Ask lib.rs to stop once trailing silence exceeds the window
+                    // (a pause isn't silence - start counting again on resume)
+                    if let Some(detector) = silence_detector.as_mut() {
+                        if is_paused_for_meter.load(Ordering::SeqCst) {
+                            detector.reset();
+                        } else if detector.observe(silence_level, recording_start.elapsed()) {
+                            log::info!("Trailing silence detected, requesting auto-stop");
+                            let _ = app_handle_for_meter.emit("auto-stop", ());
+                            silence_detector = None; // Fire once per recording
+                        }
                     }
 
                     let event = AudioLevelEvent { level, peak };
@@ -278,29 +559,169 @@ impl AudioRecorder {
             }
         });
 
+        let sample_rate = config.sample_rate().0;
+        let mut stream_failed = Arc::new(AtomicBool::new(false));
+        let mut stream = Self::open_recording_stream(
+            &device,
+            config,
+            audio_data.clone(),
+            recent_samples.clone(),
+            is_recording.clone(),
+            is_paused.clone(),
+            stream_failed.clone(),
+            mono_channel,
+        )?;
+
+        let device_name = device.name().unwrap_or_default();
+        if let Err(e) = crate::permissions::set_last_used_microphone(&device_name) {
+            log::warn!("Failed to remember microphone: {}", e);
+        }
+
+        // If the microphone goes away, switch to the default one (once)
+        let mut can_fall_back = true;
+
+        // Keep the stream alive while recording
+        let mut device_lost = false;
+        while is_recording.load(Ordering::SeqCst) {
+            match check_stream(&stream_failed, can_fall_back) {
+                StreamCheck::Healthy => {}
+                StreamCheck::FallBackToDefault => {
+                    can_fall_back = false;
+                    log::warn!("Input device failed, switching to the default microphone");
+                    stream_failed = Arc::new(AtomicBool::new(false));
+                    let fallback = Self::open_fallback_stream(
+                        &device_name,
+                        sample_rate,
+                        audio_data.clone(),
+                        recent_samples.clone(),
+                        is_recording.clone(),
+                        is_paused.clone(),
+                        stream_failed.clone(),
+                        mono_channel,
+                    );
+                    match fallback {
+                        Ok(new_stream) => stream = new_stream,
+                        Err(e) => {
+                            log::error!("Microphone fallback failed: {}", e);
+                            device_lost = true;
+                            break;
+                        }
+                    }
+                }
+                StreamCheck::DeviceLost => {
+                    device_lost = true;
+                    break;
+                }
+            }
+            std::thread::sleep(Duration::from_millis(50));
+        }
+
+        drop(stream);
+        log::info!("Audio capture stopped");
+
+        // Let lib.rs end the session rather than waiting on a dead stream
+        if device_lost {
+            log::error!("Input device lost mid-recording");
+            let _ = app_handle.emit("audio-device-lost", ());
+        }
+
+        Ok(())
+    }
+
+    /// Continue the recording on the current default microphone after
+    /// `failed_device` stopped working. The default must be a different device
+    /// recording at the same rate, since the buffer can't mix sample rates.
+    fn open_fallback_stream(
+        failed_device: &str,
+        sample_rate: u32,
+        audio_data: Arc<Mutex<Vec<f32>>>,
+        recent_samples: Arc<Mutex<Vec<f32>>>,
+        is_recording: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
+        stream_failed: Arc<AtomicBool>,
+        mono_channel: MonoChannel,
+    ) -> Result<cpal::Stream, String> {
+        let device = cpal::default_host()
+            .default_input_device()
+            .ok_or("No input device available")?;
+        let name = device.name().unwrap_or_default();
+        if name == failed_device {
+            return Err(format!(
+                "Default microphone '{}' is the one that failed",
+                name
+            ));
+        }
+
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to get default input config: {}", e))?;
+        if config.sample_rate().0 != sample_rate {
+            return Err(format!(
+                "Default microphone records at {}Hz, not {}Hz",
+                config.sample_rate().0,
+                sample_rate
+            ));
+        }
+
+        log::info!("Continuing recording on: {}", name);
+        Self::open_recording_stream(
+            &device,
+            config,
+            audio_data,
+            recent_samples,
+            is_recording,
+            is_paused,
+            stream_failed,
+            mono_channel,
+        )
+    }
+
+    /// Build and start a recording stream on `device`. Stream errors (e.g. the
+    /// device being unplugged) set `stream_failed` for the capture loop to act on.
+    fn open_recording_stream(
+        device: &cpal::Device,
+        config: cpal::SupportedStreamConfig,
+        audio_data: Arc<Mutex<Vec<f32>>>,
+        recent_samples: Arc<Mutex<Vec<f32>>>,
+        is_recording: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
+        stream_failed: Arc<AtomicBool>,
+        mono_channel: MonoChannel,
+    ) -> Result<cpal::Stream, String> {
+        let err_fn = move |err| {
+            log::error!("Audio stream error: {}", err);
+            stream_failed.store(true, Ordering::SeqCst);
+        };
+
         let stream = match config.sample_format() {
             cpal::SampleFormat::F32 => Self::build_input_stream::<f32>(
-                &device,
+                device,
                 &config.into(),
-                audio_data.clone(),
-                recent_samples.clone(),
-                is_recording.clone(),
+                audio_data,
+                recent_samples,
+                is_recording,
+                is_paused,
+                mono_channel,
                 err_fn,
             )?,
             cpal::SampleFormat::I16 => Self::build_input_stream::<i16>(
-                &device,
+                device,
                 &config.into(),
-                audio_data.clone(),
-                recent_samples.clone(),
-                is_recording.clone(),
+                audio_data,
+                recent_samples,
+                is_recording,
+                is_paused,
+                mono_channel,
                 err_fn,
             )?,
             cpal::SampleFormat::U16 => Self::build_input_stream::<u16>(
-                &device,
+                device,
                 &config.into(),
-                audio_data.clone(),
-                recent_samples.clone(),
-                is_recording.clone(),
+                audio_data,
+                recent_samples,
+                is_recording,
+                is_paused,
+                mono_channel,
                 err_fn,
             )?,
             _ => return Err("Unsupported sample format".to_string()),
@@ -309,24 +730,123 @@ impl AudioRecorder {
         stream
             .play()
             .map_err(|e| format!("Failed to play stream: {}", e))?;
+        Ok(stream)
+    }
 
-        // Keep the stream alive while recording
-        while is_recording.load(Ordering::SeqCst) {
-            std::thread::sleep(Duration::from_millis(50));
+    fn capture_prebuffer(
+        preroll: Arc<Mutex<VecDeque<f32>>>,
+        preroll_sample_rate: Arc<AtomicU32>,
+        running: Arc<AtomicBool>,
+        is_recording: Arc<AtomicBool>,
+        preroll_ms: u32,
+        device_name: Option<String>,
+        mono_channel: MonoChannel,
+    ) -> Result<(), String> {
+        let host = cpal::default_host();
+        let device = find_input_device(&host, device_name.as_deref())?;
+        let config = device
+            .default_input_config()
+            .map_err(|e| format!("Failed to get default input config: {}", e))?;
+
+        let sample_rate = config.sample_rate().0;
+        preroll_sample_rate.store(sample_rate, Ordering::SeqCst);
+        let capacity = (sample_rate as u64 * preroll_ms as u64 / 1000) as usize;
+
+        let err_fn = |err| log::error!("Pre-roll stream error: {}", err);
+        let stream = match config.sample_format() {
+            cpal::SampleFormat::F32 => Self::build_prebuffer_stream::<f32>(
+                &device,
+                &config.into(),
+                preroll,
+                capacity,
+                is_recording,
+                mono_channel,
+                err_fn,
+            )?,
+            cpal::SampleFormat::I16 => Self::build_prebuffer_stream::<i16>(
+                &device,
+                &config.into(),
+                preroll,
+                capacity,
+                is_recording,
+                mono_channel,
+                err_fn,
+            )?,
+            cpal::SampleFormat::U16 => Self::build_prebuffer_stream::<u16>(
+                &device,
+                &config.into(),
+                preroll,
+                capacity,
+                is_recording,
+                mono_channel,
+                err_fn,
+            )?,
+            _ => return Err("Unsupported sample format".to_string()),
+        };
+
+        stream
+            .play()
+            .map_err(|e| format!("Failed to play pre-roll stream: {}", e))?;
+
+        while running.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(100));
         }
 
         drop(stream);
-        println!("Audio capture stopped");
+        log::info!("Pre-roll capture stopped");
 
         Ok(())
     }
 
+    fn build_prebuffer_stream<T>(
+        device: &cpal::Device,
+        config: &cpal::StreamConfig,
+        preroll: Arc<Mutex<VecDeque<f32>>>,
+        capacity: usize,
+        is_recording: Arc<AtomicBool>,
+        mono_channel: MonoChannel,
+        err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
+    ) -> Result<cpal::Stream, String>
+    where
+        T: cpal::Sample + cpal::SizedSample + Send + 'static,
+        f32: FromSample<T>,
+    {
+        let channels = config.channels as usize;
+        let channel = mono_channel.resolve(channels);
+
+        device
+            .build_input_stream(
+                config,
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    // The recording stream takes over while recording
+                    if is_recording.load(Ordering::SeqCst) {
+                        return;
+                    }
+                    let mut mono_samples: Vec<f32> = Vec::with_capacity(data.len() / channels);
+                    let mut frame_samples: Vec<f32> = Vec::with_capacity(channels);
+                    for frame in data.chunks(channels) {
+                        frame_samples.clear();
+                        frame_samples.extend(frame.iter().map(|&s| f32::from_sample(s)));
+                        mono_samples.push(downmix_frame(&frame_samples, channel));
+                    }
+                    if let Ok(mut ring) = preroll.lock() {
+                        push_preroll(&mut ring, &mono_samples, capacity);
+                    }
+                },
+                err_fn,
+                None,
+            )
+            .map_err(|e| format!("Failed to build pre-roll stream: {}", e))
+    }
+
     fn build_input_stream<T>(
         device: &cpal::Device,
         config: &cpal::StreamConfig,
         audio_data: Arc<Mutex<Vec<f32>>>,
         recent_samples: Arc<Mutex<Vec<f32>>>,
         is_recording: Arc<AtomicBool>,
+        is_paused: Arc<AtomicBool>,
+        mono_channel: MonoChannel,
         err_fn: impl FnMut(cpal::StreamError) + Send + 'static,
     ) -> Result<cpal::Stream, String>
     where
@@ -334,19 +854,21 @@ impl AudioRecorder {
         f32: FromSample<T>,
     {
         let channels = config.channels as usize;
+        let channel = mono_channel.resolve(channels);
 
         let stream = device
             .build_input_stream(
                 config,
                 move |data: &[T], _: &cpal::InputCallbackInfo| {
-                    if is_recording.load(Ordering::SeqCst) {
+                    if is_recording.load(Ordering::SeqCst) && !is_paused.load(Ordering::SeqCst) {
                         // Convert to mono f32 samples
                         let mut mono_samples: Vec<f32> = Vec::with_capacity(data.len() / channels);
+                        let mut frame_samples: Vec<f32> = Vec::with_capacity(channels);
 
                         for frame in data.chunks(channels) {
-                            let sum: f32 = frame.iter().map(|&s| f32::from_sample(s)).sum();
-                            let mono_sample = sum / channels as f32;
-                            mono_samples.push(mono_sample);
+                            frame_samples.clear();
+                            frame_samples.extend(frame.iter().map(|&s| f32::from_sample(s)));
+                            mono_samples.push(downmix_frame(&frame_samples, channel));
                         }
 
                         // Store for WAV output
@@ -377,11 +899,300 @@ impl AudioRecorder {
     fn convert_to_wav(&self, samples: &[f32]) -> Result<Vec<u8>, String> {
         let result = encode_samples_to_wav(samples, self.sample_rate)?;
         #[cfg(debug_assertions)]
-        println!("WAV buffer size: {} bytes", result.len());
+        log::info!("WAV buffer size: {} bytes", result.len());
         Ok(result)
     }
 }
 
+/// Open an input device briefly and stream `audio-level` events so the user can
+/// confirm the microphone is live (used during onboarding). Blocks for `duration`.
+///
+/// Unlike recording, a named device that doesn't exist is an error rather than
+/// silently falling back to the default - the point is to test *that* device.
+pub fn test_input_level(
+    app_handle: &AppHandle,
+    device_name: Option<&str>,
+    duration: Duration,
+    mono_channel: MonoChannel,
+    meter_settings: MeterSettings,
+    input_gain_db: f32,
+) -> Result<(), String> {
+    let host = cpal::default_host();
+    let device = match device_name.filter(|name| *name != "default") {
+        Some(name) => host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name)))
+            .ok_or_else(|| format!("Microphone not found: {}", name))?,
+        None => host
+            .default_input_device()
+            .ok_or("No input device available")?,
+    };
+
+    let config = device
+        .default_input_config()
+        .map_err(|e| format!("Failed to get default input config: {}", e))?;
+
+    // The recording stream builder needs somewhere to put samples; only the
+    // level-metering buffer matters here
+    let audio_data = Arc::new(Mutex::new(Vec::new()));
+    let recent_samples = Arc::new(Mutex::new(Vec::new()));
+    let is_recording = Arc::new(AtomicBool::new(true));
+    let is_paused = Arc::new(AtomicBool::new(false));
+    let stream_error: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+
+    let stream_error_for_callback = stream_error.clone();
+    let err_fn = move |err: cpal::StreamError| {
+        log::error!("Microphone test stream error: {}", err);
+        if let Ok(mut e) = stream_error_for_callback.lock() {
+            e.get_or_insert_with(|| err.to_string());
+        }
+    };
+
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => AudioRecorder::build_input_stream::<f32>(
+            &device,
+            &config.into(),
+            audio_data,
+            recent_samples.clone(),
+            is_recording,
+            is_paused,
+            mono_channel,
+            err_fn,
+        )?,
+        cpal::SampleFormat::I16 => AudioRecorder::build_input_stream::<i16>(
+            &device,
+            &config.into(),
+            audio_data,
+            recent_samples.clone(),
+            is_recording,
+            is_paused,
+            mono_channel,
+            err_fn,
+        )?,
+        cpal::SampleFormat::U16 => AudioRecorder::build_input_stream::<u16>(
+            &device,
+            &config.into(),
+            audio_data,
+            recent_samples.clone(),
+            is_recording,
+            is_paused,
+            mono_channel,
+            err_fn,
+        )?,
+        _ => return Err("Unsupported sample format".to_string()),
+    };
+
+    stream
+        .play()
+        .map_err(|e| format!("Failed to start microphone: {}", e))?;
+
+    let started = Instant::now();
+    let emit_interval = Duration::from_millis(33); // ~30fps, same as recording
+    let mut meter = LevelMeter::new(meter_settings);
+    while started.elapsed() < duration {
+        std::thread::sleep(emit_interval);
+
+        if let Some(e) = stream_error.lock().ok().and_then(|e| e.clone()) {
+            return Err(format!("Microphone stopped responding: {}", e));
+        }
+
+        let (level, peak) = match recent_samples.lock() {
+            Ok(mut samples) => {
+                apply_gain(&mut samples, input_gain_db);
+                let result = meter.measure(&samples);
+                samples.clear();
+                result
+            }
+            Err(_) => (0.0, 0.0),
+        };
+        let _ = app_handle.emit("audio-level", &AudioLevelEvent { level, peak });
+    }
+
+    drop(stream);
+    Ok(())
+}
+
+/// Append samples to the pre-roll ring, keeping only the newest `capacity`.
+fn push_preroll(ring: &mut VecDeque<f32>, samples: &[f32], capacity: usize) {
+    ring.extend(samples.iter().copied());
+    if ring.len() > capacity {
+        let excess = ring.len() - capacity;
+        ring.drain(..excess);
+    }
+}
+
+/// Move the pre-roll into the recording buffer (oldest sample first).
+fn splice_preroll(audio_data: &mut Vec<f32>, ring: &mut VecDeque<f32>) {
+    audio_data.extend(ring.drain(..));
+}
+
+/// What the capture loop should do after checking its stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StreamCheck {
+    Healthy,
+    /// The stream failed; try the default microphone instead
+    FallBackToDefault,
+    /// The stream failed and there is nothing to fall back to
+    DeviceLost,
+}
+
+/// Consume the stream's error flag. Clearing it means a fallback stream
+/// starts with a clean slate.
+fn check_stream(stream_failed: &AtomicBool, can_fall_back: bool) -> StreamCheck {
+    if !stream_failed.swap(false, Ordering::SeqCst) {
+        StreamCheck::Healthy
+    } else if can_fall_back {
+        StreamCheck::FallBackToDefault
+    } else {
+        StreamCheck::DeviceLost
+    }
+}
+
+/// Which microphone a recording uses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DeviceChoice<'a> {
+    /// The microphone chosen in settings
+    Configured(&'a str),
+    /// The configured microphone is missing; the last one a recording used
+    LastUsed(&'a str),
+    /// The system default
+    Default,
+}
+
+/// Prefer the configured microphone, then (if it's missing) the last one a
+/// recording used, then the system default. `configured` is None when the
+/// user picked the default.
+fn choose_input_device<'a>(
+    available: &[String],
+    configured: Option<&'a str>,
+    last_used: Option<&'a str>,
+) -> DeviceChoice<'a> {
+    let is_available = |name: &str| available.iter().any(|d| d == name);
+    match (configured, last_used) {
+        (Some(name), _) if is_available(name) => DeviceChoice::Configured(name),
+        (Some(_), Some(name)) if is_available(name) => DeviceChoice::LastUsed(name),
+        _ => DeviceChoice::Default,
+    }
+}
+
+/// Find the microphone to record from (see `choose_input_device`). When the
+/// configured one is missing, the frontend is told once via `microphone-fallback`.
+fn select_recording_device(
+    host: &cpal::Host,
+    configured: Option<&str>,
+    app_handle: &AppHandle,
+) -> Result<cpal::Device, String> {
+    let devices: Vec<(String, cpal::Device)> = host
+        .input_devices()
+        .map(|devices| devices.filter_map(|d| Some((d.name().ok()?, d))).collect())
+        .unwrap_or_default();
+    let names: Vec<String> = devices.iter().map(|(name, _)| name.clone()).collect();
+    let last_used = crate::permissions::get_last_used_microphone();
+    let choice = choose_input_device(&names, configured, last_used.as_deref());
+
+    let chosen = match choice {
+        DeviceChoice::Configured(name) | DeviceChoice::LastUsed(name) => devices
+            .into_iter()
+            .find(|(device_name, _)| device_name == name)
+            .map(|(_, device)| device),
+        DeviceChoice::Default => None,
+    };
+    let device = match chosen {
+        Some(device) => device,
+        None => host
+            .default_input_device()
+            .ok_or("No input device available")?,
+    };
+
+    if let Some(configured) = configured {
+        if matches!(choice, DeviceChoice::Configured(_)) {
+            MICROPHONE_FALLBACK_NOTIFIED.store(false, Ordering::SeqCst);
+        } else {
+            let fallback_device = device.name().unwrap_or_default();
+            log::warn!(
+                "Selected device '{}' not found, using '{}'",
+                configured,
+                fallback_device
+            );
+            if !MICROPHONE_FALLBACK_NOTIFIED.swap(true, Ordering::SeqCst) {
+                let event = MicrophoneFallbackEvent {
+                    configured_device: configured.to_string(),
+                    fallback_device,
+                };
+                let _ = app_handle.emit("microphone-fallback", &event);
+            }
+        }
+    }
+
+    Ok(device)
+}
+
+/// Find an input device by name, falling back to the default device
+fn find_input_device(host: &cpal::Host, device_name: Option<&str>) -> Result<cpal::Device, String> {
+    if let Some(name) = device_name {
+        // Try to find the device by name
+        let found_device = host
+            .input_devices()
+            .ok()
+            .and_then(|mut devices| devices.find(|d| d.name().ok().as_deref() == Some(name)));
+
+        match found_device {
+            Some(d) => {
+                log::info!("Found selected device: {}", name);
+                return Ok(d);
+            }
+            None => {
+                log::warn!(
+                    "Selected device '{}' not found, falling back to default",
+                    name
+                );
+            }
+        }
+    }
+
+    host.default_input_device()
+        .ok_or_else(|| "No input device available".to_string())
+}
+
+/// Tracks trailing silence in the meter levels for voice-activity auto-stop.
+#[derive(Debug, Clone)]
+struct SilenceDetector {
+    timeout: Duration,
+    /// Silence threshold as a normalized meter level (same scale as `calculate_levels`)
+    threshold_level: f32,
+    /// Time since recording start when the current silent stretch began
+    silent_since: Option<Duration>,
+}
+
+impl SilenceDetector {
+    fn new(timeout_ms: u32, threshold_db: f32) -> Self {
+        let threshold_level =
+            ((threshold_db - LEVEL_MIN_DB) / (LEVEL_MAX_DB - LEVEL_MIN_DB)).clamp(0.0, 1.0);
+        SilenceDetector {
+            timeout: Duration::from_millis(timeout_ms as u64),
+            threshold_level,
+            silent_since: None,
+        }
+    }
+
+    /// Feed the latest meter level, `elapsed` since recording started.
+    /// Returns true once silence has lasted longer than the timeout.
+    fn observe(&mut self, level: f32, elapsed: Duration) -> bool {
+        if level > self.threshold_level {
+            self.silent_since = None;
+            return false;
+        }
+        let since = *self.silent_since.get_or_insert(elapsed);
+        elapsed >= Duration::from_millis(AUTO_STOP_GRACE_MS) && elapsed - since >= self.timeout
+    }
+
+    /// Forget the current silent stretch
+    fn reset(&mut self) {
+        self.silent_since = None;
+    }
+}
+
 /// Encode f32 audio samples to WAV format.
 /// This is the canonical WAV encoding function used throughout the app.
 pub fn encode_samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8>, String> {
@@ -413,6 +1224,197 @@ pub fn encode_samples_to_wav(samples: &[f32], sample_rate: u32) -> Result<Vec<u8
     Ok(buffer)
 }
 
+/// Resample mono audio from `input_rate` to 16kHz for Whisper
+pub fn resample_to_16khz(
+    samples: &[f32],
+    input_rate: u32,
+    quality: ResampleQuality,
+) -> Result<Vec<f32>, String> {
+    if input_rate == WHISPER_SAMPLE_RATE {
+        // Already at 16kHz, no resampling needed
+        return Ok(samples.to_vec());
+    }
+
+    let input_rate = input_rate as usize;
+    let output_rate = WHISPER_SAMPLE_RATE as usize;
+
+    // Create resampler
+    let (chunk_size, sub_chunks) = quality.fft_params();
+    let mut resampler = FftFixedIn::<f32>::new(
+        input_rate,
+        output_rate,
+        chunk_size,
+        sub_chunks,
+        1, // mono
+    )
+    .map_err(|e| format!("Failed to create resampler: {}", e))?;
+
+    let mut output = Vec::new();
+
+    // Process in chunks
+    for chunk in samples.chunks(chunk_size) {
+        if chunk.len() == chunk_size {
+            let input = vec![chunk.to_vec()];
+            match resampler.process(&input, None) {
+                Ok(resampled) => {
+                    if !resampled.is_empty() {
+                        output.extend_from_slice(&resampled[0]);
+                    }
+                }
+                Err(e) => {
+                    log::error!("Resampling chunk failed: {}", e);
+                }
+            }
+        } else if !chunk.is_empty() {
+            // Handle remaining samples (pad with zeros)
+            let mut padded = chunk.to_vec();
+            padded.resize(chunk_size, 0.0);
+            let input = vec![padded];
+            match resampler.process(&input, None) {
+                Ok(resampled) => {
+                    if !resampled.is_empty() {
+                        // Only take the proportional amount
+                        let ratio = output_rate as f32 / input_rate as f32;
+                        let expected_len = (chunk.len() as f32 * ratio).ceil() as usize;
+                        let take_len = expected_len.min(resampled[0].len());
+                        output.extend_from_slice(&resampled[0][..take_len]);
+                    }
+                }
+                Err(e) => {
+                    log::error!("Resampling final chunk failed: {}", e);
+                }
+            }
+        }
+    }
+
+    Ok(output)
+}
+
+/// Amplify `samples` by `gain_db`, clamping to [-1, 1].
+/// Returns how many samples were clipped by the clamp.
+pub fn apply_gain(samples: &mut [f32], gain_db: f32) -> usize {
+    if gain_db == 0.0 {
+        return 0;
+    }
+    let factor = 10f32.powf(gain_db / 20.0);
+    let mut clipped = 0;
+    for sample in samples.iter_mut() {
+        let amplified = *sample * factor;
+        if amplified.abs() > 1.0 {
+            clipped += 1;
+        }
+        *sample = amplified.clamp(-1.0, 1.0);
+    }
+    clipped
+}
+
+/// Remove leading and trailing silence, keeping a small padding margin so the
+/// edges of words aren't clipped. Returns an empty Vec if there's no speech at all.
+pub fn trim_silence(samples: &[f32], sample_rate: u32) -> Vec<f32> {
+    trim_silence_below(
+        samples,
+        sample_rate,
+        crate::config::DEFAULT_SILENCE_THRESHOLD_DB,
+    )
+}
+
+/// `trim_silence` with an explicit RMS threshold (dBFS) for what counts as silence.
+pub fn trim_silence_below(samples: &[f32], sample_rate: u32, threshold_db: f32) -> Vec<f32> {
+    let window = ((sample_rate * TRIM_WINDOW_MS / 1000) as usize).max(1);
+    let padding = (sample_rate * TRIM_PADDING_MS / 1000) as usize;
+    let threshold_rms = 10f32.powf(threshold_db / 20.0);
+
+    let is_loud = |chunk: &[f32]| {
+        let mean_square = chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32;
+        mean_square.sqrt() > threshold_rms
+    };
+
+    let Some(first) = samples.chunks(window).position(is_loud) else {
+        return Vec::new();
+    };
+    let last = samples.chunks(window).rposition(is_loud).unwrap_or(first);
+
+    let start = (first * window).saturating_sub(padding);
+    let end = ((last + 1) * window + padding).min(samples.len());
+    samples[start..end].to_vec()
+}
+
+/// Split audio into chunks of about `chunk_secs` for piecewise transcription.
+/// Each cut is made at the quietest TRIM_WINDOW_MS window in the last
+/// `search_secs` of the chunk, so it falls between words where possible.
+/// The final chunk is at least `search_secs` long (unless the audio is shorter).
+pub fn split_at_pauses(
+    samples: &[f32],
+    sample_rate: u32,
+    chunk_secs: u32,
+    search_secs: u32,
+) -> Vec<&[f32]> {
+    let chunk_len = (sample_rate as usize * chunk_secs as usize).max(1);
+    let search_len = (sample_rate as usize * search_secs as usize).min(chunk_len);
+    let window = ((sample_rate * TRIM_WINDOW_MS / 1000) as usize).max(1);
+
+    let energy = |chunk: &[f32]| chunk.iter().map(|s| s * s).sum::<f32>();
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while samples.len() - start > chunk_len + search_len {
+        let search_start = start + chunk_len - search_len;
+        let quietest = samples[search_start..start + chunk_len]
+            .chunks(window)
+            .map(energy)
+            .enumerate()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let cut =
+            (search_start + quietest * window + window / 2).clamp(start + 1, start + chunk_len);
+        chunks.push(&samples[start..cut]);
+        start = cut;
+    }
+    chunks.push(&samples[start..]);
+    chunks
+}
+
+/// Load a WAV file and return mono f32 samples with the file's sample rate.
+pub fn load_wav_file(path: &std::path::Path) -> Result<(Vec<f32>, u32), String> {
+    let file =
+        std::fs::File::open(path).map_err(|e| format!("Failed to open audio file: {}", e))?;
+    decode_wav(std::io::BufReader::new(file))
+}
+
+/// Decode WAV data into mono f32 samples (multi-channel audio is averaged down).
+fn decode_wav<R: std::io::Read>(reader: R) -> Result<(Vec<f32>, u32), String> {
+    let mut wav = hound::WavReader::new(reader)
+        .map_err(|e| format!("Unsupported or corrupt WAV file: {}", e))?;
+    let spec = wav.spec();
+    let channels = spec.channels.max(1) as usize;
+
+    let interleaved: Vec<f32> = match spec.sample_format {
+        hound::SampleFormat::Float => wav
+            .samples::<f32>()
+            .collect::<Result<_, _>>()
+            .map_err(|e| format!("Failed to read samples: {}", e))?,
+        hound::SampleFormat::Int => {
+            let scale = (1_i64 << (spec.bits_per_sample.saturating_sub(1))) as f32;
+            wav.samples::<i32>()
+                .map(|s| s.map(|v| v as f32 / scale))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Failed to read samples: {}", e))?
+        }
+    };
+
+    let mono = if channels == 1 {
+        interleaved
+    } else {
+        interleaved
+            .chunks(channels)
+            .map(|frame| frame.iter().sum::<f32>() / frame.len() as f32)
+            .collect()
+    };
+
+    Ok((mono, spec.sample_rate))
+}
+
 impl Default for AudioRecorder {
     fn default() -> Self {
         Self::new()
@@ -422,6 +1424,11 @@ impl Default for AudioRecorder {
 /// Calculate RMS level and peak from audio samples using proper dB normalization
 /// Uses -60dB to 0dB range like VoiceInk for consistent, non-flickery visualization
 fn calculate_levels(samples: &[f32]) -> (f32, f32) {
+    calculate_levels_in_range(samples, LEVEL_MIN_DB, LEVEL_MAX_DB)
+}
+
+/// `calculate_levels` with `min_db..max_db` mapped to 0-1 instead of the default range
+fn calculate_levels_in_range(samples: &[f32], min_db: f32, max_db: f32) -> (f32, f32) {
     if samples.is_empty() {
         return (0.0, 0.0);
     }
@@ -444,31 +1451,38 @@ fn calculate_levels(samples: &[f32]) -> (f32, f32) {
     let rms_db = 20.0 * (rms + 1e-10).log10();
     let peak_db = 20.0 * (peak + 1e-10).log10();
 
-    // Normalize dB to 0-1 range using -60dB to 0dB range
-    // Below -60dB = complete silence (noise floor)
-    // At 0dB = maximum level
-    const MIN_DB: f32 = -60.0;
-    const MAX_DB: f32 = 0.0;
-
-    let level = if rms_db < MIN_DB {
+    // Normalize dB to 0-1 range using min_db to max_db
+    let level = if rms_db < min_db {
         0.0
-    } else if rms_db >= MAX_DB {
+    } else if rms_db >= max_db {
         1.0
     } else {
-        (rms_db - MIN_DB) / (MAX_DB - MIN_DB)
+        (rms_db - min_db) / (max_db - min_db)
     };
 
-    let peak_normalized = if peak_db < MIN_DB {
+    let peak_normalized = if peak_db < min_db {
         0.0
-    } else if peak_db >= MAX_DB {
+    } else if peak_db >= max_db {
         1.0
     } else {
-        (peak_db - MIN_DB) / (MAX_DB - MIN_DB)
+        (peak_db - min_db) / (max_db - min_db)
     };
 
     (level, peak_normalized)
 }
 
+/// Duration and overall levels of a recording (same level scale as the live meter)
+pub fn recording_stats(samples: &[f32], sample_rate: u32) -> RecordingStatsEvent {
+    let (avg_level, peak_level) = calculate_levels(samples);
+    RecordingStatsEvent {
+        duration_ms: (samples.len() as u64 * 1000)
+            .checked_div(u64::from(sample_rate))
+            .unwrap_or(0),
+        avg_level,
+        peak_level,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -625,6 +1639,54 @@ mod tests {
         assert_eq!(level, 1.0, "Level should clamp to 1.0");
     }
 
+    #[test]
+    fn test_calculate_levels_in_range() {
+        // -20 dBFS RMS
+        let samples = vec![0.1; 100];
+        let (default_level, _) = calculate_levels(&samples);
+        assert!((default_level - 40.0 / 60.0).abs() < 0.01);
+
+        // A narrower range around the signal reads louder
+        let (level, _) = calculate_levels_in_range(&samples, -30.0, -10.0);
+        assert!((level - 0.5).abs() < 0.01);
+        let (level, peak) = calculate_levels_in_range(&samples, -60.0, -20.0);
+        assert_eq!((level, peak), (1.0, 1.0));
+    }
+
+    #[test]
+    fn test_level_meter_peak_hold() {
+        let loud = vec![1.0; 10];
+        let silence = vec![0.0; 10];
+
+        let mut plain = LevelMeter::new(MeterSettings::default());
+        plain.measure(&loud);
+        assert_eq!(plain.measure(&silence).1, 0.0);
+
+        let mut held = LevelMeter::new(MeterSettings {
+            peak_hold: true,
+            ..MeterSettings::default()
+        });
+        assert_eq!(held.measure(&loud).1, 1.0);
+        let decayed = held.measure(&silence).1;
+        assert!((decayed - PEAK_HOLD_DECAY).abs() < 1e-6);
+        assert!(held.measure(&silence).1 < decayed);
+        // A new peak takes over immediately
+        assert_eq!(held.measure(&loud).1, 1.0);
+    }
+
+    #[test]
+    fn test_recording_stats() {
+        let mut samples = vec![0.0; 24000];
+        samples[100] = 1.0;
+        let stats = recording_stats(&samples, 48000);
+        assert_eq!(stats.duration_ms, 500);
+        assert!(stats.avg_level < 0.5);
+        assert!((stats.peak_level - 1.0).abs() < 0.01);
+
+        assert_eq!(recording_stats(&[], 48000), RecordingStatsEvent::default());
+        assert_eq!(recording_stats(&samples, 0).duration_ms, 0);
+    }
+
     // ==================== AudioRecorder Tests ====================
 
     #[test]
@@ -652,7 +1714,15 @@ mod tests {
             audio_data: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
             sample_rate: 16000, // Same as WHISPER_SAMPLE_RATE
             is_recording: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            is_paused: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
             recent_samples: std::sync::Arc::new(std::sync::Mutex::new(Vec::new())),
+            silence_timeout_ms: None,
+            silence_threshold_db: crate::config::DEFAULT_SILENCE_THRESHOLD_DB,
+            resample_quality: ResampleQuality::default(),
+            preroll: std::sync::Arc::new(std::sync::Mutex::new(VecDeque::new())),
+            preroll_sample_rate: std::sync::Arc::new(AtomicU32::new(0)),
+            prebuffer_running: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+            last_stats: None,
         };
 
         let samples = vec![0.1, 0.2, 0.3, 0.4, 0.5];
@@ -670,4 +1740,362 @@ mod tests {
         let result = recorder.resample_to_16khz(&[]).unwrap();
         assert!(result.is_empty());
     }
+
+    #[test]
+    fn test_resample_free_function_halves_32khz() {
+        let samples = vec![0.0; 32000];
+        let result = resample_to_16khz(&samples, 32000, ResampleQuality::Balanced).unwrap();
+        // Allow for resampler chunk padding
+        assert!(
+            (result.len() as i64 - 16000).abs() < 1024,
+            "Expected ~16000 samples, got {}",
+            result.len()
+        );
+    }
+
+    // ==================== WAV Decoding Tests ====================
+
+    #[test]
+    fn test_decode_wav_roundtrip() {
+        let samples = vec![0.0, 0.5, -0.5, 0.25];
+        let wav = encode_samples_to_wav(&samples, 22050).unwrap();
+        let (decoded, rate) = decode_wav(std::io::Cursor::new(wav)).unwrap();
+
+        assert_eq!(rate, 22050);
+        assert_eq!(decoded.len(), samples.len());
+        for (a, b) in decoded.iter().zip(samples.iter()) {
+            assert!((a - b).abs() < 0.001, "Expected {}, got {}", b, a);
+        }
+    }
+
+    #[test]
+    fn test_decode_wav_downmixes_stereo() {
+        let spec = hound::WavSpec {
+            channels: 2,
+            sample_rate: 16000,
+            bits_per_sample: 32,
+            sample_format: hound::SampleFormat::Float,
+        };
+        let mut buffer = Vec::new();
+        {
+            let mut writer =
+                hound::WavWriter::new(std::io::Cursor::new(&mut buffer), spec).unwrap();
+            for (l, r) in [(1.0f32, 0.0f32), (0.5, -0.5)] {
+                writer.write_sample(l).unwrap();
+                writer.write_sample(r).unwrap();
+            }
+            writer.finalize().unwrap();
+        }
+
+        let (decoded, _) = decode_wav(std::io::Cursor::new(buffer)).unwrap();
+        assert_eq!(decoded, vec![0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_decode_wav_rejects_garbage() {
+        assert!(decode_wav(std::io::Cursor::new(b"not a wav file".to_vec())).is_err());
+    }
+
+    // ==================== Auto-Stop Tests ====================
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    #[test]
+    fn test_silence_detector_fires_after_timeout() {
+        let mut detector = SilenceDetector::new(1000, -45.0);
+        assert!(!detector.observe(0.8, ms(0)));
+        assert!(!detector.observe(0.0, ms(600)));
+        assert!(!detector.observe(0.0, ms(1500)));
+        assert!(detector.observe(0.0, ms(1600)));
+    }
+
+    #[test]
+    fn test_silence_detector_resets_on_speech() {
+        let mut detector = SilenceDetector::new(1000, -45.0);
+        assert!(!detector.observe(0.0, ms(600)));
+        assert!(!detector.observe(0.8, ms(1400)));
+        assert!(!detector.observe(0.0, ms(1500)));
+        assert!(!detector.observe(0.0, ms(2400)));
+        assert!(detector.observe(0.0, ms(2500)));
+    }
+
+    #[test]
+    fn test_silence_detector_reset() {
+        let mut detector = SilenceDetector::new(1000, -45.0);
+        assert!(!detector.observe(0.0, ms(600)));
+        detector.reset();
+        assert!(!detector.observe(0.0, ms(1600)));
+        assert!(detector.observe(0.0, ms(2600)));
+    }
+
+    #[test]
+    fn test_silence_detector_grace_period() {
+        // Even a tiny timeout never fires in the first 500ms
+        let mut detector = SilenceDetector::new(100, -45.0);
+        assert!(!detector.observe(0.0, ms(0)));
+        assert!(!detector.observe(0.0, ms(499)));
+        assert!(detector.observe(0.0, ms(500)));
+    }
+
+    #[test]
+    fn test_silence_threshold_matches_meter_scale() {
+        // -45dB is 15dB above the -60dB floor on a 60dB scale
+        let detector = SilenceDetector::new(1000, -45.0);
+        assert!((detector.threshold_level - 0.25).abs() < 0.001);
+        assert_eq!(SilenceDetector::new(1000, -90.0).threshold_level, 0.0);
+    }
+
+    #[test]
+    fn test_downmix_frame() {
+        let frame = [0.5, 0.1, -0.3];
+        assert!((downmix_frame(&frame, None) - 0.1).abs() < 1e-6);
+        assert_eq!(downmix_frame(&frame, Some(0)), 0.5);
+        assert_eq!(downmix_frame(&frame, Some(2)), -0.3);
+    }
+
+    #[test]
+    fn test_mono_channel() {
+        assert_eq!(MonoChannel::Mix.resolve(2), None);
+        assert_eq!(MonoChannel::Channel(1).resolve(2), Some(1));
+        // Out of range falls back to mixing
+        assert_eq!(MonoChannel::Channel(2).resolve(2), None);
+
+        let mix: MonoChannel = serde_json::from_str("\"mix\"").unwrap();
+        assert_eq!(mix, MonoChannel::Mix);
+        let first: MonoChannel = serde_json::from_str("0").unwrap();
+        assert_eq!(first, MonoChannel::Channel(0));
+        assert!(serde_json::from_str::<MonoChannel>("\"left\"").is_err());
+        assert_eq!(
+            serde_json::to_string(&MonoChannel::Channel(3)).unwrap(),
+            "3"
+        );
+        assert_eq!(serde_json::to_string(&MonoChannel::Mix).unwrap(), "\"mix\"");
+    }
+
+    #[test]
+    fn test_check_stream() {
+        let failed = AtomicBool::new(false);
+        assert_eq!(check_stream(&failed, true), StreamCheck::Healthy);
+
+        // The first failure falls back, a failure after that ends the recording
+        failed.store(true, Ordering::SeqCst);
+        assert_eq!(check_stream(&failed, true), StreamCheck::FallBackToDefault);
+        assert_eq!(check_stream(&failed, false), StreamCheck::Healthy);
+        failed.store(true, Ordering::SeqCst);
+        assert_eq!(check_stream(&failed, false), StreamCheck::DeviceLost);
+    }
+
+    #[test]
+    fn test_choose_input_device() {
+        let available = vec!["Blue Yeti".to_string(), "AirPods".to_string()];
+
+        assert_eq!(
+            choose_input_device(&available, Some("Blue Yeti"), Some("AirPods")),
+            DeviceChoice::Configured("Blue Yeti")
+        );
+        // Configured mic unplugged: the last one used, if it's still here
+        assert_eq!(
+            choose_input_device(&available, Some("USB Mic"), Some("AirPods")),
+            DeviceChoice::LastUsed("AirPods")
+        );
+        assert_eq!(
+            choose_input_device(&available, Some("USB Mic"), Some("USB Mic")),
+            DeviceChoice::Default
+        );
+        // The user picked the system default
+        assert_eq!(
+            choose_input_device(&available, None, Some("AirPods")),
+            DeviceChoice::Default
+        );
+    }
+
+    // ==================== Silence Trimming Tests ====================
+
+    /// 1kHz test signal: a loud tone of `speech_ms` between leading and trailing silence
+    fn padded_tone(lead_ms: usize, speech_ms: usize, trail_ms: usize) -> Vec<f32> {
+        let mut samples = vec![0.0; lead_ms];
+        samples.extend((0..speech_ms).map(|i| if i % 2 == 0 { 0.5 } else { -0.5 }));
+        samples.extend(vec![0.0; trail_ms]);
+        samples
+    }
+
+    #[test]
+    fn test_apply_gain_scales_samples() {
+        let mut samples = vec![0.1, -0.1, 0.0];
+        assert_eq!(apply_gain(&mut samples, 6.0), 0);
+        // +6 dB is about double
+        assert!((samples[0] - 0.1995).abs() < 1e-3);
+        assert!((samples[1] + 0.1995).abs() < 1e-3);
+        assert_eq!(samples[2], 0.0);
+
+        let mut samples = vec![0.5];
+        apply_gain(&mut samples, -20.0);
+        assert!((samples[0] - 0.05).abs() < 1e-6);
+
+        let mut samples = vec![0.3, -0.3];
+        assert_eq!(apply_gain(&mut samples, 0.0), 0);
+        assert_eq!(samples, vec![0.3, -0.3]);
+    }
+
+    #[test]
+    fn test_apply_gain_clamps() {
+        let mut samples = vec![0.5, -0.5, 0.01];
+        assert_eq!(apply_gain(&mut samples, 20.0), 2);
+        assert_eq!(samples[0], 1.0);
+        assert_eq!(samples[1], -1.0);
+        assert!((samples[2] - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_trim_silence_all_silence_returns_empty() {
+        assert!(trim_silence(&vec![0.0; 16000], 16000).is_empty());
+        assert!(trim_silence(&vec![0.001; 16000], 16000).is_empty());
+        assert!(trim_silence(&[], 16000).is_empty());
+    }
+
+    #[test]
+    fn test_trim_silence_speech_in_middle() {
+        // At 1kHz, 1 sample = 1ms: 500ms silence, 200ms speech, 500ms silence
+        let samples = padded_tone(500, 200, 500);
+        let trimmed = trim_silence(&samples, 1000);
+
+        // Speech plus 100ms padding on each side
+        assert_eq!(trimmed.len(), 400);
+        assert_eq!(trimmed[0], 0.0);
+        assert_eq!(trimmed[100], 0.5);
+    }
+
+    #[test]
+    fn test_trim_silence_speech_to_the_end() {
+        let samples = padded_tone(500, 300, 0);
+        let trimmed = trim_silence(&samples, 1000);
+
+        // Padding can't extend past the end, and the last word is kept
+        assert_eq!(trimmed.len(), 400);
+        assert_eq!(trimmed.last(), samples.last());
+    }
+
+    #[test]
+    fn test_trim_silence_speech_from_the_start() {
+        let samples = padded_tone(0, 300, 500);
+        let trimmed = trim_silence(&samples, 1000);
+        assert_eq!(trimmed.len(), 400);
+        assert_eq!(trimmed[0], 0.5);
+    }
+
+    #[test]
+    fn test_split_at_pauses_short_audio_is_one_chunk() {
+        let samples = padded_tone(0, 5000, 0);
+        let chunks = split_at_pauses(&samples, 1000, 20, 3);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].len(), samples.len());
+    }
+
+    #[test]
+    fn test_split_at_pauses_cuts_in_silence() {
+        // At 1kHz: speech with 1s pauses at 18.5-19.5s and 38-39s, 51s total
+        let mut samples = padded_tone(0, 18_500, 1000);
+        samples.extend(padded_tone(0, 18_500, 1000));
+        samples.extend(padded_tone(0, 12_000, 0));
+
+        let chunks = split_at_pauses(&samples, 1000, 20, 3);
+        assert_eq!(chunks.len(), 3);
+        assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), samples.len());
+
+        let mut cut = 0;
+        for chunk in &chunks[..chunks.len() - 1] {
+            cut += chunk.len();
+            assert_eq!(samples[cut - 1], 0.0);
+            assert_eq!(samples[cut], 0.0);
+        }
+    }
+
+    // ==================== Pre-roll Tests ====================
+
+    #[test]
+    fn test_push_preroll_keeps_newest_samples() {
+        let mut ring = VecDeque::new();
+        push_preroll(&mut ring, &[1.0, 2.0, 3.0], 4);
+        push_preroll(&mut ring, &[4.0, 5.0, 6.0], 4);
+        assert_eq!(ring, VecDeque::from(vec![3.0, 4.0, 5.0, 6.0]));
+    }
+
+    #[test]
+    fn test_splice_preroll_length() {
+        // 300ms pre-roll at 48kHz
+        let capacity = 48000 * 300 / 1000;
+        let mut ring = VecDeque::new();
+        // Feed a full second of "idle" audio in 10ms buffers
+        for _ in 0..100 {
+            push_preroll(&mut ring, &[0.1; 480], capacity);
+        }
+        assert_eq!(ring.len(), capacity);
+
+        let mut audio_data = Vec::new();
+        splice_preroll(&mut audio_data, &mut ring);
+        audio_data.extend_from_slice(&[0.5; 4800]); // 100ms of recording
+
+        assert_eq!(audio_data.len(), capacity + 4800);
+        assert_eq!(audio_data[capacity - 1], 0.1);
+        assert_eq!(audio_data[capacity], 0.5);
+        assert!(ring.is_empty(), "Pre-roll should be consumed by the splice");
+    }
+
+    #[test]
+    fn test_start_prebuffer_zero_is_disabled() {
+        let mut recorder = AudioRecorder::new();
+        recorder.start_prebuffer(0, None).unwrap();
+        assert!(!recorder.prebuffer_running.load(Ordering::SeqCst));
+    }
+
+    // ==================== Resample Quality Tests ====================
+
+    /// One second of a 100Hz-4kHz linear sine sweep at `rate`
+    fn sine_sweep(rate: u32) -> Vec<f32> {
+        let n = rate as usize;
+        (0..n)
+            .map(|i| {
+                let t = i as f32 / rate as f32;
+                let freq = 100.0 + (4000.0 - 100.0) * t / 2.0;
+                0.5 * (2.0 * std::f32::consts::PI * freq * t).sin()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_resample_quality_modes_on_sine_sweep() {
+        let sweep = sine_sweep(48000);
+
+        for quality in [
+            ResampleQuality::Fast,
+            ResampleQuality::Balanced,
+            ResampleQuality::High,
+        ] {
+            let output = resample_to_16khz(&sweep, 48000, quality).unwrap();
+
+            // One second in, ~one second out (within one output chunk of padding)
+            let (chunk_size, _) = quality.fft_params();
+            let tolerance = (chunk_size / 3 + 1) as i64;
+            assert!(
+                (output.len() as i64 - 16000).abs() <= tolerance,
+                "{:?} produced {} samples",
+                quality,
+                output.len()
+            );
+
+            // Signal survives resampling without blowing up
+            let peak = output.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+            assert!(peak > 0.3 && peak < 0.7, "{:?} peak was {}", quality, peak);
+        }
+    }
+
+    #[test]
+    fn test_resample_quality_deserialize() {
+        let quality: ResampleQuality = serde_json::from_str("\"fast\"").unwrap();
+        assert_eq!(quality, ResampleQuality::Fast);
+        assert_eq!(ResampleQuality::default(), ResampleQuality::Balanced);
+        assert!(serde_json::from_str::<ResampleQuality>("\"ultra\"").is_err());
+    }
 }
@@ -0,0 +1,217 @@
+//! Short sound cues for eyes-free feedback.
+//!
+//! Plays a system sound when recording starts, when a dictation completes and
+//! when an error is reported. Sounds are played on a separate thread by the
+//! platform's command-line player, so a cue never delays the pipeline.
+//!
+//! Cues are gated by the `play_sounds` preference; `CueSettings` turns
+//! individual cues off. The same players replay the last recording
+//! (`play_wav`) for telling microphone problems from transcription errors.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Moments that can play a sound.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cue {
+    /// Recording started
+    Start,
+    /// Dictation processed and inserted
+    Success,
+    /// An error was reported to the user
+    Error,
+}
+
+/// Per-cue switches (all on by default).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CueSettings {
+    pub start: bool,
+    pub success: bool,
+    pub error: bool,
+}
+
+impl Default for CueSettings {
+    fn default() -> Self {
+        CueSettings {
+            start: true,
+            success: true,
+            error: true,
+        }
+    }
+}
+
+impl CueSettings {
+    pub fn is_enabled(&self, cue: Cue) -> bool {
+        match cue {
+            Cue::Start => self.start,
+            Cue::Success => self.success,
+            Cue::Error => self.error,
+        }
+    }
+}
+
+/// Sound file for a cue (built-in system sounds)
+#[cfg(target_os = "macos")]
+fn sound_path(cue: Cue) -> &'static str {
+    match cue {
+        Cue::Start => "/System/Library/Sounds/Tink.aiff",
+        Cue::Success => "/System/Library/Sounds/Glass.aiff",
+        Cue::Error => "/System/Library/Sounds/Basso.aiff",
+    }
+}
+
+/// Sound file for a cue (freedesktop sound theme)
+#[cfg(target_os = "linux")]
+fn sound_path(cue: Cue) -> &'static str {
+    match cue {
+        Cue::Start => "/usr/share/sounds/freedesktop/stereo/message.oga",
+        Cue::Success => "/usr/share/sounds/freedesktop/stereo/complete.oga",
+        Cue::Error => "/usr/share/sounds/freedesktop/stereo/dialog-error.oga",
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn player_command(cue: Cue) -> Option<Command> {
+    let mut command = Command::new("afplay");
+    command.arg(sound_path(cue));
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn player_command(cue: Cue) -> Option<Command> {
+    let mut command = Command::new("paplay");
+    command.arg(sound_path(cue));
+    Some(command)
+}
+
+#[cfg(target_os = "windows")]
+fn player_command(cue: Cue) -> Option<Command> {
+    let sound = match cue {
+        Cue::Start => "Asterisk",
+        Cue::Success => "Exclamation",
+        Cue::Error => "Hand",
+    };
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        &format!("[System.Media.SystemSounds]::{}.Play()", sound),
+    ]);
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn player_command(_cue: Cue) -> Option<Command> {
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn file_player_command(path: &Path) -> Option<Command> {
+    let mut command = Command::new("afplay");
+    command.arg(path);
+    Some(command)
+}
+
+#[cfg(target_os = "linux")]
+fn file_player_command(path: &Path) -> Option<Command> {
+    let mut command = Command::new("paplay");
+    command.arg(path);
+    Some(command)
+}
+
+#[cfg(target_os = "windows")]
+fn file_player_command(path: &Path) -> Option<Command> {
+    let path = path.to_string_lossy().replace('\'', "''");
+    let mut command = Command::new("powershell");
+    command.args([
+        "-NoProfile",
+        "-Command",
+        &format!(
+            "(New-Object System.Media.SoundPlayer '{}').PlaySync()",
+            path
+        ),
+    ]);
+    Some(command)
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn file_player_command(_path: &Path) -> Option<Command> {
+    None
+}
+
+/// Run a player on a background thread, then `done`. Failures are logged.
+fn spawn_player(mut command: Command, label: String, done: impl FnOnce() + Send + 'static) {
+    std::thread::spawn(move || {
+        let result = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .status();
+        match result {
+            Ok(status) if !status.success() => {
+                log::debug!("[SOUND] Player exited with {} for {}", status, label);
+            }
+            Err(e) => log::debug!("[SOUND] Failed to play {}: {}", label, e),
+            _ => {}
+        }
+        done();
+    });
+}
+
+/// Play a cue on a background thread. Failures are logged and otherwise ignored.
+pub fn play(cue: Cue) {
+    let Some(command) = player_command(cue) else {
+        return;
+    };
+    spawn_player(command, format!("{:?}", cue), || {});
+}
+
+/// Temp file for one playback (unique, so overlapping playbacks don't clash)
+fn playback_path() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("keyhold-playback-{}.wav", nanos))
+}
+
+/// Play WAV audio through the default output device on a background thread.
+/// The players need a file, so the audio is written to a temp file that is
+/// deleted as soon as playback ends.
+pub fn play_wav(wav: &[u8]) -> Result<(), String> {
+    let path = playback_path();
+    let command = file_player_command(&path)
+        .ok_or_else(|| "Playback is not supported on this platform".to_string())?;
+    std::fs::write(&path, wav).map_err(|e| format!("Failed to prepare playback: {}", e))?;
+
+    spawn_player(command, "recording".to_string(), move || {
+        if let Err(e) = std::fs::remove_file(&path) {
+            log::warn!("[SOUND] Failed to remove playback file: {}", e);
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cue_settings_default_enables_all() {
+        let settings = CueSettings::default();
+        assert!(settings.is_enabled(Cue::Start));
+        assert!(settings.is_enabled(Cue::Success));
+        assert!(settings.is_enabled(Cue::Error));
+    }
+
+    #[test]
+    fn test_cue_settings_partial_deserialization() {
+        let settings: CueSettings = serde_json::from_str(r#"{"start": false}"#).unwrap();
+        assert!(!settings.is_enabled(Cue::Start));
+        assert!(settings.is_enabled(Cue::Success));
+        assert!(settings.is_enabled(Cue::Error));
+    }
+}
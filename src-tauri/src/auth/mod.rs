@@ -235,6 +235,19 @@ pub async fn get_access_token() -> Result<String, AuthError> {
     Ok(tokens.access_token)
 }
 
+/// Error for proxied requests when the user has to sign in (again).
+/// Callers compare against it to bring up the login window.
+pub const LOGIN_REQUIRED_ERROR: &str = "Your session has expired. Sign in again to continue.";
+
+/// Access token to send with proxy requests, refreshed if expired.
+/// A missing or rejected session becomes `LOGIN_REQUIRED_ERROR`.
+pub async fn access_token_for_proxy() -> Result<String, String> {
+    get_access_token().await.map_err(|e| match e {
+        AuthError::NotAuthenticated | AuthError::TokenExpired => LOGIN_REQUIRED_ERROR.to_string(),
+        e => format!("Could not refresh session: {}", e),
+    })
+}
+
 /// Log out the current user.
 ///
 /// Clears all stored authentication data from the keychain.
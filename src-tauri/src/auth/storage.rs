@@ -19,6 +19,12 @@ struct AuthData {
     user: Option<UserInfo>,
     /// Remembered email for faster re-login (persists after sign-out)
     remembered_email: Option<String>,
+    /// User's own Groq API key, used when the proxy is disabled (persists after sign-out)
+    #[serde(default)]
+    groq_api_key: Option<String>,
+    /// User's own Deepgram API key, used when Deepgram is the transcription provider
+    #[serde(default)]
+    deepgram_api_key: Option<String>,
 }
 
 /// Get the auth data directory path.
@@ -180,3 +186,51 @@ pub fn clear_remembered_email() -> Result<(), AuthError> {
     log::info!("Cleared remembered email");
     Ok(())
 }
+
+/// Store the user's own Groq API key.
+pub fn store_groq_api_key(key: &str) -> Result<(), AuthError> {
+    let mut data = read_auth_data()?;
+    data.groq_api_key = Some(key.to_string());
+    write_auth_data(&data)?;
+    log::info!("Stored Groq API key");
+    Ok(())
+}
+
+/// Retrieve the user's own Groq API key.
+pub fn get_groq_api_key() -> Result<Option<String>, AuthError> {
+    let data = read_auth_data()?;
+    Ok(data.groq_api_key.filter(|k| !k.is_empty()))
+}
+
+/// Delete the user's own Groq API key.
+pub fn delete_groq_api_key() -> Result<(), AuthError> {
+    let mut data = read_auth_data()?;
+    data.groq_api_key = None;
+    write_auth_data(&data)?;
+    log::info!("Deleted Groq API key");
+    Ok(())
+}
+
+/// Store the user's Deepgram API key.
+pub fn store_deepgram_api_key(key: &str) -> Result<(), AuthError> {
+    let mut data = read_auth_data()?;
+    data.deepgram_api_key = Some(key.to_string());
+    write_auth_data(&data)?;
+    log::info!("Stored Deepgram API key");
+    Ok(())
+}
+
+/// Retrieve the user's Deepgram API key.
+pub fn get_deepgram_api_key() -> Result<Option<String>, AuthError> {
+    let data = read_auth_data()?;
+    Ok(data.deepgram_api_key.filter(|k| !k.is_empty()))
+}
+
+/// Delete the user's Deepgram API key.
+pub fn delete_deepgram_api_key() -> Result<(), AuthError> {
+    let mut data = read_auth_data()?;
+    data.deepgram_api_key = None;
+    write_auth_data(&data)?;
+    log::info!("Deleted Deepgram API key");
+    Ok(())
+}
@@ -11,6 +11,9 @@ use super::types::{
 
 const WORKOS_API_BASE: &str = "https://api.workos.com";
 
+/// Where WorkOS sends the browser after sign-in (handled as a deep link)
+pub const REDIRECT_URI: &str = "keyhold://auth/callback";
+
 /// WorkOS OAuth client
 pub struct WorkOsClient {
     client_id: String,
@@ -26,7 +29,7 @@ impl WorkOsClient {
 
         Ok(Self {
             client_id,
-            redirect_uri: "keyhold://auth/callback".to_string(),
+            redirect_uri: REDIRECT_URI.to_string(),
         })
     }
 
@@ -95,11 +98,14 @@ impl WorkOsClient {
             )));
         }
 
-        log::debug!("Token exchange response: {}", response_text);
-
         let auth_response: WorkOsAuthResponse =
             serde_json::from_str(&response_text).map_err(|e| {
-                log::error!("Failed to parse response: {} - Body: {}", e, response_text);
+                // The body holds the access and refresh tokens - never log it
+                log::error!(
+                    "Failed to parse token response: {} ({} bytes)",
+                    e,
+                    response_text.len()
+                );
                 AuthError::WorkOsError(format!("Failed to parse authentication response: {}", e))
             })?;
 
@@ -183,25 +189,47 @@ impl WorkOsClient {
 
 /// Parse an OAuth callback URL into its components.
 ///
-/// Expected format: `keyhold://auth/callback?code=xxx&state=yyy`
+/// Expected format: `keyhold://auth/callback?code=xxx&state=yyy`.
+/// If sign-in was cancelled or failed, WorkOS sends `error` (and usually
+/// `error_description`) instead of a code.
 pub fn parse_callback_url(url: &str) -> Result<OAuthCallback, AuthError> {
     let parsed = url::Url::parse(url).map_err(|e| {
         log::error!("Failed to parse callback URL: {} - {}", url, e);
         AuthError::InvalidCallback
     })?;
 
-    // Extract query parameters
+    if parsed.scheme() != "keyhold"
+        || parsed.host_str() != Some("auth")
+        || parsed.path() != "/callback"
+    {
+        log::error!("Not an OAuth callback URL: {}", url);
+        return Err(AuthError::InvalidCallback);
+    }
+
+    // Extract query parameters (empty values count as missing)
     let mut code = None;
     let mut state = None;
+    let mut error = None;
+    let mut error_description = None;
 
     for (key, value) in parsed.query_pairs() {
+        if value.is_empty() {
+            continue;
+        }
         match key.as_ref() {
             "code" => code = Some(value.to_string()),
             "state" => state = Some(value.to_string()),
+            "error" => error = Some(value.to_string()),
+            "error_description" => error_description = Some(value.to_string()),
             _ => {}
         }
     }
 
+    if let Some(error) = error {
+        log::error!("Sign-in failed: {} ({:?})", error, error_description);
+        return Err(AuthError::WorkOsError(error_description.unwrap_or(error)));
+    }
+
     let code = code.ok_or_else(|| {
         log::error!("Missing 'code' parameter in callback URL");
         AuthError::InvalidCallback
@@ -214,3 +242,55 @@ pub fn parse_callback_url(url: &str) -> Result<OAuthCallback, AuthError> {
 
     Ok(OAuthCallback { code, state })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_callback_url() {
+        let callback = parse_callback_url("keyhold://auth/callback?code=abc&state=xyz").unwrap();
+        assert_eq!(callback.code, "abc");
+        assert_eq!(callback.state, "xyz");
+
+        // Percent-encoded values are decoded
+        let callback =
+            parse_callback_url("keyhold://auth/callback?state=a%2Bb&code=01H%3D").unwrap();
+        assert_eq!(callback.code, "01H=");
+        assert_eq!(callback.state, "a+b");
+    }
+
+    #[test]
+    fn test_parse_callback_url_missing_params() {
+        for url in [
+            "keyhold://auth/callback",
+            "keyhold://auth/callback?state=xyz",
+            "keyhold://auth/callback?code=abc",
+            "keyhold://auth/callback?code=&state=xyz",
+            "not a url",
+        ] {
+            assert!(
+                matches!(parse_callback_url(url), Err(AuthError::InvalidCallback)),
+                "{}",
+                url
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_callback_url_wrong_target() {
+        assert!(parse_callback_url("keyhold://auth/logout?code=abc&state=xyz").is_err());
+        assert!(parse_callback_url("https://auth/callback?code=abc&state=xyz").is_err());
+    }
+
+    #[test]
+    fn test_parse_callback_url_error() {
+        let result = parse_callback_url(
+            "keyhold://auth/callback?error=access_denied&error_description=User%20cancelled&state=xyz",
+        );
+        match result {
+            Err(AuthError::WorkOsError(message)) => assert_eq!(message, "User cancelled"),
+            other => panic!("expected a WorkOS error, got {:?}", other),
+        }
+    }
+}
@@ -1,9 +1,146 @@
 use dotenv::dotenv;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::env;
 use std::fs;
 use std::path::PathBuf;
 
+/// Default maximum selection size for Command Mode transforms (characters).
+/// Keeps the transformed output within the LLM's max_tokens budget.
+pub const DEFAULT_MAX_SELECTION_CHARS: usize = 10_000;
+
+/// Upper bound for the pre-insert delay so a typo can't make insertion feel broken (ms)
+pub const MAX_PRE_INSERT_DELAY_MS: u64 = 2000;
+
+/// Level below which audio counts as silence for auto-stop (dBFS)
+pub const DEFAULT_SILENCE_THRESHOLD_DB: f32 = -45.0;
+
+/// Allowed range for the auto-stop trailing-silence window (ms)
+pub const MIN_SILENCE_TIMEOUT_MS: u32 = 500;
+pub const MAX_SILENCE_TIMEOUT_MS: u32 = 30_000;
+
+/// Upper bound for the pre-roll buffer length (ms)
+pub const MAX_PREROLL_MS: u32 = 1000;
+
+/// Default and allowed range for the hard recording length limit (seconds)
+pub const DEFAULT_MAX_RECORDING_DURATION_SECS: u32 = 1800;
+pub const MIN_MAX_RECORDING_DURATION_SECS: u32 = 10;
+pub const MAX_MAX_RECORDING_DURATION_SECS: u32 = 7200;
+
+/// Allowed range for the per-request network timeout override (ms)
+pub const MIN_NETWORK_TIMEOUT_MS: u64 = 5_000;
+pub const MAX_NETWORK_TIMEOUT_MS: u64 = 300_000;
+
+/// How long "Done!" stays on the overlay before it hides (ms)
+pub const DEFAULT_DONE_DISPLAY_MS: u64 = 100;
+
+/// How long to wait for the target app to regain focus after the overlay
+/// hides, before typing (ms)
+pub const DEFAULT_APP_FOCUS_WAIT_MS: u64 = 30;
+
+/// Upper bound for the done-display and focus-wait delays (ms)
+pub const MAX_OVERLAY_DELAY_MS: u64 = 2000;
+
+/// Lowest noise floor the overlay level meter can be calibrated to (dBFS)
+pub const MIN_METER_DB: f32 = -100.0;
+
+/// Allowed range for the input gain applied before transcription (dB)
+pub const MIN_INPUT_GAIN_DB: f32 = -20.0;
+pub const MAX_INPUT_GAIN_DB: f32 = 30.0;
+
+/// Service that turns recorded audio into text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TranscriptionProvider {
+    /// Groq Whisper, through the proxy or with the user's own Groq key
+    #[default]
+    Groq,
+    /// Deepgram with the user's own Deepgram key
+    Deepgram,
+}
+
+/// How Command Mode (speech with text selected) decides what to do.
+/// The explicit modes skip the intent-classification LLM call, saving one
+/// round trip (typically a few hundred ms) per Command Mode dictation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandModeBehavior {
+    /// Ask the LLM whether the speech is a command or new content
+    #[default]
+    Auto,
+    /// Always treat the speech as an instruction for the selected text
+    AlwaysTransform,
+    /// Always replace the selection with the (enhanced) dictation
+    AlwaysReplace,
+}
+
+/// What happens with the finished text of a dictation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    /// Type/paste it into the active app
+    #[default]
+    Insert,
+    /// Only copy it to the clipboard (for apps that block synthetic keystrokes)
+    Clipboard,
+    /// Copy it to the clipboard and insert it
+    Both,
+}
+
+impl OutputMode {
+    pub fn copies(self) -> bool {
+        matches!(self, OutputMode::Clipboard | OutputMode::Both)
+    }
+
+    pub fn inserts(self) -> bool {
+        matches!(self, OutputMode::Insert | OutputMode::Both)
+    }
+}
+
+/// What to do with a dictation when LLM enhancement fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EnhancementFallback {
+    /// Insert the raw transcript instead
+    #[default]
+    InsertRaw,
+    /// Insert nothing
+    Cancel,
+    /// Insert nothing, but copy the raw transcript to the clipboard
+    CopyRawToClipboard,
+}
+
+/// What to do with a transcription below the confidence threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LowConfidenceAction {
+    /// Insert it and warn that it may need review
+    #[default]
+    Flag,
+    /// Don't insert it
+    Block,
+}
+
+/// LLM system prompt that users can customize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptTemplate {
+    /// Dictation Mode cleanup
+    Enhance,
+    /// Command Mode transformations
+    Transform,
+}
+
+impl PromptTemplate {
+    /// The built-in prompt
+    pub fn default_prompt(self) -> &'static str {
+        match self {
+            PromptTemplate::Enhance => crate::groq_llm::ENHANCE_SYSTEM_PROMPT,
+            PromptTemplate::Transform => crate::groq_llm::TRANSFORM_SYSTEM_PROMPT,
+        }
+    }
+}
+
 /// Stored preferences that persist to disk.
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct StoredPreferences {
@@ -16,6 +153,101 @@ pub struct StoredPreferences {
     pub language: Option<String>,
     pub onboarding_complete: Option<bool>,
     pub spoken_languages: Option<Vec<String>>,
+    // User find→replace list applied right before insertion
+    pub replacements: Option<Vec<crate::replacements::ReplacementRule>>,
+    // User additions to the IDE programming dictionary (spoken → written)
+    pub custom_dictionary: Option<Vec<(String, String)>>,
+    pub verbose_timing: Option<bool>,
+    pub max_selection_chars: Option<usize>,
+    // Per-app language overrides (bundle_id → language code)
+    pub app_languages: Option<HashMap<String, String>>,
+    // Delay before typing starts, globally and per app (bundle_id → ms)
+    pub pre_insert_delay_ms: Option<u64>,
+    pub app_pre_insert_delays: Option<HashMap<String, u64>>,
+    // Route requests through the hosted proxy (false = direct API with own key)
+    pub use_proxy: Option<bool>,
+    // Low-confidence handling: threshold (0.0-1.0) and whether to flag or block
+    pub min_confidence: Option<f32>,
+    pub low_confidence_action: Option<LowConfidenceAction>,
+    // Dedicated hotkey that captures to the scratch buffer instead of typing
+    pub scratch_hotkey: Option<String>,
+    // Auto-stop after this much trailing silence (ms) and the level that counts as silence (dBFS)
+    pub silence_timeout_ms: Option<u32>,
+    pub silence_threshold_db: Option<f32>,
+    // Audio kept from just before recording starts (ms, 0 = off)
+    pub preroll_ms: Option<u32>,
+    // Resampler speed/fidelity trade-off ("fast", "balanced", "high")
+    pub resample_quality: Option<crate::audio::ResampleQuality>,
+    // LLM enhancement of dictations (false = raw mode, insert the transcript verbatim)
+    pub enhancement_enabled: Option<bool>,
+    // Groq chat model for enhancement, transforms and intent classification
+    pub groq_model: Option<String>,
+    // Line breaks in inserted text ("collapse_to_space", "preserve_newlines", "smart")
+    pub newline_mode: Option<crate::insertion::NewlineMode>,
+    // Typing vs pasting ("auto", "keystroke", "clipboard_paste")
+    pub insertion_method: Option<crate::insertion::InsertionMethod>,
+    // Keep a local history of dictated text (opt-in, text only)
+    pub history_enabled: Option<bool>,
+    // Hotkey that types the most recent history entry again
+    pub reinsert_hotkey: Option<String>,
+    // Per-app style overrides (bundle_id → style id), checked before the built-in mappings
+    pub app_styles: Option<HashMap<String, String>>,
+    // Workspace file index extensions and per-root file limit
+    pub index_config: Option<crate::ide::file_index::IndexConfig>,
+    // Transcription service ("groq" or "deepgram") and the Deepgram model to use
+    pub transcription_provider: Option<TranscriptionProvider>,
+    pub deepgram_model: Option<String>,
+    // Recordings are stopped and processed after this many seconds
+    pub max_recording_duration: Option<u32>,
+    // Individual sound cues (all gated by play_sounds)
+    pub audio_cues: Option<crate::audio_cues::CueSettings>,
+    // Command Mode: classify intent, or always transform / always replace
+    pub command_mode_behavior: Option<CommandModeBehavior>,
+    // Insert the result, copy it to the clipboard, or both
+    pub output_mode: Option<OutputMode>,
+    // Second recording hotkey that dictates in raw mode (no LLM enhancement)
+    pub raw_hotkey: Option<String>,
+    // Names and jargon appended to the Whisper prompt, globally and per app (bundle_id → text)
+    pub vocabulary_prompt: Option<String>,
+    pub app_vocabulary_prompts: Option<HashMap<String, String>>,
+    // Suggest adding a language that mixed mode keeps detecting
+    pub adaptive_language: Option<bool>,
+    // Mixed-mode script ("romanized", "native_script", "auto")
+    pub mixed_mode_output: Option<crate::whisper_api::MixedModeOutput>,
+    // Timeout for each transcription/LLM request (ms)
+    pub network_timeout_ms: Option<u64>,
+    // Insert the raw transcript, insert nothing, or copy it when enhancement fails
+    pub fallback_on_enhancement_failure: Option<EnhancementFallback>,
+    // Variable case style per editor language (language ID → style)
+    pub case_style_by_language: Option<HashMap<String, crate::ide::variable::CaseStyle>>,
+    // Bundle IDs that always get the neutral style instead of an inferred one
+    pub style_inference_excluded: Option<Vec<String>>,
+    // Custom LLM system prompts (None = built-in prompt)
+    pub enhance_prompt: Option<String>,
+    pub transform_prompt: Option<String>,
+    // Spelled-out numbers vs digits ("unchanged", "digits", "words", "auto")
+    pub number_format: Option<crate::text_normalize::NumberFormat>,
+    // Keep the last recording (in memory) until the next one, for playback
+    pub keep_last_recording: Option<bool>,
+    // Collapse runs of spaces in inserted text (false keeps tables/code spacing)
+    pub collapse_whitespace: Option<bool>,
+    // Heuristic handling of transcripts with secrets/card numbers ("off", "mask", "skip_enhancement")
+    pub redact_sensitive: Option<crate::redact::RedactMode>,
+    // Bundle IDs where selection detection is skipped (always Dictation Mode)
+    pub command_mode_excluded: Option<Vec<String>>,
+    // "Done!" display time and app refocus wait after stopping (ms)
+    pub done_display_ms: Option<u64>,
+    pub app_focus_wait_ms: Option<u64>,
+    // Languages ("hi", "te", "mixed") whose dictations skip LLM enhancement
+    pub enhancement_disabled_languages: Option<Vec<String>>,
+    // Which input channel to record on multi-channel interfaces ("mix" or an index)
+    pub mono_channel: Option<crate::audio::MonoChannel>,
+    // Overlay level meter calibration (dBFS shown as empty / full) and peak hold
+    pub meter_min_db: Option<f32>,
+    pub meter_max_db: Option<f32>,
+    pub meter_peak_hold: Option<bool>,
+    // Gain applied to recordings before transcription (dB)
+    pub input_gain_db: Option<f32>,
 }
 
 impl StoredPreferences {
@@ -33,7 +265,7 @@ impl StoredPreferences {
             if path.exists() {
                 if let Ok(content) = fs::read_to_string(&path) {
                     if let Ok(prefs) = serde_json::from_str(&content) {
-                        println!("Loaded preferences from {:?}", path);
+                        log::info!("Loaded preferences from {:?}", path);
                         return prefs;
                     }
                 }
@@ -56,7 +288,7 @@ impl StoredPreferences {
 
         fs::write(&path, content).map_err(|e| format!("Failed to write preferences: {}", e))?;
 
-        println!("Saved preferences to {:?}", path);
+        log::info!("Saved preferences to {:?}", path);
         Ok(())
     }
 }
@@ -76,6 +308,77 @@ pub struct AppConfig {
     pub play_sounds: bool,
     pub microphone: String,
     pub language: String,
+    // Diagnostics
+    pub verbose_timing: bool,
+    // Command Mode
+    pub max_selection_chars: usize,
+    // Per-app language overrides (bundle_id → language code)
+    pub app_languages: HashMap<String, String>,
+    // Insertion
+    pub pre_insert_delay_ms: u64,
+    pub app_pre_insert_delays: HashMap<String, u64>,
+    // Autocorrect rules (edited through the replacement commands, not the form)
+    pub replacements: Vec<crate::replacements::ReplacementRule>,
+    // Privacy
+    pub use_proxy: bool,
+    // Transcription confidence (None = no threshold)
+    pub min_confidence: Option<f32>,
+    pub low_confidence_action: LowConfidenceAction,
+    // Scratch buffer hotkey (takes effect on restart)
+    pub scratch_hotkey: Option<String>,
+    // Voice-activity auto-stop (None = disabled)
+    pub silence_timeout_ms: Option<u32>,
+    pub silence_threshold_db: f32,
+    // Pre-roll buffer length (0 = off, takes effect on restart)
+    pub preroll_ms: u32,
+    pub resample_quality: crate::audio::ResampleQuality,
+    pub enhancement_enabled: bool,
+    pub groq_model: String,
+    // Line break handling for inserted text
+    pub newline_mode: crate::insertion::NewlineMode,
+    pub insertion_method: crate::insertion::InsertionMethod,
+    pub history_enabled: bool,
+    // Re-insert hotkey (takes effect on restart)
+    pub reinsert_hotkey: Option<String>,
+    // Per-app style overrides (bundle_id → style id)
+    pub app_styles: HashMap<String, String>,
+    // Workspace file indexing (applies to roots added or rebuilt afterwards)
+    pub index_config: crate::ide::file_index::IndexConfig,
+    // Transcription
+    pub transcription_provider: TranscriptionProvider,
+    pub deepgram_model: String,
+    // Which sound cues play when play_sounds is on
+    pub audio_cues: crate::audio_cues::CueSettings,
+    pub command_mode_behavior: CommandModeBehavior,
+    pub output_mode: OutputMode,
+    // Raw-mode hotkey (re-registered live by `update_preferences`)
+    pub raw_hotkey: Option<String>,
+    // Whisper vocabulary prompt (None = off) and per-app additions (bundle_id → text)
+    pub vocabulary_prompt: Option<String>,
+    pub app_vocabulary_prompts: HashMap<String, String>,
+    pub adaptive_language: bool,
+    pub mixed_mode_output: crate::whisper_api::MixedModeOutput,
+    // Per-request network timeout (None = client defaults)
+    pub network_timeout_ms: Option<u64>,
+    pub fallback_on_enhancement_failure: EnhancementFallback,
+    pub case_style_by_language: HashMap<String, crate::ide::variable::CaseStyle>,
+    pub style_inference_excluded: Vec<String>,
+    // Custom LLM system prompts (None = built-in prompt)
+    pub enhance_prompt: Option<String>,
+    pub transform_prompt: Option<String>,
+    pub number_format: crate::text_normalize::NumberFormat,
+    pub keep_last_recording: bool,
+    pub collapse_whitespace: bool,
+    pub redact_sensitive: crate::redact::RedactMode,
+    pub command_mode_excluded: Vec<String>,
+    pub done_display_ms: u64,
+    pub app_focus_wait_ms: u64,
+    pub enhancement_disabled_languages: Vec<String>,
+    pub mono_channel: crate::audio::MonoChannel,
+    pub meter_min_db: f32,
+    pub meter_max_db: f32,
+    pub meter_peak_hold: bool,
+    pub input_gain_db: f32,
 }
 
 impl AppConfig {
@@ -94,8 +397,11 @@ impl AppConfig {
         }
 
         // Load stored preferences from file
-        let stored = StoredPreferences::load();
+        Self::from_stored(StoredPreferences::load())
+    }
 
+    /// Build the config from stored preferences, with defaults for unset values
+    pub(crate) fn from_stored(stored: StoredPreferences) -> Self {
         // Groq API key from environment variable only
         let groq_api_key = env::var("GROQ_API_KEY").ok().filter(|s| !s.is_empty());
 
@@ -105,10 +411,12 @@ impl AppConfig {
                 env::var("DEFAULT_RECORDING_MODE").unwrap_or_else(|_| "push-to-talk".to_string())
             }),
             hotkey: stored.hotkey.unwrap_or_else(|| "Option+Space".to_string()),
-            max_recording_duration: env::var("MAX_RECORDING_DURATION")
-                .unwrap_or_else(|_| "1800".to_string())
-                .parse()
-                .unwrap_or(1800),
+            max_recording_duration: stored.max_recording_duration.unwrap_or_else(|| {
+                env::var("MAX_RECORDING_DURATION")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(DEFAULT_MAX_RECORDING_DURATION_SECS)
+            }),
             audio_sample_rate: env::var("AUDIO_SAMPLE_RATE")
                 .unwrap_or_else(|_| "16000".to_string())
                 .parse()
@@ -121,10 +429,226 @@ impl AppConfig {
             play_sounds: stored.play_sounds.unwrap_or(true),
             microphone: stored.microphone.unwrap_or_else(|| "default".to_string()),
             language: stored.language.unwrap_or_else(|| "en-US".to_string()),
+            verbose_timing: stored.verbose_timing.unwrap_or(false),
+            max_selection_chars: stored
+                .max_selection_chars
+                .unwrap_or(DEFAULT_MAX_SELECTION_CHARS),
+            app_languages: stored.app_languages.unwrap_or_default(),
+            pre_insert_delay_ms: stored.pre_insert_delay_ms.unwrap_or(0),
+            app_pre_insert_delays: stored.app_pre_insert_delays.unwrap_or_default(),
+            replacements: stored.replacements.unwrap_or_default(),
+            use_proxy: stored.use_proxy.unwrap_or(true),
+            min_confidence: stored.min_confidence,
+            low_confidence_action: stored.low_confidence_action.unwrap_or_default(),
+            scratch_hotkey: stored.scratch_hotkey.filter(|h| !h.is_empty()),
+            silence_timeout_ms: stored.silence_timeout_ms,
+            silence_threshold_db: stored
+                .silence_threshold_db
+                .unwrap_or(DEFAULT_SILENCE_THRESHOLD_DB),
+            preroll_ms: stored.preroll_ms.unwrap_or(0),
+            resample_quality: stored.resample_quality.unwrap_or_default(),
+            enhancement_enabled: stored.enhancement_enabled.unwrap_or(true),
+            groq_model: stored
+                .groq_model
+                .as_deref()
+                .map(|model| crate::groq_llm::resolve_model(model).to_string())
+                .unwrap_or_else(|| crate::groq_llm::DEFAULT_GROQ_MODEL.to_string()),
+            newline_mode: stored.newline_mode.unwrap_or_default(),
+            insertion_method: stored.insertion_method.unwrap_or_default(),
+            history_enabled: stored.history_enabled.unwrap_or(false),
+            reinsert_hotkey: stored.reinsert_hotkey.filter(|h| !h.is_empty()),
+            app_styles: stored.app_styles.unwrap_or_default(),
+            index_config: stored.index_config.unwrap_or_default(),
+            transcription_provider: stored.transcription_provider.unwrap_or_default(),
+            deepgram_model: stored
+                .deepgram_model
+                .filter(|m| !m.trim().is_empty())
+                .unwrap_or_else(|| crate::deepgram::DEFAULT_DEEPGRAM_MODEL.to_string()),
+            audio_cues: stored.audio_cues.unwrap_or_default(),
+            command_mode_behavior: stored.command_mode_behavior.unwrap_or_default(),
+            output_mode: stored.output_mode.unwrap_or_default(),
+            raw_hotkey: stored.raw_hotkey.filter(|h| !h.is_empty()),
+            vocabulary_prompt: stored.vocabulary_prompt.filter(|p| !p.trim().is_empty()),
+            app_vocabulary_prompts: stored.app_vocabulary_prompts.unwrap_or_default(),
+            adaptive_language: stored.adaptive_language.unwrap_or(false),
+            mixed_mode_output: stored.mixed_mode_output.unwrap_or_default(),
+            network_timeout_ms: stored.network_timeout_ms,
+            fallback_on_enhancement_failure: stored
+                .fallback_on_enhancement_failure
+                .unwrap_or_default(),
+            case_style_by_language: stored.case_style_by_language.unwrap_or_default(),
+            style_inference_excluded: stored.style_inference_excluded.unwrap_or_default(),
+            enhance_prompt: stored.enhance_prompt.filter(|p| !p.trim().is_empty()),
+            transform_prompt: stored.transform_prompt.filter(|p| !p.trim().is_empty()),
+            number_format: stored.number_format.unwrap_or_default(),
+            keep_last_recording: stored.keep_last_recording.unwrap_or(false),
+            collapse_whitespace: stored.collapse_whitespace.unwrap_or(true),
+            redact_sensitive: stored.redact_sensitive.unwrap_or_default(),
+            command_mode_excluded: stored.command_mode_excluded.unwrap_or_default(),
+            done_display_ms: stored.done_display_ms.unwrap_or(DEFAULT_DONE_DISPLAY_MS),
+            app_focus_wait_ms: stored
+                .app_focus_wait_ms
+                .unwrap_or(DEFAULT_APP_FOCUS_WAIT_MS),
+            enhancement_disabled_languages: stored
+                .enhancement_disabled_languages
+                .unwrap_or_default(),
+            mono_channel: stored.mono_channel.unwrap_or_default(),
+            meter_min_db: stored.meter_min_db.unwrap_or(crate::audio::LEVEL_MIN_DB),
+            meter_max_db: stored.meter_max_db.unwrap_or(crate::audio::LEVEL_MAX_DB),
+            meter_peak_hold: stored.meter_peak_hold.unwrap_or(false),
+            input_gain_db: stored.input_gain_db.unwrap_or(0.0),
         }
     }
 
     pub fn update_from_preferences(&mut self, prefs: Preferences) -> Result<(), String> {
+        if prefs.max_selection_chars == Some(0) {
+            return Err("Maximum selection size must be greater than zero".to_string());
+        }
+        if let Some(ref app_languages) = prefs.app_languages {
+            for (bundle_id, language) in app_languages {
+                if !crate::whisper_api::is_supported_language(language) {
+                    return Err(format!(
+                        "Unsupported language '{}' for {}",
+                        language, bundle_id
+                    ));
+                }
+            }
+        }
+        if let Some(min_confidence) = prefs.min_confidence {
+            if !(0.0..=1.0).contains(&min_confidence) {
+                return Err("Minimum confidence must be between 0 and 1".to_string());
+            }
+        }
+        if let Some(ref languages) = prefs.enhancement_disabled_languages {
+            if let Some(language) = languages
+                .iter()
+                .find(|l| !crate::whisper_api::is_supported_language(l.trim()))
+            {
+                return Err(format!("Unsupported language '{}'", language));
+            }
+        }
+        let delays = prefs.pre_insert_delay_ms.into_iter().chain(
+            prefs
+                .app_pre_insert_delays
+                .iter()
+                .flat_map(|m| m.values().copied()),
+        );
+        for delay in delays {
+            if delay > MAX_PRE_INSERT_DELAY_MS {
+                return Err(format!(
+                    "Pre-insert delay must be at most {}ms",
+                    MAX_PRE_INSERT_DELAY_MS
+                ));
+            }
+        }
+        let overlay_delays = prefs
+            .done_display_ms
+            .into_iter()
+            .chain(prefs.app_focus_wait_ms);
+        for delay in overlay_delays {
+            if delay > MAX_OVERLAY_DELAY_MS {
+                return Err(format!(
+                    "Overlay delays must be at most {}ms",
+                    MAX_OVERLAY_DELAY_MS
+                ));
+            }
+        }
+        if prefs.meter_min_db.is_some() || prefs.meter_max_db.is_some() {
+            let min_db = prefs.meter_min_db.unwrap_or(self.meter_min_db);
+            let max_db = prefs.meter_max_db.unwrap_or(self.meter_max_db);
+            if !(MIN_METER_DB..=0.0).contains(&min_db) || !(MIN_METER_DB..=0.0).contains(&max_db) {
+                return Err(format!(
+                    "Meter range must be between {} and 0 dB",
+                    MIN_METER_DB
+                ));
+            }
+            if min_db >= max_db {
+                return Err("Meter floor must be below the meter ceiling".to_string());
+            }
+        }
+        if let Some(gain) = prefs.input_gain_db {
+            if !(MIN_INPUT_GAIN_DB..=MAX_INPUT_GAIN_DB).contains(&gain) {
+                return Err(format!(
+                    "Input gain must be between {} and {} dB",
+                    MIN_INPUT_GAIN_DB, MAX_INPUT_GAIN_DB
+                ));
+            }
+        }
+        if let Some(timeout) = prefs.silence_timeout_ms.filter(|t| *t > 0) {
+            if !(MIN_SILENCE_TIMEOUT_MS..=MAX_SILENCE_TIMEOUT_MS).contains(&timeout) {
+                return Err(format!(
+                    "Silence timeout must be between {}ms and {}ms",
+                    MIN_SILENCE_TIMEOUT_MS, MAX_SILENCE_TIMEOUT_MS
+                ));
+            }
+        }
+        if let Some(threshold) = prefs.silence_threshold_db {
+            if !(-80.0..=0.0).contains(&threshold) {
+                return Err("Silence threshold must be between -80 and 0 dB".to_string());
+            }
+        }
+        if prefs.preroll_ms.is_some_and(|ms| ms > MAX_PREROLL_MS) {
+            return Err(format!("Pre-roll must be at most {}ms", MAX_PREROLL_MS));
+        }
+        if let Some(ref model) = prefs.groq_model {
+            if !crate::groq_llm::is_known_model(model) {
+                return Err(format!("Unsupported Groq model: {}", model));
+            }
+        }
+        if let Some(ref app_styles) = prefs.app_styles {
+            for (bundle_id, style_id) in app_styles {
+                if crate::styles::get_style_by_id(style_id).is_none() {
+                    return Err(format!("Unknown style '{}' for {}", style_id, bundle_id));
+                }
+            }
+        }
+
+        if let Some(ref index_config) = prefs.index_config {
+            index_config.validate()?;
+        }
+        if let Some(secs) = prefs.max_recording_duration {
+            if !(MIN_MAX_RECORDING_DURATION_SECS..=MAX_MAX_RECORDING_DURATION_SECS).contains(&secs)
+            {
+                return Err(format!(
+                    "Maximum recording duration must be between {} and {} seconds",
+                    MIN_MAX_RECORDING_DURATION_SECS, MAX_MAX_RECORDING_DURATION_SECS
+                ));
+            }
+        }
+        if prefs
+            .deepgram_model
+            .as_ref()
+            .is_some_and(|m| m.trim().is_empty())
+        {
+            return Err("Deepgram model cannot be empty".to_string());
+        }
+        if let Some(timeout) = prefs.network_timeout_ms.filter(|t| *t > 0) {
+            if !(MIN_NETWORK_TIMEOUT_MS..=MAX_NETWORK_TIMEOUT_MS).contains(&timeout) {
+                return Err(format!(
+                    "Network timeout must be between {}ms and {}ms",
+                    MIN_NETWORK_TIMEOUT_MS, MAX_NETWORK_TIMEOUT_MS
+                ));
+            }
+        }
+        let vocabulary_prompts = prefs
+            .vocabulary_prompt
+            .iter()
+            .chain(prefs.app_vocabulary_prompts.iter().flat_map(|m| m.values()));
+        for prompt in vocabulary_prompts {
+            if prompt.trim().chars().count() > crate::whisper_api::MAX_VOCABULARY_PROMPT_CHARS {
+                return Err(format!(
+                    "Vocabulary prompt must be at most {} characters",
+                    crate::whisper_api::MAX_VOCABULARY_PROMPT_CHARS
+                ));
+            }
+        }
+        if let Some(ref prompt) = prefs.enhance_prompt {
+            crate::groq_llm::validate_prompt_template("Enhancement", prompt)?;
+        }
+        if let Some(ref prompt) = prefs.transform_prompt {
+            crate::groq_llm::validate_prompt_template("Transform", prompt)?;
+        }
+
         // Update in-memory config
         self.recording_mode = prefs.recording_mode.clone();
         self.hotkey = prefs.hotkey.clone();
@@ -133,19 +657,383 @@ impl AppConfig {
         self.microphone = prefs.microphone.clone();
         self.language = prefs.language.clone();
 
-        // Persist non-sensitive preferences to disk
-        let stored = StoredPreferences {
-            recording_mode: Some(prefs.recording_mode),
-            hotkey: Some(prefs.hotkey),
-            show_indicator: Some(prefs.show_indicator),
-            play_sounds: Some(prefs.play_sounds),
-            microphone: Some(prefs.microphone),
-            language: Some(prefs.language),
-            onboarding_complete: prefs.onboarding_complete,
-            spoken_languages: prefs.spoken_languages,
-        };
+        // Persist non-sensitive preferences to disk, keeping fields that are
+        // managed outside the preferences form (e.g. replacements)
+        let mut stored = StoredPreferences::load();
+        self.replacements = stored.replacements.clone().unwrap_or_default();
+        stored.recording_mode = Some(prefs.recording_mode);
+        stored.hotkey = Some(prefs.hotkey);
+        stored.show_indicator = Some(prefs.show_indicator);
+        stored.play_sounds = Some(prefs.play_sounds);
+        stored.microphone = Some(prefs.microphone);
+        stored.language = Some(prefs.language);
+        stored.onboarding_complete = prefs.onboarding_complete;
+        stored.spoken_languages = prefs.spoken_languages;
+        if let Some(verbose_timing) = prefs.verbose_timing {
+            self.verbose_timing = verbose_timing;
+            stored.verbose_timing = Some(verbose_timing);
+        }
+        if let Some(max_selection_chars) = prefs.max_selection_chars {
+            self.max_selection_chars = max_selection_chars;
+            stored.max_selection_chars = Some(max_selection_chars);
+        }
+        if let Some(app_languages) = prefs.app_languages {
+            self.app_languages = app_languages.clone();
+            stored.app_languages = Some(app_languages);
+        }
+        if let Some(pre_insert_delay_ms) = prefs.pre_insert_delay_ms {
+            self.pre_insert_delay_ms = pre_insert_delay_ms;
+            stored.pre_insert_delay_ms = Some(pre_insert_delay_ms);
+        }
+        if let Some(app_pre_insert_delays) = prefs.app_pre_insert_delays {
+            self.app_pre_insert_delays = app_pre_insert_delays.clone();
+            stored.app_pre_insert_delays = Some(app_pre_insert_delays);
+        }
+        if let Some(use_proxy) = prefs.use_proxy {
+            self.use_proxy = use_proxy;
+            stored.use_proxy = Some(use_proxy);
+        }
+        if let Some(min_confidence) = prefs.min_confidence {
+            // 0 disables the threshold
+            let threshold = (min_confidence > 0.0).then_some(min_confidence);
+            self.min_confidence = threshold;
+            stored.min_confidence = threshold;
+        }
+        if let Some(action) = prefs.low_confidence_action {
+            self.low_confidence_action = action;
+            stored.low_confidence_action = Some(action);
+        }
+        if let Some(scratch_hotkey) = prefs.scratch_hotkey {
+            // Empty string clears the scratch hotkey
+            let scratch_hotkey = (!scratch_hotkey.is_empty()).then_some(scratch_hotkey);
+            self.scratch_hotkey = scratch_hotkey.clone();
+            stored.scratch_hotkey = scratch_hotkey;
+        }
+        if let Some(silence_timeout_ms) = prefs.silence_timeout_ms {
+            // 0 disables auto-stop
+            let timeout = (silence_timeout_ms > 0).then_some(silence_timeout_ms);
+            self.silence_timeout_ms = timeout;
+            stored.silence_timeout_ms = timeout;
+        }
+        if let Some(silence_threshold_db) = prefs.silence_threshold_db {
+            self.silence_threshold_db = silence_threshold_db;
+            stored.silence_threshold_db = Some(silence_threshold_db);
+        }
+        if let Some(preroll_ms) = prefs.preroll_ms {
+            self.preroll_ms = preroll_ms;
+            stored.preroll_ms = Some(preroll_ms);
+        }
+        if let Some(resample_quality) = prefs.resample_quality {
+            self.resample_quality = resample_quality;
+            stored.resample_quality = Some(resample_quality);
+        }
+        if let Some(enhancement_enabled) = prefs.enhancement_enabled {
+            self.enhancement_enabled = enhancement_enabled;
+            stored.enhancement_enabled = Some(enhancement_enabled);
+        }
+        if let Some(groq_model) = prefs.groq_model {
+            let groq_model = groq_model.trim().to_string();
+            self.groq_model = groq_model.clone();
+            stored.groq_model = Some(groq_model);
+        }
+        if let Some(newline_mode) = prefs.newline_mode {
+            self.newline_mode = newline_mode;
+            stored.newline_mode = Some(newline_mode);
+        }
+        if let Some(insertion_method) = prefs.insertion_method {
+            self.insertion_method = insertion_method;
+            stored.insertion_method = Some(insertion_method);
+        }
+        if let Some(history_enabled) = prefs.history_enabled {
+            self.history_enabled = history_enabled;
+            stored.history_enabled = Some(history_enabled);
+        }
+        if let Some(reinsert_hotkey) = prefs.reinsert_hotkey {
+            // Empty string clears the re-insert hotkey
+            let reinsert_hotkey = (!reinsert_hotkey.is_empty()).then_some(reinsert_hotkey);
+            self.reinsert_hotkey = reinsert_hotkey.clone();
+            stored.reinsert_hotkey = reinsert_hotkey;
+        }
+        if let Some(app_styles) = prefs.app_styles {
+            self.app_styles = app_styles.clone();
+            stored.app_styles = Some(app_styles);
+        }
+        if let Some(index_config) = prefs.index_config {
+            self.index_config = index_config.clone();
+            stored.index_config = Some(index_config);
+        }
+        if let Some(transcription_provider) = prefs.transcription_provider {
+            self.transcription_provider = transcription_provider;
+            stored.transcription_provider = Some(transcription_provider);
+        }
+        if let Some(deepgram_model) = prefs.deepgram_model {
+            let deepgram_model = deepgram_model.trim().to_string();
+            self.deepgram_model = deepgram_model.clone();
+            stored.deepgram_model = Some(deepgram_model);
+        }
+        if let Some(max_recording_duration) = prefs.max_recording_duration {
+            self.max_recording_duration = max_recording_duration;
+            stored.max_recording_duration = Some(max_recording_duration);
+        }
+        if let Some(audio_cues) = prefs.audio_cues {
+            self.audio_cues = audio_cues.clone();
+            stored.audio_cues = Some(audio_cues);
+        }
+        if let Some(command_mode_behavior) = prefs.command_mode_behavior {
+            self.command_mode_behavior = command_mode_behavior;
+            stored.command_mode_behavior = Some(command_mode_behavior);
+        }
+        if let Some(output_mode) = prefs.output_mode {
+            self.output_mode = output_mode;
+            stored.output_mode = Some(output_mode);
+        }
+        if let Some(raw_hotkey) = prefs.raw_hotkey {
+            // Empty string clears the raw hotkey
+            let raw_hotkey = (!raw_hotkey.is_empty()).then_some(raw_hotkey);
+            self.raw_hotkey = raw_hotkey.clone();
+            stored.raw_hotkey = raw_hotkey;
+        }
+        if let Some(vocabulary_prompt) = prefs.vocabulary_prompt {
+            // Empty string clears the vocabulary prompt
+            let vocabulary_prompt = vocabulary_prompt.trim().to_string();
+            let vocabulary_prompt = (!vocabulary_prompt.is_empty()).then_some(vocabulary_prompt);
+            self.vocabulary_prompt = vocabulary_prompt.clone();
+            stored.vocabulary_prompt = vocabulary_prompt;
+        }
+        if let Some(app_vocabulary_prompts) = prefs.app_vocabulary_prompts {
+            self.app_vocabulary_prompts = app_vocabulary_prompts.clone();
+            stored.app_vocabulary_prompts = Some(app_vocabulary_prompts);
+        }
+        if let Some(adaptive_language) = prefs.adaptive_language {
+            self.adaptive_language = adaptive_language;
+            stored.adaptive_language = Some(adaptive_language);
+        }
+        if let Some(mixed_mode_output) = prefs.mixed_mode_output {
+            self.mixed_mode_output = mixed_mode_output;
+            stored.mixed_mode_output = Some(mixed_mode_output);
+        }
+        if let Some(network_timeout_ms) = prefs.network_timeout_ms {
+            // 0 restores the default timeouts
+            let timeout = (network_timeout_ms > 0).then_some(network_timeout_ms);
+            self.network_timeout_ms = timeout;
+            stored.network_timeout_ms = timeout;
+        }
+        if let Some(fallback) = prefs.fallback_on_enhancement_failure {
+            self.fallback_on_enhancement_failure = fallback;
+            stored.fallback_on_enhancement_failure = Some(fallback);
+        }
+        if let Some(case_style_by_language) = prefs.case_style_by_language {
+            // Language IDs are lowercase ("python", "typescript")
+            let case_style_by_language: HashMap<_, _> = case_style_by_language
+                .into_iter()
+                .map(|(language, style)| (language.trim().to_lowercase(), style))
+                .filter(|(language, _)| !language.is_empty())
+                .collect();
+            self.case_style_by_language = case_style_by_language.clone();
+            stored.case_style_by_language = Some(case_style_by_language);
+        }
+        if let Some(style_inference_excluded) = prefs.style_inference_excluded {
+            let mut style_inference_excluded: Vec<String> = style_inference_excluded
+                .into_iter()
+                .map(|bundle_id| bundle_id.trim().to_string())
+                .filter(|bundle_id| !bundle_id.is_empty())
+                .collect();
+            style_inference_excluded.sort();
+            style_inference_excluded.dedup();
+            self.style_inference_excluded = style_inference_excluded.clone();
+            stored.style_inference_excluded = Some(style_inference_excluded);
+        }
+        if let Some(enhance_prompt) = prefs.enhance_prompt {
+            let enhance_prompt = custom_prompt(PromptTemplate::Enhance, &enhance_prompt);
+            self.enhance_prompt = enhance_prompt.clone();
+            stored.enhance_prompt = enhance_prompt;
+        }
+        if let Some(transform_prompt) = prefs.transform_prompt {
+            let transform_prompt = custom_prompt(PromptTemplate::Transform, &transform_prompt);
+            self.transform_prompt = transform_prompt.clone();
+            stored.transform_prompt = transform_prompt;
+        }
+        if let Some(number_format) = prefs.number_format {
+            self.number_format = number_format;
+            stored.number_format = Some(number_format);
+        }
+        if let Some(keep_last_recording) = prefs.keep_last_recording {
+            self.keep_last_recording = keep_last_recording;
+            stored.keep_last_recording = Some(keep_last_recording);
+        }
+        if let Some(collapse_whitespace) = prefs.collapse_whitespace {
+            self.collapse_whitespace = collapse_whitespace;
+            stored.collapse_whitespace = Some(collapse_whitespace);
+        }
+        if let Some(redact_sensitive) = prefs.redact_sensitive {
+            self.redact_sensitive = redact_sensitive;
+            stored.redact_sensitive = Some(redact_sensitive);
+        }
+        if let Some(command_mode_excluded) = prefs.command_mode_excluded {
+            let mut command_mode_excluded: Vec<String> = command_mode_excluded
+                .into_iter()
+                .map(|bundle_id| bundle_id.trim().to_string())
+                .filter(|bundle_id| !bundle_id.is_empty())
+                .collect();
+            command_mode_excluded.sort();
+            command_mode_excluded.dedup();
+            self.command_mode_excluded = command_mode_excluded.clone();
+            stored.command_mode_excluded = Some(command_mode_excluded);
+        }
+        if let Some(done_display_ms) = prefs.done_display_ms {
+            self.done_display_ms = done_display_ms;
+            stored.done_display_ms = Some(done_display_ms);
+        }
+        if let Some(app_focus_wait_ms) = prefs.app_focus_wait_ms {
+            self.app_focus_wait_ms = app_focus_wait_ms;
+            stored.app_focus_wait_ms = Some(app_focus_wait_ms);
+        }
+        if let Some(languages) = prefs.enhancement_disabled_languages {
+            let mut languages: Vec<String> = languages
+                .into_iter()
+                .map(|language| language.trim().to_string())
+                .filter(|language| !language.is_empty())
+                .collect();
+            languages.sort();
+            languages.dedup();
+            self.enhancement_disabled_languages = languages.clone();
+            stored.enhancement_disabled_languages = Some(languages);
+        }
+        if let Some(mono_channel) = prefs.mono_channel {
+            self.mono_channel = mono_channel;
+            stored.mono_channel = Some(mono_channel);
+        }
+        if let Some(meter_min_db) = prefs.meter_min_db {
+            self.meter_min_db = meter_min_db;
+            stored.meter_min_db = Some(meter_min_db);
+        }
+        if let Some(meter_max_db) = prefs.meter_max_db {
+            self.meter_max_db = meter_max_db;
+            stored.meter_max_db = Some(meter_max_db);
+        }
+        if let Some(meter_peak_hold) = prefs.meter_peak_hold {
+            self.meter_peak_hold = meter_peak_hold;
+            stored.meter_peak_hold = Some(meter_peak_hold);
+        }
+        if let Some(input_gain_db) = prefs.input_gain_db {
+            self.input_gain_db = input_gain_db;
+            stored.input_gain_db = Some(input_gain_db);
+        }
         stored.save()
     }
+
+    /// Prompt currently used for `template`: the custom one if set, otherwise the built-in one.
+    pub fn prompt_template(&self, template: PromptTemplate) -> &str {
+        let custom = match template {
+            PromptTemplate::Enhance => self.enhance_prompt.as_deref(),
+            PromptTemplate::Transform => self.transform_prompt.as_deref(),
+        };
+        custom.unwrap_or_else(|| template.default_prompt())
+    }
+
+    /// Drop the custom prompt for `template` and persist it.
+    pub fn reset_prompt_template(&mut self, template: PromptTemplate) -> Result<(), String> {
+        let mut stored = StoredPreferences::load();
+        match template {
+            PromptTemplate::Enhance => stored.enhance_prompt = None,
+            PromptTemplate::Transform => stored.transform_prompt = None,
+        }
+        stored.save()?;
+        match template {
+            PromptTemplate::Enhance => self.enhance_prompt = None,
+            PromptTemplate::Transform => self.transform_prompt = None,
+        }
+        Ok(())
+    }
+
+    /// Set (Some) or clear (None) the style override for an app and persist it.
+    pub fn set_app_style(&mut self, bundle_id: &str, style_id: Option<&str>) -> Result<(), String> {
+        let mut app_styles = self.app_styles.clone();
+        match style_id {
+            Some(style_id) => {
+                if crate::styles::get_style_by_id(style_id).is_none() {
+                    return Err(format!("Unknown style: {}", style_id));
+                }
+                app_styles.insert(bundle_id.to_string(), style_id.to_string());
+            }
+            None => {
+                app_styles.remove(bundle_id);
+            }
+        }
+
+        let mut stored = StoredPreferences::load();
+        stored.app_styles = Some(app_styles.clone());
+        stored.save()?;
+        self.app_styles = app_styles;
+        Ok(())
+    }
+
+    /// Language to transcribe with for the given app: the per-app override
+    /// if one is set, otherwise the global language.
+    pub fn language_for_app(&self, bundle_id: Option<&str>) -> String {
+        bundle_id
+            .and_then(|id| self.app_languages.get(id))
+            .cloned()
+            .unwrap_or_else(|| self.language.clone())
+    }
+
+    /// Delay before typing starts for the given app: the per-app override
+    /// if one is set, otherwise the global delay.
+    pub fn pre_insert_delay_for_app(&self, bundle_id: Option<&str>) -> u64 {
+        bundle_id
+            .and_then(|id| self.app_pre_insert_delays.get(id))
+            .copied()
+            .unwrap_or(self.pre_insert_delay_ms)
+    }
+
+    /// Vocabulary prompt for the given app: the global prompt followed by
+    /// the app's own additions, or None if neither is set.
+    pub fn vocabulary_prompt_for_app(&self, bundle_id: Option<&str>) -> Option<String> {
+        let app_prompt = bundle_id
+            .and_then(|id| self.app_vocabulary_prompts.get(id))
+            .map(|p| p.trim())
+            .filter(|p| !p.is_empty());
+        match (self.vocabulary_prompt.as_deref(), app_prompt) {
+            (Some(global), Some(app)) => Some(format!("{} {}", global, app)),
+            (global, app) => global.or(app).map(str::to_string),
+        }
+    }
+
+    /// Whether selection detection (and so Command Mode) is skipped for
+    /// the given app.
+    pub fn command_mode_excluded_for_app(&self, bundle_id: Option<&str>) -> bool {
+        bundle_id.is_some_and(|id| self.command_mode_excluded.iter().any(|e| e == id))
+    }
+
+    /// Whether LLM enhancement is turned off for dictations in `language`.
+    /// Entries match on the base code ("hi" covers "hi-IN"); "mixed" also
+    /// covers "auto".
+    pub fn enhancement_disabled_for_language(&self, language: &str) -> bool {
+        let base = |code: &str| match code {
+            "auto" => "mixed".to_string(),
+            code => code.split('-').next().unwrap_or(code).to_lowercase(),
+        };
+        let language = base(language);
+        self.enhancement_disabled_languages
+            .iter()
+            .any(|disabled| base(disabled) == language)
+    }
+
+    /// Overlay level meter calibration
+    pub fn meter_settings(&self) -> crate::audio::MeterSettings {
+        crate::audio::MeterSettings {
+            min_db: self.meter_min_db,
+            max_db: self.meter_max_db,
+            peak_hold: self.meter_peak_hold,
+        }
+    }
+}
+
+/// The prompt to store for a submitted template: None when it is the
+/// built-in one, so later changes to the default still reach the user.
+fn custom_prompt(template: PromptTemplate, prompt: &str) -> Option<String> {
+    let prompt = prompt.trim();
+    (prompt != template.default_prompt().trim()).then(|| prompt.to_string())
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -161,12 +1049,203 @@ pub struct Preferences {
     pub onboarding_complete: Option<bool>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub spoken_languages: Option<Vec<String>>,
+    /// Log per-stage latency for every dictation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verbose_timing: Option<bool>,
+    /// Largest selection (characters) Command Mode will send for transformation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_selection_chars: Option<usize>,
+    /// Per-app language overrides (bundle_id → language code)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_languages: Option<HashMap<String, String>>,
+    /// Delay before typing starts (ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre_insert_delay_ms: Option<u64>,
+    /// Per-app pre-insert delay overrides (bundle_id → ms)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_pre_insert_delays: Option<HashMap<String, u64>>,
+    /// Route audio and text through the hosted proxy (false requires own API key)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub use_proxy: Option<bool>,
+    /// Transcription confidence threshold (0.0-1.0, 0 disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_confidence: Option<f32>,
+    /// What to do below the threshold: "flag" (insert and warn) or "block" (don't insert)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_confidence_action: Option<LowConfidenceAction>,
+    /// Hotkey that sends the dictation to the scratch buffer (empty clears it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scratch_hotkey: Option<String>,
+    /// Auto-stop after this much trailing silence (ms, 0 disables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub silence_timeout_ms: Option<u32>,
+    /// Level below which audio counts as silence for auto-stop (dBFS, -80 to 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub silence_threshold_db: Option<f32>,
+    /// Audio kept from just before the hotkey is pressed (ms, 0 disables; applies on restart)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preroll_ms: Option<u32>,
+    /// Resampler speed/fidelity trade-off: "fast", "balanced" or "high"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resample_quality: Option<crate::audio::ResampleQuality>,
+    /// Enhance dictations with the LLM (false = raw mode; IDE transforms still apply)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhancement_enabled: Option<bool>,
+    /// Groq chat model (must be one of `groq_llm::KNOWN_GROQ_MODELS`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub groq_model: Option<String>,
+    /// How line breaks are inserted ("collapse_to_space", "preserve_newlines", "smart")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub newline_mode: Option<crate::insertion::NewlineMode>,
+    /// Force keystroke or clipboard insertion ("auto", "keystroke", "clipboard_paste")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub insertion_method: Option<crate::insertion::InsertionMethod>,
+    /// Save completed dictations to the local history (text only, never audio)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_enabled: Option<bool>,
+    /// Hotkey that re-inserts the last dictation from history (empty clears it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reinsert_hotkey: Option<String>,
+    /// Per-app style overrides (bundle_id → style id)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_styles: Option<HashMap<String, String>>,
+    /// Extra/excluded file extensions and per-root file limit for workspace indexing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index_config: Option<crate::ide::file_index::IndexConfig>,
+    /// Transcription service: "groq" or "deepgram" (needs a Deepgram API key)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transcription_provider: Option<TranscriptionProvider>,
+    /// Deepgram model (e.g. "nova-2")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deepgram_model: Option<String>,
+    /// Recordings are stopped and processed after this many seconds (10-7200)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_recording_duration: Option<u32>,
+    /// Turn individual sound cues (start/success/error) on or off
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audio_cues: Option<crate::audio_cues::CueSettings>,
+    /// Command Mode: "auto" (LLM decides), "always_transform" or "always_replace".
+    /// The explicit modes skip the classification call and respond faster.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_mode_behavior: Option<CommandModeBehavior>,
+    /// Output of a dictation: "insert", "clipboard" or "both"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_mode: Option<OutputMode>,
+    /// Hotkey that records a raw (unenhanced) dictation (empty clears it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub raw_hotkey: Option<String>,
+    /// Names and jargon to help Groq Whisper spell them right (empty clears it).
+    /// At most `whisper_api::MAX_VOCABULARY_PROMPT_CHARS` characters: Whisper
+    /// ignores prompt text beyond ~224 tokens.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vocabulary_prompt: Option<String>,
+    /// Per-app vocabulary added after the global one (bundle_id → text)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_vocabulary_prompts: Option<HashMap<String, String>>,
+    /// Emit `suggest-language` when mixed mode keeps detecting a language
+    /// that isn't in `spoken_languages`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub adaptive_language: Option<bool>,
+    /// Mixed-mode script: "romanized", "native_script", or "auto" (native
+    /// script for the first spoken language only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mixed_mode_output: Option<crate::whisper_api::MixedModeOutput>,
+    /// Timeout for each transcription and LLM request (ms, 5000-300000; 0
+    /// restores the defaults of 30s for transcription and 20s otherwise).
+    /// Transcription never gets less than 30s; long recordings may need more.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network_timeout_ms: Option<u64>,
+    /// When enhancement fails: "insert_raw" (default), "cancel" (insert
+    /// nothing) or "copy_raw_to_clipboard" (insert nothing, copy the transcript)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fallback_on_enhancement_failure: Option<EnhancementFallback>,
+    /// Variable case style per editor language, overriding the language's
+    /// convention (e.g. {"python": "camel_case"}). Language IDs: "python",
+    /// "rust", "javascript", "typescript", "csharp", ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub case_style_by_language: Option<HashMap<String, crate::ide::variable::CaseStyle>>,
+    /// Bundle IDs that always use the neutral style: no built-in mapping or
+    /// keyword inference (for mis-categorized apps). `app_styles` still wins.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style_inference_excluded: Option<Vec<String>>,
+    /// System prompt for Dictation Mode enhancement. Reads back as the
+    /// built-in prompt until customized; `reset_prompt_template` restores it.
+    /// Non-empty, at most `groq_llm::MAX_PROMPT_TEMPLATE_CHARS` characters.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhance_prompt: Option<String>,
+    /// System prompt for Command Mode transformations (same rules)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transform_prompt: Option<String>,
+    /// How numbers are written: "unchanged" (default), "digits", "words", or
+    /// "auto" (digits for years, amounts, dates and 10 and up)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub number_format: Option<crate::text_normalize::NumberFormat>,
+    /// Keep the last recording in memory until the next one (instead of a few
+    /// minutes for re-transcription) so `play_last_recording` can play it.
+    /// Off by default; audio only touches disk briefly during playback.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_last_recording: Option<bool>,
+    /// Collapse runs of spaces when inserting (default true). Turn off to keep
+    /// the exact spacing of tables, code and ASCII art.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collapse_whitespace: Option<bool>,
+    /// Opt-in masking of things that look like passwords, keys or card numbers
+    /// in transcripts ("off", "mask", "skip_enhancement"). Heuristic: it won't
+    /// catch every secret and may flag harmless numbers.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redact_sensitive: Option<crate::redact::RedactMode>,
+    /// Bundle IDs that never enter Command Mode: selection detection is
+    /// skipped when recording starts in them, so they always dictate.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_mode_excluded: Option<Vec<String>>,
+    /// How long "Done!" shows before the overlay hides (ms, default 100).
+    /// Together with `app_focus_wait_ms` this is the pause between stopping
+    /// and typing: lower feels snappier, higher gives slow or busy machines
+    /// time to refocus the target app so text doesn't land in the wrong window.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub done_display_ms: Option<u64>,
+    /// Wait after re-activating the target app before typing (ms, default 30)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_focus_wait_ms: Option<u64>,
+    /// Languages whose dictations are inserted without LLM enhancement
+    /// (e.g. ["hi", "te"], or "mixed" for mixed mode), for when the
+    /// English-tuned cleanup degrades them. Matched against the language
+    /// the dictation was transcribed with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enhancement_disabled_languages: Option<Vec<String>>,
+    /// How multi-channel interfaces are reduced to mono: "mix" (average all
+    /// channels) or a channel index (0 = first) to record only that input.
+    /// An index the device doesn't have falls back to "mix".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mono_channel: Option<crate::audio::MonoChannel>,
+    /// Level meter floor: input at or below this shows as empty (dBFS,
+    /// default -60). Raise it in noisy rooms so background noise reads flat.
+    /// Only changes the overlay, not what is recorded or auto-stop.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meter_min_db: Option<f32>,
+    /// Level meter ceiling: input at or above this shows as full (dBFS,
+    /// default 0). Lower it for quiet microphones.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meter_max_db: Option<f32>,
+    /// Hold the meter's peak and let it fall back gradually
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub meter_peak_hold: Option<bool>,
+    /// Gain applied to the recording before transcription and on the level
+    /// meter (dB, -20 to 30, default 0), for microphones that record too
+    /// quietly. Loud input clips at high gain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub input_gain_db: Option<f32>,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Default config that doesn't depend on the preferences file on disk
+    fn test_config() -> AppConfig {
+        AppConfig::from_stored(StoredPreferences::default())
+    }
+
     #[test]
     fn test_stored_preferences_default() {
         let prefs = StoredPreferences::default();
@@ -178,6 +1257,58 @@ mod tests {
         assert!(prefs.language.is_none());
         assert!(prefs.onboarding_complete.is_none());
         assert!(prefs.spoken_languages.is_none());
+        assert!(prefs.replacements.is_none());
+        assert!(prefs.verbose_timing.is_none());
+        assert!(prefs.max_selection_chars.is_none());
+        assert!(prefs.app_languages.is_none());
+        assert!(prefs.pre_insert_delay_ms.is_none());
+        assert!(prefs.app_pre_insert_delays.is_none());
+        assert!(prefs.use_proxy.is_none());
+        assert!(prefs.min_confidence.is_none());
+        assert!(prefs.low_confidence_action.is_none());
+        assert!(prefs.scratch_hotkey.is_none());
+        assert!(prefs.silence_timeout_ms.is_none());
+        assert!(prefs.silence_threshold_db.is_none());
+        assert!(prefs.preroll_ms.is_none());
+        assert!(prefs.resample_quality.is_none());
+        assert!(prefs.enhancement_enabled.is_none());
+        assert!(prefs.groq_model.is_none());
+        assert!(prefs.newline_mode.is_none());
+        assert!(prefs.insertion_method.is_none());
+        assert!(prefs.history_enabled.is_none());
+        assert!(prefs.reinsert_hotkey.is_none());
+        assert!(prefs.app_styles.is_none());
+        assert!(prefs.index_config.is_none());
+        assert!(prefs.transcription_provider.is_none());
+        assert!(prefs.deepgram_model.is_none());
+        assert!(prefs.max_recording_duration.is_none());
+        assert!(prefs.audio_cues.is_none());
+        assert!(prefs.command_mode_behavior.is_none());
+        assert!(prefs.output_mode.is_none());
+        assert!(prefs.raw_hotkey.is_none());
+        assert!(prefs.vocabulary_prompt.is_none());
+        assert!(prefs.app_vocabulary_prompts.is_none());
+        assert!(prefs.adaptive_language.is_none());
+        assert!(prefs.mixed_mode_output.is_none());
+        assert!(prefs.network_timeout_ms.is_none());
+        assert!(prefs.fallback_on_enhancement_failure.is_none());
+        assert!(prefs.case_style_by_language.is_none());
+        assert!(prefs.style_inference_excluded.is_none());
+        assert!(prefs.enhance_prompt.is_none());
+        assert!(prefs.transform_prompt.is_none());
+        assert!(prefs.number_format.is_none());
+        assert!(prefs.keep_last_recording.is_none());
+        assert!(prefs.collapse_whitespace.is_none());
+        assert!(prefs.redact_sensitive.is_none());
+        assert!(prefs.command_mode_excluded.is_none());
+        assert!(prefs.done_display_ms.is_none());
+        assert!(prefs.app_focus_wait_ms.is_none());
+        assert!(prefs.enhancement_disabled_languages.is_none());
+        assert!(prefs.mono_channel.is_none());
+        assert!(prefs.meter_min_db.is_none());
+        assert!(prefs.meter_max_db.is_none());
+        assert!(prefs.meter_peak_hold.is_none());
+        assert!(prefs.input_gain_db.is_none());
     }
 
     #[test]
@@ -191,6 +1322,59 @@ mod tests {
             language: Some("en-US".to_string()),
             onboarding_complete: Some(true),
             spoken_languages: Some(vec!["en".to_string(), "es".to_string()]),
+            replacements: None,
+            custom_dictionary: None,
+            verbose_timing: Some(true),
+            max_selection_chars: None,
+            app_languages: None,
+            pre_insert_delay_ms: None,
+            app_pre_insert_delays: None,
+            use_proxy: Some(false),
+            min_confidence: None,
+            low_confidence_action: None,
+            scratch_hotkey: None,
+            silence_timeout_ms: None,
+            silence_threshold_db: None,
+            preroll_ms: None,
+            resample_quality: None,
+            enhancement_enabled: None,
+            groq_model: None,
+            newline_mode: None,
+            insertion_method: None,
+            history_enabled: None,
+            reinsert_hotkey: None,
+            app_styles: None,
+            index_config: None,
+            transcription_provider: None,
+            deepgram_model: None,
+            max_recording_duration: None,
+            audio_cues: None,
+            command_mode_behavior: None,
+            output_mode: None,
+            raw_hotkey: None,
+            vocabulary_prompt: None,
+            app_vocabulary_prompts: None,
+            adaptive_language: None,
+            mixed_mode_output: None,
+            network_timeout_ms: None,
+            fallback_on_enhancement_failure: None,
+            case_style_by_language: None,
+            style_inference_excluded: None,
+            enhance_prompt: None,
+            transform_prompt: None,
+            number_format: None,
+            keep_last_recording: None,
+            collapse_whitespace: None,
+            redact_sensitive: None,
+            command_mode_excluded: None,
+            done_display_ms: None,
+            app_focus_wait_ms: None,
+            enhancement_disabled_languages: None,
+            mono_channel: None,
+            meter_min_db: None,
+            meter_max_db: None,
+            meter_peak_hold: None,
+            input_gain_db: None,
         };
 
         let json = serde_json::to_string(&prefs).unwrap();
@@ -211,4 +1395,132 @@ mod tests {
         assert_eq!(prefs.show_indicator, Some(false));
         assert!(prefs.hotkey.is_none());
     }
+
+    #[test]
+    fn test_language_for_app() {
+        let mut config = test_config();
+        config.language = "en-US".to_string();
+        config.app_languages = HashMap::from([("com.apple.Notes".to_string(), "hi".to_string())]);
+
+        assert_eq!(config.language_for_app(Some("com.apple.Notes")), "hi");
+        assert_eq!(
+            config.language_for_app(Some("com.tinyspeck.slackmacgap")),
+            "en-US"
+        );
+        assert_eq!(config.language_for_app(None), "en-US");
+    }
+
+    #[test]
+    fn test_pre_insert_delay_for_app() {
+        let mut config = test_config();
+        config.pre_insert_delay_ms = 0;
+        config.app_pre_insert_delays = HashMap::from([("com.microsoft.teams2".to_string(), 150)]);
+
+        assert_eq!(
+            config.pre_insert_delay_for_app(Some("com.microsoft.teams2")),
+            150
+        );
+        assert_eq!(config.pre_insert_delay_for_app(Some("com.apple.Notes")), 0);
+        assert_eq!(config.pre_insert_delay_for_app(None), 0);
+    }
+
+    #[test]
+    fn test_vocabulary_prompt_for_app() {
+        let mut config = test_config();
+        config.vocabulary_prompt = Some("Keyhold, Groq".to_string());
+        config.app_vocabulary_prompts = HashMap::from([(
+            "com.epic.Haiku".to_string(),
+            "metoprolol, tachycardia".to_string(),
+        )]);
+
+        assert_eq!(
+            config.vocabulary_prompt_for_app(Some("com.epic.Haiku")),
+            Some("Keyhold, Groq metoprolol, tachycardia".to_string())
+        );
+        assert_eq!(
+            config.vocabulary_prompt_for_app(None),
+            Some("Keyhold, Groq".to_string())
+        );
+
+        config.vocabulary_prompt = None;
+        assert_eq!(
+            config.vocabulary_prompt_for_app(Some("com.epic.Haiku")),
+            Some("metoprolol, tachycardia".to_string())
+        );
+        assert_eq!(
+            config.vocabulary_prompt_for_app(Some("com.apple.Notes")),
+            None
+        );
+    }
+
+    #[test]
+    fn test_command_mode_excluded_for_app() {
+        let mut config = test_config();
+        config.command_mode_excluded = vec!["com.googlecode.iterm2".to_string()];
+
+        assert!(config.command_mode_excluded_for_app(Some("com.googlecode.iterm2")));
+        assert!(!config.command_mode_excluded_for_app(Some("com.apple.Notes")));
+        assert!(!config.command_mode_excluded_for_app(None));
+    }
+
+    #[test]
+    fn test_enhancement_disabled_for_language() {
+        let mut config = test_config();
+        config.enhancement_disabled_languages = vec!["hi".to_string(), "mixed".to_string()];
+
+        assert!(config.enhancement_disabled_for_language("hi"));
+        assert!(config.enhancement_disabled_for_language("hi-IN"));
+        assert!(config.enhancement_disabled_for_language("mixed"));
+        assert!(config.enhancement_disabled_for_language("auto"));
+        assert!(!config.enhancement_disabled_for_language("en-US"));
+
+        config.enhancement_disabled_languages.clear();
+        assert!(!config.enhancement_disabled_for_language("hi"));
+    }
+
+    #[test]
+    fn test_prompt_template() {
+        let mut config = test_config();
+        config.enhance_prompt = Some("Only fix punctuation.".to_string());
+        config.transform_prompt = None;
+
+        assert_eq!(
+            config.prompt_template(PromptTemplate::Enhance),
+            "Only fix punctuation."
+        );
+        assert_eq!(
+            config.prompt_template(PromptTemplate::Transform),
+            crate::groq_llm::TRANSFORM_SYSTEM_PROMPT
+        );
+
+        // Submitting the built-in prompt unchanged keeps following the default
+        let default = format!("{}\n", crate::groq_llm::ENHANCE_SYSTEM_PROMPT);
+        assert_eq!(custom_prompt(PromptTemplate::Enhance, &default), None);
+        assert_eq!(
+            custom_prompt(PromptTemplate::Enhance, "  Be terse. "),
+            Some("Be terse.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_output_mode() {
+        assert!(OutputMode::Insert.inserts() && !OutputMode::Insert.copies());
+        assert!(!OutputMode::Clipboard.inserts() && OutputMode::Clipboard.copies());
+        assert!(OutputMode::Both.inserts() && OutputMode::Both.copies());
+
+        let mode: OutputMode = serde_json::from_str("\"clipboard\"").unwrap();
+        assert_eq!(mode, OutputMode::Clipboard);
+    }
+
+    #[test]
+    fn test_low_confidence_action() {
+        assert_eq!(
+            test_config().low_confidence_action,
+            LowConfidenceAction::Flag
+        );
+
+        let action: LowConfidenceAction = serde_json::from_str("\"block\"").unwrap();
+        assert_eq!(action, LowConfidenceAction::Block);
+        assert!(serde_json::from_str::<LowConfidenceAction>("\"warn\"").is_err());
+    }
 }
@@ -0,0 +1,178 @@
+//! Deepgram transcription client (bring-your-own-key).
+//!
+//! Sends the recorded WAV to Deepgram's prerecorded `/v1/listen` endpoint with
+//! the user's own API key. There is no proxy for Deepgram, so requests always
+//! go directly to the API.
+
+use crate::http_client;
+use crate::rate_limit::{check_rate_limit, Service};
+use reqwest::Client;
+use serde::Deserialize;
+
+const DEEPGRAM_API_URL: &str = "https://api.deepgram.com/v1/listen";
+
+/// Model used when the user hasn't picked one
+pub const DEFAULT_DEEPGRAM_MODEL: &str = "nova-2";
+
+/// Response from Deepgram's prerecorded API (only the fields we read)
+#[derive(Debug, Deserialize)]
+struct ListenResponse {
+    results: ListenResults,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenResults {
+    #[serde(default)]
+    channels: Vec<ListenChannel>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenChannel {
+    #[serde(default)]
+    alternatives: Vec<ListenAlternative>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ListenAlternative {
+    transcript: String,
+}
+
+impl ListenResponse {
+    /// Transcript of the first channel's best alternative (empty if there is none)
+    fn transcript(&self) -> String {
+        self.results
+            .channels
+            .first()
+            .and_then(|c| c.alternatives.first())
+            .map(|a| a.transcript.trim().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// The user's Deepgram API key: the stored key first, then DEEPGRAM_API_KEY.
+pub fn api_key() -> Option<String> {
+    crate::auth::storage::get_deepgram_api_key()
+        .ok()
+        .flatten()
+        .or_else(|| {
+            std::env::var("DEEPGRAM_API_KEY")
+                .ok()
+                .filter(|k| !k.is_empty())
+        })
+}
+
+/// Query parameters for a listen request.
+/// "mixed"/"auto" let Deepgram detect the language; otherwise the code is
+/// passed through (Deepgram accepts both "en" and "en-US").
+fn query_params(model: &str, language: &str) -> Vec<(&'static str, String)> {
+    let mut params = vec![
+        ("model", model.to_string()),
+        ("smart_format", "true".to_string()),
+        ("punctuate", "true".to_string()),
+    ];
+    if language == "mixed" || language == "auto" {
+        params.push(("detect_language", "true".to_string()));
+    } else {
+        params.push(("language", language.to_string()));
+    }
+    params
+}
+
+pub struct DeepgramClient {
+    client: &'static Client,
+    api_key: String,
+    model: String,
+}
+
+impl DeepgramClient {
+    /// Create a client for `model` with the stored API key.
+    /// Errors if no key has been set.
+    pub fn new(model: &str) -> Result<Self, String> {
+        let api_key = api_key().ok_or_else(|| {
+            "No Deepgram API key is set. Add your API key in Preferences.".to_string()
+        })?;
+        Ok(DeepgramClient {
+            client: http_client::get_transcription_client()?,
+            api_key,
+            model: model.to_string(),
+        })
+    }
+
+    /// Transcribe WAV audio with Deepgram's prerecorded API.
+    ///
+    /// # Arguments
+    /// * `audio_wav` - WAV-encoded audio bytes
+    /// * `language` - Language code (e.g., "en-US", "hi") or "mixed" to auto-detect
+    pub async fn transcribe(&self, audio_wav: &[u8], language: &str) -> Result<String, String> {
+        check_rate_limit(Service::Deepgram)?;
+
+        log::info!(
+            "[DEEPGRAM] Sending audio ({} bytes, model {})",
+            audio_wav.len(),
+            self.model
+        );
+
+        let params = query_params(&self.model, language);
+        let response =
+            http_client::send_with_retry(&http_client::TRANSCRIPTION_RETRY, "DEEPGRAM", || {
+                Ok(self
+                    .client
+                    .post(DEEPGRAM_API_URL)
+                    .query(&params)
+                    .header("Authorization", format!("Token {}", self.api_key))
+                    .header("Content-Type", "audio/wav")
+                    .body(audio_wav.to_vec()))
+            })
+            .await
+            .map_err(|e| format!("Failed to send request to Deepgram: {}", e))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response
+                .text()
+                .await
+                .unwrap_or_else(|_| "Unknown error".to_string());
+            return Err(format!("Deepgram API error ({}): {}", status, error_text));
+        }
+
+        let result: ListenResponse = response
+            .json()
+            .await
+            .map_err(|e| format!("Failed to parse Deepgram response: {}", e))?;
+
+        Ok(result.transcript())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_transcript() {
+        let json = r#"{
+            "metadata": {"request_id": "abc"},
+            "results": {"channels": [{"alternatives": [
+                {"transcript": " Hello world. ", "confidence": 0.98},
+                {"transcript": "Hollow world.", "confidence": 0.4}
+            ]}]}
+        }"#;
+        let response: ListenResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(response.transcript(), "Hello world.");
+
+        let empty: ListenResponse =
+            serde_json::from_str(r#"{"results": {"channels": []}}"#).unwrap();
+        assert_eq!(empty.transcript(), "");
+    }
+
+    #[test]
+    fn test_query_params_language() {
+        let params = query_params("nova-2", "en-US");
+        assert!(params.contains(&("model", "nova-2".to_string())));
+        assert!(params.contains(&("language", "en-US".to_string())));
+
+        let params = query_params("nova-2", "mixed");
+        assert!(params.contains(&("detect_language", "true".to_string())));
+        assert!(!params.iter().any(|(key, _)| *key == "language"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Diagnostics bundle for bug reports.
+//!
+//! Collects what's needed to triage a dictation problem into one folder the
+//! user can attach to an issue: recent logs with secrets stripped, plus a
+//! `diagnostics.json` report (non-sensitive preferences, permission status,
+//! selected microphone, workspace index status). Audio and API keys are never
+//! included.
+
+use crate::error::sanitize_error_message;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Most recently modified log files copied into a bundle
+const MAX_LOG_FILES: usize = 3;
+
+/// Lines kept from the end of each log file
+const MAX_LOG_LINES: usize = 5000;
+
+/// Write `report` and the most recent logs from `log_dir` into a new folder
+/// under `output_dir`. Returns the folder's path.
+pub fn write_bundle<T: Serialize>(
+    output_dir: &Path,
+    log_dir: &Path,
+    report: &T,
+) -> Result<PathBuf, String> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let bundle_dir = output_dir.join(format!("keyhold-diagnostics-{}", timestamp));
+    let logs_dir = bundle_dir.join("logs");
+    fs::create_dir_all(&logs_dir)
+        .map_err(|e| format!("Failed to create diagnostics folder: {}", e))?;
+
+    let report = serde_json::to_string_pretty(report)
+        .map_err(|e| format!("Failed to serialize diagnostics: {}", e))?;
+    fs::write(
+        bundle_dir.join("diagnostics.json"),
+        sanitize_error_message(&report),
+    )
+    .map_err(|e| format!("Failed to write diagnostics: {}", e))?;
+
+    for log_file in recent_log_files(log_dir) {
+        let Some(name) = log_file.file_name() else {
+            continue;
+        };
+        match fs::read(&log_file) {
+            Ok(bytes) => {
+                let content = String::from_utf8_lossy(&bytes);
+                if let Err(e) = fs::write(logs_dir.join(name), sanitize_log(&content)) {
+                    log::warn!("[DIAGNOSTICS] Failed to copy {:?}: {}", name, e);
+                }
+            }
+            Err(e) => log::warn!("[DIAGNOSTICS] Failed to read {:?}: {}", log_file, e),
+        }
+    }
+
+    Ok(bundle_dir)
+}
+
+/// `.log` files in `log_dir`, newest first (at most MAX_LOG_FILES)
+fn recent_log_files(log_dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(log_dir) else {
+        return Vec::new();
+    };
+
+    let mut files: Vec<(SystemTime, PathBuf)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|path| {
+            let modified = fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((modified, path))
+        })
+        .collect();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+    files
+        .into_iter()
+        .take(MAX_LOG_FILES)
+        .map(|(_, path)| path)
+        .collect()
+}
+
+/// Last MAX_LOG_LINES lines of a log, each run through `sanitize_error_message`
+fn sanitize_log(content: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(MAX_LOG_LINES);
+    let mut sanitized: Vec<String> = lines[start..]
+        .iter()
+        .map(|line| sanitize_error_message(line))
+        .collect();
+    sanitized.push(String::new());
+    sanitized.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_sanitize_log_keeps_tail_and_redacts() {
+        let mut content = String::new();
+        for i in 0..MAX_LOG_LINES + 10 {
+            content.push_str(&format!("line {}\n", i));
+        }
+        content.push_str("Groq API error: key gsk_abcdefghijklmnop rejected\n");
+
+        let sanitized = sanitize_log(&content);
+        assert!(!sanitized.contains("line 0\n"));
+        assert!(sanitized.contains(&format!("line {}\n", MAX_LOG_LINES + 9)));
+        assert!(!sanitized.contains("abcdefghijklmnop"));
+        assert_eq!(sanitized.lines().count(), MAX_LOG_LINES);
+    }
+
+    #[test]
+    fn test_write_bundle() {
+        let logs = TempDir::new().unwrap();
+        fs::write(logs.path().join("keyhold.log"), "started\n").unwrap();
+        fs::write(logs.path().join("notes.txt"), "not a log\n").unwrap();
+        let output = TempDir::new().unwrap();
+
+        let report = serde_json::json!({ "microphone": "MacBook Pro Microphone" });
+        let bundle = write_bundle(output.path(), logs.path(), &report).unwrap();
+
+        assert!(bundle.join("diagnostics.json").exists());
+        assert!(bundle.join("logs").join("keyhold.log").exists());
+        assert!(!bundle.join("logs").join("notes.txt").exists());
+    }
+}
@@ -1,15 +1,102 @@
 //! Groq LLM client for text transformation and enhancement.
 //!
 //! Uses the Groq API with OpenAI-compatible chat completions format.
-//! Model: llama-3.3-70b-versatile (free tier, 128K context)
+//! Default model: llama-3.3-70b-versatile (free tier, 128K context).
+//! Users can pick another model from `KNOWN_GROQ_MODELS` in preferences.
 
-use crate::http_client;
+use crate::http_client::{self, RetryPolicy};
 use crate::rate_limit::{check_rate_limit, Service};
 use crate::signing;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 use serde::{Deserialize, Serialize};
 
-const GROQ_MODEL: &str = "llama-3.3-70b-versatile";
+pub const DEFAULT_GROQ_MODEL: &str = "llama-3.3-70b-versatile";
+
+/// Groq chat models users may select. Anything else falls back to the default.
+pub const KNOWN_GROQ_MODELS: &[&str] = &[
+    "llama-3.3-70b-versatile",
+    "llama-3.1-8b-instant",
+    "openai/gpt-oss-120b",
+    "openai/gpt-oss-20b",
+    "meta-llama/llama-4-maverick-17b-128e-instruct",
+    "meta-llama/llama-4-scout-17b-16e-instruct",
+    "moonshotai/kimi-k2-instruct",
+    "qwen/qwen3-32b",
+];
+
+/// Whether `model` is a non-empty, known Groq chat model.
+pub fn is_known_model(model: &str) -> bool {
+    let model = model.trim();
+    !model.is_empty() && KNOWN_GROQ_MODELS.contains(&model)
+}
+
+/// The model to use for a configured value: the value itself if it's known,
+/// otherwise the default (with a warning).
+pub fn resolve_model(model: &str) -> &str {
+    if is_known_model(model) {
+        model.trim()
+    } else {
+        log::warn!(
+            "[GROQ] Unknown model '{}', falling back to {}",
+            model,
+            DEFAULT_GROQ_MODEL
+        );
+        DEFAULT_GROQ_MODEL
+    }
+}
+
+/// Why a Groq chat request failed.
+///
+/// Callers fall back to the raw transcript or original selection in every
+/// case; the kind tells them whether trying again later could help.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroqError {
+    /// Rejected credentials (401/403): the API key or proxy signature is bad
+    Unauthorized(String),
+    /// Still rate limited, failing (429/5xx) or unreachable after retries
+    Unavailable(String),
+    /// Anything else: bad request, unparseable response, local rate limit
+    Failed(String),
+}
+
+impl GroqError {
+    /// Classify a non-success response status
+    fn from_status(status: StatusCode, body: &str) -> Self {
+        let message = format!("Groq API error ({}): {}", status, body);
+        if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            GroqError::Unauthorized(message)
+        } else if http_client::is_retryable_status(status) {
+            GroqError::Unavailable(message)
+        } else {
+            GroqError::Failed(message)
+        }
+    }
+}
+
+impl std::fmt::Display for GroqError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GroqError::Unauthorized(message)
+            | GroqError::Unavailable(message)
+            | GroqError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for GroqError {}
+
+impl From<String> for GroqError {
+    fn from(message: String) -> Self {
+        GroqError::Failed(message)
+    }
+}
+
+/// Convert GroqError to String for Tauri command compatibility.
+impl From<GroqError> for String {
+    fn from(error: GroqError) -> Self {
+        error.to_string()
+    }
+}
 
 /// User intent when text is selected
 #[derive(Debug, Clone, PartialEq)]
@@ -78,8 +165,11 @@ fn strip_wrapping(s: &str) -> String {
     result.to_string()
 }
 
-/// System prompt for Command Mode transformations
-const TRANSFORM_SYSTEM_PROMPT: &str = r#"You are a text transformation assistant. Transform the selected text according to the user's voice command.
+/// Upper bound for a custom system prompt, leaving the context window to the text itself
+pub const MAX_PROMPT_TEMPLATE_CHARS: usize = 8000;
+
+/// Default system prompt for Command Mode transformations
+pub const TRANSFORM_SYSTEM_PROMPT: &str = r#"You are a text transformation assistant. Transform the selected text according to the user's voice command.
 
 Rules:
 - Output ONLY the transformed text, nothing else
@@ -91,8 +181,8 @@ Rules:
 - For formatting commands (bullets, numbered list), apply the formatting
 - If the command is unclear, make a reasonable interpretation"#;
 
-/// System prompt for Dictation Mode enhancement
-const ENHANCE_SYSTEM_PROMPT: &str = r#"You are a speech-to-text enhancement assistant. Clean up and improve the transcription.
+/// Default system prompt for Dictation Mode enhancement
+pub const ENHANCE_SYSTEM_PROMPT: &str = r#"You are a speech-to-text enhancement assistant. Clean up and improve the transcription.
 
 Instructions:
 1. Remove filler words (um, uh, like, you know, etc.)
@@ -108,6 +198,29 @@ Instructions:
 6. PRESERVE all @-prefixed references exactly as-is (e.g., @components.json, @main.rs, @UserService) - these are intentional file/symbol tags
 7. Output ONLY the enhanced text, nothing else"#;
 
+/// Check a custom system prompt before it is saved; `name` labels the error.
+pub fn validate_prompt_template(name: &str, prompt: &str) -> Result<(), String> {
+    let length = prompt.trim().chars().count();
+    if length == 0 {
+        return Err(format!("{} prompt cannot be empty", name));
+    }
+    if length > MAX_PROMPT_TEMPLATE_CHARS {
+        return Err(format!(
+            "{} prompt must be at most {} characters",
+            name, MAX_PROMPT_TEMPLATE_CHARS
+        ));
+    }
+    Ok(())
+}
+
+/// The user's custom prompt, or `default` if none is set
+fn system_prompt(custom: Option<&str>, default: &str) -> String {
+    custom
+        .filter(|p| !p.trim().is_empty())
+        .unwrap_or(default)
+        .to_string()
+}
+
 #[derive(Debug, Serialize)]
 struct ChatRequest {
     model: String,
@@ -139,22 +252,27 @@ struct MessageContent {
 
 pub struct GroqLlmClient {
     client: &'static Client,
+    model: String,
+    retry: RetryPolicy,
 }
 
 impl GroqLlmClient {
-    pub fn new() -> Result<Self, String> {
+    /// Create a client for the given chat model (unknown models fall back to the default).
+    pub fn new(model: &str) -> Result<Self, String> {
         // Use cached client for connection reuse
         Ok(GroqLlmClient {
             client: http_client::get_client()?,
+            model: resolve_model(model).to_string(),
+            retry: http_client::LLM_RETRY,
         })
     }
 
     /// Get the API URL and optional API key based on build type.
     /// - Debug builds: use direct API with GROQ_API_KEY
     /// - Release builds: always use proxy
-    fn get_api_config(&self) -> (String, Option<String>) {
-        let (_, chat_url, api_key) = signing::get_api_config();
-        (chat_url.to_string(), api_key)
+    fn get_api_config(&self) -> Result<(String, Option<String>), String> {
+        let (_, chat_url, api_key) = signing::get_api_config()?;
+        Ok((chat_url.to_string(), api_key))
     }
 
     /// Classify user intent: is this a command to transform text, or new content?
@@ -168,17 +286,17 @@ impl GroqLlmClient {
     ///
     /// # Returns
     /// UserIntent::Command or UserIntent::Dictation
-    pub async fn classify_intent(&self, transcription: &str) -> Result<UserIntent, String> {
+    pub async fn classify_intent(&self, transcription: &str) -> Result<UserIntent, GroqError> {
         // Check rate limit before making API call
         check_rate_limit(Service::Groq)?;
 
         #[cfg(debug_assertions)]
-        println!("Classifying intent for: {}", transcription);
+        log::debug!("Classifying intent for: {}", transcription);
 
-        let (api_url, api_key) = self.get_api_config();
+        let (api_url, api_key) = self.get_api_config()?;
 
         let request = ChatRequest {
-            model: GROQ_MODEL.to_string(),
+            model: self.model.clone(),
             messages: vec![
                 Message {
                     role: "system".to_string(),
@@ -205,7 +323,7 @@ impl GroqLlmClient {
             UserIntent::Dictation
         };
 
-        println!("Intent classified as: {:?}", intent);
+        log::info!("Intent classified as: {:?}", intent);
         Ok(intent)
     }
 
@@ -214,6 +332,7 @@ impl GroqLlmClient {
     /// # Arguments
     /// * `selected_text` - The text that was selected by the user
     /// * `command` - The voice command describing the transformation
+    /// * `custom_prompt` - The user's system prompt, replacing the built-in one
     ///
     /// # Returns
     /// The transformed text
@@ -221,17 +340,18 @@ impl GroqLlmClient {
         &self,
         selected_text: &str,
         command: &str,
-    ) -> Result<String, String> {
+        custom_prompt: Option<&str>,
+    ) -> Result<String, GroqError> {
         // Check rate limit before making API call
         check_rate_limit(Service::Groq)?;
 
         #[cfg(debug_assertions)]
         {
-            println!("Transforming text with Groq LLM...");
-            println!("Command: {}", command);
+            log::info!("Transforming text with Groq LLM...");
+            log::debug!("Command: {}", command);
         }
 
-        let (api_url, api_key) = self.get_api_config();
+        let (api_url, api_key) = self.get_api_config()?;
 
         let user_message = format!(
             "SELECTED TEXT:\n\"\"\"\n{}\n\"\"\"\n\nCOMMAND: \"{}\"",
@@ -239,11 +359,11 @@ impl GroqLlmClient {
         );
 
         let request = ChatRequest {
-            model: GROQ_MODEL.to_string(),
+            model: self.model.clone(),
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: TRANSFORM_SYSTEM_PROMPT.to_string(),
+                    content: system_prompt(custom_prompt, TRANSFORM_SYSTEM_PROMPT),
                 },
                 Message {
                     role: "user".to_string(),
@@ -263,6 +383,7 @@ impl GroqLlmClient {
     /// # Arguments
     /// * `transcript` - The raw transcription from speech-to-text
     /// * `style_prompt` - Optional style guidance (e.g., "casual", "professional")
+    /// * `custom_prompt` - The user's system prompt, replacing the built-in one
     ///
     /// # Returns
     /// The enhanced text
@@ -270,22 +391,24 @@ impl GroqLlmClient {
         &self,
         transcript: &str,
         style_prompt: Option<&str>,
-    ) -> Result<String, String> {
+        custom_prompt: Option<&str>,
+    ) -> Result<String, GroqError> {
         // Check rate limit before making API call
         check_rate_limit(Service::Groq)?;
 
-        println!("Enhancing text with Groq LLM...");
+        log::info!("Enhancing text with Groq LLM...");
 
-        let (api_url, api_key) = self.get_api_config();
+        let (api_url, api_key) = self.get_api_config()?;
 
-        // Build system prompt with optional style guidance
+        // Build system prompt (custom or built-in) with optional style guidance
+        let base_prompt = system_prompt(custom_prompt, ENHANCE_SYSTEM_PROMPT);
         let system_prompt = match style_prompt {
-            Some(style) => format!("{}\n\nStyle guidance: {}", ENHANCE_SYSTEM_PROMPT, style),
-            None => ENHANCE_SYSTEM_PROMPT.to_string(),
+            Some(style) => format!("{}\n\nStyle guidance: {}", base_prompt, style),
+            None => base_prompt,
         };
 
         let request = ChatRequest {
-            model: GROQ_MODEL.to_string(),
+            model: self.model.clone(),
             messages: vec![
                 Message {
                     role: "system".to_string(),
@@ -304,40 +427,43 @@ impl GroqLlmClient {
             .await
     }
 
-    /// Send a request to the Groq API (or proxy)
+    /// Send a request to the Groq API (or proxy).
+    /// Rate limiting, server errors, timeouts and connection failures are
+    /// retried with backoff (honoring Retry-After); auth and other 4xx errors
+    /// fail immediately.
     async fn send_request(
         &self,
         api_url: &str,
         api_key: Option<&str>,
         request: &ChatRequest,
-    ) -> Result<String, String> {
+    ) -> Result<String, GroqError> {
         // Serialize request body for signing
         let body_bytes = serde_json::to_vec(request)
             .map_err(|e| format!("Failed to serialize request: {}", e))?;
 
-        let mut http_request = self
-            .client
-            .post(api_url)
-            .header("Content-Type", "application/json");
-
-        // Add Authorization header only for direct API (dev mode)
-        // Add HMAC signature for proxy mode
-        if let Some(key) = api_key {
-            http_request = http_request.header("Authorization", format!("Bearer {}", key));
-        } else {
-            // Production mode: add HMAC signature for proxy authentication
-            let (timestamp, nonce, signature) = signing::sign_request(&body_bytes);
-            http_request = http_request
-                .header("X-Murmur-Timestamp", timestamp)
-                .header("X-Murmur-Nonce", nonce)
-                .header("X-Murmur-Signature", signature);
-        }
-
-        let response = http_request
-            .body(body_bytes)
-            .send()
-            .await
-            .map_err(|e| format!("Failed to send request to Groq: {}", e))?;
+        let response = http_client::send_with_retry(&self.retry, "GROQ", || {
+            let http_request = self
+                .client
+                .post(api_url)
+                .header("Content-Type", "application/json")
+                .body(body_bytes.clone());
+
+            // Add Authorization header only for direct API (dev mode)
+            // Add HMAC signature for proxy mode
+            if let Some(key) = api_key {
+                Ok(http_request.header("Authorization", format!("Bearer {}", key)))
+            } else {
+                // Production mode: add HMAC signature for proxy authentication.
+                // Signed per attempt so a retry doesn't reuse the nonce.
+                let (timestamp, nonce, signature) = signing::sign_request(&body_bytes);
+                Ok(http_request
+                    .header("X-Murmur-Timestamp", timestamp)
+                    .header("X-Murmur-Nonce", nonce)
+                    .header("X-Murmur-Signature", signature))
+            }
+        })
+        .await
+        .map_err(|e| GroqError::Unavailable(format!("Failed to send request to Groq: {}", e)))?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -345,7 +471,7 @@ impl GroqLlmClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Groq API error ({}): {}", status, error_text));
+            return Err(GroqError::from_status(status, &error_text));
         }
 
         let chat_response: ChatResponse = response
@@ -357,13 +483,13 @@ impl GroqLlmClient {
             .choices
             .first()
             .map(|c| c.message.content.trim().to_string())
-            .ok_or("No response content from Groq")?;
+            .ok_or_else(|| GroqError::Failed("No response content from Groq".to_string()))?;
 
         // Clean up any accidental quote/code fence wrapping from LLM
         let cleaned = strip_wrapping(&result);
 
         #[cfg(debug_assertions)]
-        println!("Groq LLM result: {}", cleaned);
+        log::debug!("Groq LLM result: {}", cleaned);
 
         Ok(cleaned)
     }
@@ -378,4 +504,137 @@ mod tests {
         assert!(!TRANSFORM_SYSTEM_PROMPT.is_empty());
         assert!(!ENHANCE_SYSTEM_PROMPT.is_empty());
     }
+
+    #[test]
+    fn test_prompt_templates() {
+        assert!(validate_prompt_template("Enhancement", ENHANCE_SYSTEM_PROMPT).is_ok());
+        assert!(validate_prompt_template("Transform", TRANSFORM_SYSTEM_PROMPT).is_ok());
+        assert!(validate_prompt_template("Enhancement", "   ").is_err());
+        let too_long = "x".repeat(MAX_PROMPT_TEMPLATE_CHARS + 1);
+        assert!(validate_prompt_template("Enhancement", &too_long).is_err());
+
+        assert_eq!(
+            system_prompt(None, ENHANCE_SYSTEM_PROMPT),
+            ENHANCE_SYSTEM_PROMPT
+        );
+        assert_eq!(
+            system_prompt(Some(" "), ENHANCE_SYSTEM_PROMPT),
+            ENHANCE_SYSTEM_PROMPT
+        );
+        assert_eq!(
+            system_prompt(Some("Be terse."), ENHANCE_SYSTEM_PROMPT),
+            "Be terse."
+        );
+    }
+
+    #[test]
+    fn test_resolve_model() {
+        assert_eq!(
+            resolve_model("llama-3.1-8b-instant"),
+            "llama-3.1-8b-instant"
+        );
+        assert_eq!(resolve_model(" qwen/qwen3-32b "), "qwen/qwen3-32b");
+        assert_eq!(resolve_model(""), DEFAULT_GROQ_MODEL);
+        assert_eq!(resolve_model("gpt-4o"), DEFAULT_GROQ_MODEL);
+        assert!(is_known_model(DEFAULT_GROQ_MODEL));
+    }
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const TEST_RETRY: RetryPolicy = RetryPolicy {
+        max_retries: 2,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(50),
+        retry_timeouts: true,
+        min_timeout: None,
+    };
+
+    /// Chat endpoint answering each request with the next status in
+    /// `statuses` (repeating the last one); 200s carry a completion.
+    /// Returns its URL and a hit counter.
+    async fn mock_groq(statuses: Vec<u16>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let hit = counter.fetch_add(1, Ordering::SeqCst);
+                let status = statuses[hit.min(statuses.len() - 1)];
+                let mut buf = [0u8; 4096];
+                let _ = socket.read(&mut buf).await;
+                let body = if status == 200 {
+                    r#"{"choices":[{"message":{"content":"Hello there."}}]}"#
+                } else {
+                    r#"{"error":{"message":"test"}}"#
+                };
+                let response = format!(
+                    "HTTP/1.1 {} Test\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, hits)
+    }
+
+    async fn enhance(url: &str) -> Result<String, GroqError> {
+        let client = GroqLlmClient {
+            client: Box::leak(Box::new(Client::new())),
+            model: DEFAULT_GROQ_MODEL.to_string(),
+            retry: TEST_RETRY,
+        };
+        let request = ChatRequest {
+            model: client.model.clone(),
+            messages: vec![Message {
+                role: "user".to_string(),
+                content: "hello there".to_string(),
+            }],
+            temperature: 0.3,
+            max_tokens: 16,
+        };
+        client.send_request(url, Some("test-key"), &request).await
+    }
+
+    #[tokio::test]
+    async fn test_send_request_retries_transient_errors() {
+        let (url, hits) = mock_groq(vec![429, 503, 200]).await;
+        assert_eq!(enhance(&url).await, Ok("Hello there.".to_string()));
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_request_gives_up_after_retries() {
+        let (url, hits) = mock_groq(vec![503]).await;
+        assert!(matches!(
+            enhance(&url).await,
+            Err(GroqError::Unavailable(_))
+        ));
+        assert_eq!(
+            hits.load(Ordering::SeqCst),
+            1 + TEST_RETRY.max_retries as usize
+        );
+    }
+
+    #[tokio::test]
+    async fn test_send_request_does_not_retry_fatal_errors() {
+        let (url, hits) = mock_groq(vec![401]).await;
+        assert!(matches!(
+            enhance(&url).await,
+            Err(GroqError::Unauthorized(_))
+        ));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+
+        let (url, hits) = mock_groq(vec![400]).await;
+        assert!(matches!(enhance(&url).await, Err(GroqError::Failed(_))));
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
 }
@@ -0,0 +1,170 @@
+//! Connectivity and credential checks for the configured providers.
+//!
+//! Backs the settings "test connection" button: one cheap authenticated
+//! request per provider (Groq's model list, or a 1-token chat through the
+//! proxy; Deepgram's project list). Nothing is transcribed or stored.
+
+use crate::deepgram;
+use crate::error::sanitize_error_message;
+use crate::groq_llm::DEFAULT_GROQ_MODEL;
+use crate::http_client::{self, NO_RETRY};
+use crate::rate_limit::{check_rate_limit, Service};
+use crate::signing;
+use reqwest::{Response, StatusCode};
+use serde::Serialize;
+
+const GROQ_MODELS_URL: &str = "https://api.groq.com/openai/v1/models";
+const DEEPGRAM_PROJECTS_URL: &str = "https://api.deepgram.com/v1/projects";
+
+/// Result of checking one provider
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProviderStatus {
+    /// The provider answered (with any status)
+    pub reachable: bool,
+    /// The provider accepted our credentials
+    pub authenticated: bool,
+    /// What went wrong, with secrets and paths stripped
+    pub error: Option<String>,
+}
+
+impl ProviderStatus {
+    fn unreachable(error: &str) -> Self {
+        ProviderStatus {
+            reachable: false,
+            authenticated: false,
+            error: Some(sanitize_error_message(error)),
+        }
+    }
+
+    /// Status for the response to a check request
+    fn from_status(status: StatusCode) -> Self {
+        let error = if status.is_success() {
+            None
+        } else if status == StatusCode::UNAUTHORIZED || status == StatusCode::FORBIDDEN {
+            Some(format!("Credentials were rejected ({})", status))
+        } else {
+            Some(format!("Unexpected response ({})", status))
+        };
+        ProviderStatus {
+            reachable: true,
+            authenticated: status.is_success(),
+            error,
+        }
+    }
+
+    fn from_response(result: Result<Response, String>) -> Self {
+        match result {
+            Ok(response) => Self::from_status(response.status()),
+            Err(e) => Self::unreachable(&e),
+        }
+    }
+}
+
+/// Health of every configured provider
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderHealth {
+    /// Groq (transcription and enhancement), through the proxy or the user's key
+    pub groq: ProviderStatus,
+    /// Deepgram, when a Deepgram API key is set
+    pub deepgram: Option<ProviderStatus>,
+}
+
+/// Check each configured provider. Errors only when checks are rate limited.
+pub async fn check_providers() -> Result<ProviderHealth, String> {
+    check_rate_limit(Service::HealthCheck)?;
+
+    let deepgram_check = async {
+        match deepgram::api_key() {
+            Some(key) => Some(check_deepgram(&key).await),
+            None => None,
+        }
+    };
+    let (groq, deepgram) = tokio::join!(check_groq(), deepgram_check);
+
+    log::info!(
+        "[HEALTH] Groq: {:?}, Deepgram: {:?}",
+        groq,
+        deepgram.as_ref().map(|d| d.authenticated)
+    );
+    Ok(ProviderHealth { groq, deepgram })
+}
+
+/// With the user's key, list models; through the proxy (which only forwards
+/// transcription and chat), send a signed 1-token chat request.
+async fn check_groq() -> ProviderStatus {
+    let (_, chat_url, api_key) = match signing::get_api_config() {
+        Ok(config) => config,
+        Err(e) => return ProviderStatus::unreachable(&e),
+    };
+    let client = match http_client::get_client() {
+        Ok(client) => client,
+        Err(e) => return ProviderStatus::unreachable(&e),
+    };
+
+    let body = serde_json::json!({
+        "model": DEFAULT_GROQ_MODEL,
+        "messages": [{ "role": "user", "content": "ping" }],
+        "max_tokens": 1,
+    })
+    .to_string();
+
+    let result = http_client::send_with_retry(&NO_RETRY, "HEALTH", || match api_key {
+        Some(ref key) => Ok(client
+            .get(GROQ_MODELS_URL)
+            .header("Authorization", format!("Bearer {}", key))),
+        None => {
+            let (timestamp, nonce, signature) = signing::sign_request(body.as_bytes());
+            Ok(client
+                .post(chat_url)
+                .header("Content-Type", "application/json")
+                .header("X-Murmur-Timestamp", timestamp)
+                .header("X-Murmur-Nonce", nonce)
+                .header("X-Murmur-Signature", signature)
+                .body(body.clone()))
+        }
+    })
+    .await;
+    ProviderStatus::from_response(result)
+}
+
+async fn check_deepgram(api_key: &str) -> ProviderStatus {
+    let client = match http_client::get_client() {
+        Ok(client) => client,
+        Err(e) => return ProviderStatus::unreachable(&e),
+    };
+
+    let result = http_client::send_with_retry(&NO_RETRY, "HEALTH", || {
+        Ok(client
+            .get(DEEPGRAM_PROJECTS_URL)
+            .header("Authorization", format!("Token {}", api_key)))
+    })
+    .await;
+    ProviderStatus::from_response(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_from_response_code() {
+        let ok = ProviderStatus::from_status(StatusCode::OK);
+        assert!(ok.reachable && ok.authenticated);
+        assert_eq!(ok.error, None);
+
+        let rejected = ProviderStatus::from_status(StatusCode::UNAUTHORIZED);
+        assert!(rejected.reachable && !rejected.authenticated);
+        assert!(rejected.error.unwrap().contains("rejected"));
+
+        let down = ProviderStatus::from_status(StatusCode::SERVICE_UNAVAILABLE);
+        assert!(down.reachable && !down.authenticated);
+        assert!(down.error.unwrap().contains("503"));
+    }
+
+    #[test]
+    fn test_unreachable_error_is_sanitized() {
+        let status = ProviderStatus::unreachable("connect failed for key gsk_abcdefghijklmnop");
+        assert!(!status.reachable && !status.authenticated);
+        assert!(!status.error.unwrap().contains("abcdefghijklmnop"));
+    }
+}
@@ -0,0 +1,170 @@
+//! Transcription history.
+//!
+//! When enabled in preferences, every completed dictation is appended to a
+//! rolling log so text pasted into the wrong window can be recovered. Only
+//! text is stored, never audio.
+//!
+//! Entries persist as JSON lines to `history.jsonl` in the app's data directory.
+
+use crate::state::{DictationMode, TranscriptionCompleteEvent};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of entries kept (oldest are dropped first)
+const MAX_ENTRIES: usize = 500;
+
+/// A completed dictation.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: u64,
+    pub raw_transcript: String,
+    /// Text that was inserted (after enhancement and replacements)
+    pub enhanced_text: String,
+    pub mode: DictationMode,
+    /// Bundle ID of the app the text was dictated into
+    pub app_bundle_id: Option<String>,
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+fn history_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("keyhold").join("history.jsonl"))
+}
+
+/// Load all entries, oldest first. Unreadable lines are skipped.
+fn load_entries() -> Vec<HistoryEntry> {
+    history_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|content| parse_entries(&content))
+        .unwrap_or_default()
+}
+
+fn parse_entries(content: &str) -> Vec<HistoryEntry> {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+fn save_entries(entries: &[HistoryEntry]) -> Result<(), String> {
+    let path = history_path().ok_or("Could not find data directory")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let mut content = String::new();
+    for entry in entries {
+        let line = serde_json::to_string(entry)
+            .map_err(|e| format!("Failed to serialize history entry: {}", e))?;
+        content.push_str(&line);
+        content.push('\n');
+    }
+    fs::write(&path, content).map_err(|e| format!("Failed to write history: {}", e))
+}
+
+/// Record a completed dictation and persist it.
+pub fn add_entry(
+    event: &TranscriptionCompleteEvent,
+    mode: DictationMode,
+    app_bundle_id: Option<&str>,
+) -> Result<HistoryEntry, String> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let entry = HistoryEntry {
+        id: format!("{:x}", created_at),
+        created_at,
+        raw_transcript: event.raw_transcript.clone(),
+        enhanced_text: event.enhanced_text.clone(),
+        mode,
+        app_bundle_id: app_bundle_id.map(|s| s.to_string()),
+        confidence: event.confidence,
+    };
+
+    let mut entries = load_entries();
+    entries.push(entry.clone());
+    trim_to_limit(&mut entries);
+    save_entries(&entries)?;
+
+    Ok(entry)
+}
+
+/// The most recent `limit` entries, newest first.
+pub fn recent_entries(limit: usize) -> Vec<HistoryEntry> {
+    newest_first(load_entries(), limit)
+}
+
+fn newest_first(entries: Vec<HistoryEntry>, limit: usize) -> Vec<HistoryEntry> {
+    entries.into_iter().rev().take(limit).collect()
+}
+
+/// Delete an entry by id.
+pub fn delete_entry(id: &str) -> Result<(), String> {
+    let mut entries = load_entries();
+    entries.retain(|e| e.id != id);
+    save_entries(&entries)
+}
+
+/// Delete all entries.
+pub fn clear_entries() -> Result<(), String> {
+    save_entries(&[])
+}
+
+/// Drop the oldest entries beyond MAX_ENTRIES.
+fn trim_to_limit(entries: &mut Vec<HistoryEntry>) {
+    if entries.len() > MAX_ENTRIES {
+        let excess = entries.len() - MAX_ENTRIES;
+        entries.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(i: usize) -> HistoryEntry {
+        HistoryEntry {
+            id: i.to_string(),
+            created_at: i as u64,
+            raw_transcript: format!("raw {}", i),
+            enhanced_text: format!("Enhanced {}.", i),
+            mode: DictationMode::Dictation,
+            app_bundle_id: None,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn test_trim_to_limit_drops_oldest() {
+        let mut entries: Vec<HistoryEntry> = (0..MAX_ENTRIES + 3).map(entry).collect();
+        trim_to_limit(&mut entries);
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0].id, "3");
+    }
+
+    #[test]
+    fn test_newest_first_with_limit() {
+        let entries: Vec<HistoryEntry> = (0..5).map(entry).collect();
+        let ids: Vec<String> = newest_first(entries, 2).into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, vec!["4", "3"]);
+    }
+
+    #[test]
+    fn test_parse_entries_skips_bad_lines() {
+        let good = serde_json::to_string(&entry(1)).unwrap();
+        let content = format!("{}\nnot json\n\n{}\n", good, good);
+        assert_eq!(parse_entries(&content).len(), 2);
+        assert!(good.contains("\"enhancedText\":\"Enhanced 1.\""));
+        assert!(good.contains("\"mode\":\"dictation\""));
+    }
+}
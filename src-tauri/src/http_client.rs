@@ -5,37 +5,247 @@
 //! - HTTPS-only enforcement
 //! - Reasonable timeouts
 //! - Connection pooling and reuse via global cached clients
+//! - Bounded retries with exponential backoff for transient failures
+//! - Cancellation of in-flight requests
 
-use reqwest::Client;
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::OnceLock;
 use std::time::Duration;
+use tokio_util::sync::CancellationToken;
 
-/// Default timeout for API requests (30 seconds)
-const DEFAULT_TIMEOUT_SECS: u64 = 30;
+/// Default timeout for LLM and other API requests (20 seconds)
+const LLM_TIMEOUT_SECS: u64 = 20;
 
-/// Extended timeout for audio transcription (120 seconds)
-const TRANSCRIPTION_TIMEOUT_SECS: u64 = 120;
+/// Longer timeout for audio transcription uploads (30 seconds)
+const TRANSCRIPTION_TIMEOUT_SECS: u64 = 30;
 
-/// Global cached client for standard API calls (30s timeout)
+/// Global cached client for standard API calls (20s timeout)
 static CACHED_CLIENT: OnceLock<Client> = OnceLock::new();
 
-/// Global cached client for transcription (120s timeout)
+/// Global cached client for transcription (30s timeout)
 static CACHED_TRANSCRIPTION_CLIENT: OnceLock<Client> = OnceLock::new();
 
-/// Get the shared secure HTTP client (30s timeout).
+/// Per-request timeout set by the `network_timeout_ms` preference (0 = client defaults)
+static REQUEST_TIMEOUT_MS: AtomicU64 = AtomicU64::new(0);
+
+/// Override the timeout of every request sent through `send_with_retry`.
+/// None restores the client defaults (20s, 30s for transcription).
+pub fn set_request_timeout(timeout_ms: Option<u64>) {
+    REQUEST_TIMEOUT_MS.store(timeout_ms.unwrap_or(0), Ordering::Relaxed);
+}
+
+fn request_timeout() -> Option<Duration> {
+    let ms = REQUEST_TIMEOUT_MS.load(Ordering::Relaxed);
+    (ms > 0).then(|| Duration::from_millis(ms))
+}
+
+/// The override for a request under `policy`, raised to its `min_timeout`
+fn policy_timeout(policy: &RetryPolicy, timeout: Option<Duration>) -> Option<Duration> {
+    timeout.map(|t| policy.min_timeout.map_or(t, |min| t.max(min)))
+}
+
+/// How many times, and how patiently, to retry a transient failure.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each retry
+    pub base_delay: Duration,
+    /// Upper bound for any single wait, including a server's Retry-After
+    pub max_delay: Duration,
+    /// Also retry requests that timed out (not worth it for long uploads)
+    pub retry_timeouts: bool,
+    /// Floor for the `network_timeout_ms` override, so a timeout tuned for
+    /// LLM calls doesn't cut off audio uploads
+    pub min_timeout: Option<Duration>,
+}
+
+/// Retry policy for Whisper transcription: up to 3 retries (0.5s, 1s, 2s + jitter)
+pub const TRANSCRIPTION_RETRY: RetryPolicy = RetryPolicy {
+    max_retries: 3,
+    base_delay: Duration::from_millis(500),
+    max_delay: Duration::from_secs(8),
+    retry_timeouts: false,
+    min_timeout: Some(Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS)),
+};
+
+/// Retry policy for Groq chat requests: up to 2 retries (0.5s, 1s + jitter).
+/// Kept short since the user is waiting and can fall back to the raw transcript.
+pub const LLM_RETRY: RetryPolicy = RetryPolicy {
+    max_retries: 2,
+    base_delay: Duration::from_millis(500),
+    max_delay: Duration::from_secs(4),
+    retry_timeouts: true,
+    min_timeout: None,
+};
+
+/// Single attempt, for checks that should report a failure right away
+pub const NO_RETRY: RetryPolicy = RetryPolicy {
+    max_retries: 0,
+    base_delay: Duration::from_millis(0),
+    max_delay: Duration::from_millis(0),
+    retry_timeouts: false,
+    min_timeout: None,
+};
+
+/// Rate limiting and server-side errors are worth retrying; other 4xx
+/// (bad request, auth) will fail the same way again.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Parse a `Retry-After` header given in seconds.
+fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Exponential backoff for the given retry (0-based), plus up to 50% jitter.
+fn backoff_delay(policy: &RetryPolicy, retry: u32) -> Duration {
+    let base = policy.base_delay.saturating_mul(2u32.saturating_pow(retry));
+    let jitter_ms = rand::thread_rng().gen_range(0..=base.as_millis() as u64 / 2);
+    (base + Duration::from_millis(jitter_ms)).min(policy.max_delay)
+}
+
+/// Send a request, retrying on 429/5xx responses and connection errors
+/// (and timeouts, if the policy allows).
+///
+/// `build` is called once per attempt, since multipart bodies and request
+/// signatures (nonce/timestamp) can't be reused. A `Retry-After` header is
+/// honored when it fits within `policy.max_delay`; otherwise we give up early.
+///
+/// # Returns
+/// The first successful or non-retryable response, or the last response once
+/// retries are exhausted. Callers handle the status as before. Errors are the
+/// underlying send error (or "Request timed out"), for the caller to add
+/// context to.
+pub async fn send_with_retry<F>(
+    policy: &RetryPolicy,
+    label: &str,
+    build: F,
+) -> Result<Response, String>
+where
+    F: FnMut() -> Result<RequestBuilder, String>,
+{
+    let timeout = policy_timeout(policy, request_timeout());
+    send_with_timeout(policy, label, timeout, build).await
+}
+
+/// `send_with_retry` with an explicit per-request timeout (None = the client's)
+async fn send_with_timeout<F>(
+    policy: &RetryPolicy,
+    label: &str,
+    timeout: Option<Duration>,
+    mut build: F,
+) -> Result<Response, String>
+where
+    F: FnMut() -> Result<RequestBuilder, String>,
+{
+    let mut retry = 0;
+    loop {
+        let mut request = build()?;
+        if let Some(timeout) = timeout {
+            request = request.timeout(timeout);
+        }
+
+        let wait = match request.send().await {
+            Ok(response) if !is_retryable_status(response.status()) => return Ok(response),
+            Ok(response) => {
+                if retry >= policy.max_retries {
+                    return Ok(response);
+                }
+                let wait = match retry_after(&response) {
+                    Some(after) if after > policy.max_delay => {
+                        log::warn!(
+                            "[{}] Server asked to retry after {:?}, giving up",
+                            label,
+                            after
+                        );
+                        return Ok(response);
+                    }
+                    Some(after) => after,
+                    None => backoff_delay(policy, retry),
+                };
+                log::warn!(
+                    "[{}] Request failed with {}, retrying in {:?} ({}/{})",
+                    label,
+                    response.status(),
+                    wait,
+                    retry + 1,
+                    policy.max_retries
+                );
+                wait
+            }
+            Err(e)
+                if (e.is_connect() || (policy.retry_timeouts && e.is_timeout()))
+                    && retry < policy.max_retries =>
+            {
+                let wait = backoff_delay(policy, retry);
+                log::warn!(
+                    "[{}] Request failed ({}), retrying in {:?} ({}/{})",
+                    label,
+                    e,
+                    wait,
+                    retry + 1,
+                    policy.max_retries
+                );
+                wait
+            }
+            Err(e) if e.is_timeout() => {
+                log::warn!("[{}] Request timed out", label);
+                return Err(match timeout {
+                    Some(timeout) => format!("Request timed out after {:?}", timeout),
+                    None => "Request timed out".to_string(),
+                });
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+
+        tokio::time::sleep(wait).await;
+        retry += 1;
+    }
+}
+
+/// Error returned when a request is abandoned because the user cancelled.
+pub const CANCELLED_ERROR: &str = "Cancelled by user";
+
+/// Run a request until it completes or `token` is cancelled.
+/// On cancellation the future is dropped, which aborts the in-flight HTTP request.
+pub async fn cancellable<T, E: From<String>>(
+    token: &CancellationToken,
+    request: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    tokio::select! {
+        biased;
+        _ = token.cancelled() => Err(CANCELLED_ERROR.to_string().into()),
+        result = request => result,
+    }
+}
+
+/// Get the shared secure HTTP client (20s timeout).
 /// This reuses connections across requests for better performance.
 pub fn get_client() -> Result<&'static Client, String> {
     Ok(CACHED_CLIENT.get_or_init(|| {
         Client::builder()
             .use_native_tls()
             .https_only(true)
-            .timeout(Duration::from_secs(DEFAULT_TIMEOUT_SECS))
+            .timeout(Duration::from_secs(LLM_TIMEOUT_SECS))
             .build()
             .expect("Failed to create HTTP client - this should never happen")
     }))
 }
 
-/// Get the shared transcription client (120s timeout).
+/// Get the shared transcription client (30s timeout).
 /// This reuses connections for transcription requests.
 pub fn get_transcription_client() -> Result<&'static Client, String> {
     Ok(CACHED_TRANSCRIPTION_CLIENT.get_or_init(|| {
@@ -68,6 +278,233 @@ mod tests {
         );
     }
 
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const TEST_RETRY: RetryPolicy = RetryPolicy {
+        max_retries: 3,
+        base_delay: Duration::from_millis(1),
+        max_delay: Duration::from_millis(50),
+        retry_timeouts: false,
+        min_timeout: None,
+    };
+
+    /// Minimal HTTP server answering each request with the next status in
+    /// `statuses` (repeating the last one). Returns its URL and a hit counter.
+    async fn mock_server(statuses: Vec<(u16, Option<&'static str>)>) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let Ok((mut socket, _)) = listener.accept().await else {
+                    break;
+                };
+                let hit = counter.fetch_add(1, Ordering::SeqCst);
+                let (status, retry_after) = statuses[hit.min(statuses.len() - 1)];
+                let mut buf = [0u8; 1024];
+                let _ = socket.read(&mut buf).await;
+                let extra = retry_after
+                    .map(|s| format!("Retry-After: {}\r\n", s))
+                    .unwrap_or_default();
+                let response = format!(
+                    "HTTP/1.1 {} Test\r\nContent-Length: 0\r\nConnection: close\r\n{}\r\n",
+                    status, extra
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        (url, hits)
+    }
+
+    async fn send(url: &str) -> Result<Response, String> {
+        let client = Client::new();
+        send_with_retry(&TEST_RETRY, "TEST", || Ok(client.get(url))).await
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_statuses_until_success() {
+        let (url, hits) = mock_server(vec![(429, None), (503, None), (200, None)]).await;
+        let response = send(&url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(hits.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (url, hits) = mock_server(vec![(500, None)]).await;
+        let response = send(&url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        assert_eq!(
+            hits.load(Ordering::SeqCst),
+            1 + TEST_RETRY.max_retries as usize
+        );
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        for status in [400, 401, 403] {
+            let (url, hits) = mock_server(vec![(status, None)]).await;
+            let response = send(&url).await.unwrap();
+            assert_eq!(response.status().as_u16(), status);
+            assert_eq!(hits.load(Ordering::SeqCst), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_when_retry_after_too_long() {
+        let (url, hits) = mock_server(vec![(429, Some("120"))]).await;
+        let response = send(&url).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(hits.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retries_connection_errors() {
+        // Bind then drop a listener to get a port nothing is listening on
+        let port = TcpListener::bind("127.0.0.1:0")
+            .await
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let attempts = AtomicUsize::new(0);
+        let client = Client::new();
+        let url = format!("http://127.0.0.1:{}/", port);
+        let result = send_with_retry(&TEST_RETRY, "TEST", || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Ok(client.get(&url))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            1 + TEST_RETRY.max_retries as usize
+        );
+    }
+
+    /// Server that accepts connections but never answers
+    async fn silent_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut held = Vec::new();
+            while let Ok((socket, _)) = listener.accept().await {
+                held.push(socket);
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout() {
+        let url = silent_server().await;
+        let client = Client::new();
+        let timeout = Some(Duration::from_millis(100));
+
+        let attempts = AtomicUsize::new(0);
+        let result = send_with_timeout(&TEST_RETRY, "TEST", timeout, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Ok(client.get(&url))
+        })
+        .await;
+        assert_eq!(result.unwrap_err(), "Request timed out after 100ms");
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let policy = RetryPolicy {
+            retry_timeouts: true,
+            ..TEST_RETRY
+        };
+        let attempts = AtomicUsize::new(0);
+        let result = send_with_timeout(&policy, "TEST", timeout, || {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Ok(client.get(&url))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(
+            attempts.load(Ordering::SeqCst),
+            1 + policy.max_retries as usize
+        );
+    }
+
+    #[test]
+    fn test_policy_timeout_keeps_transcription_floor() {
+        let short = Some(Duration::from_secs(10));
+        assert_eq!(policy_timeout(&LLM_RETRY, short), short);
+        assert_eq!(
+            policy_timeout(&TRANSCRIPTION_RETRY, short),
+            Some(Duration::from_secs(TRANSCRIPTION_TIMEOUT_SECS))
+        );
+
+        let long = Some(Duration::from_secs(60));
+        assert_eq!(policy_timeout(&TRANSCRIPTION_RETRY, long), long);
+        assert_eq!(policy_timeout(&TRANSCRIPTION_RETRY, None), None);
+    }
+
+    #[test]
+    fn test_backoff_delay_grows_and_is_capped() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(500),
+            retry_timeouts: false,
+            min_timeout: None,
+        };
+        let first = backoff_delay(&policy, 0);
+        assert!(first >= Duration::from_millis(100) && first <= Duration::from_millis(150));
+        let second = backoff_delay(&policy, 1);
+        assert!(second >= Duration::from_millis(200) && second <= Duration::from_millis(300));
+        assert_eq!(backoff_delay(&policy, 4), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_aborts_in_flight_request() {
+        let url = silent_server().await;
+
+        let token = CancellationToken::new();
+        let canceller = token.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            canceller.cancel();
+        });
+
+        // Stand-in for the dictation pipeline: completion is only reported
+        // after the request returns
+        let completed = AtomicUsize::new(0);
+        let pipeline = async {
+            let response = cancellable(&token, send(&url)).await?;
+            completed.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, String>(response)
+        };
+
+        let result = tokio::time::timeout(Duration::from_secs(5), pipeline)
+            .await
+            .expect("cancellation should abort the request promptly");
+        assert_eq!(result.unwrap_err(), CANCELLED_ERROR);
+        assert_eq!(completed.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancellable_passes_through_results() {
+        let token = CancellationToken::new();
+        assert_eq!(
+            cancellable(&token, async { Ok::<_, String>(7) }).await,
+            Ok(7)
+        );
+
+        token.cancel();
+        assert_eq!(
+            cancellable(&token, async { Ok::<_, String>(7) }).await,
+            Err(CANCELLED_ERROR.to_string())
+        );
+    }
+
     #[tokio::test]
     async fn test_https_only_enforcement() {
         let client = get_client().unwrap();
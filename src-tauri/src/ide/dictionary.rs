@@ -4,10 +4,23 @@
 //! - "A P I" → "API"
 //! - "jason" → "JSON"
 //! - "sequel" → "SQL"
+//!
+//! A few terms only make sense in some languages ("vector" → "Vec" is Rust);
+//! when the editor's language is known they're skipped everywhere else.
+//!
+//! Users can add their own project jargon ("kube cuddle" → "kubectl"), stored in
+//! preferences. Custom entries are applied after the built-in terms and win over
+//! a built-in term with the same spoken form.
 
 use regex::Regex;
 use std::sync::LazyLock;
 
+use crate::config::StoredPreferences;
+use crate::replacements::{apply_replacements, ReplacementRule};
+
+/// Maximum number of custom dictionary entries a user can store
+const MAX_CUSTOM_ENTRIES: usize = 500;
+
 /// Programming terms: (spoken pattern, correct form)
 /// Patterns are case-insensitive.
 const PROGRAMMING_TERMS: &[(&str, &str)] = &[
@@ -153,42 +166,135 @@ const PROGRAMMING_TERMS: &[(&str, &str)] = &[
     ("git status", "git status"),
 ];
 
-/// Compiled regex patterns for dictionary replacement.
-/// Uses LazyLock for thread-safe lazy initialization.
-static DICTIONARY_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
-    PROGRAMMING_TERMS
+/// Built-in terms (by spoken pattern) that only apply in these languages.
+/// Applied everywhere when the editor's language is unknown.
+const LANGUAGE_SCOPED_TERMS: &[(&str, &[&str])] = &[
+    ("hash map", &["rust", "java", "kotlin"]),
+    ("hash set", &["rust", "java", "kotlin"]),
+    ("vector", &["rust"]),
+    ("vec", &["rust"]),
+];
+
+/// Whether a built-in term is scoped to other languages than `language`
+fn out_of_scope(spoken: &str, language: Option<&str>) -> bool {
+    let Some(language) = language else {
+        return false;
+    };
+    LANGUAGE_SCOPED_TERMS
         .iter()
-        .filter_map(|(pattern, replacement)| {
-            // Create case-insensitive word-boundary regex
-            let regex_pattern = format!(r"(?i)\b{}\b", regex::escape(pattern));
-            Regex::new(&regex_pattern).ok().map(|re| (re, *replacement))
-        })
-        .collect()
-});
+        .any(|(term, languages)| *term == spoken && !languages.contains(&language))
+}
+
+/// Compiled regex patterns for dictionary replacement, with the spoken pattern
+/// they came from. Uses LazyLock for thread-safe lazy initialization.
+static DICTIONARY_PATTERNS: LazyLock<Vec<(Regex, &'static str, &'static str)>> =
+    LazyLock::new(|| {
+        PROGRAMMING_TERMS
+            .iter()
+            .filter_map(|(pattern, replacement)| {
+                // Create case-insensitive word-boundary regex
+                let regex_pattern = format!(r"(?i)\b{}\b", regex::escape(pattern));
+                Regex::new(&regex_pattern)
+                    .ok()
+                    .map(|re| (re, *pattern, *replacement))
+            })
+            .collect()
+    });
 
-/// Apply programming dictionary to text.
+/// Apply the built-in dictionary, then the user's custom entries.
 ///
-/// Replaces commonly mis-transcribed programming terms with their correct forms.
+/// Built-in terms whose spoken form is overridden by a custom entry are skipped,
+/// so "jason" → "Jason" (custom) beats "jason" → "JSON" (built-in). Custom entries
+/// match case-insensitively on whole words and never touch `@`-tagged filenames.
+/// `language` is the editor's language, if known (see `LANGUAGE_SCOPED_TERMS`).
 ///
 /// # Examples
 /// ```ignore
-/// let result = apply_dictionary("Use the A P I to fetch jason data");
-/// assert_eq!(result, "Use the API to fetch JSON data");
+/// let custom = vec![("kube cuddle".to_string(), "kubectl".to_string())];
+/// assert_eq!(apply_dictionary_with_custom("run kube cuddle get pods", &custom, None), "run kubectl get pods");
 /// ```
-pub fn apply_dictionary(text: &str) -> String {
+pub fn apply_dictionary_with_custom(
+    text: &str,
+    custom: &[(String, String)],
+    language: Option<&str>,
+) -> String {
     let mut result = text.to_string();
 
-    for (regex, replacement) in DICTIONARY_PATTERNS.iter() {
-        result = regex.replace_all(&result, *replacement).to_string();
+    for (regex, spoken, replacement) in DICTIONARY_PATTERNS.iter() {
+        let overridden = custom
+            .iter()
+            .any(|(custom_spoken, _)| custom_spoken.eq_ignore_ascii_case(spoken));
+        if !overridden && !out_of_scope(spoken, language) {
+            result = regex.replace_all(&result, *replacement).to_string();
+        }
+    }
+
+    if !custom.is_empty() {
+        result = apply_replacements(&result, &custom_rules(custom));
     }
 
     result
 }
 
+/// Custom entries as whole-word, case-insensitive replacement rules.
+fn custom_rules(custom: &[(String, String)]) -> Vec<ReplacementRule> {
+    custom
+        .iter()
+        .map(|(spoken, written)| ReplacementRule::new(spoken.clone(), written.clone(), true, false))
+        .collect()
+}
+
+/// Load the user's custom dictionary entries from stored preferences.
+pub fn load_custom_entries() -> Vec<(String, String)> {
+    StoredPreferences::load()
+        .custom_dictionary
+        .unwrap_or_default()
+}
+
+fn save_custom_entries(entries: Vec<(String, String)>) -> Result<(), String> {
+    let mut stored = StoredPreferences::load();
+    stored.custom_dictionary = Some(entries);
+    stored.save()
+}
+
+/// Add (or replace) a custom entry and persist it.
+/// An existing entry with the same spoken form (ignoring case) is replaced.
+pub fn add_custom_entry(spoken: &str, written: &str) -> Result<(), String> {
+    let spoken = spoken.trim();
+    ReplacementRule::new(spoken.to_string(), written.to_string(), true, false).validate()?;
+
+    let mut entries = load_custom_entries();
+    entries.retain(|(s, _)| !s.eq_ignore_ascii_case(spoken));
+    if entries.len() >= MAX_CUSTOM_ENTRIES {
+        return Err(format!(
+            "Too many dictionary entries (max {})",
+            MAX_CUSTOM_ENTRIES
+        ));
+    }
+    entries.push((spoken.to_string(), written.to_string()));
+    save_custom_entries(entries)
+}
+
+/// Remove a custom entry by its spoken form (ignoring case) and persist the change.
+pub fn remove_custom_entry(spoken: &str) -> Result<(), String> {
+    let mut entries = load_custom_entries();
+    let before = entries.len();
+    entries.retain(|(s, _)| !s.eq_ignore_ascii_case(spoken.trim()));
+    if entries.len() == before {
+        return Err(format!("Dictionary entry not found: {}", spoken));
+    }
+    save_custom_entries(entries)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// The built-in dictionary alone
+    fn apply_dictionary(text: &str) -> String {
+        apply_dictionary_with_custom(text, &[], None)
+    }
+
     #[test]
     fn test_api_spaced() {
         assert_eq!(apply_dictionary("Use the A P I"), "Use the API");
@@ -241,4 +347,79 @@ mod tests {
     fn test_kubernetes() {
         assert_eq!(apply_dictionary("Deploy to K 8 S"), "Deploy to k8s");
     }
+
+    fn entries(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(s, w)| (s.to_string(), w.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_custom_entry_applied() {
+        let custom = entries(&[("kube cuddle", "kubectl")]);
+        assert_eq!(
+            apply_dictionary_with_custom("Run Kube Cuddle get pods", &custom, None),
+            "Run kubectl get pods"
+        );
+    }
+
+    #[test]
+    fn test_custom_entry_overrides_builtin() {
+        let custom = entries(&[("jason", "Jason")]);
+        assert_eq!(
+            apply_dictionary_with_custom("Ask jason about the A P I", &custom, None),
+            "Ask Jason about the API"
+        );
+        // Other built-in spellings of the term still apply
+        assert_eq!(
+            apply_dictionary_with_custom("Return Jay son data", &custom, None),
+            "Return JSON data"
+        );
+    }
+
+    #[test]
+    fn test_custom_entry_applied_after_builtin() {
+        // Built-in turns "sequel" into "SQL"; a custom entry can then refine it
+        let custom = entries(&[("SQL", "SQLite")]);
+        assert_eq!(
+            apply_dictionary_with_custom("Use sequel here", &custom, None),
+            "Use SQLite here"
+        );
+    }
+
+    #[test]
+    fn test_custom_entry_whole_words_only() {
+        let custom = entries(&[("pod", "Pod")]);
+        assert_eq!(
+            apply_dictionary_with_custom("pod podcast ipod", &custom, None),
+            "Pod podcast ipod"
+        );
+    }
+
+    #[test]
+    fn test_language_scoped_terms() {
+        assert_eq!(
+            apply_dictionary_with_custom("push to the vector", &[], Some("rust")),
+            "push to the Vec"
+        );
+        assert_eq!(
+            apply_dictionary_with_custom("push to the vector", &[], Some("python")),
+            "push to the vector"
+        );
+        // Unknown language keeps every term
+        assert_eq!(
+            apply_dictionary_with_custom("push to the vector", &[], None),
+            "push to the Vec"
+        );
+    }
+
+    #[test]
+    fn test_custom_entry_skips_tagged_filenames() {
+        let custom = entries(&[("config", "Config")]);
+        assert_eq!(
+            apply_dictionary_with_custom("Open @config.ts and the config", &custom, None),
+            "Open @config.ts and the Config"
+        );
+    }
 }
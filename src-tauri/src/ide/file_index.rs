@@ -2,14 +2,39 @@
 //!
 //! Indexes files in user-configured workspace roots for voice-based file tagging.
 //! Uses the `ignore` crate to respect .gitignore patterns.
+//! An optional file watcher keeps the index current as files are created,
+//! renamed, or deleted, without rebuilding it.
 
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
 use ignore::WalkBuilder;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
-use std::time::Instant;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 
 /// Maximum number of files to index per workspace.
-const MAX_FILES: usize = 10_000;
+pub const MAX_FILES: usize = 10_000;
+
+/// Maximum number of files across all workspace roots combined.
+pub const MAX_TOTAL_FILES: usize = 30_000;
+
+/// Maximum directory depth to index (matches the walker's limit).
+const MAX_DEPTH: usize = 20;
+
+/// How long the watcher waits for file events to settle before applying them.
+/// Git checkouts and builds touch many files at once; batching avoids
+/// re-locking the index and emitting an event for each one.
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Minimum time between progress reports while building an index.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Report progress at least every this many scanned files, even if the
+/// interval hasn't elapsed.
+const PROGRESS_EVERY_FILES: usize = 500;
 
 /// File extensions to include in the index.
 const INCLUDED_EXTENSIONS: &[&str] = &[
@@ -71,6 +96,81 @@ const INCLUDED_EXTENSIONS: &[&str] = &[
     "dockerfile",
 ];
 
+/// User adjustments to what gets indexed.
+///
+/// Extensions are lowercase and without the leading dot. The defaults index
+/// exactly the built-in extension list, capped at `MAX_FILES`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IndexConfig {
+    /// Extensions to index in addition to the built-in list (e.g. "ipynb")
+    pub extra_extensions: Vec<String>,
+    /// Built-in extensions to leave out (e.g. "txt")
+    pub excluded_extensions: Vec<String>,
+    /// Maximum number of files to index per workspace
+    pub max_files: usize,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        IndexConfig {
+            extra_extensions: Vec::new(),
+            excluded_extensions: Vec::new(),
+            max_files: MAX_FILES,
+        }
+    }
+}
+
+impl IndexConfig {
+    /// Check that every extension is lowercase without a leading dot and that
+    /// `max_files` is between 1 and `MAX_TOTAL_FILES`.
+    pub fn validate(&self) -> Result<(), String> {
+        for ext in self
+            .extra_extensions
+            .iter()
+            .chain(&self.excluded_extensions)
+        {
+            if ext.is_empty() {
+                return Err("File extensions can't be empty".to_string());
+            }
+            if ext.starts_with('.') {
+                return Err(format!(
+                    "File extension '{}' should not start with a dot",
+                    ext
+                ));
+            }
+            if ext
+                .chars()
+                .any(|c| c.is_uppercase() || c.is_whitespace() || c == '/')
+            {
+                return Err(format!(
+                    "File extension '{}' must be lowercase with no spaces or slashes",
+                    ext
+                ));
+            }
+        }
+        if !(1..=MAX_TOTAL_FILES).contains(&self.max_files) {
+            return Err(format!(
+                "Maximum indexed files must be between 1 and {}",
+                MAX_TOTAL_FILES
+            ));
+        }
+        Ok(())
+    }
+
+    /// Whether the file has an extension this config indexes.
+    fn includes(&self, path: &Path) -> bool {
+        let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+            return false;
+        };
+        let ext = ext.to_lowercase();
+        if self.excluded_extensions.contains(&ext) {
+            return false;
+        }
+        INCLUDED_EXTENSIONS.contains(&ext.as_str()) || self.extra_extensions.contains(&ext)
+    }
+}
+
 /// A file entry in the workspace index.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FileEntry {
@@ -123,22 +223,28 @@ pub struct WorkspaceIndex {
     pub updated_at: Option<Instant>,
     /// Number of files that were skipped due to limits
     pub files_skipped: usize,
+    /// Extension filters and file limit the index was built with
+    #[serde(skip)]
+    pub config: IndexConfig,
 }
 
 impl WorkspaceIndex {
-    /// Build an index of files in the workspace.
+    /// Build an index of files in the workspace, calling `on_progress`
+    /// periodically while walking.
     ///
-    /// # Arguments
-    /// * `root` - The root directory to index
-    ///
-    /// # Returns
-    /// A WorkspaceIndex containing all indexed files, or an error message.
+    /// The total number of files isn't known until the walk finishes, so the
+    /// reports carry running counts; the last one has `done` set. Reports are
+    /// throttled to every 100ms or 500 scanned files.
     ///
     /// # Limits
-    /// - Maximum 10,000 files
+    /// - Maximum `config.max_files` files (10,000 by default)
     /// - Respects .gitignore
     /// - Only includes files with recognized extensions
-    pub fn build(root: &Path) -> Result<Self, String> {
+    pub fn build_with_progress(
+        root: &Path,
+        config: &IndexConfig,
+        mut on_progress: impl FnMut(WorkspaceIndexProgressEvent),
+    ) -> Result<Self, String> {
         if !root.exists() {
             return Err(format!("Workspace root does not exist: {}", root.display()));
         }
@@ -152,6 +258,19 @@ impl WorkspaceIndex {
 
         let mut files = Vec::new();
         let mut files_skipped = 0;
+        let mut files_scanned = 0;
+        let root_display = root.to_string_lossy().to_string();
+        let progress =
+            |files_scanned: usize, files_indexed: usize, done: bool| WorkspaceIndexProgressEvent {
+                root: root_display.clone(),
+                files_scanned,
+                files_indexed,
+                done,
+            };
+
+        on_progress(progress(0, 0, false));
+        let mut last_report = Instant::now();
+        let mut last_report_count = 0;
 
         // Use ignore crate's WalkBuilder to respect .gitignore
         let walker = WalkBuilder::new(root)
@@ -159,7 +278,7 @@ impl WorkspaceIndex {
             .git_ignore(true) // Respect .gitignore
             .git_global(true) // Respect global gitignore
             .git_exclude(true) // Respect .git/info/exclude
-            .max_depth(Some(20)) // Reasonable depth limit
+            .max_depth(Some(MAX_DEPTH)) // Reasonable depth limit
             .build();
 
         for entry in walker {
@@ -173,25 +292,24 @@ impl WorkspaceIndex {
                 continue;
             }
 
+            files_scanned += 1;
+            if files_scanned - last_report_count >= PROGRESS_EVERY_FILES
+                || last_report.elapsed() >= PROGRESS_INTERVAL
+            {
+                on_progress(progress(files_scanned, files.len(), false));
+                last_report = Instant::now();
+                last_report_count = files_scanned;
+            }
+
             let path = entry.path();
 
             // Check extension
-            let extension = path
-                .extension()
-                .and_then(|e| e.to_str())
-                .map(|s| s.to_lowercase());
-
-            let has_valid_extension = extension
-                .as_ref()
-                .map(|ext| INCLUDED_EXTENSIONS.contains(&ext.as_str()))
-                .unwrap_or(false);
-
-            if !has_valid_extension {
+            if !config.includes(path) {
                 continue;
             }
 
             // Check file limit
-            if files.len() >= MAX_FILES {
+            if files.len() >= config.max_files {
                 files_skipped += 1;
                 continue;
             }
@@ -202,11 +320,14 @@ impl WorkspaceIndex {
             }
         }
 
+        on_progress(progress(files_scanned, files.len(), true));
+
         Ok(WorkspaceIndex {
             root: root.to_path_buf(),
             files,
             updated_at: Some(Instant::now()),
             files_skipped,
+            config: config.clone(),
         })
     }
 
@@ -239,6 +360,273 @@ impl WorkspaceIndex {
         let name_lower = name.to_lowercase();
         self.files.iter().find(|f| f.name_normalized == name_lower)
     }
+
+    /// Apply a single file system change to the index.
+    ///
+    /// If `path` exists it is added (a directory adds every file below it),
+    /// subject to the same hidden, .gitignore and extension filters as
+    /// `build_with_progress` and to `max_files` (which callers derive from
+    /// `config.max_files`). If it no longer exists, it and anything below it
+    /// is removed.
+    ///
+    /// # Returns
+    /// Whether the index changed.
+    pub fn apply_change(&mut self, path: &Path, max_files: usize) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return false;
+        };
+        if relative.as_os_str().is_empty() {
+            return false;
+        }
+
+        if path.is_file() {
+            self.add_file(path, max_files)
+        } else if path.is_dir() {
+            if is_excluded(&self.root, path) {
+                return false;
+            }
+
+            // The walker reads .gitignore files from parent directories too
+            let walker = WalkBuilder::new(path)
+                .hidden(true)
+                .git_ignore(true)
+                .git_global(true)
+                .git_exclude(true)
+                .build();
+
+            let mut changed = false;
+            for entry in walker.flatten() {
+                if entry.file_type().is_some_and(|ft| ft.is_file())
+                    && self.config.includes(entry.path())
+                {
+                    changed |= self.insert(entry.path(), max_files);
+                }
+            }
+            changed
+        } else {
+            let relative = relative.to_string_lossy().to_string();
+            let dir_prefix = format!("{}{}", relative, std::path::MAIN_SEPARATOR);
+            let before = self.files.len();
+            self.files.retain(|f| {
+                f.relative_path != relative && !f.relative_path.starts_with(&dir_prefix)
+            });
+            self.files.len() != before
+        }
+    }
+
+    /// Add one file if it passes the index filters and isn't already present.
+    fn add_file(&mut self, path: &Path, max_files: usize) -> bool {
+        if !self.config.includes(path) || is_excluded(&self.root, path) {
+            return false;
+        }
+        self.insert(path, max_files)
+    }
+
+    /// Insert an already-filtered file, respecting the depth and file limits.
+    fn insert(&mut self, path: &Path, max_files: usize) -> bool {
+        let too_deep = path
+            .strip_prefix(&self.root)
+            .map(|rel| rel.components().count() > MAX_DEPTH)
+            .unwrap_or(true);
+        if too_deep {
+            return false;
+        }
+
+        let Some(entry) = FileEntry::from_path(path, &self.root) else {
+            return false;
+        };
+        if self
+            .files
+            .iter()
+            .any(|f| f.relative_path == entry.relative_path)
+        {
+            return false;
+        }
+
+        if self.files.len() >= max_files {
+            self.files_skipped += 1;
+            return false;
+        }
+
+        self.files.push(entry);
+        true
+    }
+
+    /// Start watching the workspace root for created, renamed and deleted files.
+    ///
+    /// Events are debounced and applied to this root's index in `AppState`
+    /// (while it is still registered), and a `workspace-index-updated` event
+    /// is emitted after each batch that changed it.
+    ///
+    /// Watching stops when the returned `WorkspaceWatcher` is dropped.
+    pub fn start_watching(&self, app: AppHandle) -> Result<WorkspaceWatcher, String> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(tx)
+            .map_err(|e| format!("Failed to create file watcher: {}", e))?;
+        watcher
+            .watch(&self.root, RecursiveMode::Recursive)
+            .map_err(|e| format!("Failed to watch {}: {}", self.root.display(), e))?;
+
+        let root = self.root.clone();
+        std::thread::spawn(move || {
+            log::info!("[WORKSPACE] Watching {} for changes", root.display());
+            let mut pending: HashSet<PathBuf> = HashSet::new();
+
+            loop {
+                // Block until something happens, then keep collecting until quiet
+                let received = if pending.is_empty() {
+                    rx.recv().map_err(|_| mpsc::RecvTimeoutError::Disconnected)
+                } else {
+                    rx.recv_timeout(WATCH_DEBOUNCE)
+                };
+
+                match received {
+                    Ok(Ok(event)) => {
+                        if is_structural_change(&event.kind) {
+                            pending.extend(event.paths);
+                        }
+                    }
+                    Ok(Err(e)) => log::warn!("[WORKSPACE] Watcher error: {}", e),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {
+                        let paths: Vec<PathBuf> = pending.drain().collect();
+                        apply_watched_changes(&app, &root, &paths);
+                    }
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+
+            log::info!("[WORKSPACE] Stopped watching {}", root.display());
+        });
+
+        Ok(WorkspaceWatcher {
+            root: self.root.clone(),
+            _watcher: watcher,
+        })
+    }
+}
+
+/// Handle to a running workspace file watcher. Dropping it stops watching.
+pub struct WorkspaceWatcher {
+    /// Root directory being watched
+    pub root: PathBuf,
+    _watcher: RecommendedWatcher,
+}
+
+/// Total number of indexed files across several workspace roots.
+pub fn total_file_count(indexes: &[WorkspaceIndex]) -> usize {
+    indexes.iter().map(|index| index.file_count()).sum()
+}
+
+/// Payload of the `workspace-index-updated` event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceIndexUpdatedEvent {
+    pub root: String,
+    pub file_count: usize,
+    pub files_skipped: usize,
+}
+
+/// Payload of the `workspace-index-progress` event, emitted while an index is built.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceIndexProgressEvent {
+    pub root: String,
+    /// Files walked so far (including ones without an indexed extension)
+    pub files_scanned: usize,
+    /// Files added to the index so far
+    pub files_indexed: usize,
+    /// True on the final report
+    pub done: bool,
+}
+
+/// Apply a debounced batch of changed paths to the shared index and notify the UI.
+fn apply_watched_changes(app: &AppHandle, root: &Path, paths: &[PathBuf]) {
+    let state = app.state::<crate::AppState>();
+    let update = state.update_workspace_index(root, |index, other_files| {
+        let max_files = index
+            .config
+            .max_files
+            .min(MAX_TOTAL_FILES.saturating_sub(other_files));
+
+        let mut changed = false;
+        for path in paths {
+            changed |= index.apply_change(path, max_files);
+        }
+        if !changed {
+            return None;
+        }
+
+        index.updated_at = Some(Instant::now());
+        Some(WorkspaceIndexUpdatedEvent {
+            root: index.root.to_string_lossy().to_string(),
+            file_count: index.file_count(),
+            files_skipped: index.files_skipped,
+        })
+    });
+
+    if let Some(event) = update.flatten() {
+        state.refresh_symbols(root, paths);
+        log::info!(
+            "[WORKSPACE] Index updated: {} files ({} changed paths)",
+            event.file_count,
+            paths.len()
+        );
+        let _ = app.emit("workspace-index-updated", event);
+    }
+}
+
+/// Whether a watcher event can add or remove files (content edits can't).
+fn is_structural_change(kind: &notify::EventKind) -> bool {
+    use notify::event::ModifyKind;
+    use notify::EventKind;
+
+    matches!(
+        kind,
+        EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_))
+    )
+}
+
+/// Whether a file under `root` would be skipped by the walker in
+/// `build_with_progress`: hidden, too deep, or matched by a .gitignore between
+/// the root and the file (or by .git/info/exclude or the global gitignore).
+fn is_excluded(root: &Path, path: &Path) -> bool {
+    let Ok(relative) = path.strip_prefix(root) else {
+        return true;
+    };
+
+    if relative.components().count() > MAX_DEPTH
+        || relative
+            .components()
+            .any(|c| c.as_os_str().to_string_lossy().starts_with('.'))
+    {
+        return true;
+    }
+
+    let (global, _) = Gitignore::global();
+    if global.matched_path_or_any_parents(path, false).is_ignore() {
+        return true;
+    }
+
+    let mut dir = root.to_path_buf();
+    let mut dirs = vec![dir.clone()];
+    if let Some(parent) = relative.parent() {
+        for component in parent.components() {
+            dir.push(component);
+            dirs.push(dir.clone());
+        }
+    }
+
+    dirs.iter().any(|dir| {
+        let mut builder = GitignoreBuilder::new(dir);
+        builder.add(dir.join(".gitignore"));
+        if dir == root {
+            builder.add(dir.join(".git").join("info").join("exclude"));
+        }
+        builder
+            .build()
+            .map(|gi| gi.matched_path_or_any_parents(path, false).is_ignore())
+            .unwrap_or(false)
+    })
 }
 
 #[cfg(test)]
@@ -271,7 +659,9 @@ mod tests {
     #[test]
     fn test_build_index() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         // Should have indexed the code files
         assert!(index.file_count() >= 5);
@@ -281,7 +671,9 @@ mod tests {
     #[test]
     fn test_find_by_name() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         let results = index.find_by_name("main");
         assert!(!results.is_empty());
@@ -291,7 +683,9 @@ mod tests {
     #[test]
     fn test_find_exact() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         let result = index.find_exact("main");
         assert!(result.is_some());
@@ -301,7 +695,9 @@ mod tests {
     #[test]
     fn test_excludes_binary_files() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         // Should not include the .png file
         let results = index.find_by_name("image");
@@ -311,7 +707,9 @@ mod tests {
     #[test]
     fn test_normalized_name() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         let result = index.find_exact("utils");
         assert!(result.is_some());
@@ -321,16 +719,93 @@ mod tests {
         assert_eq!(entry.extension, Some("ts".to_string()));
     }
 
+    #[test]
+    fn test_build_reports_progress() {
+        let temp_dir = create_test_workspace();
+        let mut reports = Vec::new();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |p| {
+                reports.push(p)
+            })
+            .unwrap();
+
+        let first = reports.first().unwrap();
+        assert_eq!((first.files_scanned, first.done), (0, false));
+
+        let last = reports.last().unwrap();
+        assert!(last.done);
+        assert_eq!(last.files_indexed, index.file_count());
+        assert!(last.files_scanned > index.file_count()); // image.png is scanned, not indexed
+        assert_eq!(reports.iter().filter(|p| p.done).count(), 1);
+    }
+
+    #[test]
+    fn test_build_with_custom_extensions() {
+        let temp_dir = create_test_workspace();
+        fs::write(temp_dir.path().join("analysis.ipynb"), "{}").unwrap();
+        let config = IndexConfig {
+            extra_extensions: vec!["ipynb".to_string()],
+            excluded_extensions: vec!["md".to_string()],
+            ..Default::default()
+        };
+
+        let index = WorkspaceIndex::build_with_progress(temp_dir.path(), &config, |_| {}).unwrap();
+        assert!(index.find_exact("analysis").is_some());
+        assert!(index.find_exact("readme").is_none());
+        assert!(index.find_exact("main").is_some());
+    }
+
+    #[test]
+    fn test_build_respects_max_files() {
+        let temp_dir = create_test_workspace();
+        let config = IndexConfig {
+            max_files: 2,
+            ..Default::default()
+        };
+
+        let index = WorkspaceIndex::build_with_progress(temp_dir.path(), &config, |_| {}).unwrap();
+        assert_eq!(index.file_count(), 2);
+        assert_eq!(index.files_skipped, 4);
+    }
+
+    #[test]
+    fn test_index_config_validation() {
+        assert!(IndexConfig::default().validate().is_ok());
+
+        let with_extensions = |ext: &str| IndexConfig {
+            extra_extensions: vec![ext.to_string()],
+            ..Default::default()
+        };
+        assert!(with_extensions("shader").validate().is_ok());
+        assert!(with_extensions(".shader").validate().is_err());
+        assert!(with_extensions("Shader").validate().is_err());
+        assert!(with_extensions("").validate().is_err());
+
+        let with_max = |max_files| IndexConfig {
+            max_files,
+            ..Default::default()
+        };
+        assert!(with_max(0).validate().is_err());
+        assert!(with_max(MAX_TOTAL_FILES).validate().is_ok());
+        assert!(with_max(MAX_TOTAL_FILES + 1).validate().is_err());
+    }
+
     #[test]
     fn test_nonexistent_root() {
-        let result = WorkspaceIndex::build(Path::new("/nonexistent/path"));
+        let result = WorkspaceIndex::build_with_progress(
+            Path::new("/nonexistent/path"),
+            &IndexConfig::default(),
+            |_| {},
+        );
         assert!(result.is_err());
     }
 
     #[test]
     fn test_file_entry_relative_path() {
         let temp_dir = create_test_workspace();
-        let index = WorkspaceIndex::build(temp_dir.path()).unwrap();
+        let index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
 
         // Find the nested file
         let results = index.find_by_name("app");
@@ -339,4 +814,104 @@ mod tests {
         let app_file = results.iter().find(|f| f.name == "app.tsx").unwrap();
         assert!(app_file.relative_path.contains("src"));
     }
+
+    #[test]
+    fn test_apply_change_adds_and_removes_files() {
+        let temp_dir = create_test_workspace();
+        let mut index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+        let count = index.file_count();
+
+        let new_file = temp_dir.path().join("src").join("router.ts");
+        fs::write(&new_file, "export {}").unwrap();
+        assert!(index.apply_change(&new_file, MAX_FILES));
+        assert!(index.find_exact("router").is_some());
+
+        // Already indexed, nothing to do
+        assert!(!index.apply_change(&new_file, MAX_FILES));
+        assert_eq!(index.file_count(), count + 1);
+
+        fs::remove_file(&new_file).unwrap();
+        assert!(index.apply_change(&new_file, MAX_FILES));
+        assert!(index.find_exact("router").is_none());
+        assert_eq!(index.file_count(), count);
+    }
+
+    #[test]
+    fn test_apply_change_respects_file_limit() {
+        let temp_dir = create_test_workspace();
+        let mut index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+        let count = index.file_count();
+
+        let new_file = temp_dir.path().join("extra.rs");
+        fs::write(&new_file, "").unwrap();
+        assert!(!index.apply_change(&new_file, count));
+        assert_eq!(index.file_count(), count);
+        assert_eq!(index.files_skipped, 1);
+    }
+
+    #[test]
+    fn test_apply_change_removes_deleted_directory() {
+        let temp_dir = create_test_workspace();
+        let mut index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+
+        let src_dir = temp_dir.path().join("src");
+        fs::remove_dir_all(&src_dir).unwrap();
+        assert!(index.apply_change(&src_dir, MAX_FILES));
+        assert!(index.find_exact("app").is_none());
+        assert!(index.find_exact("index").is_none());
+        assert!(index.find_exact("main").is_some());
+    }
+
+    #[test]
+    fn test_apply_change_adds_new_directory() {
+        let temp_dir = create_test_workspace();
+        let mut index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+
+        let lib_dir = temp_dir.path().join("lib");
+        fs::create_dir(&lib_dir).unwrap();
+        fs::write(lib_dir.join("parser.rs"), "").unwrap();
+        fs::write(lib_dir.join("logo.png"), [0u8; 10]).unwrap();
+
+        assert!(index.apply_change(&lib_dir, MAX_FILES));
+        assert!(index.find_exact("parser").is_some());
+        assert!(index.find_exact("logo").is_none());
+    }
+
+    #[test]
+    fn test_apply_change_respects_filters() {
+        let temp_dir = create_test_workspace();
+        fs::write(temp_dir.path().join(".gitignore"), "dist/\n*.log.md\n").unwrap();
+        let mut index =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+
+        let dist_dir = temp_dir.path().join("dist");
+        fs::create_dir(&dist_dir).unwrap();
+        let ignored = dist_dir.join("bundle.js");
+        fs::write(&ignored, "").unwrap();
+        assert!(!index.apply_change(&ignored, MAX_FILES));
+
+        let ignored_by_glob = temp_dir.path().join("build.log.md");
+        fs::write(&ignored_by_glob, "").unwrap();
+        assert!(!index.apply_change(&ignored_by_glob, MAX_FILES));
+
+        let hidden = temp_dir.path().join(".secrets.env");
+        fs::write(&hidden, "").unwrap();
+        assert!(!index.apply_change(&hidden, MAX_FILES));
+
+        let binary = temp_dir.path().join("photo.png");
+        fs::write(&binary, [0u8; 10]).unwrap();
+        assert!(!index.apply_change(&binary, MAX_FILES));
+
+        let outside = Path::new("/tmp/not-in-workspace.rs");
+        assert!(!index.apply_change(outside, MAX_FILES));
+    }
 }
@@ -1,10 +1,13 @@
 //! File tagging by voice.
 //!
-//! Matches spoken filenames against the workspace index and converts them to @filename syntax:
+//! Matches spoken filenames against the workspace indexes (one per root) and converts them
+//! to @filename syntax:
 //! - "auth check dot ts" → "@authCheck.ts"
 //! - "the main file" → "@main.rs"
+//! - "the parse config function" → "@parseConfig" (symbol tagging)
 
 use super::file_index::{FileEntry, WorkspaceIndex};
+use super::symbol_index::{self, Symbol, SymbolIndex, SymbolKind};
 use fuzzy_matcher::skim::SkimMatcherV2;
 use fuzzy_matcher::FuzzyMatcher;
 use regex::Regex;
@@ -13,6 +16,17 @@ use std::sync::LazyLock;
 /// Minimum fuzzy match score to consider a match valid.
 const MIN_MATCH_SCORE: i64 = 50;
 
+/// Extensions Whisper commonly mishears: (heard, intended).
+/// Used as a fallback only when no file matches the extension as heard.
+const EXTENSION_HOMOPHONES: &[(&str, &str)] = &[
+    ("gs", "js"),
+    ("gsx", "jsx"),
+    ("tz", "ts"),
+    ("tzx", "tsx"),
+    ("pie", "py"),
+    ("jason", "json"),
+];
+
 // ============================================================================
 // STATIC REGEX PATTERNS
 // SAFETY: All unwrap() calls below are safe because these are compile-time
@@ -26,7 +40,7 @@ const MIN_MATCH_SCORE: i64 = 50;
 static DOT_EXTENSION_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
     // Match 1-2 words (typical filename length) before "dot extension"
     // Using word boundaries to ensure we capture just the filename
-    Regex::new(r"(?i)\b(\w+(?:\s+\w+)?)\s+dot\s+(ts|tsx|js|jsx|cjs|mjs|vue|svelte|rs|py|go|rb|java|html|css|scss|sass|less|json|yaml|yml|md|txt|toml|sh|sql|graphql)\b").unwrap()
+    Regex::new(r"(?i)\b(\w+(?:\s+\w+)?)\s+dot\s+(ts|tsx|js|jsx|cjs|mjs|vue|svelte|rs|py|go|rb|java|html|css|scss|sass|less|json|yaml|yml|md|txt|toml|sh|sql|graphql|gs|gsx|tz|tzx|pie|jason)\b").unwrap()
 });
 
 /// Pattern for already-formatted filenames: "filename.ext"
@@ -47,6 +61,12 @@ static THE_FILE_PATTERN: LazyLock<Regex> =
 static FILE_PREFIX_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)\bfile\s+(\w+(?:\s+dot\s+\w+)?)\b").unwrap());
 
+/// Pattern for symbol references: "the [name] function/class/..."
+/// Captures up to three words of name; shorter suffixes are tried if the full capture doesn't match.
+static THE_SYMBOL_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)\bthe\s+(\w+(?:\s+\w+){0,2})\s+(function|method|func|class|struct|type|interface|enum|trait|constant|const)\b").unwrap()
+});
+
 /// Fuzzy matcher for filename matching.
 static FUZZY_MATCHER: LazyLock<SkimMatcherV2> = LazyLock::new(SkimMatcherV2::default);
 
@@ -61,48 +81,98 @@ static PUNCTUATION_CLEANUP_PATTERN: LazyLock<Regex> = LazyLock::new(|| {
 
 /// Apply file tagging to text.
 ///
-/// IMPORTANT: Only tags files that ACTUALLY EXIST in the workspace index.
+/// IMPORTANT: Only tags files that ACTUALLY EXIST in one of the workspace indexes.
 /// If no workspace index is provided, no tagging is performed.
 /// This ensures we don't create @ references to non-existent files.
 ///
 /// # Arguments
 /// * `text` - The text to process
-/// * `index` - Workspace file index (required for tagging to work)
+/// * `indexes` - Workspace file indexes, one per root (required for tagging to work)
 ///
 /// # Examples
 /// ```ignore
-/// let result = apply_file_tagging("Fix the bug in auth check dot ts", Some(&index));
+/// let result = apply_file_tagging("Fix the bug in auth check dot ts", &[index]);
 /// // If authCheck.ts exists in index: "Fix the bug in @authCheck.ts"
 /// // If authCheck.ts does NOT exist: "Fix the bug in auth check dot ts" (unchanged)
 ///
-/// let result = apply_file_tagging("Open components.json", None);
+/// let result = apply_file_tagging("Open components.json", &[]);
 /// // No index = no tagging: "Open components.json" (unchanged)
 /// ```
-pub fn apply_file_tagging(text: &str, index: Option<&WorkspaceIndex>) -> String {
+pub fn apply_file_tagging(text: &str, indexes: &[WorkspaceIndex]) -> String {
     // If no workspace index, skip all tagging - we can't verify files exist
-    let idx = match index {
-        Some(idx) => idx,
-        None => {
-            println!("[FILE_TAGGER] No workspace index - skipping file tagging");
-            return text.to_string();
-        }
-    };
+    if indexes.is_empty() {
+        log::debug!("[FILE_TAGGER] No workspace index - skipping file tagging");
+        return text.to_string();
+    }
 
     let mut result = text.to_string();
 
     // 1. Match literal filenames like "components.json" → "@components.json"
     // Only if the file exists in the index
-    result = apply_literal_filename_pattern(&result, idx);
+    result = apply_literal_filename_pattern(&result, indexes);
 
     // 2. Match "[words] dot [extension]" pattern
-    result = apply_dot_extension_pattern(&result, idx);
+    result = apply_dot_extension_pattern(&result, indexes);
 
     // 3. Match "the [name] file" pattern
-    result = apply_the_file_pattern(&result, idx);
+    result = apply_the_file_pattern(&result, indexes);
 
     // 4. Match "file [name]" pattern
-    result = apply_file_prefix_pattern(&result, idx);
+    result = apply_file_prefix_pattern(&result, indexes);
+
+    result
+}
+
+/// Apply symbol tagging to text ("the parse config function" → "@parseConfig").
+///
+/// Like file tagging, only tags symbols that exist in one of the symbol indexes.
+pub fn apply_symbol_tagging(text: &str, indexes: &[SymbolIndex]) -> String {
+    if indexes.is_empty() {
+        log::debug!("[FILE_TAGGER] No symbol index - skipping symbol tagging");
+        return text.to_string();
+    }
+
+    let mut replacements: Vec<(String, String)> = Vec::new();
+
+    for captures in THE_SYMBOL_PATTERN.captures_iter(text) {
+        let full_match = captures.get(0).unwrap().as_str();
+        let words: Vec<&str> = captures
+            .get(1)
+            .unwrap()
+            .as_str()
+            .split_whitespace()
+            .collect();
+        let kind = SymbolKind::from_spoken(captures.get(2).unwrap().as_str());
+
+        // "the old parse config function" - try "old parse config", then "parse config", ...
+        let matched = (0..words.len()).find_map(|start| {
+            let symbol = find_best_symbol(&words[start..].join(" "), kind, indexes)?;
+            Some((start, symbol))
+        });
+
+        if let Some((start, symbol)) = matched {
+            // Keep any leading words that weren't part of the symbol name
+            let kept: Vec<&str> = std::iter::once("the")
+                .chain(words[..start].iter().copied())
+                .collect();
+            let replacement = if start == 0 {
+                format!("@{}", symbol.name)
+            } else {
+                format!("{} @{}", kept.join(" "), symbol.name)
+            };
+            log::debug!(
+                "[FILE_TAGGER] Matched spoken '{}' to @{}",
+                words[start..].join(" "),
+                symbol.name
+            );
+            replacements.push((full_match.to_string(), replacement));
+        }
+    }
 
+    let mut result = text.to_string();
+    for (from, to) in replacements {
+        result = result.replacen(&from, &to, 1);
+    }
     result
 }
 
@@ -121,9 +191,10 @@ pub fn cleanup_tagged_punctuation(text: &str) -> String {
         .replace_all(text, "$1 $2")
         .to_string();
     if result != text {
-        println!(
+        log::debug!(
             "[FILE_TAGGER] Cleaned up punctuation: '{}' -> '{}'",
-            text, result
+            text,
+            result
         );
     }
     result
@@ -131,16 +202,19 @@ pub fn cleanup_tagged_punctuation(text: &str) -> String {
 
 /// Apply the literal filename pattern (e.g., "components.json" → "@components.json").
 /// This handles cases where the transcription already outputs proper filenames.
-/// ONLY tags files that exist in the workspace index.
-fn apply_literal_filename_pattern(text: &str, index: &WorkspaceIndex) -> String {
+/// ONLY tags files that exist in one of the workspace indexes.
+fn apply_literal_filename_pattern(text: &str, indexes: &[WorkspaceIndex]) -> String {
     let mut result = text.to_string();
 
     // Find all potential filename matches
     for captures in LITERAL_FILENAME_PATTERN.captures_iter(text) {
         let filename = captures.get(2).unwrap().as_str();
 
-        // Check if this file exists in the workspace index
-        let file_exists = index.files.iter().any(|f| f.name == filename);
+        // Check if this file exists in any workspace index
+        let file_exists = indexes
+            .iter()
+            .flat_map(|index| index.files.iter())
+            .any(|f| f.name == filename);
 
         if file_exists {
             // Replace only this occurrence
@@ -148,12 +222,13 @@ fn apply_literal_filename_pattern(text: &str, index: &WorkspaceIndex) -> String
             let prefix = captures.get(1).unwrap().as_str();
             let replacement = format!("{}@{}", prefix, filename);
             result = result.replacen(full_match, &replacement, 1);
-            println!(
+            log::debug!(
                 "[FILE_TAGGER] Tagged verified file: {} -> @{}",
-                filename, filename
+                filename,
+                filename
             );
         } else {
-            println!(
+            log::debug!(
                 "[FILE_TAGGER] File '{}' not in workspace - not tagging",
                 filename
             );
@@ -165,7 +240,7 @@ fn apply_literal_filename_pattern(text: &str, index: &WorkspaceIndex) -> String
 
 /// Apply the "[words] dot [extension]" pattern.
 /// Only tags if a matching file is found in the index - no guessing.
-fn apply_dot_extension_pattern(text: &str, index: &WorkspaceIndex) -> String {
+fn apply_dot_extension_pattern(text: &str, indexes: &[WorkspaceIndex]) -> String {
     // Collect all replacements first to avoid borrow issues
     let mut replacements: Vec<(String, String)> = Vec::new();
 
@@ -178,15 +253,16 @@ fn apply_dot_extension_pattern(text: &str, index: &WorkspaceIndex) -> String {
         let normalized = normalize_spoken_filename(words);
 
         // Try to find a matching file - ONLY tag if we find one
-        if let Some(file) = find_best_match(&normalized, Some(extension), index) {
+        if let Some(file) = find_best_match(&normalized, Some(extension), indexes) {
             let replacement = format!("@{}", file.name);
-            println!("[FILE_TAGGER] Matched spoken '{}' to @{}", words, file.name);
+            log::debug!("[FILE_TAGGER] Matched spoken '{}' to @{}", words, file.name);
             replacements.push((full_match, replacement));
         } else {
             // No match found - leave the text unchanged, don't guess
-            println!(
+            log::debug!(
                 "[FILE_TAGGER] No match for '{} dot {}' - leaving unchanged",
-                words, extension
+                words,
+                extension
             );
         }
     }
@@ -200,7 +276,7 @@ fn apply_dot_extension_pattern(text: &str, index: &WorkspaceIndex) -> String {
 }
 
 /// Apply the "the [name] file" pattern.
-fn apply_the_file_pattern(text: &str, index: &WorkspaceIndex) -> String {
+fn apply_the_file_pattern(text: &str, indexes: &[WorkspaceIndex]) -> String {
     let mut result = text.to_string();
 
     while let Some(captures) = THE_FILE_PATTERN.captures(&result) {
@@ -208,7 +284,7 @@ fn apply_the_file_pattern(text: &str, index: &WorkspaceIndex) -> String {
         let name = captures.get(1).unwrap().as_str();
 
         // Try to find a matching file
-        if let Some(file) = find_best_match(name, None, index) {
+        if let Some(file) = find_best_match(name, None, indexes) {
             let replacement = format!("@{}", file.name);
             result = result.replace(full_match.as_str(), &replacement);
         }
@@ -222,7 +298,7 @@ fn apply_the_file_pattern(text: &str, index: &WorkspaceIndex) -> String {
 }
 
 /// Apply the "file [name]" pattern.
-fn apply_file_prefix_pattern(text: &str, index: &WorkspaceIndex) -> String {
+fn apply_file_prefix_pattern(text: &str, indexes: &[WorkspaceIndex]) -> String {
     let mut result = text.to_string();
 
     while let Some(captures) = FILE_PREFIX_PATTERN.captures(&result) {
@@ -243,7 +319,7 @@ fn apply_file_prefix_pattern(text: &str, index: &WorkspaceIndex) -> String {
         };
 
         // Try to find a matching file
-        if let Some(file) = find_best_match(base_name, extension, index) {
+        if let Some(file) = find_best_match(base_name, extension, indexes) {
             let replacement = format!("@{}", file.name);
             result = result.replace(full_match.as_str(), &replacement);
         }
@@ -287,66 +363,182 @@ fn normalize_spoken_filename(spoken: &str) -> String {
         .collect()
 }
 
-/// Find the best matching file in the index.
+/// A file candidate together with the index (root) it came from.
+type Candidate<'a> = (&'a WorkspaceIndex, &'a FileEntry);
+
+/// Find the best matching file across all workspace indexes.
+///
+/// When an extension is given, files with exactly that extension are always
+/// preferred. Only if none match do we try its homophones (e.g. "gs" → "js").
+/// An exact name match in any root wins over a fuzzy match in another.
 fn find_best_match<'a>(
     name: &str,
     extension: Option<&str>,
-    index: &'a WorkspaceIndex,
+    indexes: &'a [WorkspaceIndex],
 ) -> Option<&'a FileEntry> {
     let name_lower = name.to_lowercase().replace(' ', "");
 
-    // First, try exact match
-    let candidates: Vec<&FileEntry> = if let Some(ext) = extension {
-        let ext_lower = ext.to_lowercase();
-        index
-            .files
-            .iter()
-            .filter(|f| {
-                f.extension
-                    .as_ref()
-                    .map(|e| e.to_lowercase() == ext_lower)
-                    .unwrap_or(false)
+    let matched = match extension {
+        None => best_match_among(&name_lower, all_files(indexes)),
+        Some(ext) => {
+            let ext_lower = ext.to_lowercase();
+            best_match_among(&name_lower, files_with_extension(indexes, &ext_lower)).or_else(|| {
+                near_extensions(&ext_lower).into_iter().find_map(|near| {
+                    let candidate =
+                        best_match_among(&name_lower, files_with_extension(indexes, near))?;
+                    log::debug!(
+                        "[FILE_TAGGER] Treating spoken extension '{}' as '{}'",
+                        ext_lower,
+                        near
+                    );
+                    Some(candidate)
+                })
             })
-            .collect()
-    } else {
-        index.files.iter().collect()
+        }
     };
 
+    let (index, file) = matched?;
+    log::debug!(
+        "[FILE_TAGGER] '{}' matched {} in {}",
+        name,
+        file.relative_path,
+        index.root.display()
+    );
+    Some(file)
+}
+
+/// Extensions that sound like `ext`, in either direction of the homophone map.
+fn near_extensions(ext: &str) -> Vec<&'static str> {
+    EXTENSION_HOMOPHONES
+        .iter()
+        .filter_map(|(heard, intended)| {
+            if *heard == ext {
+                Some(*intended)
+            } else if *intended == ext {
+                Some(*heard)
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+fn all_files(indexes: &[WorkspaceIndex]) -> Vec<Candidate<'_>> {
+    indexes
+        .iter()
+        .flat_map(|index| index.files.iter().map(move |f| (index, f)))
+        .collect()
+}
+
+fn files_with_extension<'a>(indexes: &'a [WorkspaceIndex], ext_lower: &str) -> Vec<Candidate<'a>> {
+    all_files(indexes)
+        .into_iter()
+        .filter(|(_, f)| {
+            f.extension
+                .as_ref()
+                .map(|e| e.to_lowercase() == ext_lower)
+                .unwrap_or(false)
+        })
+        .collect()
+}
+
+/// Pick the best candidate for a normalized name: exact match first, then fuzzy.
+fn best_match_among<'a>(name_lower: &str, candidates: Vec<Candidate<'a>>) -> Option<Candidate<'a>> {
     // Exact match on normalized name
-    if let Some(file) = candidates.iter().find(|f| f.name_normalized == name_lower) {
-        return Some(file);
+    if let Some(candidate) = candidates
+        .iter()
+        .find(|(_, f)| f.name_normalized == name_lower)
+    {
+        return Some(*candidate);
     }
 
     // Fuzzy match
-    let mut best_match: Option<(&FileEntry, i64)> = None;
+    let mut best_match: Option<(Candidate<'a>, i64)> = None;
 
-    for file in candidates {
+    for candidate in candidates {
+        let file = candidate.1;
         // Score against normalized name
-        if let Some(score) = FUZZY_MATCHER.fuzzy_match(&file.name_normalized, &name_lower) {
+        if let Some(score) = FUZZY_MATCHER.fuzzy_match(&file.name_normalized, name_lower) {
             if score >= MIN_MATCH_SCORE {
                 if best_match.is_none() || score > best_match.unwrap().1 {
-                    best_match = Some((file, score));
+                    best_match = Some((candidate, score));
                 }
             }
         }
 
         // Also try matching against the full name (without extension)
-        if let Some(score) = FUZZY_MATCHER.fuzzy_match(&file.name, &name_lower) {
+        if let Some(score) = FUZZY_MATCHER.fuzzy_match(&file.name, name_lower) {
             if score >= MIN_MATCH_SCORE {
                 if best_match.is_none() || score > best_match.unwrap().1 {
-                    best_match = Some((file, score));
+                    best_match = Some((candidate, score));
                 }
             }
         }
     }
 
-    best_match.map(|(file, _)| file)
+    best_match.map(|(candidate, _)| candidate)
+}
+
+/// Find the best matching symbol across all symbol indexes.
+///
+/// Symbols of the spoken kind are preferred; within them, an exact name match
+/// in any root wins over a fuzzy match.
+fn find_best_symbol<'a>(
+    spoken: &str,
+    kind: Option<SymbolKind>,
+    indexes: &'a [SymbolIndex],
+) -> Option<&'a Symbol> {
+    let name_lower = symbol_index::normalize_symbol_name(&spoken.replace(' ', ""));
+    let all: Vec<(&SymbolIndex, &Symbol)> = indexes
+        .iter()
+        .flat_map(|index| index.symbols.iter().map(move |s| (index, s)))
+        .collect();
+
+    let of_kind: Vec<(&SymbolIndex, &Symbol)> = all
+        .iter()
+        .filter(|(_, s)| kind.is_none_or(|k| s.kind == k))
+        .copied()
+        .collect();
+
+    let (index, symbol) = best_symbol_among(&name_lower, &of_kind)
+        .or_else(|| best_symbol_among(&name_lower, &all))?;
+    log::debug!(
+        "[FILE_TAGGER] '{}' matched symbol {} in {} ({})",
+        spoken,
+        symbol.name,
+        symbol.relative_path,
+        index.root.display()
+    );
+    Some(symbol)
+}
+
+/// Pick the best symbol for a normalized name: exact match first, then fuzzy.
+fn best_symbol_among<'a>(
+    name_lower: &str,
+    candidates: &[(&'a SymbolIndex, &'a Symbol)],
+) -> Option<(&'a SymbolIndex, &'a Symbol)> {
+    if let Some(candidate) = candidates
+        .iter()
+        .find(|(_, s)| s.name_normalized == name_lower)
+    {
+        return Some(*candidate);
+    }
+
+    candidates
+        .iter()
+        .filter_map(|candidate| {
+            let score = FUZZY_MATCHER.fuzzy_match(&candidate.1.name_normalized, name_lower)?;
+            (score >= MIN_MATCH_SCORE).then_some((*candidate, score))
+        })
+        .max_by_key(|(_, score)| *score)
+        .map(|(candidate, _)| candidate)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use std::path::PathBuf;
+    use std::slice;
 
     fn create_test_index() -> WorkspaceIndex {
         WorkspaceIndex {
@@ -391,21 +583,22 @@ mod tests {
     #[test]
     fn test_dot_extension_pattern() {
         let index = create_test_index();
-        let result = apply_file_tagging("Fix the bug in auth check dot ts", Some(&index));
+        let result =
+            apply_file_tagging("Fix the bug in auth check dot ts", slice::from_ref(&index));
         assert_eq!(result, "Fix the bug in @authCheck.ts");
     }
 
     #[test]
     fn test_the_file_pattern() {
         let index = create_test_index();
-        let result = apply_file_tagging("Check the main file", Some(&index));
+        let result = apply_file_tagging("Check the main file", slice::from_ref(&index));
         assert_eq!(result, "Check @main.rs");
     }
 
     #[test]
     fn test_no_match_preserves_text() {
         let index = create_test_index();
-        let result = apply_file_tagging("Hello world", Some(&index));
+        let result = apply_file_tagging("Hello world", slice::from_ref(&index));
         assert_eq!(result, "Hello world");
     }
 
@@ -419,7 +612,8 @@ mod tests {
     #[test]
     fn test_case_insensitive() {
         let index = create_test_index();
-        let result = apply_file_tagging("Fix the bug in AUTH CHECK DOT TS", Some(&index));
+        let result =
+            apply_file_tagging("Fix the bug in AUTH CHECK DOT TS", slice::from_ref(&index));
         assert_eq!(result, "Fix the bug in @authCheck.ts");
     }
 
@@ -427,27 +621,27 @@ mod tests {
     fn test_unknown_file_not_tagged() {
         let index = create_test_index();
         // When no file matches, DON'T tag - leave text unchanged
-        let result = apply_file_tagging("edit the unknown dot ts", Some(&index));
+        let result = apply_file_tagging("edit the unknown dot ts", slice::from_ref(&index));
         assert!(!result.contains("@"), "Unknown files should NOT be tagged");
 
-        let result2 = apply_file_tagging("open mystery dot ts", Some(&index));
+        let result2 = apply_file_tagging("open mystery dot ts", slice::from_ref(&index));
         assert!(!result2.contains("@"), "Unknown files should NOT be tagged");
     }
 
     #[test]
     fn test_literal_filename_only_tags_existing() {
         // Without an index, nothing should be tagged
-        let result = apply_file_tagging("Open components.json", None);
+        let result = apply_file_tagging("Open components.json", &[]);
         assert_eq!(result, "Open components.json", "Without index, no tagging");
 
         // With an index, only existing files get tagged
         let index = create_test_index();
         // main.rs exists in index
-        let result = apply_file_tagging("Check main.rs for errors", Some(&index));
+        let result = apply_file_tagging("Check main.rs for errors", slice::from_ref(&index));
         assert_eq!(result, "Check @main.rs for errors");
 
         // nonexistent.ts does NOT exist in index - should NOT be tagged
-        let result = apply_file_tagging("Open nonexistent.ts", Some(&index));
+        let result = apply_file_tagging("Open nonexistent.ts", slice::from_ref(&index));
         assert_eq!(
             result, "Open nonexistent.ts",
             "Non-existent file should not be tagged"
@@ -458,7 +652,10 @@ mod tests {
     fn test_literal_filename_multiple_existing() {
         let index = create_test_index();
         // main.rs and lib.rs both exist in index
-        let result = apply_file_tagging("Check main.rs and lib.rs for errors", Some(&index));
+        let result = apply_file_tagging(
+            "Check main.rs and lib.rs for errors",
+            slice::from_ref(&index),
+        );
         assert_eq!(result, "Check @main.rs and @lib.rs for errors");
     }
 
@@ -466,7 +663,7 @@ mod tests {
     fn test_literal_filename_not_double_tagged() {
         // Already tagged files should not get double-tagged
         let index = create_test_index();
-        let result = apply_file_tagging("Open @main.rs", Some(&index));
+        let result = apply_file_tagging("Open @main.rs", slice::from_ref(&index));
         assert_eq!(result, "Open @main.rs");
     }
 
@@ -474,17 +671,17 @@ mod tests {
     fn test_literal_filename_in_path_not_tagged() {
         // Filenames in paths (after /) should not get @ prefix
         let index = create_test_index();
-        let result = apply_file_tagging("Edit /src/main.rs", Some(&index));
+        let result = apply_file_tagging("Edit /src/main.rs", slice::from_ref(&index));
         assert_eq!(result, "Edit /src/main.rs");
     }
 
     #[test]
     fn test_no_index_means_no_tagging() {
         // No workspace index = no tagging at all
-        let result = apply_file_tagging("Check main.rs and lib.rs", None);
+        let result = apply_file_tagging("Check main.rs and lib.rs", &[]);
         assert_eq!(result, "Check main.rs and lib.rs");
 
-        let result = apply_file_tagging("Fix auth check dot ts", None);
+        let result = apply_file_tagging("Fix auth check dot ts", &[]);
         assert_eq!(result, "Fix auth check dot ts");
     }
 
@@ -517,4 +714,153 @@ mod tests {
         let result = cleanup_tagged_punctuation("Check @main.rs, @lib.rs, and @mod.rs.");
         assert_eq!(result, "Check @main.rs , @lib.rs , and @mod.rs .");
     }
+
+    fn js_entry(name: &str, normalized: &str, ext: &str) -> FileEntry {
+        FileEntry {
+            relative_path: format!("src/{}", name),
+            name: name.to_string(),
+            name_normalized: normalized.to_string(),
+            extension: Some(ext.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_homophone_extension_ts() {
+        let index = create_test_index();
+        let result =
+            apply_file_tagging("Fix the bug in auth check dot tz", slice::from_ref(&index));
+        assert_eq!(result, "Fix the bug in @authCheck.ts");
+    }
+
+    #[test]
+    fn test_homophone_extension_js() {
+        let mut index = create_test_index();
+        index
+            .files
+            .push(js_entry("apiClient.js", "apiclient", "js"));
+        let result = apply_file_tagging("Open api client dot GS", slice::from_ref(&index));
+        assert_eq!(result, "Open @apiClient.js");
+    }
+
+    #[test]
+    fn test_exact_extension_preferred_over_homophone() {
+        let mut index = create_test_index();
+        index.files.push(js_entry("macros.js", "macros", "js"));
+        index.files.push(js_entry("macros.gs", "macros", "gs"));
+        let result = apply_file_tagging("macros dot gs needs a fix", slice::from_ref(&index));
+        assert_eq!(result, "@macros.gs needs a fix");
+    }
+
+    #[test]
+    fn test_homophone_extension_unknown_file_not_tagged() {
+        let index = create_test_index();
+        let result = apply_file_tagging("open mystery dot gs", slice::from_ref(&index));
+        assert!(!result.contains("@"), "Unknown files should NOT be tagged");
+    }
+
+    #[test]
+    fn test_near_extensions_bidirectional() {
+        assert_eq!(near_extensions("gs"), vec!["js"]);
+        assert_eq!(near_extensions("js"), vec!["gs"]);
+        assert!(near_extensions("rs").is_empty());
+    }
+
+    fn index_with(root: &str, files: &[(&str, &str)]) -> WorkspaceIndex {
+        WorkspaceIndex {
+            root: PathBuf::from(root),
+            files: files
+                .iter()
+                .map(|(name, ext)| FileEntry {
+                    relative_path: format!("{}.{}", name, ext),
+                    name: format!("{}.{}", name, ext),
+                    name_normalized: name.to_lowercase(),
+                    extension: Some(ext.to_string()),
+                })
+                .collect(),
+            updated_at: None,
+            files_skipped: 0,
+        }
+    }
+
+    #[test]
+    fn test_matches_across_multiple_roots() {
+        let indexes = vec![
+            index_with("/monorepo", &[("server", "rs")]),
+            index_with("/sibling", &[("billingClient", "ts")]),
+        ];
+        let result = apply_file_tagging("Fix billing client dot ts", &indexes);
+        assert_eq!(result, "Fix @billingClient.ts");
+
+        let result = apply_file_tagging("Check server.rs please", &indexes);
+        assert_eq!(result, "Check @server.rs please");
+    }
+
+    fn create_symbol_index() -> SymbolIndex {
+        SymbolIndex {
+            root: PathBuf::from("/test"),
+            symbols: [
+                ("parseConfig", SymbolKind::Function),
+                ("parse_config_file", SymbolKind::Function),
+                ("ConfigLoader", SymbolKind::Type),
+                ("MAX_RETRIES", SymbolKind::Constant),
+            ]
+            .iter()
+            .map(|(name, kind)| Symbol {
+                name: name.to_string(),
+                name_normalized: symbol_index::normalize_symbol_name(name),
+                kind: *kind,
+                relative_path: "src/config.ts".to_string(),
+            })
+            .collect(),
+        }
+    }
+
+    #[test]
+    fn test_symbol_tagging_function() {
+        let index = create_symbol_index();
+        let result = apply_symbol_tagging(
+            "Call the parse config function here",
+            slice::from_ref(&index),
+        );
+        assert_eq!(result, "Call @parseConfig here");
+    }
+
+    #[test]
+    fn test_symbol_tagging_by_kind() {
+        let index = create_symbol_index();
+        let result =
+            apply_symbol_tagging("Extend the config loader class", slice::from_ref(&index));
+        assert_eq!(result, "Extend @ConfigLoader");
+
+        let result = apply_symbol_tagging("Bump the max retries constant", slice::from_ref(&index));
+        assert_eq!(result, "Bump @MAX_RETRIES");
+    }
+
+    #[test]
+    fn test_symbol_tagging_keeps_leading_words() {
+        let index = create_symbol_index();
+        let result = apply_symbol_tagging(
+            "Rename the old parse config function",
+            slice::from_ref(&index),
+        );
+        assert_eq!(result, "Rename the old @parseConfig");
+    }
+
+    #[test]
+    fn test_symbol_tagging_unknown_symbol_unchanged() {
+        let index = create_symbol_index();
+        let text = "Fix the zebra crossing function";
+        assert_eq!(apply_symbol_tagging(text, slice::from_ref(&index)), text);
+        assert_eq!(apply_symbol_tagging(text, &[]), text);
+    }
+
+    #[test]
+    fn test_exact_match_in_later_root_beats_fuzzy_in_earlier() {
+        let indexes = vec![
+            index_with("/first", &[("authCheckHelpers", "ts")]),
+            index_with("/second", &[("authCheck", "ts")]),
+        ];
+        let result = apply_file_tagging("Fix auth check dot ts", &indexes);
+        assert_eq!(result, "Fix @authCheck.ts");
+    }
 }
@@ -0,0 +1,104 @@
+//! Programming language detection for the active editor.
+//!
+//! Most editors and terminals put the open file's name in the window title
+//! ("● main.py — project — Visual Studio Code", "lib.rs - murmur - Cursor",
+//! "vim config.ts"). The file extension tells us the language, which sets the
+//! default variable case and which dictionary terms apply.
+
+use super::variable::CaseStyle;
+
+/// File extension → language ID
+const EXTENSION_LANGUAGES: &[(&str, &str)] = &[
+    ("py", "python"),
+    ("pyi", "python"),
+    ("rs", "rust"),
+    ("js", "javascript"),
+    ("jsx", "javascript"),
+    ("mjs", "javascript"),
+    ("cjs", "javascript"),
+    ("ts", "typescript"),
+    ("tsx", "typescript"),
+    ("go", "go"),
+    ("rb", "ruby"),
+    ("java", "java"),
+    ("kt", "kotlin"),
+    ("kts", "kotlin"),
+    ("swift", "swift"),
+    ("c", "c"),
+    ("h", "c"),
+    ("cpp", "cpp"),
+    ("cc", "cpp"),
+    ("hpp", "cpp"),
+    ("cs", "csharp"),
+    ("php", "php"),
+    ("sh", "shell"),
+    ("bash", "shell"),
+    ("zsh", "shell"),
+    ("css", "css"),
+    ("scss", "css"),
+    ("html", "html"),
+    ("sql", "sql"),
+    ("ex", "elixir"),
+    ("exs", "elixir"),
+];
+
+/// Detect the language of the file named in a window title.
+/// Returns None when no word in the title has a known extension.
+pub fn detect_from_title(title: &str) -> Option<&'static str> {
+    title.split_whitespace().find_map(|word| {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric() && c != '_');
+        let (stem, extension) = word.rsplit_once('.')?;
+        if stem.is_empty() {
+            return None;
+        }
+        EXTENSION_LANGUAGES
+            .iter()
+            .find(|(ext, _)| ext.eq_ignore_ascii_case(extension))
+            .map(|(_, language)| *language)
+    })
+}
+
+/// Conventional case for variable names in `language`, if it has one
+pub fn default_case_style(language: &str) -> Option<CaseStyle> {
+    match language {
+        "python" | "rust" | "ruby" | "c" | "elixir" | "shell" | "sql" => Some(CaseStyle::SnakeCase),
+        "javascript" | "typescript" | "go" | "java" | "kotlin" | "swift" | "php" | "cpp" => {
+            Some(CaseStyle::CamelCase)
+        }
+        "csharp" => Some(CaseStyle::PascalCase),
+        "css" | "html" => Some(CaseStyle::KebabCase),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_title() {
+        assert_eq!(
+            detect_from_title("● main.py — project — Visual Studio Code"),
+            Some("python")
+        );
+        assert_eq!(detect_from_title("lib.rs - murmur - Cursor"), Some("rust"));
+        assert_eq!(detect_from_title("vim App.TSX"), Some("typescript"));
+        assert_eq!(detect_from_title("(config.js)"), Some("javascript"));
+    }
+
+    #[test]
+    fn test_detect_from_title_unknown() {
+        assert_eq!(detect_from_title("Welcome — Visual Studio Code"), None);
+        assert_eq!(detect_from_title("ssh user@github.com"), None);
+        assert_eq!(detect_from_title("~/.zshrc"), None);
+    }
+
+    #[test]
+    fn test_default_case_style() {
+        assert_eq!(default_case_style("python"), Some(CaseStyle::SnakeCase));
+        assert_eq!(default_case_style("typescript"), Some(CaseStyle::CamelCase));
+        assert_eq!(default_case_style("css"), Some(CaseStyle::KebabCase));
+        assert_eq!(default_case_style("csharp"), Some(CaseStyle::PascalCase));
+        assert_eq!(default_case_style("markdown"), None);
+    }
+}
@@ -5,14 +5,19 @@
 //! - CLI syntax patterns (dash, pipe, &&, etc.)
 //! - Variable case recognition (camelCase, snake_case, etc.)
 //! - File tagging by voice (@filename syntax)
+//! - Symbol tagging by voice (@functionName syntax)
+//! - Language detection from the editor's window title
 
 pub mod cli_syntax;
 pub mod dictionary;
 pub mod file_index;
 pub mod file_tagger;
+pub mod language;
+pub mod symbol_index;
 pub mod variable;
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// IDE context captured when recording starts.
@@ -22,7 +27,7 @@ pub struct IDEContext {
     pub is_ide: bool,
     /// The bundle ID of the active app
     pub bundle_id: String,
-    /// Detected programming language (if any)
+    /// Programming language of the open file, from the window title (if any)
     pub language: Option<String>,
 }
 
@@ -41,31 +46,55 @@ impl Default for IDEContext {
 pub struct IDESettings {
     /// Enable file tagging by voice (@filename syntax)
     pub file_tagging_enabled: bool,
+    /// Enable symbol tagging by voice ("the parse config function" → @parseConfig)
+    pub symbol_tagging_enabled: bool,
     /// Enable variable case recognition (camelCase, snake_case, etc.)
     pub variable_recognition_enabled: bool,
     /// Enable CLI syntax patterns (dash, pipe, etc.)
     pub cli_syntax_enabled: bool,
     /// Enable programming dictionary (API, JSON, etc.)
     pub dictionary_enabled: bool,
-    /// Default variable case style
+    /// Variable case style when the editor's language has no convention
     pub default_case_style: variable::CaseStyle,
+    /// User overrides of the case style per language ID ("python" → snake_case)
+    pub case_style_by_language: HashMap<String, variable::CaseStyle>,
     /// User-configured workspace roots for file indexing
     pub workspace_roots: Vec<PathBuf>,
+    /// User additions to the programming dictionary (spoken → written)
+    pub custom_dictionary: Vec<(String, String)>,
 }
 
 impl Default for IDESettings {
     fn default() -> Self {
         IDESettings {
             file_tagging_enabled: true,
+            symbol_tagging_enabled: true,
             variable_recognition_enabled: true,
             cli_syntax_enabled: true,
             dictionary_enabled: true,
             default_case_style: variable::CaseStyle::CamelCase,
+            case_style_by_language: HashMap::new(),
             workspace_roots: Vec::new(),
+            custom_dictionary: Vec::new(),
         }
     }
 }
 
+impl IDESettings {
+    /// Case style for variables in `language`: the user's override, then the
+    /// language's convention, then `default_case_style`
+    pub fn case_style_for(&self, language: Option<&str>) -> variable::CaseStyle {
+        language
+            .and_then(|language| {
+                self.case_style_by_language
+                    .get(language)
+                    .copied()
+                    .or_else(|| language::default_case_style(language))
+            })
+            .unwrap_or(self.default_case_style)
+    }
+}
+
 /// Supported IDE bundle IDs.
 /// These are used to detect when the user is in a code editor.
 const IDE_BUNDLE_IDS: &[&str] = &[
@@ -151,9 +180,10 @@ pub fn is_ide(bundle_id: &str) -> bool {
     if bundle_id.contains("TextInputUI") || bundle_id.contains("InputMethod") {
         for keyword in IDE_TEXT_INPUT_KEYWORDS {
             if bundle_id.contains(keyword) {
-                println!(
+                log::info!(
                     "[IDE] Detected IDE via text input service: {} (matched '{}')",
-                    bundle_id, keyword
+                    bundle_id,
+                    keyword
                 );
                 return true;
             }
@@ -164,11 +194,17 @@ pub fn is_ide(bundle_id: &str) -> bool {
 }
 
 /// Get IDE context for the active application.
-pub fn get_ide_context(bundle_id: &str) -> IDEContext {
+/// `window_title` is the editor's front window title, used to detect the language.
+pub fn get_ide_context(bundle_id: &str, window_title: Option<&str>) -> IDEContext {
+    let language = window_title.and_then(language::detect_from_title);
+    if let Some(language) = language {
+        log::debug!("[IDE] Detected language: {}", language);
+    }
+
     IDEContext {
         is_ide: is_ide(bundle_id),
         bundle_id: bundle_id.to_string(),
-        language: None, // Language detection can be added later
+        language: language.map(str::to_string),
     }
 }
 
@@ -178,29 +214,33 @@ pub fn get_ide_context(bundle_id: &str) -> IDEContext {
 /// Transformations are applied in order:
 /// 1. Programming dictionary (API, JSON, etc.)
 /// 2. CLI syntax patterns (dash, pipe, etc.)
-/// 3. Variable case recognition (camelCase triggers)
+/// 3. Variable case recognition (camelCase triggers, default case from the language)
 /// 4. File tagging (if workspace is indexed)
+/// 5. Symbol tagging (if workspace symbols are indexed)
 ///
 /// # Arguments
 /// * `text` - The raw transcription text
 /// * `context` - IDE context for the active app
 /// * `settings` - User's IDE settings
-/// * `workspace_index` - Optional workspace file index for file tagging
+/// * `workspace_indexes` - Workspace file indexes (one per root) for file tagging
+/// * `symbol_indexes` - Workspace symbol indexes (one per root) for symbol tagging
 pub fn apply_ide_transformations(
     text: &str,
     context: &IDEContext,
     settings: &IDESettings,
-    workspace_index: Option<&file_index::WorkspaceIndex>,
+    workspace_indexes: &[file_index::WorkspaceIndex],
+    symbol_indexes: &[symbol_index::SymbolIndex],
 ) -> String {
-    println!("[IDE] apply_ide_transformations called with: {:?}", text);
-    println!(
+    log::debug!("[IDE] apply_ide_transformations called with: {:?}", text);
+    log::debug!(
         "[IDE] context.is_ide={}, bundle_id={}",
-        context.is_ide, context.bundle_id
+        context.is_ide,
+        context.bundle_id
     );
 
     // Only apply transformations if in an IDE
     if !context.is_ide {
-        println!("[IDE] Not in IDE, skipping transformations");
+        log::debug!("[IDE] Not in IDE, skipping transformations");
         return text.to_string();
     }
 
@@ -208,7 +248,11 @@ pub fn apply_ide_transformations(
 
     // 1. Programming dictionary
     if settings.dictionary_enabled {
-        result = dictionary::apply_dictionary(&result);
+        result = dictionary::apply_dictionary_with_custom(
+            &result,
+            &settings.custom_dictionary,
+            context.language.as_deref(),
+        );
     }
 
     // 2. CLI syntax patterns
@@ -218,14 +262,20 @@ pub fn apply_ide_transformations(
 
     // 3. Variable case recognition
     if settings.variable_recognition_enabled {
-        result = variable::apply_variable_patterns(&result, settings.default_case_style);
+        let case_style = settings.case_style_for(context.language.as_deref());
+        result = variable::apply_variable_patterns(&result, case_style);
     }
 
     // 4. File tagging
     // Some patterns (literal filenames) work without an index
     // Others (fuzzy matching) require a workspace index
     if settings.file_tagging_enabled {
-        result = file_tagger::apply_file_tagging(&result, workspace_index);
+        result = file_tagger::apply_file_tagging(&result, workspace_indexes);
+    }
+
+    // 5. Symbol tagging
+    if settings.symbol_tagging_enabled {
+        result = file_tagger::apply_symbol_tagging(&result, symbol_indexes);
     }
 
     result
@@ -258,9 +308,64 @@ mod tests {
 
     #[test]
     fn test_get_ide_context() {
-        let context = get_ide_context("com.microsoft.VSCode");
+        let context = get_ide_context("com.microsoft.VSCode", None);
         assert!(context.is_ide);
         assert_eq!(context.bundle_id, "com.microsoft.VSCode");
+        assert_eq!(context.language, None);
+    }
+
+    #[test]
+    fn test_get_ide_context_language() {
+        let context = get_ide_context("com.microsoft.VSCode", Some("main.py — project"));
+        assert_eq!(context.language.as_deref(), Some("python"));
+    }
+
+    #[test]
+    fn test_language_sets_default_case() {
+        let settings = IDESettings::default();
+        let python = get_ide_context("com.microsoft.VSCode", Some("main.py"));
+        assert_eq!(
+            apply_ide_transformations("variable name user name", &python, &settings, &[], &[]),
+            "user_name"
+        );
+        let csharp = get_ide_context("com.microsoft.VSCode", Some("Program.cs"));
+        assert_eq!(
+            apply_ide_transformations("variable name user name", &csharp, &settings, &[], &[]),
+            "UserName"
+        );
+        let unknown = get_ide_context("com.microsoft.VSCode", None);
+        assert_eq!(
+            apply_ide_transformations("variable name user name", &unknown, &settings, &[], &[]),
+            "userName"
+        );
+    }
+
+    #[test]
+    fn test_case_style_by_language_override() {
+        let settings = IDESettings {
+            case_style_by_language: HashMap::from([(
+                "python".to_string(),
+                variable::CaseStyle::CamelCase,
+            )]),
+            default_case_style: variable::CaseStyle::KebabCase,
+            ..Default::default()
+        };
+        assert_eq!(
+            settings.case_style_for(Some("python")),
+            variable::CaseStyle::CamelCase
+        );
+        assert_eq!(
+            settings.case_style_for(Some("rust")),
+            variable::CaseStyle::SnakeCase
+        );
+        assert_eq!(
+            settings.case_style_for(Some("markdown")),
+            variable::CaseStyle::KebabCase
+        );
+        assert_eq!(
+            settings.case_style_for(None),
+            variable::CaseStyle::KebabCase
+        );
     }
 
     #[test]
@@ -270,6 +375,7 @@ mod tests {
         assert!(settings.dictionary_enabled);
         assert!(settings.cli_syntax_enabled);
         assert!(settings.variable_recognition_enabled);
+        assert!(settings.symbol_tagging_enabled);
     }
 
     #[test]
@@ -0,0 +1,323 @@
+//! Code symbol indexing.
+//!
+//! Lightweight scan of indexed workspace files for top-level declarations
+//! (functions, classes/types, constants) so they can be tagged by voice:
+//! - "the parse config function" → "@parseConfig"
+//!
+//! This is a line-based regex scan, not a parser: only declarations that start
+//! at column 0 are picked up, which keeps methods and locals out of the index.
+
+use super::file_index::WorkspaceIndex;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+/// Maximum number of symbols to index per workspace root.
+const MAX_SYMBOLS: usize = 50_000;
+
+/// Files larger than this are skipped (generated or vendored code).
+const MAX_FILE_BYTES: u64 = 512 * 1024;
+
+/// Kind of a top-level declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    /// fn / def / func / function
+    Function,
+    /// class / struct / enum / trait / interface / type / module
+    Type,
+    /// const / static / top-level let/var
+    Constant,
+}
+
+impl SymbolKind {
+    /// Map a spoken noun ("function", "class", ...) to a symbol kind.
+    pub fn from_spoken(noun: &str) -> Option<Self> {
+        match noun.to_lowercase().as_str() {
+            "function" | "method" | "func" => Some(SymbolKind::Function),
+            "class" | "struct" | "type" | "interface" | "enum" | "trait" => Some(SymbolKind::Type),
+            "constant" | "const" => Some(SymbolKind::Constant),
+            _ => None,
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Self {
+        match keyword {
+            "fn" | "def" | "func" | "function" | "function*" => SymbolKind::Function,
+            "const" | "static" | "let" | "var" => SymbolKind::Constant,
+            _ => SymbolKind::Type,
+        }
+    }
+}
+
+/// A top-level symbol declaration.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Symbol {
+    /// Symbol name as declared (e.g. "parseConfig")
+    pub name: String,
+    /// Lowercase name without underscores (for matching spoken words)
+    pub name_normalized: String,
+    pub kind: SymbolKind,
+    /// Relative path of the declaring file from the workspace root
+    pub relative_path: String,
+}
+
+impl Symbol {
+    fn new(name: &str, kind: SymbolKind, relative_path: &str) -> Self {
+        Symbol {
+            name: name.to_string(),
+            name_normalized: normalize_symbol_name(name),
+            kind,
+            relative_path: relative_path.to_string(),
+        }
+    }
+}
+
+/// Symbols declared in one workspace root.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymbolIndex {
+    /// Root path of the workspace (matches the `WorkspaceIndex` root)
+    pub root: PathBuf,
+    pub symbols: Vec<Symbol>,
+}
+
+// ============================================================================
+// STATIC REGEX PATTERNS
+// SAFETY: All unwrap() calls below are safe because these are compile-time
+// constant regex strings that have been validated during development.
+// Group 1 = declaration keyword, group 2 = symbol name.
+// ============================================================================
+
+static RUST_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:pub(?:\([^)]*\))?\s+)?(?:async\s+)?(?:const\s+)?(?:unsafe\s+)?(fn|struct|enum|trait|type|const|static|mod)\s+([A-Za-z_]\w*)").unwrap()
+});
+
+static PYTHON_DECL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(?:async\s+)?(def|class)\s+([A-Za-z_]\w*)").unwrap());
+
+static JS_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:export\s+)?(?:default\s+)?(?:declare\s+)?(?:abstract\s+)?(?:async\s+)?(function\*?|class|interface|type|enum|const|let|var)\s+([A-Za-z_$][\w$]*)").unwrap()
+});
+
+static GO_DECL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(func|type|const|var)\s+(?:\([^)]*\)\s*)?([A-Za-z_]\w*)").unwrap()
+});
+
+static RUBY_DECL: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(def|class|module)\s+(?:self\.)?([A-Za-z_]\w*)").unwrap());
+
+/// Declaration pattern for a file extension, if the language is supported.
+fn pattern_for_extension(extension: &str) -> Option<&'static Regex> {
+    match extension.to_lowercase().as_str() {
+        "rs" => Some(&RUST_DECL),
+        "py" => Some(&PYTHON_DECL),
+        "ts" | "tsx" | "js" | "jsx" | "mjs" | "cjs" => Some(&JS_DECL),
+        "go" => Some(&GO_DECL),
+        "rb" => Some(&RUBY_DECL),
+        _ => None,
+    }
+}
+
+/// "parse_config" / "parseConfig" → "parseconfig"
+pub fn normalize_symbol_name(name: &str) -> String {
+    name.chars()
+        .filter(|c| *c != '_' && *c != '$')
+        .flat_map(|c| c.to_lowercase())
+        .collect()
+}
+
+/// Extract top-level symbol declarations from source text.
+pub fn scan_source(source: &str, extension: &str, relative_path: &str) -> Vec<Symbol> {
+    let Some(pattern) = pattern_for_extension(extension) else {
+        return Vec::new();
+    };
+
+    source
+        .lines()
+        .filter_map(|line| {
+            let captures = pattern.captures(line)?;
+            let keyword = captures.get(1)?.as_str();
+            let name = captures.get(2)?.as_str();
+            Some(Symbol::new(
+                name,
+                SymbolKind::from_keyword(keyword),
+                relative_path,
+            ))
+        })
+        .collect()
+}
+
+impl SymbolIndex {
+    /// Scan every supported file in a workspace index for top-level symbols.
+    pub fn build(index: &WorkspaceIndex) -> Self {
+        let mut symbols = Vec::new();
+
+        for file in &index.files {
+            if symbols.len() >= MAX_SYMBOLS {
+                break;
+            }
+            symbols.extend(scan_file(&index.root, &file.relative_path));
+        }
+        symbols.truncate(MAX_SYMBOLS);
+
+        SymbolIndex {
+            root: index.root.clone(),
+            symbols,
+        }
+    }
+
+    /// Re-scan a changed path (file or directory) after a file system event.
+    /// Symbols from files under `path` are dropped, then re-read from whatever
+    /// the workspace index now holds there.
+    pub fn refresh(&mut self, index: &WorkspaceIndex, path: &Path) {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return;
+        };
+        let relative = relative.to_string_lossy().to_string();
+        let dir_prefix = format!("{}{}", relative, std::path::MAIN_SEPARATOR);
+        let under_path = |p: &str| p == relative || p.starts_with(&dir_prefix);
+
+        self.symbols.retain(|s| !under_path(&s.relative_path));
+
+        for file in index.files.iter().filter(|f| under_path(&f.relative_path)) {
+            if self.symbols.len() >= MAX_SYMBOLS {
+                break;
+            }
+            self.symbols
+                .extend(scan_file(&self.root, &file.relative_path));
+        }
+        self.symbols.truncate(MAX_SYMBOLS);
+    }
+
+    /// Get the number of indexed symbols.
+    pub fn symbol_count(&self) -> usize {
+        self.symbols.len()
+    }
+}
+
+/// Read one file and extract its symbols. Unreadable or oversized files yield none.
+fn scan_file(root: &Path, relative_path: &str) -> Vec<Symbol> {
+    let path = root.join(relative_path);
+    let Some(extension) = path.extension().and_then(|e| e.to_str()) else {
+        return Vec::new();
+    };
+    if pattern_for_extension(extension).is_none() {
+        return Vec::new();
+    }
+
+    let too_large = fs::metadata(&path)
+        .map(|m| m.len() > MAX_FILE_BYTES)
+        .unwrap_or(true);
+    if too_large {
+        return Vec::new();
+    }
+
+    match fs::read_to_string(&path) {
+        Ok(source) => scan_source(&source, extension, relative_path),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ide::file_index::IndexConfig;
+    use tempfile::TempDir;
+
+    fn names(symbols: &[Symbol]) -> Vec<&str> {
+        symbols.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_scan_rust() {
+        let source = "pub fn parse_config() {}\n\
+                      pub(crate) struct AppState {}\n\
+                      const MAX_FILES: usize = 1;\n\
+                      impl AppState {\n    fn method(&self) {}\n}\n\
+                      async fn fetch() {}\n";
+        let symbols = scan_source(source, "rs", "src/lib.rs");
+        assert_eq!(
+            names(&symbols),
+            vec!["parse_config", "AppState", "MAX_FILES", "fetch"]
+        );
+        assert_eq!(symbols[0].kind, SymbolKind::Function);
+        assert_eq!(symbols[1].kind, SymbolKind::Type);
+        assert_eq!(symbols[2].kind, SymbolKind::Constant);
+    }
+
+    #[test]
+    fn test_scan_typescript() {
+        let source = "export async function parseConfig() {}\n\
+                      export default class ConfigLoader {}\n\
+                      export interface Options {}\n\
+                      const DEFAULT_PORT = 3000;\n\
+                      function helper() {\n  const inner = 1;\n}\n";
+        let symbols = scan_source(source, "ts", "src/config.ts");
+        assert_eq!(
+            names(&symbols),
+            vec![
+                "parseConfig",
+                "ConfigLoader",
+                "Options",
+                "DEFAULT_PORT",
+                "helper"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scan_python_go_ruby() {
+        let py = scan_source(
+            "def load():\n    def inner(): pass\nclass Loader:\n",
+            "py",
+            "a.py",
+        );
+        assert_eq!(names(&py), vec!["load", "Loader"]);
+
+        let go = scan_source(
+            "func (s *Server) Start() {}\ntype Server struct {}\n",
+            "go",
+            "a.go",
+        );
+        assert_eq!(names(&go), vec!["Start", "Server"]);
+
+        let rb = scan_source("module Billing\nclass Invoice\n  def total\n", "rb", "a.rb");
+        assert_eq!(names(&rb), vec!["Billing", "Invoice"]);
+    }
+
+    #[test]
+    fn test_unsupported_language_yields_nothing() {
+        assert!(scan_source("# fn heading", "md", "README.md").is_empty());
+    }
+
+    #[test]
+    fn test_normalize_symbol_name() {
+        assert_eq!(normalize_symbol_name("parse_config"), "parseconfig");
+        assert_eq!(normalize_symbol_name("parseConfig"), "parseconfig");
+        assert_eq!(normalize_symbol_name("MAX_FILES"), "maxfiles");
+    }
+
+    #[test]
+    fn test_build_and_refresh() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(
+            temp_dir.path().join("config.ts"),
+            "export function parseConfig() {}\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("README.md"), "# function notASymbol\n").unwrap();
+
+        let workspace =
+            WorkspaceIndex::build_with_progress(temp_dir.path(), &IndexConfig::default(), |_| {})
+                .unwrap();
+        let mut symbols = SymbolIndex::build(&workspace);
+        assert_eq!(names(&symbols.symbols), vec!["parseConfig"]);
+
+        let path = workspace.root.join("config.ts");
+        fs::write(&path, "export function loadConfig() {}\n").unwrap();
+        symbols.refresh(&workspace, &path);
+        assert_eq!(names(&symbols.symbols), vec!["loadConfig"]);
+    }
+}
@@ -3,6 +3,7 @@
 //! Detects trigger words for case styles and applies them:
 //! - "camel case user name" → "userName"
 //! - "snake case user name" → "user_name"
+//! - "variable name user name" → the editor language's convention ("user_name" in Python)
 //! - "is login error" → "isLoginError" (detected from context)
 
 use regex::Regex;
@@ -11,6 +12,7 @@ use std::sync::LazyLock;
 
 /// Case style for variable names.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
 pub enum CaseStyle {
     /// camelCase - first word lowercase, rest capitalized
     #[default]
@@ -110,6 +112,10 @@ static CASE_TRIGGER_PATTERNS: LazyLock<Vec<(Regex, CaseStyle)>> = LazyLock::new(
     ]
 });
 
+/// "variable name [words]" → the default case style (the language's convention)
+static DEFAULT_CASE_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)\bvariable\s+name\s+(.+)").unwrap());
+
 /// Pattern for "underscore" between words (implies snake_case).
 static UNDERSCORE_PATTERN: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"(?i)(\w+)\s+underscore\s+(\w+)").unwrap());
@@ -124,14 +130,14 @@ static DASH_BETWEEN_PATTERN: LazyLock<Regex> =
 ///
 /// # Arguments
 /// * `text` - The text to process
-/// * `default_style` - Default case style to use when none is specified
+/// * `default_style` - Case style for "variable name [words]", where none is spoken
 ///
 /// # Examples
 /// ```ignore
 /// let result = apply_variable_patterns("camel case user name", CaseStyle::CamelCase);
 /// assert_eq!(result, "userName");
 /// ```
-pub fn apply_variable_patterns(text: &str, _default_style: CaseStyle) -> String {
+pub fn apply_variable_patterns(text: &str, default_style: CaseStyle) -> String {
     let mut result = text.to_string();
 
     // Check for explicit case triggers first
@@ -145,6 +151,18 @@ pub fn apply_variable_patterns(text: &str, _default_style: CaseStyle) -> String
         }
     }
 
+    // "variable name [words]" → default style
+    if let Some(words_match) = DEFAULT_CASE_PATTERN
+        .captures(&result)
+        .and_then(|captures| captures.get(1))
+    {
+        let words: Vec<&str> = words_match.as_str().split_whitespace().collect();
+        let converted = default_style.apply(&words);
+        result = DEFAULT_CASE_PATTERN
+            .replace(&result, converted.as_str())
+            .to_string();
+    }
+
     // Handle "underscore" between words → snake_case
     while UNDERSCORE_PATTERN.is_match(&result) {
         result = UNDERSCORE_PATTERN.replace(&result, "${1}_${2}").to_string();
@@ -253,6 +271,14 @@ mod tests {
         assert_eq!(result, "MAX_VALUE");
     }
 
+    #[test]
+    fn test_variable_name_uses_default_style() {
+        let python = apply_variable_patterns("variable name user name", CaseStyle::SnakeCase);
+        assert_eq!(python, "user_name");
+        let js = apply_variable_patterns("variable name user name", CaseStyle::CamelCase);
+        assert_eq!(js, "userName");
+    }
+
     #[test]
     fn test_constant_case_trigger() {
         let result = apply_variable_patterns("constant case default timeout", CaseStyle::CamelCase);
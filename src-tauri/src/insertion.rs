@@ -0,0 +1,234 @@
+//! Text preparation for insertion into the target app.
+//!
+//! Controls whether text is typed or pasted, and how its whitespace and line
+//! breaks are cleaned up before insertion. Pressing Enter in a chat app sends
+//! the message, so multi-line output is collapsed there, while editors and
+//! documents can keep it.
+
+use serde::{Deserialize, Serialize};
+
+/// Apps where a typed newline sends the message instead of breaking the line.
+const CHAT_BUNDLE_IDS: &[&str] = &[
+    "com.tinyspeck.slackmacgap",
+    "com.apple.MobileSMS",
+    "com.apple.iChat",
+    "com.hnc.Discord",
+    "com.facebook.Messenger",
+    "net.whatsapp.WhatsApp",
+    "org.telegram.desktop",
+    "ru.keepcoder.Telegram",
+    "org.whispersystems.signal-desktop",
+    "com.microsoft.teams",
+    "com.microsoft.teams2",
+    "com.wechat.WeChat",
+    "jp.naver.line.mac",
+    "com.viber.osx",
+    "im.riot.app",
+    "us.zoom.xos",
+];
+
+/// Whole dictations that undo the previous insertion instead of being typed.
+const UNDO_PHRASES: &[&str] = &["undo that", "undo this", "undo last", "undo the last one"];
+
+/// How newlines in the final text are handled before insertion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewlineMode {
+    /// Replace every line break with a space (single-line output)
+    #[default]
+    CollapseToSpace,
+    /// Keep line breaks as typed Return keys / pasted newlines
+    PreserveNewlines,
+    /// Keep line breaks, except in chat apps where Enter sends the message
+    Smart,
+}
+
+/// How text is put into the target app.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertionMethod {
+    /// Keystrokes for ASCII text, clipboard paste when it contains Unicode
+    #[default]
+    Auto,
+    /// Always type the text (non-ASCII characters may be dropped)
+    Keystroke,
+    /// Always paste via the clipboard, restoring its previous contents
+    ClipboardPaste,
+}
+
+impl InsertionMethod {
+    /// Whether `text` should be pasted rather than typed.
+    pub fn uses_clipboard(self, text: &str) -> bool {
+        match self {
+            InsertionMethod::Auto => !text.is_ascii(),
+            InsertionMethod::Keystroke => false,
+            InsertionMethod::ClipboardPaste => true,
+        }
+    }
+}
+
+/// Whether Enter sends the message in the given app.
+pub fn is_chat_app(bundle_id: Option<&str>) -> bool {
+    bundle_id.is_some_and(|id| CHAT_BUNDLE_IDS.contains(&id))
+}
+
+/// Whether a transcript is the spoken undo command ("Undo that.").
+/// Case and surrounding punctuation are ignored; the phrase must be the whole dictation.
+pub fn is_undo_command(transcript: &str) -> bool {
+    let phrase = transcript
+        .trim()
+        .trim_matches(|c: char| c.is_ascii_punctuation() || c.is_whitespace())
+        .to_lowercase();
+    UNDO_PHRASES.contains(&phrase.as_str())
+}
+
+/// Normalize whitespace in text about to be inserted.
+/// Line breaks are kept or replaced depending on the mode (and, for `Smart`,
+/// the target app). Runs of spaces are collapsed unless `collapse_whitespace`
+/// is off, which keeps the spacing of tables, code and ASCII art as-is.
+pub fn normalize_for_insertion(
+    text: &str,
+    mode: NewlineMode,
+    bundle_id: Option<&str>,
+    collapse_whitespace: bool,
+) -> String {
+    let keep_newlines = match mode {
+        NewlineMode::CollapseToSpace => false,
+        NewlineMode::PreserveNewlines => true,
+        NewlineMode::Smart => !is_chat_app(bundle_id),
+    };
+
+    let text = text.replace("\r\n", "\n").replace('\r', "\n");
+
+    if !keep_newlines {
+        if collapse_whitespace {
+            return text.split_whitespace().collect::<Vec<&str>>().join(" ");
+        }
+        // One space per line break, and the lines themselves untouched
+        return text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .collect::<Vec<&str>>()
+            .join(" ");
+    }
+
+    // Collapse spaces within each line (if enabled), and runs of blank lines
+    // to a single paragraph break
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = if line.trim().is_empty() {
+            String::new()
+        } else if collapse_whitespace {
+            line.split_whitespace().collect::<Vec<&str>>().join(" ")
+        } else {
+            line.to_string()
+        };
+        if line.is_empty() && lines.last().is_none_or(|l| l.is_empty()) {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collapse_to_space() {
+        assert_eq!(
+            normalize_for_insertion(
+                "first line\r\nsecond  line\n",
+                NewlineMode::CollapseToSpace,
+                None,
+                true
+            ),
+            "first line second line"
+        );
+    }
+
+    #[test]
+    fn test_preserve_newlines() {
+        assert_eq!(
+            normalize_for_insertion(
+                "\nDear team,\r\n\n\n  Thanks   for\rthe update.\n\n",
+                NewlineMode::PreserveNewlines,
+                Some("com.tinyspeck.slackmacgap"),
+                true
+            ),
+            "Dear team,\n\nThanks for\nthe update."
+        );
+    }
+
+    #[test]
+    fn test_preserve_spacing_when_not_collapsing() {
+        let table = "| name  | size |\n|-------|------|\n|  a    |   1  |\n\n\n";
+        assert_eq!(
+            normalize_for_insertion(table, NewlineMode::PreserveNewlines, None, false),
+            "| name  | size |\n|-------|------|\n|  a    |   1  |"
+        );
+        assert_eq!(
+            normalize_for_insertion(table, NewlineMode::CollapseToSpace, None, false),
+            "| name  | size | |-------|------| |  a    |   1  |"
+        );
+        assert_eq!(
+            normalize_for_insertion("let  x =  1;", NewlineMode::Smart, None, true),
+            "let x = 1;"
+        );
+    }
+
+    #[test]
+    fn test_smart_collapses_only_in_chat_apps() {
+        let text = "- buy milk\n- call Sam";
+        assert_eq!(
+            normalize_for_insertion(
+                text,
+                NewlineMode::Smart,
+                Some("com.tinyspeck.slackmacgap"),
+                true
+            ),
+            "- buy milk - call Sam"
+        );
+        assert_eq!(
+            normalize_for_insertion(text, NewlineMode::Smart, Some("com.apple.TextEdit"), true),
+            text
+        );
+        assert_eq!(
+            normalize_for_insertion(text, NewlineMode::Smart, None, true),
+            text
+        );
+    }
+
+    #[test]
+    fn test_is_undo_command() {
+        assert!(is_undo_command("Undo that."));
+        assert!(is_undo_command("  undo this! "));
+        assert!(!is_undo_command("Undo that change in the config"));
+        assert!(!is_undo_command("Let's undo that."));
+        assert!(!is_undo_command("Undo"));
+    }
+
+    #[test]
+    fn test_insertion_method_uses_clipboard() {
+        assert!(!InsertionMethod::Auto.uses_clipboard("hello"));
+        assert!(InsertionMethod::Auto.uses_clipboard("नमस्ते"));
+        assert!(!InsertionMethod::Keystroke.uses_clipboard("नमस्ते"));
+        assert!(InsertionMethod::ClipboardPaste.uses_clipboard("hello"));
+
+        let method: InsertionMethod = serde_json::from_str("\"clipboard_paste\"").unwrap();
+        assert_eq!(method, InsertionMethod::ClipboardPaste);
+    }
+
+    #[test]
+    fn test_newline_mode_deserialize() {
+        let mode: NewlineMode = serde_json::from_str("\"smart\"").unwrap();
+        assert_eq!(mode, NewlineMode::Smart);
+        let mode: NewlineMode = serde_json::from_str("\"preserve_newlines\"").unwrap();
+        assert_eq!(mode, NewlineMode::PreserveNewlines);
+        assert!(serde_json::from_str::<NewlineMode>("\"keep\"").is_err());
+    }
+}
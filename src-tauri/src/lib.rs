@@ -1,3 +1,4 @@
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Mutex;
 use std::time::Instant;
 use tauri::{
@@ -5,28 +6,41 @@ use tauri::{
     tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent},
     App, AppHandle, Emitter, Listener, Manager, Runtime, State, WindowEvent,
 };
+use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_global_shortcut::{GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
+use tokio_util::sync::CancellationToken;
 
 mod audio;
+mod audio_cues;
 mod auth;
 mod config;
+mod deepgram;
+mod diagnostics;
 mod error;
 mod groq_llm;
+mod health;
+mod history;
 mod http_client;
 mod ide;
+mod insertion;
 mod permissions;
 mod platform;
 mod rate_limit;
+mod redact;
+mod replacements;
+mod scratch;
 mod signing;
 mod state;
 mod styles;
+mod text_normalize;
 mod whisper_api;
 
 use audio::{encode_samples_to_wav, AudioRecorder};
 use config::AppConfig;
 use groq_llm::{GroqLlmClient, UserIntent};
 use state::{
-    DictationMode, ErrorEvent, RecordingState, StateChangeEvent, TranscriptionCompleteEvent,
+    DictationMode, ErrorEvent, LatencyReport, RecordingState, StateChangeEvent,
+    TranscriptionCompleteEvent,
 };
 
 // ============================================================================
@@ -64,13 +78,76 @@ fn sentry_capture_error(error: &str, context: Option<&str>) {
 /// Offset from bottom of screen for overlay positioning (pixels)
 const OVERLAY_BOTTOM_OFFSET: i32 = 300;
 
-/// How long to display "Done!" state before hiding overlay (ms)
-/// Reduced from 300ms for snappier feel while still showing completion
-const DONE_DISPLAY_DELAY_MS: u64 = 100;
+/// Overlay size used when the window size can't be queried (matches tauri.conf.json)
+const OVERLAY_DEFAULT_WIDTH: u32 = 500;
+const OVERLAY_DEFAULT_HEIGHT: u32 = 80;
 
-/// How long to wait for the target app to regain focus after hiding overlay (ms)
-/// Reduced from 100ms - AppleScript activation is fast
-const APP_FOCUS_WAIT_MS: u64 = 30;
+/// Overlay position used when no monitor information is available at all
+const OVERLAY_FALLBACK_POSITION: (i32, i32) = (100, 100);
+
+/// Maximum length of a typed command for `transform_clipboard` (characters)
+const MAX_TRANSFORM_COMMAND_LENGTH: usize = 500;
+
+/// How often the permission watcher re-checks permission status (ms)
+const PERMISSION_WATCH_INTERVAL_MS: u64 = 1000;
+
+/// Longest recording kept for `retranscribe_last` (~7.5MB of 16kHz f32 samples)
+const MAX_RETAINED_AUDIO_SECS: usize = 120;
+
+/// How long the last recording stays available for `retranscribe_last`
+/// (unless the user opted in to keeping it for playback)
+const RETAINED_AUDIO_TTL: std::time::Duration = std::time::Duration::from_secs(300);
+
+/// How often the max-duration watchdog checks a paused recording
+const WATCHDOG_PAUSED_POLL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// How long after an insertion it can still be undone. Past this the user has
+/// likely typed more, and deleting characters would remove their text instead.
+const UNDO_INSERTION_WINDOW: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// The most recent dictation result, kept so it can be re-processed
+#[derive(Debug, Clone)]
+struct LastDictation {
+    /// Raw Whisper transcript (after IDE transforms, before LLM enhancement)
+    raw_transcript: String,
+    /// Text that was inserted into the target app
+    inserted_text: String,
+    /// Transcription confidence (0.0-1.0), if reported by the API
+    confidence: Option<f32>,
+}
+
+/// Text most recently put into the target app, kept so it can be undone
+#[derive(Debug, Clone)]
+struct LastInsertion {
+    /// Text as inserted (after whitespace normalization)
+    text: String,
+    /// Characters typed, i.e. how many Delete presses remove it
+    char_count: usize,
+    /// Pasted via the clipboard (undone with the app's Undo) rather than typed
+    pasted: bool,
+    inserted_at: Instant,
+}
+
+/// Identifies the current recording, so work started for one recording
+/// (e.g. the async selection detection) can tell when it has gone stale.
+#[derive(Default)]
+struct RecordingSession(AtomicU64);
+
+impl RecordingSession {
+    /// Start a new recording and return its id
+    fn begin(&self) -> u64 {
+        self.0.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Invalidate the current id (recording stopped or cancelled)
+    fn end(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn is_current(&self, id: u64) -> bool {
+        self.0.load(Ordering::SeqCst) == id
+    }
+}
 
 /// Application state - single source of truth
 pub struct AppState {
@@ -78,18 +155,62 @@ pub struct AppState {
     config: Mutex<AppConfig>,
     recording_state: Mutex<RecordingState>,
     recording_start: Mutex<Option<Instant>>,
+    /// When the current recording was paused (None while not paused)
+    recording_paused_at: Mutex<Option<Instant>>,
     /// Current mode: Dictation (default) or Command (when text is selected)
     dictation_mode: Mutex<DictationMode>,
     /// Selected text captured at recording start (for Command Mode)
     selected_text: Mutex<Option<String>>,
     /// Active app captured at recording start (for context-aware styles)
     active_style: Mutex<Option<styles::Style>>,
+    /// Style forced by the user, used instead of the detected style until cleared
+    forced_style: Mutex<Option<styles::Style>>,
     /// Bundle ID of active app (for IDE detection)
     active_bundle_id: Mutex<Option<String>>,
-    /// Workspace file index for file tagging (built on startup)
-    workspace_index: Mutex<Option<ide::file_index::WorkspaceIndex>>,
+    /// Workspace file indexes for file tagging, one per workspace root
+    workspace_indexes: Mutex<Vec<ide::file_index::WorkspaceIndex>>,
+    /// File watchers keeping the workspace indexes current (dropped to stop them)
+    workspace_watchers: Mutex<Vec<ide::file_index::WorkspaceWatcher>>,
+    /// Top-level code symbols per workspace root (built in the background)
+    symbol_indexes: Mutex<Vec<ide::symbol_index::SymbolIndex>>,
     /// Flag to track when text insertion is in progress (prevents new recordings)
     is_inserting: Mutex<bool>,
+    /// Last permission status seen by `recheck_permissions` / the watcher
+    permission_status: Mutex<Option<permissions::PermissionStatus>>,
+    /// Id of the running background permission watcher (None when stopped).
+    /// A watcher thread exits as soon as its id is no longer the active one.
+    permission_watcher: Mutex<Option<u64>>,
+    next_permission_watcher_id: AtomicU64,
+    /// Last completed dictation (for re-enhancing with a different style)
+    last_dictation: Mutex<Option<LastDictation>>,
+    /// Send the current recording to the scratch buffer instead of typing it
+    scratch_capture: Mutex<bool>,
+    /// The current recording was started with the raw hotkey - skip LLM enhancement
+    raw_session: Mutex<bool>,
+    /// Last text inserted into the target app (for `undo_last_insertion`)
+    last_insertion: Mutex<Option<LastInsertion>>,
+    /// The current dictation was the spoken undo command - undo instead of inserting
+    undo_requested: Mutex<bool>,
+    /// The current result was delivered via the clipboard only - don't type it
+    clipboard_delivered: Mutex<bool>,
+    /// The current result matched the sensitive-text patterns - keep it out of the logs
+    sensitive_transcript: Mutex<bool>,
+    /// 16kHz samples of the last recording and when they were captured
+    last_audio: Mutex<Option<(Vec<f32>, Instant)>>,
+    /// Cancels the in-flight transcription/enhancement (None when not processing)
+    processing_cancel: Mutex<Option<CancellationToken>>,
+    /// Disarms the max-duration timer of the current recording (None when not recording)
+    recording_watchdog: Mutex<Option<CancellationToken>>,
+    /// Id of the current recording; bumped on stop/cancel to discard late async results
+    recording_session: RecordingSession,
+    /// Recording hotkey currently registered with the OS (unregistered on change)
+    main_shortcut: Mutex<Option<Shortcut>>,
+    /// Raw-mode recording hotkey currently registered with the OS (None if unset)
+    raw_shortcut: Mutex<Option<Shortcut>>,
+    /// Hotkey that started the current recording (None if started from the UI)
+    session_shortcut: Mutex<Option<Shortcut>>,
+    /// Consecutive mixed-mode detections of an unlisted language (adaptive language)
+    language_streak: Mutex<whisper_api::LanguageStreak>,
 }
 
 impl AppState {
@@ -99,12 +220,34 @@ impl AppState {
             config: Mutex::new(config),
             recording_state: Mutex::new(RecordingState::Idle),
             recording_start: Mutex::new(None),
+            recording_paused_at: Mutex::new(None),
             dictation_mode: Mutex::new(DictationMode::Dictation),
             selected_text: Mutex::new(None),
             active_style: Mutex::new(None),
+            forced_style: Mutex::new(None),
             active_bundle_id: Mutex::new(None),
-            workspace_index: Mutex::new(None),
+            workspace_indexes: Mutex::new(Vec::new()),
+            workspace_watchers: Mutex::new(Vec::new()),
+            symbol_indexes: Mutex::new(Vec::new()),
             is_inserting: Mutex::new(false),
+            permission_status: Mutex::new(None),
+            permission_watcher: Mutex::new(None),
+            next_permission_watcher_id: AtomicU64::new(0),
+            last_dictation: Mutex::new(None),
+            scratch_capture: Mutex::new(false),
+            raw_session: Mutex::new(false),
+            last_insertion: Mutex::new(None),
+            undo_requested: Mutex::new(false),
+            clipboard_delivered: Mutex::new(false),
+            sensitive_transcript: Mutex::new(false),
+            last_audio: Mutex::new(None),
+            processing_cancel: Mutex::new(None),
+            recording_watchdog: Mutex::new(None),
+            recording_session: RecordingSession::default(),
+            main_shortcut: Mutex::new(None),
+            raw_shortcut: Mutex::new(None),
+            session_shortcut: Mutex::new(None),
+            language_streak: Mutex::new(whisper_api::LanguageStreak::default()),
         }
     }
 
@@ -144,6 +287,41 @@ impl AppState {
         }
     }
 
+    /// Start a new recording session and return its id
+    fn begin_recording_session(&self) -> u64 {
+        self.recording_session.begin()
+    }
+
+    /// Mark the current recording session as over. Holds the mode lock so a
+    /// concurrent `apply_selection` either lands before the stop or not at all.
+    fn end_recording_session(&self) {
+        let _mode = self.dictation_mode.lock();
+        self.recording_session.end();
+    }
+
+    /// Switch to Command Mode with the detected selection, unless `session`
+    /// has since been stopped or replaced. Returns whether it was applied.
+    fn apply_selection(&self, session: u64, text: String) -> bool {
+        let Ok(mut mode) = self.dictation_mode.lock() else {
+            return false;
+        };
+        if !self.recording_session.is_current(session) {
+            log::info!("[MODE] Discarding selection from a stopped recording");
+            return false;
+        }
+        *mode = DictationMode::Command;
+        self.set_selected_text(Some(text));
+        true
+    }
+
+    /// Record a transcription's new language; returns it once it should be suggested
+    fn record_new_language(&self, new_language: Option<&str>) -> Option<String> {
+        self.language_streak
+            .lock()
+            .ok()
+            .and_then(|mut streak| streak.record(new_language))
+    }
+
     fn get_active_style(&self) -> Option<styles::Style> {
         self.active_style.lock().ok().and_then(|s| s.clone())
     }
@@ -154,6 +332,16 @@ impl AppState {
         }
     }
 
+    fn get_forced_style(&self) -> Option<styles::Style> {
+        self.forced_style.lock().ok().and_then(|s| s.clone())
+    }
+
+    fn set_forced_style(&self, style: Option<styles::Style>) {
+        if let Ok(mut s) = self.forced_style.lock() {
+            *s = style;
+        }
+    }
+
     fn get_active_bundle_id(&self) -> Option<String> {
         self.active_bundle_id.lock().ok().and_then(|b| b.clone())
     }
@@ -164,16 +352,113 @@ impl AppState {
         }
     }
 
-    fn get_workspace_index(&self) -> Option<ide::file_index::WorkspaceIndex> {
-        self.workspace_index.lock().ok().and_then(|idx| idx.clone())
+    fn get_workspace_indexes(&self) -> Vec<ide::file_index::WorkspaceIndex> {
+        self.workspace_indexes
+            .lock()
+            .map(|idx| idx.clone())
+            .unwrap_or_default()
+    }
+
+    /// Add (or replace) the index for a root, together with its watcher if any.
+    fn add_workspace_index(
+        &self,
+        index: ide::file_index::WorkspaceIndex,
+        watcher: Option<ide::file_index::WorkspaceWatcher>,
+    ) {
+        self.remove_workspace_index(&index.root);
+        if let Ok(mut w) = self.workspace_watchers.lock() {
+            w.extend(watcher);
+        }
+        if let Ok(mut idx) = self.workspace_indexes.lock() {
+            idx.push(index);
+        }
+    }
+
+    /// Remove a root's index and stop its watcher. Returns false if it wasn't indexed.
+    fn remove_workspace_index(&self, root: &std::path::Path) -> bool {
+        if let Ok(mut w) = self.workspace_watchers.lock() {
+            w.retain(|watcher| watcher.root != root);
+        }
+        if let Ok(mut sym) = self.symbol_indexes.lock() {
+            sym.retain(|index| index.root != root);
+        }
+        match self.workspace_indexes.lock() {
+            Ok(mut idx) => {
+                let before = idx.len();
+                idx.retain(|index| index.root != root);
+                idx.len() != before
+            }
+            Err(_) => false,
+        }
+    }
+
+    fn clear_workspace_indexes(&self) {
+        if let Ok(mut w) = self.workspace_watchers.lock() {
+            w.clear();
+        }
+        if let Ok(mut sym) = self.symbol_indexes.lock() {
+            sym.clear();
+        }
+        if let Ok(mut idx) = self.workspace_indexes.lock() {
+            idx.clear();
+        }
+    }
+
+    fn get_symbol_indexes(&self) -> Vec<ide::symbol_index::SymbolIndex> {
+        self.symbol_indexes
+            .lock()
+            .map(|sym| sym.clone())
+            .unwrap_or_default()
+    }
+
+    /// Store a root's symbol index, unless the root was removed while it was being built.
+    fn set_symbol_index(&self, symbols: ide::symbol_index::SymbolIndex) {
+        let still_indexed = self
+            .workspace_indexes
+            .lock()
+            .map(|idx| idx.iter().any(|index| index.root == symbols.root))
+            .unwrap_or(false);
+        if !still_indexed {
+            return;
+        }
+        if let Ok(mut sym) = self.symbol_indexes.lock() {
+            sym.retain(|index| index.root != symbols.root);
+            sym.push(symbols);
+        }
     }
 
-    fn set_workspace_index(&self, index: Option<ide::file_index::WorkspaceIndex>) {
-        if let Ok(mut idx) = self.workspace_index.lock() {
-            *idx = index;
+    /// Re-scan symbols for changed paths under a root (after the file index was updated).
+    fn refresh_symbols(&self, root: &std::path::Path, paths: &[std::path::PathBuf]) {
+        let Some(index) = self
+            .get_workspace_indexes()
+            .into_iter()
+            .find(|index| index.root == root)
+        else {
+            return;
+        };
+        if let Ok(mut sym) = self.symbol_indexes.lock() {
+            if let Some(symbols) = sym.iter_mut().find(|s| s.root == root) {
+                for path in paths {
+                    symbols.refresh(&index, path);
+                }
+            }
         }
     }
 
+    /// Modify a root's index in place. The closure also receives the number of
+    /// files indexed under the other roots. Returns None if the root isn't indexed.
+    fn update_workspace_index<R>(
+        &self,
+        root: &std::path::Path,
+        f: impl FnOnce(&mut ide::file_index::WorkspaceIndex, usize) -> R,
+    ) -> Option<R> {
+        let mut indexes = self.workspace_indexes.lock().ok()?;
+        let total = ide::file_index::total_file_count(&indexes);
+        let index = indexes.iter_mut().find(|index| index.root == root)?;
+        let other_files = total - index.file_count();
+        Some(f(index, other_files))
+    }
+
     fn is_inserting(&self) -> bool {
         self.is_inserting.lock().map(|v| *v).unwrap_or(false)
     }
@@ -184,11 +469,273 @@ impl AppState {
         }
     }
 
+    /// Store the latest permission status, returning the previous one
+    fn swap_permission_status(
+        &self,
+        status: permissions::PermissionStatus,
+    ) -> Option<permissions::PermissionStatus> {
+        self.permission_status
+            .lock()
+            .ok()
+            .and_then(|mut s| s.replace(status))
+    }
+
+    /// Register a new permission watcher and return its id,
+    /// or None if one is already running
+    fn begin_permission_watcher(&self) -> Option<u64> {
+        let mut active = self.permission_watcher.lock().ok()?;
+        if active.is_some() {
+            return None;
+        }
+        let id = self
+            .next_permission_watcher_id
+            .fetch_add(1, Ordering::SeqCst)
+            + 1;
+        *active = Some(id);
+        Some(id)
+    }
+
+    fn is_permission_watcher_current(&self, id: u64) -> bool {
+        self.permission_watcher
+            .lock()
+            .map(|active| *active == Some(id))
+            .unwrap_or(false)
+    }
+
+    /// Stop watcher `id` (if it is still the active one), or any watcher if None
+    fn end_permission_watcher(&self, id: Option<u64>) {
+        if let Ok(mut active) = self.permission_watcher.lock() {
+            if id.is_none() || *active == id {
+                *active = None;
+            }
+        }
+    }
+
+    fn get_last_dictation(&self) -> Option<LastDictation> {
+        self.last_dictation.lock().ok().and_then(|d| d.clone())
+    }
+
+    fn set_last_dictation(&self, dictation: Option<LastDictation>) {
+        if let Ok(mut d) = self.last_dictation.lock() {
+            *d = dictation;
+        }
+    }
+
+    /// Keep the last recording for re-transcription (skipped if it's too long)
+    fn set_last_audio(&self, samples: Option<Vec<f32>>) {
+        let samples = samples.filter(|s| s.len() <= MAX_RETAINED_AUDIO_SECS * 16000);
+        if let Ok(mut a) = self.last_audio.lock() {
+            *a = samples.map(|s| (s, Instant::now()));
+        }
+    }
+
+    /// Get the last recording, dropping it if it has expired
+    fn get_last_audio(&self) -> Option<Vec<f32>> {
+        let keep = self
+            .with_config(|cfg| cfg.keep_last_recording)
+            .unwrap_or(false);
+        let mut audio = self.last_audio.lock().ok()?;
+        if !keep
+            && audio
+                .as_ref()
+                .is_some_and(|(_, at)| at.elapsed() > RETAINED_AUDIO_TTL)
+        {
+            *audio = None;
+        }
+        audio.as_ref().map(|(samples, _)| samples.clone())
+    }
+
+    /// Start a processing run and get the token that cancels it
+    fn begin_processing(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut t) = self.processing_cancel.lock() {
+            *t = Some(token.clone());
+        }
+        token
+    }
+
+    fn finish_processing(&self) {
+        if let Ok(mut t) = self.processing_cancel.lock() {
+            *t = None;
+        }
+    }
+
+    /// Arm a max-duration timer for a new recording, disarming any previous one
+    fn arm_recording_watchdog(&self) -> CancellationToken {
+        let token = CancellationToken::new();
+        if let Ok(mut t) = self.recording_watchdog.lock() {
+            if let Some(previous) = t.replace(token.clone()) {
+                previous.cancel();
+            }
+        }
+        token
+    }
+
+    fn disarm_recording_watchdog(&self) {
+        if let Ok(mut t) = self.recording_watchdog.lock() {
+            if let Some(token) = t.take() {
+                token.cancel();
+            }
+        }
+    }
+
+    /// Cancel the in-flight processing run. Returns false if nothing was running.
+    fn cancel_processing(&self) -> bool {
+        match self
+            .processing_cancel
+            .lock()
+            .ok()
+            .and_then(|mut t| t.take())
+        {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn set_scratch_capture(&self, enabled: bool) {
+        if let Ok(mut v) = self.scratch_capture.lock() {
+            *v = enabled;
+        }
+    }
+
+    /// Read and reset the scratch capture flag
+    fn take_scratch_capture(&self) -> bool {
+        self.scratch_capture
+            .lock()
+            .map(|mut v| std::mem::take(&mut *v))
+            .unwrap_or(false)
+    }
+
+    fn set_raw_session(&self, raw: bool) {
+        if let Ok(mut v) = self.raw_session.lock() {
+            *v = raw;
+        }
+    }
+
+    /// Read and reset the raw session flag
+    fn take_raw_session(&self) -> bool {
+        self.raw_session
+            .lock()
+            .map(|mut v| std::mem::take(&mut *v))
+            .unwrap_or(false)
+    }
+
+    fn set_last_insertion(&self, insertion: Option<LastInsertion>) {
+        if let Ok(mut i) = self.last_insertion.lock() {
+            *i = insertion;
+        }
+    }
+
+    /// Take the last insertion so it can only be undone once
+    fn take_last_insertion(&self) -> Option<LastInsertion> {
+        self.last_insertion.lock().ok().and_then(|mut i| i.take())
+    }
+
+    fn set_undo_requested(&self, requested: bool) {
+        if let Ok(mut v) = self.undo_requested.lock() {
+            *v = requested;
+        }
+    }
+
+    /// Read and reset the undo request flag
+    fn take_undo_requested(&self) -> bool {
+        self.undo_requested
+            .lock()
+            .map(|mut v| std::mem::take(&mut *v))
+            .unwrap_or(false)
+    }
+
+    fn set_clipboard_delivered(&self, delivered: bool) {
+        if let Ok(mut v) = self.clipboard_delivered.lock() {
+            *v = delivered;
+        }
+    }
+
+    fn get_session_shortcut(&self) -> Option<Shortcut> {
+        self.session_shortcut.lock().ok().and_then(|s| *s)
+    }
+
+    fn set_session_shortcut(&self, shortcut: Option<Shortcut>) {
+        if let Ok(mut s) = self.session_shortcut.lock() {
+            *s = shortcut;
+        }
+    }
+
+    fn get_main_shortcut(&self) -> Option<Shortcut> {
+        self.main_shortcut.lock().ok().and_then(|s| *s)
+    }
+
+    fn set_main_shortcut(&self, shortcut: Option<Shortcut>) {
+        if let Ok(mut s) = self.main_shortcut.lock() {
+            *s = shortcut;
+        }
+    }
+
+    fn get_raw_shortcut(&self) -> Option<Shortcut> {
+        self.raw_shortcut.lock().ok().and_then(|s| *s)
+    }
+
+    fn set_raw_shortcut(&self, shortcut: Option<Shortcut>) {
+        if let Ok(mut s) = self.raw_shortcut.lock() {
+            *s = shortcut;
+        }
+    }
+
+    /// Read and reset the clipboard-only delivery flag
+    fn take_clipboard_delivered(&self) -> bool {
+        self.clipboard_delivered
+            .lock()
+            .map(|mut v| std::mem::take(&mut *v))
+            .unwrap_or(false)
+    }
+
+    fn set_sensitive_transcript(&self, sensitive: bool) {
+        if let Ok(mut v) = self.sensitive_transcript.lock() {
+            *v = sensitive;
+        }
+    }
+
+    /// Read and reset the sensitive-transcript flag
+    fn take_sensitive_transcript(&self) -> bool {
+        self.sensitive_transcript
+            .lock()
+            .map(|mut v| std::mem::take(&mut *v))
+            .unwrap_or(false)
+    }
+
+    /// How long the current recording has been capturing, excluding pauses
     fn get_recording_duration_ms(&self) -> Option<u64> {
-        self.recording_start
+        let paused_at = self.recording_paused_at.lock().ok().and_then(|p| *p);
+        let start = self.recording_start.lock().ok().and_then(|s| *s)?;
+        let end = paused_at.unwrap_or_else(Instant::now);
+        Some(end.saturating_duration_since(start).as_millis() as u64)
+    }
+
+    /// Stop the recording clock while paused
+    fn pause_recording_clock(&self) {
+        if let Ok(mut p) = self.recording_paused_at.lock() {
+            p.get_or_insert_with(Instant::now);
+        }
+    }
+
+    /// Restart the recording clock, leaving the paused time out of the duration
+    fn resume_recording_clock(&self) {
+        let Some(paused_at) = self
+            .recording_paused_at
             .lock()
             .ok()
-            .and_then(|start| start.map(|s| s.elapsed().as_millis() as u64))
+            .and_then(|mut p| p.take())
+        else {
+            return;
+        };
+        if let Ok(mut s) = self.recording_start.lock() {
+            if let Some(start) = s.as_mut() {
+                *start += paused_at.elapsed();
+            }
+        }
     }
 
     /// Execute a closure with read access to the config
@@ -202,6 +749,15 @@ impl AppState {
             .map_err(|e| format!("Failed to lock config: {}", e))
     }
 
+    /// Re-read the replacement rules after one of the replacement commands saved them
+    fn reload_replacements(&self) -> Result<(), String> {
+        let rules = replacements::load_rules();
+        self.config
+            .lock()
+            .map(|mut c| c.replacements = rules)
+            .map_err(|e| format!("Failed to lock config: {}", e))
+    }
+
     /// Execute a closure with mutable access to the recorder
     fn with_recorder_mut<F, R>(&self, f: F) -> Result<R, String>
     where
@@ -213,8 +769,11 @@ impl AppState {
             .map_err(|e| format!("Failed to lock recorder: {}", e))
     }
 
-    /// Set the recording start time
+    /// Set the recording start time (a new or finished recording isn't paused)
     fn set_recording_start(&self, start: Option<Instant>) {
+        if let Ok(mut p) = self.recording_paused_at.lock() {
+            *p = None;
+        }
         if let Ok(mut s) = self.recording_start.lock() {
             *s = start;
         }
@@ -249,6 +808,130 @@ fn emit_error(app: &AppHandle, error: ErrorEvent) {
         log::error!("Failed to emit error: {}", e);
     }
     log::info!("Error: {} - {}", error.code, error.message);
+    play_cue(app, audio_cues::Cue::Error);
+}
+
+/// Play a sound cue if `play_sounds` and that cue are both enabled
+fn play_cue(app: &AppHandle, cue: audio_cues::Cue) {
+    let state: tauri::State<'_, AppState> = app.state();
+    let enabled = state
+        .with_config(|cfg| cfg.play_sounds && cfg.audio_cues.is_enabled(cue))
+        .unwrap_or(false);
+    if enabled {
+        audio_cues::play(cue);
+    }
+}
+
+/// Emit as soon as LLM enhancement fails, before the raw transcript is inserted,
+/// so the overlay can show that enhancement was skipped and preview the raw text.
+/// This is a warning, not a `recording-error`: the dictation still succeeds, so
+/// no error cue plays.
+fn emit_enhancement_fallback(app: &AppHandle, state: &AppState, error: &str, raw_text: &str) {
+    let event = ErrorEvent::groq_error(error, Some(raw_text.to_string()));
+    log::warn!("Enhancement failed, using raw text: {}", event.message);
+
+    if let Err(e) = app.emit("enhancement-fallback", &event) {
+        log::error!("Failed to emit enhancement fallback: {}", e);
+    }
+    emit_state_change(
+        app,
+        state,
+        Some("Enhancement skipped, using raw text".to_string()),
+    );
+}
+
+/// Flag a low-confidence transcription that is still inserted, so the UI can
+/// ask the user to review it. Like `emit_enhancement_fallback`, this is a
+/// warning rather than a `recording-error`, so no error cue plays.
+fn emit_low_confidence_flag(app: &AppHandle, confidence: f32, transcript: &str) {
+    let event = ErrorEvent::low_confidence(confidence, false, transcript.to_string());
+    if let Err(e) = app.emit("low-confidence", &event) {
+        log::error!("Failed to emit low confidence flag: {}", e);
+    }
+}
+
+/// Whether the user is signed in. If not, report it and bring up the login
+/// window, rather than letting the recording fail at the proxy later.
+/// Uses the in-memory auth flag, so it's cheap enough for hotkey handlers.
+fn check_authenticated(app: &AppHandle) -> bool {
+    if auth::is_authenticated_fast() {
+        return true;
+    }
+    log::info!("[AUTH] Cannot start recording: user not authenticated");
+    prompt_login(app);
+    false
+}
+
+/// Whether a deep link is the OAuth redirect back from sign-in
+fn is_auth_callback(url: &str) -> bool {
+    url.starts_with(auth::workos::REDIRECT_URI)
+}
+
+/// Finish signing in from an OAuth callback deep link, in the background.
+///
+/// The sign-in started in this process (its PKCE state lives in memory), so a
+/// callback that launched the app cold can't complete. Failures bring the
+/// login window back with an `auth-error` so the user can try again.
+fn handle_auth_callback(app: &AppHandle, url: String) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        match auth::handle_callback(&app, &url).await {
+            Ok(()) => log::info!("[AUTH] OAuth callback handled successfully"),
+            // The same link delivered twice (e.g. as an event and as the launch URL)
+            Err(auth::AuthError::StateMismatch) if auth::is_authenticated_fast() => {
+                log::info!("[AUTH] Ignoring repeated OAuth callback");
+            }
+            Err(e) => {
+                log::error!("[AUTH] OAuth callback failed: {}", e);
+                sentry_capture_error(&format!("OAuth callback failed: {}", e), None);
+                let message = match e {
+                    auth::AuthError::StateMismatch => {
+                        "This sign-in link has expired. Please sign in again.".to_string()
+                    }
+                    e => format!("Sign-in failed: {}", e),
+                };
+                let _ = app.emit("auth-error", &message);
+                if let Some(login) = app.get_webview_window("login") {
+                    let _ = login.show();
+                    let _ = login.set_focus();
+                }
+            }
+        }
+    });
+}
+
+/// Report that the user needs to sign in and bring up the login window
+fn prompt_login(app: &AppHandle) {
+    emit_error(app, ErrorEvent::not_authenticated());
+    if let Some(login) = app.get_webview_window("login") {
+        let _ = login.show();
+        let _ = login.set_focus();
+    }
+}
+
+/// When the proxy is disabled a user-provided API key is required, and the
+/// Deepgram provider needs a Deepgram API key.
+/// Returns the error (after emitting it) if recording shouldn't start.
+fn check_api_key_available(app: &AppHandle, state: &AppState) -> Result<(), String> {
+    let provider = state
+        .with_config(|cfg| cfg.transcription_provider)
+        .unwrap_or_default();
+    if provider == config::TranscriptionProvider::Deepgram && deepgram::api_key().is_none() {
+        log::warn!("[API] Deepgram selected but no Deepgram API key set");
+        let message = "No Deepgram API key is set. Add it in Preferences.";
+        emit_error(app, ErrorEvent::deepgram_error(message));
+        return Err(message.to_string());
+    }
+
+    let use_proxy = state.with_config(|cfg| cfg.use_proxy).unwrap_or(true);
+    if !use_proxy && signing::user_api_key().is_none() {
+        log::warn!("[API] Proxy disabled and no Groq API key set");
+        let event = ErrorEvent::missing_api_key();
+        let message = event.message.clone();
+        emit_error(app, event);
+        return Err(message);
+    }
+    Ok(())
 }
 
 // ============================================================================
@@ -282,6 +965,12 @@ async fn start_recording(app_handle: AppHandle, state: State<'_, AppState>) -> R
         ));
     }
 
+    if !check_authenticated(&app_handle) {
+        return Err("Not authenticated".to_string());
+    }
+
+    check_api_key_available(&app_handle, &state)?;
+
     // =========================================================================
     // PHASE 1: INSTANT RESPONSE (no blocking operations)
     // =========================================================================
@@ -290,6 +979,10 @@ async fn start_recording(app_handle: AppHandle, state: State<'_, AppState>) -> R
     state.set_state(RecordingState::Recording);
     state.set_mode(DictationMode::Dictation); // Default, may update async
     state.set_recording_start(Some(Instant::now()));
+    let session = state.begin_recording_session();
+    // Only the raw hotkey records raw dictations
+    state.set_raw_session(false);
+    state.set_session_shortcut(None);
 
     // Show overlay IMMEDIATELY - no delay
     if let Some(overlay) = app_handle.get_webview_window("overlay") {
@@ -300,13 +993,34 @@ async fn start_recording(app_handle: AppHandle, state: State<'_, AppState>) -> R
     // Emit initial state (Dictation mode by default)
     emit_state_change(&app_handle, &state, Some("Recording...".to_string()));
 
+    // A new recording replaces whatever was kept for re-transcription
+    state.set_last_audio(None);
+
     // Start audio capture IMMEDIATELY
     let result = {
         let mut recorder = state
             .recorder
             .lock()
             .map_err(|e| format!("Failed to lock recorder: {}", e))?;
-        let selected_mic = permissions::get_selected_microphone_name();
+        let selected_mic = permissions::get_configured_microphone_name();
+        if let Ok((timeout, threshold, quality, mono_channel, meter, gain_db)) =
+            state.with_config(|cfg| {
+                (
+                    cfg.silence_timeout_ms,
+                    cfg.silence_threshold_db,
+                    cfg.resample_quality,
+                    cfg.mono_channel,
+                    cfg.meter_settings(),
+                    cfg.input_gain_db,
+                )
+            })
+        {
+            recorder.set_silence_timeout(timeout, threshold);
+            recorder.set_resample_quality(quality);
+            recorder.set_mono_channel(mono_channel);
+            recorder.set_meter_settings(meter);
+            recorder.set_input_gain(gain_db);
+        }
         recorder.start_recording_with_device(app_handle.clone(), selected_mic)
     };
 
@@ -315,6 +1029,8 @@ async fn start_recording(app_handle: AppHandle, state: State<'_, AppState>) -> R
         emit_error(&app_handle, ErrorEvent::no_audio_device());
         return Err(e);
     }
+    start_recording_watchdog(&app_handle);
+    play_cue(&app_handle, audio_cues::Cue::Start);
 
     // =========================================================================
     // PHASE 2: ASYNC CONTEXT CAPTURE (happens while user speaks)
@@ -330,14 +1046,27 @@ async fn start_recording(app_handle: AppHandle, state: State<'_, AppState>) -> R
         }
 
         // 1. Detect active app for context-aware styles
-        let active_style = styles::get_current_style();
+        let (app_styles, inference_excluded) = state
+            .with_config(|cfg| (cfg.app_styles.clone(), cfg.style_inference_excluded.clone()))
+            .unwrap_or_default();
+        let active_style = styles::get_current_style(&app_styles, &inference_excluded);
         state.set_active_style(Some(active_style));
 
-        // 2. Detect selection - if found, switch to Command Mode
+        // 2. Detect selection - if found, switch to Command Mode (unless the
+        // user excluded this app from Command Mode)
+        let bundle_id = styles::detection::get_active_app().map(|a| a.bundle_id);
+        if state
+            .with_config(|cfg| cfg.command_mode_excluded_for_app(bundle_id.as_deref()))
+            .unwrap_or(false)
+        {
+            log::debug!("[MODE] Selection detection skipped for {:?}", bundle_id);
+            return;
+        }
         match platform::selection::get_selected_text() {
             Ok(text) => {
-                state.set_mode(DictationMode::Command);
-                state.set_selected_text(Some(text));
+                if !state.apply_selection(session, text) {
+                    return;
+                }
 
                 // Update overlay to show Command Mode
                 if state.get_state() == RecordingState::Recording {
@@ -370,6 +1099,13 @@ async fn stop_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Re
 
     // Capture the bundle_id BEFORE processing clears it
     let bundle_id = state.get_active_bundle_id();
+    let (pre_insert_delay_ms, done_display_ms, app_focus_wait_ms) = state.with_config(|cfg| {
+        (
+            cfg.pre_insert_delay_for_app(bundle_id.as_deref()),
+            cfg.done_display_ms,
+            cfg.app_focus_wait_ms,
+        )
+    })?;
 
     // Use shared processing logic
     let final_text = process_recording_stop(&app_handle, &state).await?;
@@ -384,21 +1120,30 @@ async fn stop_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Re
         }
 
         // Brief delay to show "Done!" state
-        std::thread::sleep(std::time::Duration::from_millis(DONE_DISPLAY_DELAY_MS));
+        std::thread::sleep(std::time::Duration::from_millis(done_display_ms));
         hide_overlay(&app_clone);
         // Reactivate the previous app explicitly
-        if let Some(bid) = bundle_id {
-            activate_app_by_bundle_id(&bid);
+        if let Some(ref bid) = bundle_id {
+            activate_app_by_bundle_id(bid);
         }
         // Wait for the app to regain focus
-        std::thread::sleep(std::time::Duration::from_millis(APP_FOCUS_WAIT_MS));
-        // Insert text while preserving clipboard
-        insert_text_directly(&final_text);
+        std::thread::sleep(std::time::Duration::from_millis(app_focus_wait_ms));
+        // Insert text while preserving clipboard (or save it to the scratch buffer)
+        let insert_start = Instant::now();
+        insert_or_capture_scratch(
+            &app_clone,
+            &final_text,
+            bundle_id.as_deref(),
+            pre_insert_delay_ms,
+        );
 
         // Clear inserting flag
         {
             let state: tauri::State<'_, AppState> = app_clone.state();
             state.set_inserting(false);
+            if state.with_config(|cfg| cfg.verbose_timing).unwrap_or(false) {
+                log::debug!("[TIMING] insert {}ms", insert_start.elapsed().as_millis());
+            }
         }
     });
 
@@ -409,134 +1154,342 @@ async fn stop_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Re
 struct RecordingStopConfig {
     language: String,
     spoken_languages: Vec<String>,
+    verbose_timing: bool,
+    max_selection_chars: usize,
+    min_confidence: Option<f32>,
+    low_confidence_action: config::LowConfidenceAction,
+    enhancement_enabled: bool,
+    groq_model: String,
+    history_enabled: bool,
+    transcription_provider: config::TranscriptionProvider,
+    deepgram_model: String,
+    command_mode_behavior: config::CommandModeBehavior,
+    output_mode: config::OutputMode,
+    replacements: Vec<replacements::ReplacementRule>,
+    vocabulary_prompt: Option<String>,
+    enhance_prompt: Option<String>,
+    transform_prompt: Option<String>,
+    adaptive_language: bool,
+    mixed_mode_output: whisper_api::MixedModeOutput,
+    enhancement_fallback: config::EnhancementFallback,
+    case_style_by_language: std::collections::HashMap<String, ide::variable::CaseStyle>,
+    number_format: text_normalize::NumberFormat,
+    redact_sensitive: redact::RedactMode,
+}
+
+impl RecordingStopConfig {
+    /// Snapshot the settings for one dictation (the language depends on `bundle_id`)
+    fn load(state: &AppState, bundle_id: Option<&str>) -> Result<Self, String> {
+        Self::from_state(state, bundle_id, config::StoredPreferences::load())
+    }
+
+    /// `load` with the preferences file already read
+    fn from_state(
+        state: &AppState,
+        bundle_id: Option<&str>,
+        stored: config::StoredPreferences,
+    ) -> Result<Self, String> {
+        let spoken_langs = stored
+            .spoken_languages
+            .unwrap_or_else(|| vec!["en".to_string()]);
+
+        state.with_config(|cfg| {
+            let language = cfg.language_for_app(bundle_id);
+            let language_enhancement_disabled = cfg.enhancement_disabled_for_language(&language);
+            if cfg.enhancement_enabled && language_enhancement_disabled {
+                log::info!(
+                    "[ENHANCE] Enhancement disabled for language '{}' - inserting the transcript",
+                    language
+                );
+            }
+            RecordingStopConfig {
+                language,
+                spoken_languages: spoken_langs,
+                verbose_timing: cfg.verbose_timing,
+                max_selection_chars: cfg.max_selection_chars,
+                min_confidence: cfg.min_confidence,
+                low_confidence_action: cfg.low_confidence_action,
+                enhancement_enabled: cfg.enhancement_enabled && !language_enhancement_disabled,
+                groq_model: cfg.groq_model.clone(),
+                history_enabled: cfg.history_enabled,
+                transcription_provider: cfg.transcription_provider,
+                deepgram_model: cfg.deepgram_model.clone(),
+                command_mode_behavior: cfg.command_mode_behavior,
+                output_mode: cfg.output_mode,
+                replacements: cfg.replacements.clone(),
+                vocabulary_prompt: cfg.vocabulary_prompt_for_app(bundle_id),
+                enhance_prompt: cfg.enhance_prompt.clone(),
+                transform_prompt: cfg.transform_prompt.clone(),
+                adaptive_language: cfg.adaptive_language,
+                mixed_mode_output: cfg.mixed_mode_output,
+                enhancement_fallback: cfg.fallback_on_enhancement_failure,
+                case_style_by_language: cfg.case_style_by_language.clone(),
+                number_format: cfg.number_format,
+                redact_sensitive: cfg.redact_sensitive,
+            }
+        })
+    }
 }
 
 /// Shared logic for stopping a recording and processing the audio.
 /// Used by both the Tauri command `stop_recording` and the shortcut handler.
+/// The run can be aborted with `cancel_recording`, in which case nothing is inserted.
 async fn process_recording_stop(
     app_handle: &AppHandle,
     state: &AppState,
 ) -> Result<String, String> {
-    // Update state to transcribing
-    state.set_state(RecordingState::Transcribing);
-    emit_state_change(app_handle, state, Some("Processing audio...".to_string()));
+    state.disarm_recording_watchdog();
+    state.end_recording_session();
+    let cancel = state.begin_processing();
+    let result = process_recording_pipeline(app_handle, state, &cancel).await;
+    state.finish_processing();
+
+    // The pipeline may have moved to a later stage after `cancel_recording`
+    // reset the state - make sure a cancelled run always ends Idle
+    if result.is_err() && cancel.is_cancelled() {
+        state.set_state(RecordingState::Idle);
+        emit_state_change(app_handle, state, Some("Cancelled".to_string()));
+        hide_overlay(app_handle);
+    }
+    result
+}
 
-    // Get configuration
-    let stored = config::StoredPreferences::load();
-    let spoken_langs = stored
-        .spoken_languages
-        .unwrap_or_else(|| vec!["en".to_string()]);
+/// Transcribe a recording with the configured provider.
+/// Deepgram doesn't report a comparable confidence, so its transcripts have none.
+async fn transcribe_audio(
+    config: &RecordingStopConfig,
+    wav: &[u8],
+) -> Result<whisper_api::Transcription, String> {
+    match config.transcription_provider {
+        config::TranscriptionProvider::Groq => {
+            whisper_api::WhisperApiClient::new()?
+                .transcribe(
+                    wav,
+                    &config.language,
+                    &config.spoken_languages,
+                    config.vocabulary_prompt.as_deref(),
+                    config.mixed_mode_output,
+                )
+                .await
+        }
+        config::TranscriptionProvider::Deepgram => {
+            let text = deepgram::DeepgramClient::new(&config.deepgram_model)?
+                .transcribe(wav, &config.language)
+                .await?;
+            Ok(whisper_api::Transcription {
+                text,
+                avg_logprob: None,
+                new_language: None,
+            })
+        }
+    }
+}
 
-    let config = state.with_config(|cfg| RecordingStopConfig {
-        language: cfg.language.clone(),
-        spoken_languages: spoken_langs,
-    })?;
+/// Groq recordings longer than this are transcribed in pieces so the overlay
+/// can show partial text while the rest is transcribed (seconds)
+const PARTIAL_TRANSCRIPT_MIN_SECS: usize = 30;
 
-    // Stop recording and get audio data (always use Whisper format)
-    let audio_samples_16khz =
-        state.with_recorder_mut(|recorder| recorder.stop_recording_for_whisper())??;
+/// Target length of each piece, and how far back from it to look for a pause (seconds)
+const PARTIAL_CHUNK_SECS: u32 = 20;
+const PARTIAL_PAUSE_SEARCH_SECS: u32 = 3;
 
-    // Check if we have audio
-    if audio_samples_16khz.is_empty() {
-        state.set_state(RecordingState::Error);
-        emit_error(app_handle, ErrorEvent::no_audio_captured());
-        hide_overlay(app_handle);
-        return Err("No audio captured".to_string());
+/// Most pieces per recording (longer ones get longer pieces), keeping a long
+/// dictation well within the transcription rate limit
+const MAX_PARTIAL_CHUNKS: u32 = 8;
+
+/// `transcribe_audio` for the recording pipeline: long Groq recordings are
+/// transcribed piece by piece and the text so far is sent to the overlay as
+/// `partial-transcript` events. The result is the complete transcription, or
+/// the text so far (reported as a partial transcription) if a later piece fails.
+async fn transcribe_audio_with_partials(
+    app_handle: &AppHandle,
+    config: &RecordingStopConfig,
+    samples_16khz: &[f32],
+    wav: &[u8],
+) -> Result<whisper_api::Transcription, String> {
+    let duration_secs = samples_16khz.len() / 16000;
+    if config.transcription_provider != config::TranscriptionProvider::Groq
+        || duration_secs <= PARTIAL_TRANSCRIPT_MIN_SECS
+    {
+        return transcribe_audio(config, wav).await;
     }
 
-    // Transcribe using Groq Whisper API
-    emit_state_change(app_handle, state, Some("Transcribing...".to_string()));
+    let chunk_secs = PARTIAL_CHUNK_SECS.max((duration_secs as u32).div_ceil(MAX_PARTIAL_CHUNKS));
+    let chunks =
+        audio::split_at_pauses(samples_16khz, 16000, chunk_secs, PARTIAL_PAUSE_SEARCH_SECS)
+            .into_iter()
+            .map(|chunk| encode_samples_to_wav(chunk, 16000))
+            .collect::<Result<Vec<_>, _>>()?;
 
-    let wav = encode_samples_to_wav(&audio_samples_16khz, 16000)?;
-    let client = whisper_api::WhisperApiClient::new()?;
-    let transcript = client
-        .transcribe(&wav, &config.language, &config.spoken_languages)
-        .await
-        .map_err(|e| {
-            state.set_state(RecordingState::Error);
-            emit_error(app_handle, ErrorEvent::whisper_error(&e));
-            hide_overlay(app_handle);
-            e
-        })?;
+    // Forward partial text until the sender is dropped (done or cancelled)
+    let (partials, mut received) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let app = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        while let Some(text) = received.recv().await {
+            if let Some(overlay) = app.get_webview_window("overlay") {
+                if let Err(e) = overlay.emit("partial-transcript", &text) {
+                    log::error!("Failed to emit partial transcript to overlay: {}", e);
+                }
+            }
+        }
+    });
 
-    if transcript.is_empty() {
-        state.set_state(RecordingState::Error);
-        emit_error(app_handle, ErrorEvent::no_audio_captured());
-        hide_overlay(app_handle);
-        return Err("No speech detected".to_string());
+    let result = whisper_api::WhisperApiClient::new()?
+        .transcribe_streaming(
+            &chunks,
+            &config.language,
+            &config.spoken_languages,
+            config.vocabulary_prompt.as_deref(),
+            config.mixed_mode_output,
+            &partials,
+        )
+        .await?;
+
+    // Insert what was transcribed instead of losing the whole dictation
+    if let Some(error) = result.incomplete {
+        emit_error(app_handle, ErrorEvent::partial_transcription(&error));
     }
+    Ok(result.transcription)
+}
 
-    #[cfg(debug_assertions)]
-    log::info!("Transcript (groq): {}", transcript);
+/// What the user was doing when they dictated
+struct DictationContext {
+    mode: DictationMode,
+    /// Selection to transform in Command Mode
+    selected_text: Option<String>,
+    active_style: Option<styles::Style>,
+}
 
-    // Apply IDE transformations if we're in a code editor
-    let active_bundle_id = state.get_active_bundle_id();
-    let workspace_index = state.get_workspace_index();
-    let transcript = if let Some(ref bundle_id) = active_bundle_id {
-        if ide::is_ide(bundle_id) {
-            let ide_context = ide::get_ide_context(bundle_id);
-            let ide_settings = ide::IDESettings::default();
-            let transformed = ide::apply_ide_transformations(
-                &transcript,
-                &ide_context,
-                &ide_settings,
-                workspace_index.as_ref(),
-            );
-            #[cfg(debug_assertions)]
-            if transformed != transcript {
-                log::info!("IDE transformed: {}", transformed);
+/// Turn a transcript into the text to insert: Command Mode transform or LLM
+/// enhancement (with style), then tag cleanup and the user's replacements.
+/// Shared by the recording pipeline and `process_audio_dry_run`; `ui` is None
+/// for dry runs, which leave the recording state and overlay alone.
+async fn enhance_transcript(
+    ui: Option<&AppHandle>,
+    state: &AppState,
+    config: &RecordingStopConfig,
+    cancel: &CancellationToken,
+    transcript: &str,
+    context: DictationContext,
+) -> Result<String, String> {
+    let DictationContext {
+        mode,
+        selected_text,
+        active_style,
+    } = context;
+
+    let progress = |stage: RecordingState, message: &str| {
+        if let Some(app_handle) = ui {
+            state.set_state(stage);
+            emit_state_change(app_handle, state, Some(message.to_string()));
+        }
+    };
+    let report_error = |error: ErrorEvent| {
+        if let Some(app_handle) = ui {
+            emit_error(app_handle, error);
+        }
+    };
+    // Enhancement failed: fall back to the raw transcript, or insert nothing
+    // (optionally copying the transcript) if the user prefers that
+    let enhancement_failed = |error: &str| -> Result<String, String> {
+        let Some(app_handle) = ui else {
+            return match config.enhancement_fallback {
+                config::EnhancementFallback::InsertRaw => Ok(transcript.to_string()),
+                _ => Err(format!("Enhancement failed: {}", error)),
+            };
+        };
+        match config.enhancement_fallback {
+            config::EnhancementFallback::InsertRaw => {
+                emit_enhancement_fallback(app_handle, state, error, transcript);
+                Ok(transcript.to_string())
+            }
+            fallback => {
+                let copied = fallback == config::EnhancementFallback::CopyRawToClipboard
+                    && copy_result_to_clipboard(app_handle, transcript);
+                log::info!(
+                    "[ENHANCE] Enhancement failed, not inserting (copied raw text: {})",
+                    copied
+                );
+                state.set_state(RecordingState::Error);
+                emit_error(
+                    app_handle,
+                    ErrorEvent::enhancement_failed(error, copied, transcript.to_string()),
+                );
+                hide_overlay(app_handle);
+                Err(format!("Enhancement failed: {}", error))
             }
-            transformed
-        } else {
-            transcript
         }
-    } else {
-        transcript
     };
 
-    // Get context for mode-based processing
-    let current_mode = state.get_mode();
-    let selected_text_for_transform = state.get_selected_text();
-    let active_style = state.get_active_style();
-
     // Process based on mode
-    let final_text = match current_mode {
+    let final_text = match mode {
         DictationMode::Command => {
             // Command mode: classify intent and either transform or dictate
-            let selected_text = match selected_text_for_transform {
+            let selected_text = match selected_text {
                 Some(text) => text,
                 None => {
                     #[cfg(debug_assertions)]
                     log::info!("Command Mode but no selected text, falling back to raw transcript");
-                    transcript.clone()
+                    transcript.to_string()
                 }
             };
 
-            let groq_client = GroqLlmClient::new()?;
+            let groq_client = GroqLlmClient::new(&config.groq_model)?;
 
-            // Classify intent
-            state.set_state(RecordingState::Transforming);
-            emit_state_change(app_handle, state, Some("Analyzing...".to_string()));
+            // Classify intent, unless the user picked a fixed behavior
+            let intent = match config.command_mode_behavior {
+                config::CommandModeBehavior::AlwaysTransform => UserIntent::Command,
+                config::CommandModeBehavior::AlwaysReplace => UserIntent::Dictation,
+                config::CommandModeBehavior::Auto => {
+                    progress(RecordingState::Transforming, "Analyzing...");
 
-            let intent = match groq_client.classify_intent(&transcript).await {
-                Ok(i) => i,
-                Err(_e) => {
-                    #[cfg(debug_assertions)]
-                    log::info!(
-                        "Intent classification failed, defaulting to Dictation: {}",
-                        _e
-                    );
-                    UserIntent::Dictation
+                    match http_client::cancellable(cancel, groq_client.classify_intent(transcript))
+                        .await
+                    {
+                        Ok(i) => i,
+                        Err(e) if cancel.is_cancelled() => return Err(e.into()),
+                        Err(_e) => {
+                            #[cfg(debug_assertions)]
+                            log::info!(
+                                "Intent classification failed, defaulting to Dictation: {}",
+                                _e
+                            );
+                            UserIntent::Dictation
+                        }
+                    }
                 }
             };
 
             match intent {
+                UserIntent::Command
+                    if selected_text.chars().count() > config.max_selection_chars =>
+                {
+                    // Don't risk replacing a huge selection with truncated LLM output
+                    log::warn!(
+                        "[TRANSFORM] Selection too large ({} chars, limit {}), leaving it unchanged",
+                        selected_text.chars().count(),
+                        config.max_selection_chars
+                    );
+                    report_error(ErrorEvent::selection_too_large(
+                        selected_text.chars().count(),
+                        config.max_selection_chars,
+                        selected_text.clone(),
+                    ));
+                    selected_text
+                }
                 UserIntent::Command => {
-                    emit_state_change(app_handle, state, Some("Transforming...".to_string()));
-                    match groq_client
-                        .transform_text(&selected_text, &transcript)
-                        .await
+                    progress(RecordingState::Transforming, "Transforming...");
+                    match http_client::cancellable(
+                        cancel,
+                        groq_client.transform_text(
+                            &selected_text,
+                            transcript,
+                            config.transform_prompt.as_deref(),
+                        ),
+                    )
+                    .await
                     {
+                        Err(e) if cancel.is_cancelled() => return Err(e.into()),
                         Ok(transformed) => {
                             #[cfg(debug_assertions)]
                             log::info!("Transformed text: {}", transformed);
@@ -545,18 +1498,22 @@ async fn process_recording_stop(
                         Err(e) => {
                             #[cfg(debug_assertions)]
                             log::info!("Transformation failed, keeping original: {}", e);
-                            emit_error(
-                                app_handle,
-                                ErrorEvent::groq_error(&e, Some(selected_text.clone())),
-                            );
+                            report_error(ErrorEvent::groq_error(
+                                &e.to_string(),
+                                Some(selected_text.clone()),
+                            ));
                             selected_text
                         }
                     }
                 }
+                UserIntent::Dictation if !config.enhancement_enabled => {
+                    log::info!("[RAW] Enhancement disabled - replacing selection verbatim");
+                    transcript.to_string()
+                }
                 UserIntent::Dictation => {
                     #[cfg(debug_assertions)]
                     log::info!("Intent: Dictation - will replace selection with new content");
-                    emit_state_change(app_handle, state, Some("Enhancing...".to_string()));
+                    progress(RecordingState::Transforming, "Enhancing...");
 
                     let style_prompt = active_style.as_ref().map(|s| {
                         #[cfg(debug_assertions)]
@@ -564,29 +1521,44 @@ async fn process_recording_stop(
                         s.prompt_modifier.as_str()
                     });
 
-                    match groq_client.enhance_text(&transcript, style_prompt).await {
+                    match http_client::cancellable(
+                        cancel,
+                        groq_client.enhance_text(
+                            transcript,
+                            style_prompt,
+                            config.enhance_prompt.as_deref(),
+                        ),
+                    )
+                    .await
+                    {
+                        Err(e) if cancel.is_cancelled() => return Err(e.into()),
                         Ok(enhanced) => {
                             #[cfg(debug_assertions)]
                             log::info!("Enhanced dictation (replacing selection): {}", enhanced);
-                            enhanced
+                            match active_style {
+                                Some(ref style) => {
+                                    styles::apply_auto_features(&enhanced, &style.auto_features)
+                                }
+                                None => enhanced,
+                            }
                         }
                         Err(e) => {
                             #[cfg(debug_assertions)]
-                            log::info!("Enhancement failed, using raw transcript: {}", e);
-                            emit_error(
-                                app_handle,
-                                ErrorEvent::groq_error(&e, Some(transcript.clone())),
-                            );
-                            transcript.clone()
+                            log::info!("Enhancement failed: {}", e);
+                            enhancement_failed(&e.to_string())?
                         }
                     }
                 }
             }
         }
+        DictationMode::Dictation if !config.enhancement_enabled => {
+            // Raw mode: IDE transforms and file tagging already ran, skip the LLM
+            log::info!("[RAW] Enhancement disabled - inserting transcript verbatim");
+            transcript.to_string()
+        }
         DictationMode::Dictation => {
             // Dictation mode: enhance with Groq
-            state.set_state(RecordingState::Enhancing);
-            emit_state_change(app_handle, state, Some("Enhancing...".to_string()));
+            progress(RecordingState::Enhancing, "Enhancing...");
 
             let style_prompt = active_style.as_ref().map(|s| {
                 #[cfg(debug_assertions)]
@@ -594,25 +1566,36 @@ async fn process_recording_stop(
                 s.prompt_modifier.as_str()
             });
 
-            let groq_client = GroqLlmClient::new()?;
+            let groq_client = GroqLlmClient::new(&config.groq_model)?;
 
             #[cfg(debug_assertions)]
             log::info!("Before LLM enhancement: {}", transcript);
 
-            match groq_client.enhance_text(&transcript, style_prompt).await {
+            match http_client::cancellable(
+                cancel,
+                groq_client.enhance_text(
+                    transcript,
+                    style_prompt,
+                    config.enhance_prompt.as_deref(),
+                ),
+            )
+            .await
+            {
+                Err(e) if cancel.is_cancelled() => return Err(e.into()),
                 Ok(enhanced) => {
                     #[cfg(debug_assertions)]
                     log::info!("Enhanced with Groq: {}", enhanced);
-                    enhanced
+                    match active_style {
+                        Some(ref style) => {
+                            styles::apply_auto_features(&enhanced, &style.auto_features)
+                        }
+                        None => enhanced,
+                    }
                 }
                 Err(groq_error) => {
                     #[cfg(debug_assertions)]
                     log::info!("Groq enhancement failed: {}", groq_error);
-                    emit_error(
-                        app_handle,
-                        ErrorEvent::groq_error(&groq_error, Some(transcript.clone())),
-                    );
-                    transcript.clone()
+                    enhancement_failed(&groq_error.to_string())?
                 }
             }
         }
@@ -621,17 +1604,302 @@ async fn process_recording_stop(
     // Clean up punctuation attached to @-tagged filenames
     let final_text = ide::file_tagger::cleanup_tagged_punctuation(&final_text);
 
+    // Write numbers the way the user prefers, independent of what the LLM did
+    let final_text = text_normalize::normalize_numbers(&final_text, config.number_format);
+
+    // Apply the user's find→replace list as the very last step before insertion
+    Ok(replacements::apply_replacements(
+        &final_text,
+        &config.replacements,
+    ))
+}
+
+async fn process_recording_pipeline(
+    app_handle: &AppHandle,
+    state: &AppState,
+    cancel: &CancellationToken,
+) -> Result<String, String> {
+    let processing_start = Instant::now();
+    let mut latency = LatencyReport {
+        capture_ms: state.get_recording_duration_ms().unwrap_or(0),
+        ..Default::default()
+    };
+
+    // Update state to transcribing
+    state.set_state(RecordingState::Transcribing);
+    emit_state_change(app_handle, state, Some("Processing audio...".to_string()));
+
+    // Get configuration
+    let active_bundle_id = state.get_active_bundle_id();
+    let mut config = RecordingStopConfig::load(state, active_bundle_id.as_deref())?;
+    if state.take_raw_session() {
+        log::info!("[RAW] Recorded with the raw hotkey - skipping enhancement");
+        config.enhancement_enabled = false;
+    }
+
+    // Stop recording and get audio data (always use Whisper format)
+    let (audio_samples_16khz, recording_stats) = state.with_recorder_mut(|recorder| {
+        let samples = recorder.stop_recording_for_whisper();
+        (samples, recorder.last_recording_stats())
+    })?;
+
+    // Local-only stats for "why was my dictation empty" diagnostics
+    if let Some(stats) = recording_stats {
+        log::info!(
+            "[AUDIO] Recording: {}ms, avg level {:.2}, peak {:.2}",
+            stats.duration_ms,
+            stats.avg_level,
+            stats.peak_level
+        );
+        if let Err(e) = app_handle.emit("recording-stats", stats) {
+            log::error!("Failed to emit recording stats: {}", e);
+        }
+    }
+    let audio_samples_16khz = audio_samples_16khz?;
+
+    // Check if we have audio
+    if audio_samples_16khz.is_empty() {
+        state.set_state(RecordingState::Error);
+        emit_error(app_handle, ErrorEvent::no_audio_captured());
+        hide_overlay(app_handle);
+        return Err("No audio captured".to_string());
+    }
+
+    // Keep the audio so a bad transcription can be retried without re-recording
+    state.set_last_audio(Some(audio_samples_16khz.clone()));
+
+    // Tell the user how long to wait instead of failing with a bare string
+    let transcription_service = match config.transcription_provider {
+        config::TranscriptionProvider::Groq => rate_limit::Service::WhisperApi,
+        config::TranscriptionProvider::Deepgram => rate_limit::Service::Deepgram,
+    };
+    if let Err(error) = rate_limit::peek_rate_limit(transcription_service) {
+        log::warn!("[RATE_LIMIT] {:?}", error);
+        state.set_state(RecordingState::Error);
+        emit_error(app_handle, ErrorEvent::from(error.clone()));
+        hide_overlay(app_handle);
+        return Err(error.into());
+    }
+
+    // Transcribe using Groq Whisper API
+    emit_state_change(app_handle, state, Some("Transcribing...".to_string()));
+
+    let wav = encode_samples_to_wav(&audio_samples_16khz, 16000)?;
+    latency.audio_processing_ms = processing_start.elapsed().as_millis() as u64;
+
+    let stage_start = Instant::now();
+    let transcription = http_client::cancellable(
+        cancel,
+        transcribe_audio_with_partials(app_handle, &config, &audio_samples_16khz, &wav),
+    )
+    .await
+    .map_err(|e| {
+        // A cancelled run has already been reset by `cancel_recording`
+        if !cancel.is_cancelled() {
+            state.set_state(RecordingState::Error);
+            if e == auth::LOGIN_REQUIRED_ERROR {
+                log::warn!("[AUTH] Session expired during transcription");
+                prompt_login(app_handle);
+            } else {
+                let error = match config.transcription_provider {
+                    config::TranscriptionProvider::Groq => ErrorEvent::whisper_error(&e),
+                    config::TranscriptionProvider::Deepgram => ErrorEvent::deepgram_error(&e),
+                };
+                emit_error(app_handle, error);
+            }
+            hide_overlay(app_handle);
+        }
+        e
+    })?;
+    latency.transcribe_ms = stage_start.elapsed().as_millis() as u64;
+    let confidence = transcription.confidence();
+
+    if config.adaptive_language {
+        if let Some(language) = state.record_new_language(transcription.new_language.as_deref()) {
+            log::info!("[LANGUAGE] Suggesting spoken language '{}'", language);
+            if let Err(e) = app_handle.emit("suggest-language", &language) {
+                log::error!("Failed to emit suggest-language: {}", e);
+            }
+        }
+    }
+    let mut transcript = transcription.text;
+
+    if transcript.is_empty() {
+        state.set_state(RecordingState::Error);
+        emit_error(app_handle, ErrorEvent::no_audio_captured());
+        hide_overlay(app_handle);
+        return Err("No speech detected".to_string());
+    }
+
+    // Opt-in: keep things that look like passwords or card numbers away from
+    // the LLM and the logs (heuristic, see `redact::RedactMode`)
+    let mut sensitive = false;
+    if config.redact_sensitive != redact::RedactMode::Off {
+        if let Some(redacted) = redact::redact_sensitive_text(&transcript) {
+            if config.redact_sensitive == redact::RedactMode::SkipEnhancement
+                && state.get_mode() == DictationMode::Dictation
+            {
+                log::info!("[REDACT] Sensitive text detected - skipping enhancement");
+                config.enhancement_enabled = false;
+                sensitive = true;
+            } else {
+                log::info!("[REDACT] Masked sensitive text in the transcript");
+                transcript = redacted;
+            }
+        }
+    }
+
+    #[cfg(debug_assertions)]
+    if !sensitive {
+        log::info!("Transcript (groq): {}", transcript);
+    }
+
+    // "Undo that" removes the previous insertion instead of being typed
+    if state.get_mode() == DictationMode::Dictation && insertion::is_undo_command(&transcript) {
+        log::info!("[UNDO] Undo command recognized");
+        state.set_undo_requested(true);
+        state.set_state(RecordingState::Idle);
+        state.set_active_style(None);
+        state.set_active_bundle_id(None);
+        state.set_recording_start(None);
+        emit_state_change(app_handle, state, Some("Undoing...".to_string()));
+        return Ok(transcript);
+    }
+
+    // Flag or block low-confidence transcriptions
+    let low_confidence = match (confidence, config.min_confidence) {
+        (Some(c), Some(min)) if c < min => Some(c),
+        _ => None,
+    };
+    if let Some(c) = low_confidence {
+        log::warn!(
+            "[CONFIDENCE] Low transcription confidence: {:.2} (min {:?})",
+            c,
+            config.min_confidence
+        );
+        match config.low_confidence_action {
+            config::LowConfidenceAction::Flag => {
+                emit_low_confidence_flag(app_handle, c, &transcript);
+            }
+            config::LowConfidenceAction::Block => {
+                emit_error(
+                    app_handle,
+                    ErrorEvent::low_confidence(c, true, transcript.clone()),
+                );
+                // Keep the transcript so it can be reviewed/re-done, but don't insert it
+                state.set_last_dictation(Some(LastDictation {
+                    raw_transcript: transcript.clone(),
+                    inserted_text: transcript.clone(),
+                    confidence,
+                }));
+                state.set_state(RecordingState::Error);
+                hide_overlay(app_handle);
+                return Err("Transcription confidence below threshold".to_string());
+            }
+        }
+    }
+
+    // Apply IDE transformations if we're in a code editor
+    let stage_start = Instant::now();
+    let workspace_indexes = state.get_workspace_indexes();
+    let symbol_indexes = state.get_symbol_indexes();
+    let transcript = if let Some(ref bundle_id) = active_bundle_id {
+        if ide::is_ide(bundle_id) {
+            let window_title = platform::window::frontmost_window_title();
+            let ide_context = ide::get_ide_context(bundle_id, window_title.as_deref());
+            let ide_settings = ide::IDESettings {
+                custom_dictionary: ide::dictionary::load_custom_entries(),
+                case_style_by_language: config.case_style_by_language.clone(),
+                ..Default::default()
+            };
+            let transformed = ide::apply_ide_transformations(
+                &transcript,
+                &ide_context,
+                &ide_settings,
+                &workspace_indexes,
+                &symbol_indexes,
+            );
+            #[cfg(debug_assertions)]
+            if transformed != transcript && !sensitive {
+                log::info!("IDE transformed: {}", transformed);
+            }
+            transformed
+        } else {
+            transcript
+        }
+    } else {
+        transcript
+    };
+
+    latency.ide_transform_ms = stage_start.elapsed().as_millis() as u64;
+
+    // Get context for mode-based processing
+    let stage_start = Instant::now();
+    // A style forced by the user wins over the one detected from the active app
+    let active_style = state
+        .get_forced_style()
+        .or_else(|| state.get_active_style());
+    let final_text = enhance_transcript(
+        Some(app_handle),
+        state,
+        &config,
+        cancel,
+        &transcript,
+        DictationContext {
+            mode: state.get_mode(),
+            selected_text: state.get_selected_text(),
+            active_style,
+        },
+    )
+    .await?;
+    latency.llm_ms = stage_start.elapsed().as_millis() as u64;
+
+    // Cancelled after the last request finished - drop the result
+    if cancel.is_cancelled() {
+        return Err(http_client::CANCELLED_ERROR.to_string());
+    }
+
+    latency.total_ms = processing_start.elapsed().as_millis() as u64;
+    if config.verbose_timing {
+        log::debug!("[TIMING] {}", latency.summary());
+    }
+
+    state.set_last_dictation(Some(LastDictation {
+        raw_transcript: transcript.clone(),
+        inserted_text: final_text.clone(),
+        confidence,
+    }));
+
+    // Copy the result if the output mode asks for it. Clipboard-only output
+    // skips typing, unless the copy failed and inserting is the only way left.
+    let copied_to_clipboard =
+        config.output_mode.copies() && copy_result_to_clipboard(app_handle, &final_text);
+    state.set_clipboard_delivered(copied_to_clipboard && !config.output_mode.inserts());
+    state.set_sensitive_transcript(sensitive);
+
     // Emit completion
     let completion_event = TranscriptionCompleteEvent {
         raw_transcript: transcript,
         enhanced_text: final_text.clone(),
-        copied_to_clipboard: false,
+        copied_to_clipboard,
+        latency,
+        confidence,
     };
 
     if let Err(e) = app_handle.emit("transcription-complete", &completion_event) {
         log::error!("Failed to emit completion: {}", e);
     }
 
+    if config.history_enabled && !sensitive {
+        if let Err(e) = history::add_entry(
+            &completion_event,
+            state.get_mode(),
+            active_bundle_id.as_deref(),
+        ) {
+            log::error!("[HISTORY] Failed to save history entry: {}", e);
+        }
+    }
+
     // Reset state
     state.set_state(RecordingState::Idle);
     state.set_mode(DictationMode::Dictation);
@@ -640,6 +1908,7 @@ async fn process_recording_stop(
     state.set_active_bundle_id(None);
     state.set_recording_start(None);
     emit_state_change(app_handle, state, Some("Done!".to_string()));
+    play_cue(app_handle, audio_cues::Cue::Success);
 
     Ok(final_text)
 }
@@ -650,14 +1919,22 @@ async fn cancel_recording(app_handle: AppHandle, state: State<'_, AppState>) ->
     if !current_state.can_cancel() {
         return Ok(()); // Nothing to cancel
     }
+    state.disarm_recording_watchdog();
+    state.end_recording_session();
 
     // Stop recording if active
-    if current_state == RecordingState::Recording {
+    if matches!(
+        current_state,
+        RecordingState::Recording | RecordingState::Paused
+    ) {
         let mut recorder = state
             .recorder
             .lock()
             .map_err(|e| format!("Failed to lock recorder: {}", e))?;
         let _ = recorder.stop_recording(); // Discard audio
+    } else if state.cancel_processing() {
+        // Abort the in-flight transcription/enhancement so nothing gets inserted
+        log::info!("[CANCEL] Cancelled processing in {:?} state", current_state);
     }
 
     // Return to idle
@@ -669,50 +1946,676 @@ async fn cancel_recording(app_handle: AppHandle, state: State<'_, AppState>) ->
     Ok(())
 }
 
+/// End a recording whose microphone went away: discard the audio, report
+/// the missing device and hide the overlay
+fn handle_audio_device_lost(app_handle: &AppHandle) {
+    let state: tauri::State<'_, AppState> = app_handle.state();
+    if !matches!(
+        state.get_state(),
+        RecordingState::Recording | RecordingState::Paused
+    ) {
+        return;
+    }
+    log::warn!("[RECORDING] Microphone disconnected, ending recording");
+
+    state.disarm_recording_watchdog();
+    state.end_recording_session();
+    let _ = state.with_recorder_mut(|recorder| recorder.stop_recording()); // Discard audio
+
+    state.set_state(RecordingState::Error);
+    state.set_recording_start(None);
+    emit_error(app_handle, ErrorEvent::no_audio_device());
+    hide_overlay(app_handle);
+}
+
+/// Put the current recording on hold without ending it. Nothing is captured
+/// while paused, and paused time doesn't count toward the recording length.
 #[tauri::command]
-async fn toggle_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+fn pause_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
     let current_state = state.get_state();
-
-    match current_state {
-        RecordingState::Idle | RecordingState::Error => start_recording(app_handle, state).await,
-        RecordingState::Recording => stop_recording(app_handle, state).await,
-        _ => {
-            // Transcribing or Enhancing - can't toggle, maybe cancel?
-            Ok(())
-        }
+    if current_state != RecordingState::Recording {
+        return Err(format!(
+            "Cannot pause recording from state: {:?}",
+            current_state
+        ));
     }
+
+    state.with_recorder_mut(|recorder| recorder.set_paused(true))?;
+    state.pause_recording_clock();
+    state.set_state(RecordingState::Paused);
+    emit_state_change(&app_handle, &state, Some("Paused".to_string()));
+    log::info!("[RECORDING] Paused");
+    Ok(())
 }
 
+/// Continue a paused recording into the same audio buffer
 #[tauri::command]
-async fn show_preferences(app_handle: AppHandle) -> Result<(), String> {
-    // Don't show preferences if not authenticated
-    if !auth::is_authenticated() {
-        log::warn!("[PREFERENCES] Cannot show preferences - user not authenticated");
-        return Err("Not authenticated".to_string());
+fn resume_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let current_state = state.get_state();
+    if current_state != RecordingState::Paused {
+        return Err(format!(
+            "Cannot resume recording from state: {:?}",
+            current_state
+        ));
     }
 
-    if let Some(window) = app_handle.get_webview_window("main") {
-        window
-            .show()
-            .map_err(|e| format!("Failed to show window: {}", e))?;
-        window
-            .set_focus()
-            .map_err(|e| format!("Failed to focus window: {}", e))?;
-    }
+    state.resume_recording_clock();
+    state.with_recorder_mut(|recorder| recorder.set_paused(false))?;
+    state.set_state(RecordingState::Recording);
+    emit_state_change(&app_handle, &state, Some("Recording...".to_string()));
+    log::info!("[RECORDING] Resumed");
     Ok(())
 }
 
+/// Run transcription and enhancement on a WAV recording and return the result
+/// without inserting it. Nothing is typed, saved to history or shown in the
+/// overlay. There is no selection here, so Command Mode transforms the
+/// transcript itself.
 #[tauri::command]
-async fn update_preferences(
+async fn process_audio_dry_run(
     state: State<'_, AppState>,
-    preferences: config::Preferences,
-) -> Result<(), String> {
-    let mut config = state
+    wav_base64: String,
+    mode: DictationMode,
+) -> Result<TranscriptionCompleteEvent, String> {
+    use base64::Engine;
+
+    let wav = base64::engine::general_purpose::STANDARD
+        .decode(wav_base64.trim())
+        .map_err(|e| format!("Invalid base64 audio: {}", e))?;
+    if wav.is_empty() {
+        return Err("No audio provided".to_string());
+    }
+
+    process_wav_without_insert(&state, &wav, mode).await
+}
+
+/// Transcribe and enhance a WAV recording without inserting the result.
+/// Shared by `process_audio_dry_run` and `transcribe_file`.
+async fn process_wav_without_insert(
+    state: &AppState,
+    wav: &[u8],
+    mode: DictationMode,
+) -> Result<TranscriptionCompleteEvent, String> {
+    let processing_start = Instant::now();
+    let mut latency = LatencyReport::default();
+
+    let config = RecordingStopConfig::load(state, None)?;
+    let cancel = CancellationToken::new();
+
+    let stage_start = Instant::now();
+    let transcription = transcribe_audio(&config, wav).await?;
+    latency.transcribe_ms = stage_start.elapsed().as_millis() as u64;
+    let confidence = transcription.confidence();
+    let transcript = transcription.text;
+    if transcript.is_empty() {
+        return Err("No speech detected".to_string());
+    }
+
+    let stage_start = Instant::now();
+    let enhanced_text = enhance_transcript(
+        None,
+        state,
+        &config,
+        &cancel,
+        &transcript,
+        DictationContext {
+            mode,
+            selected_text: None,
+            active_style: state.get_forced_style(),
+        },
+    )
+    .await?;
+    latency.llm_ms = stage_start.elapsed().as_millis() as u64;
+    latency.total_ms = processing_start.elapsed().as_millis() as u64;
+
+    Ok(TranscriptionCompleteEvent {
+        raw_transcript: transcript,
+        enhanced_text,
+        copied_to_clipboard: false,
+        latency,
+        confidence,
+    })
+}
+
+#[tauri::command]
+async fn toggle_recording(app_handle: AppHandle, state: State<'_, AppState>) -> Result<(), String> {
+    let current_state = state.get_state();
+
+    match current_state {
+        RecordingState::Idle | RecordingState::Error => start_recording(app_handle, state).await,
+        RecordingState::Recording | RecordingState::Paused => {
+            stop_recording(app_handle, state).await
+        }
+        _ => {
+            // Transcribing or Enhancing - can't toggle, maybe cancel?
+            Ok(())
+        }
+    }
+}
+
+/// Transform the current selection (or clipboard contents) with a typed command.
+/// Command Mode without voice: runs the text through the same transform pipeline
+/// and replaces the selection, or writes the result back to the clipboard.
+/// With `paste`, a clipboard result is pasted at the cursor instead and the
+/// copied text stays on the clipboard.
+#[tauri::command]
+async fn transform_clipboard(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    command: String,
+    paste: Option<bool>,
+) -> Result<String, String> {
+    let command = command.trim();
+    if command.is_empty() {
+        return Err("Command cannot be empty".to_string());
+    }
+    if command.chars().count() > MAX_TRANSFORM_COMMAND_LENGTH {
+        return Err(format!(
+            "Command too long (max {} characters)",
+            MAX_TRANSFORM_COMMAND_LENGTH
+        ));
+    }
+
+    if !state.get_state().can_start_recording() || state.is_inserting() {
+        return Err("Cannot transform while a recording is in progress".to_string());
+    }
+
+    // Prefer the live selection so the result can replace it in place
+    let (source_text, from_selection) = match platform::selection::get_selected_text() {
+        Ok(text) => (text, true),
+        Err(_) => {
+            use tauri_plugin_clipboard_manager::ClipboardExt;
+            let text = app_handle
+                .clipboard()
+                .read_text()
+                .map_err(|e| format!("Failed to read clipboard: {}", e))?;
+            (text, false)
+        }
+    };
+
+    if source_text.trim().is_empty() {
+        return Err("No selected text or clipboard contents to transform".to_string());
+    }
+
+    log::info!(
+        "[TRANSFORM] Transforming {} chars from {}",
+        source_text.len(),
+        if from_selection {
+            "selection"
+        } else {
+            "clipboard"
+        }
+    );
+
+    let max_selection_chars = state.with_config(|cfg| cfg.max_selection_chars)?;
+    let source_chars = source_text.chars().count();
+    if source_chars > max_selection_chars {
+        emit_error(
+            &app_handle,
+            ErrorEvent::selection_too_large(source_chars, max_selection_chars, source_text),
+        );
+        return Err(format!(
+            "Selection too large ({} characters, limit is {})",
+            source_chars, max_selection_chars
+        ));
+    }
+
+    let (groq_model, transform_prompt) =
+        state.with_config(|cfg| (cfg.groq_model.clone(), cfg.transform_prompt.clone()))?;
+    let groq_client = GroqLlmClient::new(&groq_model)?;
+    let transformed = groq_client
+        .transform_text(&source_text, command, transform_prompt.as_deref())
+        .await
+        .inspect_err(|e| {
+            emit_error(
+                &app_handle,
+                ErrorEvent::groq_error(&e.to_string(), Some(source_text.clone())),
+            );
+        })?;
+
+    // Pasting goes through the clipboard-preserving insertion path, so the
+    // copied source text is still on the clipboard afterwards
+    let inserted = (from_selection || paste.unwrap_or(false)) && {
+        let (pre_insert_delay_ms, newline_mode, method) = state.with_config(|cfg| {
+            (
+                cfg.pre_insert_delay_for_app(None),
+                cfg.newline_mode,
+                cfg.insertion_method,
+            )
+        })?;
+        state.set_inserting(true);
+        let inserted = insert_text_directly(
+            &state,
+            &transformed,
+            pre_insert_delay_ms,
+            newline_mode,
+            method,
+            None,
+            false,
+        )
+        .is_ok();
+        state.set_inserting(false);
+        inserted
+    };
+
+    if !inserted {
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        app_handle
+            .clipboard()
+            .write_text(transformed.clone())
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+    }
+
+    Ok(transformed)
+}
+
+/// Re-run enhancement on the last dictation with a different style.
+/// Replaces the previously inserted text if it is still selected,
+/// otherwise copies the result to the clipboard.
+#[tauri::command]
+async fn reenhance_last(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    style_id: String,
+) -> Result<String, String> {
+    let style =
+        styles::get_style_by_id(&style_id).ok_or_else(|| format!("Unknown style: {}", style_id))?;
+
+    let last = state
+        .get_last_dictation()
+        .ok_or_else(|| "No previous dictation to re-enhance".to_string())?;
+
+    if !state.get_state().can_start_recording() || state.is_inserting() {
+        return Err("Cannot re-enhance while a recording is in progress".to_string());
+    }
+
+    log::info!(
+        "[REENHANCE] Re-enhancing last dictation with style: {}",
+        style.id
+    );
+
+    let (groq_model, number_format, enhance_prompt, rules) = state.with_config(|cfg| {
+        (
+            cfg.groq_model.clone(),
+            cfg.number_format,
+            cfg.enhance_prompt.clone(),
+            cfg.replacements.clone(),
+        )
+    })?;
+    let groq_client = GroqLlmClient::new(&groq_model)?;
+    let enhanced = groq_client
+        .enhance_text(
+            &last.raw_transcript,
+            Some(style.prompt_modifier.as_str()),
+            enhance_prompt.as_deref(),
+        )
+        .await
+        .inspect_err(|e| {
+            emit_error(
+                &app_handle,
+                ErrorEvent::groq_error(&e.to_string(), Some(last.inserted_text.clone())),
+            );
+        })?;
+    let enhanced = styles::apply_auto_features(&enhanced, &style.auto_features);
+    let enhanced = ide::file_tagger::cleanup_tagged_punctuation(&enhanced);
+    let enhanced = text_normalize::normalize_numbers(&enhanced, number_format);
+    let enhanced = replacements::apply_replacements(&enhanced, &rules);
+
+    // Only replace in place if the previous result is still what's selected
+    let previous_still_selected = platform::selection::get_selected_text()
+        .map(|selected| {
+            normalize_whitespace(&selected) == normalize_whitespace(&last.inserted_text)
+        })
+        .unwrap_or(false);
+
+    let inserted = previous_still_selected && {
+        let (pre_insert_delay_ms, newline_mode, method) = state.with_config(|cfg| {
+            (
+                cfg.pre_insert_delay_for_app(None),
+                cfg.newline_mode,
+                cfg.insertion_method,
+            )
+        })?;
+        state.set_inserting(true);
+        let inserted = insert_text_directly(
+            &state,
+            &enhanced,
+            pre_insert_delay_ms,
+            newline_mode,
+            method,
+            None,
+            false,
+        )
+        .is_ok();
+        state.set_inserting(false);
+        inserted
+    };
+
+    if !inserted {
+        log::info!("[REENHANCE] Could not replace previous text, copying to clipboard");
+        use tauri_plugin_clipboard_manager::ClipboardExt;
+        app_handle
+            .clipboard()
+            .write_text(enhanced.clone())
+            .map_err(|e| format!("Failed to write clipboard: {}", e))?;
+    }
+
+    state.set_last_dictation(Some(LastDictation {
+        raw_transcript: last.raw_transcript,
+        inserted_text: enhanced.clone(),
+        confidence: last.confidence,
+    }));
+
+    Ok(enhanced)
+}
+
+/// Re-send the last recording to the transcription provider without
+/// re-recording, e.g. when it heard the wrong language. Returns the new
+/// transcript; the frontend decides what to do with it. Re-enhancing
+/// afterwards uses the new transcript.
+#[tauri::command]
+async fn retranscribe_last(
+    state: State<'_, AppState>,
+    language: Option<String>,
+) -> Result<String, String> {
+    retranscribe(&state, language).await
+}
+
+/// `retranscribe_last` without the Tauri state wrapper
+async fn retranscribe(state: &AppState, language: Option<String>) -> Result<String, String> {
+    if let Some(ref lang) = language {
+        if !whisper_api::is_supported_language(lang) {
+            return Err(format!("Unsupported language: {}", lang));
+        }
+    }
+
+    if !state.get_state().can_start_recording() || state.is_inserting() {
+        return Err("Cannot re-transcribe while a recording is in progress".to_string());
+    }
+
+    let samples = state
+        .get_last_audio()
+        .ok_or_else(|| "No recent recording to re-transcribe".to_string())?;
+
+    let bundle_id = state.get_active_bundle_id();
+    let mut config = RecordingStopConfig::load(state, bundle_id.as_deref())?;
+    if let Some(language) = language {
+        config.language = language;
+    }
+
+    log::info!(
+        "[RETRANSCRIBE] Re-transcribing last recording as '{}'",
+        config.language
+    );
+
+    let wav = encode_samples_to_wav(&samples, 16000)?;
+    let transcription = transcribe_audio(&config, &wav).await?;
+    let confidence = transcription.confidence();
+    let transcript = transcription.text;
+
+    if transcript.is_empty() {
+        return Err("No speech detected".to_string());
+    }
+
+    let inserted_text = state
+        .get_last_dictation()
+        .map(|d| d.inserted_text)
+        .unwrap_or_default();
+    state.set_last_dictation(Some(LastDictation {
+        raw_transcript: transcript.clone(),
+        inserted_text,
+        confidence,
+    }));
+
+    let transcript = ide::file_tagger::cleanup_tagged_punctuation(&transcript);
+    Ok(replacements::apply_replacements(
+        &transcript,
+        &config.replacements,
+    ))
+}
+
+/// Play the last recording through the default output device, to hear what
+/// the microphone actually captured. Requires the `keep_last_recording` opt-in.
+#[tauri::command]
+fn play_last_recording(state: State<'_, AppState>) -> Result<(), String> {
+    if !state.with_config(|cfg| cfg.keep_last_recording)? {
+        return Err("Turn on \"Keep last recording\" to play recordings back".to_string());
+    }
+    let samples = state
+        .get_last_audio()
+        .ok_or_else(|| "No recent recording to play".to_string())?;
+
+    log::info!(
+        "[PLAYBACK] Playing last recording ({:.1}s)",
+        samples.len() as f32 / 16000.0
+    );
+    let wav = encode_samples_to_wav(&samples, 16000)?;
+    audio_cues::play_wav(&wav)
+}
+
+/// Collapse all runs of whitespace to single spaces (matches what insertion does)
+fn normalize_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Preferences tabs `show_preferences_tab` can open (must match the tabs in
+/// `PreferencesWindow.tsx`)
+const PREFERENCES_TABS: &[&str] = &["general", "audio"];
+
+#[tauri::command]
+async fn show_preferences(app_handle: AppHandle) -> Result<(), String> {
+    // Don't show preferences if not authenticated
+    if !auth::is_authenticated() {
+        log::warn!("[PREFERENCES] Cannot show preferences - user not authenticated");
+        return Err("Not authenticated".to_string());
+    }
+
+    if let Some(window) = app_handle.get_webview_window("main") {
+        window
+            .show()
+            .map_err(|e| format!("Failed to show window: {}", e))?;
+        window
+            .set_focus()
+            .map_err(|e| format!("Failed to focus window: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Show preferences on a specific tab, e.g. from an error that needs a
+/// setting changed. The frontend switches tabs on `navigate-preferences`.
+#[tauri::command]
+async fn show_preferences_tab(app_handle: AppHandle, tab: String) -> Result<(), String> {
+    let tab = tab.trim().to_lowercase();
+    if !PREFERENCES_TABS.contains(&tab.as_str()) {
+        return Err(format!(
+            "Unknown preferences tab: {} (expected one of: {})",
+            tab,
+            PREFERENCES_TABS.join(", ")
+        ));
+    }
+
+    show_preferences(app_handle.clone()).await?;
+    app_handle
+        .emit_to("main", "navigate-preferences", &tab)
+        .map_err(|e| format!("Failed to emit navigate-preferences: {}", e))
+}
+
+#[tauri::command]
+async fn update_preferences(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    preferences: config::Preferences,
+) -> Result<(), String> {
+    // Swap the registered recording hotkeys first, so a combo the OS rejects
+    // is reported before anything is saved
+    let previous_shortcut = state.get_main_shortcut();
+    let hotkey_changed = state.with_config(|cfg| cfg.hotkey != preferences.hotkey)?;
+    let new_shortcut = if hotkey_changed {
+        shortcut_change(previous_shortcut, &preferences.hotkey)
+            .map_err(|e| format!("Invalid hotkey \"{}\": {}", preferences.hotkey, e))?
+    } else {
+        None
+    };
+    let previous_raw = state.get_raw_shortcut();
+    let raw_change =
+        raw_shortcut_change(previous_raw, preferences.raw_hotkey.as_deref()).map_err(|e| {
+            format!(
+                "Invalid raw hotkey \"{}\": {}",
+                preferences.raw_hotkey.as_deref().unwrap_or_default(),
+                e
+            )
+        })?;
+    if let Some(shortcut) = new_shortcut {
+        replace_main_shortcut(&app_handle, &state, shortcut)?;
+    }
+    if let Some(raw_shortcut) = raw_change {
+        if let Err(e) = replace_raw_shortcut(&app_handle, &state, raw_shortcut) {
+            if let (Some(_), Some(previous)) = (new_shortcut, previous_shortcut) {
+                let _ = replace_main_shortcut(&app_handle, &state, previous);
+            }
+            return Err(e);
+        }
+    }
+
+    let result = state
         .config
         .lock()
-        .map_err(|e| format!("Failed to lock config: {}", e))?;
-    config.update_from_preferences(preferences)?;
-    Ok(())
+        .map_err(|e| format!("Failed to lock config: {}", e))
+        .and_then(|mut config| {
+            config.update_from_preferences(preferences)?;
+            http_client::set_request_timeout(config.network_timeout_ms);
+            Ok(())
+        });
+    if result.is_err() {
+        // Preferences weren't saved - put the old hotkeys back
+        if let (Some(_), Some(previous)) = (new_shortcut, previous_shortcut) {
+            let _ = replace_main_shortcut(&app_handle, &state, previous);
+        }
+        if raw_change.is_some() {
+            let _ = replace_raw_shortcut(&app_handle, &state, previous_raw);
+        }
+    }
+    result
+}
+
+/// The shortcut to register for `hotkey`, or None if it's already the one
+/// registered
+fn shortcut_change(
+    current: Option<Shortcut>,
+    hotkey: &str,
+) -> Result<Option<Shortcut>, HotkeyParseError> {
+    let shortcut = parse_hotkey(hotkey)?;
+    Ok((current != Some(shortcut)).then_some(shortcut))
+}
+
+/// The raw hotkey to switch to for the `raw_hotkey` preference (Some(None)
+/// removes it), or None if it stays as it is. An empty string clears it.
+fn raw_shortcut_change(
+    current: Option<Shortcut>,
+    hotkey: Option<&str>,
+) -> Result<Option<Option<Shortcut>>, HotkeyParseError> {
+    match hotkey {
+        Some("") => Ok(current.is_some().then_some(None)),
+        Some(hotkey) => Ok(shortcut_change(current, hotkey)?.map(Some)),
+        None => Ok(None),
+    }
+}
+
+/// Unregister the current recording hotkey and register `shortcut` in its
+/// place. The global shortcut handler is shared, so the new combo behaves
+/// exactly like the old one. If the OS refuses the new combo (e.g. another
+/// app owns it) the old one is restored and an error returned.
+fn replace_main_shortcut(
+    app: &AppHandle,
+    state: &AppState,
+    shortcut: Shortcut,
+) -> Result<(), String> {
+    let global_shortcut = app.global_shortcut();
+    if global_shortcut.is_registered(shortcut) {
+        return Err("That hotkey is already used by another Murmur shortcut".to_string());
+    }
+
+    let previous = state.get_main_shortcut();
+    if let Some(previous) = previous {
+        if let Err(e) = global_shortcut.unregister(previous) {
+            log::warn!("[HOTKEY] Failed to unregister {:?}: {}", previous, e);
+        }
+    }
+
+    match global_shortcut.register(shortcut) {
+        Ok(()) => {
+            log::info!("[HOTKEY] Recording hotkey changed to {:?}", shortcut);
+            state.set_main_shortcut(Some(shortcut));
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("[HOTKEY] Failed to register {:?}: {}", shortcut, e);
+            if let Some(previous) = previous {
+                if let Err(e) = global_shortcut.register(previous) {
+                    log::error!("[HOTKEY] Failed to restore {:?}: {}", previous, e);
+                    state.set_main_shortcut(None);
+                }
+            }
+            Err(format!(
+                "Couldn't register the hotkey - it may be taken by the system or another app ({})",
+                e
+            ))
+        }
+    }
+}
+
+/// Unregister the current raw-mode hotkey and register `shortcut` (None just
+/// removes it). Like `replace_main_shortcut`, the old combo is restored if the
+/// OS refuses the new one.
+fn replace_raw_shortcut(
+    app: &AppHandle,
+    state: &AppState,
+    shortcut: Option<Shortcut>,
+) -> Result<(), String> {
+    let global_shortcut = app.global_shortcut();
+    if shortcut.is_some_and(|s| global_shortcut.is_registered(s)) {
+        return Err("That hotkey is already used by another Murmur shortcut".to_string());
+    }
+
+    let previous = state.get_raw_shortcut();
+    if let Some(previous) = previous {
+        if let Err(e) = global_shortcut.unregister(previous) {
+            log::warn!("[HOTKEY] Failed to unregister {:?}: {}", previous, e);
+        }
+    }
+    state.set_raw_shortcut(None);
+
+    let Some(shortcut) = shortcut else {
+        log::info!("[HOTKEY] Raw hotkey removed");
+        return Ok(());
+    };
+    match global_shortcut.register(shortcut) {
+        Ok(()) => {
+            log::info!("[HOTKEY] Raw hotkey changed to {:?}", shortcut);
+            state.set_raw_shortcut(Some(shortcut));
+            Ok(())
+        }
+        Err(e) => {
+            log::error!("[HOTKEY] Failed to register {:?}: {}", shortcut, e);
+            if let Some(previous) = previous {
+                match global_shortcut.register(previous) {
+                    Ok(()) => state.set_raw_shortcut(Some(previous)),
+                    Err(e) => log::error!("[HOTKEY] Failed to restore {:?}: {}", previous, e),
+                }
+            }
+            Err(format!(
+                "Couldn't register the raw hotkey - it may be taken by the system or another app ({})",
+                e
+            ))
+        }
+    }
+}
+
+/// Check a hotkey string without registering it, so the preferences UI can
+/// validate it as the user types
+#[tauri::command]
+fn validate_hotkey(hotkey: String) -> Result<(), String> {
+    parse_hotkey(&hotkey).map(|_| ()).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -734,6 +2637,65 @@ async fn get_preferences(state: State<'_, AppState>) -> Result<config::Preferenc
         language: config.language.clone(),
         onboarding_complete: stored.onboarding_complete,
         spoken_languages: stored.spoken_languages,
+        verbose_timing: Some(config.verbose_timing),
+        max_selection_chars: Some(config.max_selection_chars),
+        app_languages: Some(config.app_languages.clone()),
+        pre_insert_delay_ms: Some(config.pre_insert_delay_ms),
+        app_pre_insert_delays: Some(config.app_pre_insert_delays.clone()),
+        use_proxy: Some(config.use_proxy),
+        min_confidence: Some(config.min_confidence.unwrap_or(0.0)),
+        low_confidence_action: Some(config.low_confidence_action),
+        scratch_hotkey: config.scratch_hotkey.clone(),
+        silence_timeout_ms: Some(config.silence_timeout_ms.unwrap_or(0)),
+        silence_threshold_db: Some(config.silence_threshold_db),
+        preroll_ms: Some(config.preroll_ms),
+        resample_quality: Some(config.resample_quality),
+        enhancement_enabled: Some(config.enhancement_enabled),
+        groq_model: Some(config.groq_model.clone()),
+        newline_mode: Some(config.newline_mode),
+        insertion_method: Some(config.insertion_method),
+        history_enabled: Some(config.history_enabled),
+        reinsert_hotkey: config.reinsert_hotkey.clone(),
+        app_styles: Some(config.app_styles.clone()),
+        index_config: Some(config.index_config.clone()),
+        transcription_provider: Some(config.transcription_provider),
+        deepgram_model: Some(config.deepgram_model.clone()),
+        max_recording_duration: Some(config.max_recording_duration),
+        audio_cues: Some(config.audio_cues.clone()),
+        command_mode_behavior: Some(config.command_mode_behavior),
+        output_mode: Some(config.output_mode),
+        raw_hotkey: config.raw_hotkey.clone(),
+        vocabulary_prompt: Some(config.vocabulary_prompt.clone().unwrap_or_default()),
+        app_vocabulary_prompts: Some(config.app_vocabulary_prompts.clone()),
+        adaptive_language: Some(config.adaptive_language),
+        mixed_mode_output: Some(config.mixed_mode_output),
+        network_timeout_ms: Some(config.network_timeout_ms.unwrap_or(0)),
+        fallback_on_enhancement_failure: Some(config.fallback_on_enhancement_failure),
+        case_style_by_language: Some(config.case_style_by_language.clone()),
+        style_inference_excluded: Some(config.style_inference_excluded.clone()),
+        enhance_prompt: Some(
+            config
+                .prompt_template(config::PromptTemplate::Enhance)
+                .to_string(),
+        ),
+        transform_prompt: Some(
+            config
+                .prompt_template(config::PromptTemplate::Transform)
+                .to_string(),
+        ),
+        number_format: Some(config.number_format),
+        keep_last_recording: Some(config.keep_last_recording),
+        collapse_whitespace: Some(config.collapse_whitespace),
+        redact_sensitive: Some(config.redact_sensitive),
+        command_mode_excluded: Some(config.command_mode_excluded.clone()),
+        done_display_ms: Some(config.done_display_ms),
+        app_focus_wait_ms: Some(config.app_focus_wait_ms),
+        enhancement_disabled_languages: Some(config.enhancement_disabled_languages.clone()),
+        mono_channel: Some(config.mono_channel),
+        meter_min_db: Some(config.meter_min_db),
+        meter_max_db: Some(config.meter_max_db),
+        meter_peak_hold: Some(config.meter_peak_hold),
+        input_gain_db: Some(config.input_gain_db),
     })
 }
 
@@ -743,10 +2705,73 @@ async fn get_preferences(state: State<'_, AppState>) -> Result<config::Preferenc
 
 #[tauri::command]
 fn check_permissions() -> permissions::PermissionStatus {
-    permissions::PermissionStatus {
-        microphone: permissions::check_microphone_permission(),
-        accessibility: permissions::check_accessibility_permission(),
+    permissions::PermissionStatus::current()
+}
+
+/// Re-query permission status and emit `permissions-changed` if it differs
+/// from the last known status (e.g. user granted access in System Settings)
+#[tauri::command]
+fn recheck_permissions(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> permissions::PermissionStatus {
+    refresh_permission_status(&app_handle, &state)
+}
+
+/// Start polling permission status in the background.
+/// Emits `permissions-changed` on every change and stops automatically once
+/// all permissions are granted or `stop_permission_watcher` is called.
+#[tauri::command]
+fn start_permission_watcher(app_handle: AppHandle, state: State<'_, AppState>) {
+    // A stop followed by a quick restart gets a new id, so the old thread
+    // notices on its next tick and exits instead of running alongside
+    let Some(id) = state.begin_permission_watcher() else {
+        return;
+    };
+
+    std::thread::spawn(move || {
+        log::info!("[PERMISSIONS] Watcher {} started", id);
+        loop {
+            let state: tauri::State<'_, AppState> = app_handle.state();
+            if !state.is_permission_watcher_current(id) {
+                break;
+            }
+
+            if refresh_permission_status(&app_handle, &state).all_granted() {
+                state.end_permission_watcher(Some(id));
+                break;
+            }
+
+            std::thread::sleep(std::time::Duration::from_millis(
+                PERMISSION_WATCH_INTERVAL_MS,
+            ));
+        }
+        log::info!("[PERMISSIONS] Watcher {} stopped", id);
+    });
+}
+
+#[tauri::command]
+fn stop_permission_watcher(state: State<'_, AppState>) {
+    state.end_permission_watcher(None);
+}
+
+/// Check permissions and emit `permissions-changed` when the status flips
+fn refresh_permission_status(app: &AppHandle, state: &AppState) -> permissions::PermissionStatus {
+    let current = permissions::PermissionStatus::current();
+    let previous = state.swap_permission_status(current.clone());
+
+    if previous.as_ref() != Some(&current) {
+        log::info!(
+            "[PERMISSIONS] Status changed: microphone={}, accessibility={}",
+            current.microphone,
+            current.accessibility
+        );
+        if let Err(e) = app.emit("permissions-changed", &current) {
+            log::error!("Failed to emit permissions change: {}", e);
+        }
     }
+
+    current
 }
 
 #[tauri::command]
@@ -754,6 +2779,34 @@ fn get_microphones() -> Vec<permissions::MicrophoneDevice> {
     permissions::get_microphone_devices()
 }
 
+/// Open the microphone for a couple of seconds and stream `audio-level` events,
+/// so onboarding can show a live meter before the user finishes setup
+#[tauri::command]
+async fn test_microphone_level(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    device_id: Option<String>,
+) -> Result<(), String> {
+    if !state.get_state().can_start_recording() {
+        return Err("Cannot test the microphone while recording".to_string());
+    }
+    let (mono_channel, meter, gain_db) =
+        state.with_config(|cfg| (cfg.mono_channel, cfg.meter_settings(), cfg.input_gain_db))?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        audio::test_input_level(
+            &app_handle,
+            device_id.as_deref(),
+            std::time::Duration::from_millis(audio::MIC_TEST_DURATION_MS),
+            mono_channel,
+            meter,
+            gain_db,
+        )
+    })
+    .await
+    .map_err(|e| format!("Microphone test failed: {}", e))?
+}
+
 #[tauri::command]
 fn request_microphone_permission() -> bool {
     permissions::request_microphone_permission()
@@ -902,113 +2955,767 @@ const BLOCKED_DIRECTORIES: &[&str] = &[
     ".secrets",
 ];
 
-/// Validate that a path is safe to index
-fn validate_workspace_path(path: &std::path::Path) -> Result<std::path::PathBuf, String> {
-    // Canonicalize to resolve symlinks and get absolute path
-    let canonical = path
-        .canonicalize()
-        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+/// Validate that a path is safe to index
+fn validate_workspace_path(path: &std::path::Path) -> Result<std::path::PathBuf, String> {
+    // Canonicalize to resolve symlinks and get absolute path
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+
+    // Get home directory
+    let home_dir = dirs::home_dir().ok_or("Could not determine home directory")?;
+
+    // Path must be under home directory (security: prevent indexing system dirs)
+    if !canonical.starts_with(&home_dir) {
+        return Err(format!(
+            "Workspace must be within your home directory: {}",
+            home_dir.display()
+        ));
+    }
+
+    // Check against blocked directories
+    let relative_to_home = canonical
+        .strip_prefix(&home_dir)
+        .map_err(|_| "Path is not under home directory")?;
+
+    if let Some(blocked) = find_blocked_directory(relative_to_home) {
+        return Err(format!("Cannot index sensitive directory: {}", blocked));
+    }
+
+    Ok(canonical)
+}
+
+/// Return the blocked directory a home-relative path falls under, if any
+fn find_blocked_directory(relative_to_home: &std::path::Path) -> Option<&'static str> {
+    let path_str = relative_to_home.to_string_lossy();
+    BLOCKED_DIRECTORIES
+        .iter()
+        .find(|blocked| {
+            path_str.starts_with(*blocked) || path_str.contains(&format!("/{}/", blocked))
+        })
+        .copied()
+}
+
+/// Maximum size of an audio file accepted by `transcribe_file`
+const MAX_AUDIO_FILE_BYTES: u64 = 200 * 1024 * 1024;
+
+/// Maximum duration of an audio file accepted by `transcribe_file`.
+/// 10 minutes of 16kHz 16-bit mono stays under Groq's 25MB upload limit.
+const MAX_AUDIO_FILE_SECS: usize = 600;
+
+/// Validate that a path is a readable WAV file the user is allowed to transcribe
+fn validate_audio_file_path(path: &std::path::Path) -> Result<std::path::PathBuf, String> {
+    let canonical = path
+        .canonicalize()
+        .map_err(|e| format!("Failed to resolve path: {}", e))?;
+
+    let metadata =
+        std::fs::metadata(&canonical).map_err(|e| format!("Cannot read audio file: {}", e))?;
+    if !metadata.is_file() {
+        return Err(format!("Path is not a file: {}", canonical.display()));
+    }
+    if metadata.len() > MAX_AUDIO_FILE_BYTES {
+        return Err(format!(
+            "Audio file too large (max {} MB)",
+            MAX_AUDIO_FILE_BYTES / (1024 * 1024)
+        ));
+    }
+
+    let is_wav = canonical
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("wav"));
+    if !is_wav {
+        return Err("Only WAV audio files are supported".to_string());
+    }
+
+    // Same boundaries as workspace indexing: home directory only, no sensitive dirs
+    let home_dir = dirs::home_dir().ok_or("Could not determine home directory")?;
+    let relative_to_home = canonical.strip_prefix(&home_dir).map_err(|_| {
+        format!(
+            "Audio file must be within your home directory: {}",
+            home_dir.display()
+        )
+    })?;
+    if let Some(blocked) = find_blocked_directory(relative_to_home) {
+        return Err(format!("Cannot read from sensitive directory: {}", blocked));
+    }
+
+    Ok(canonical)
+}
+
+/// Transcribe an existing WAV file (e.g. a voice memo) and return the result
+/// without inserting it. Runs the same transcription and enhancement pipeline
+/// as `process_audio_dry_run`, after resampling the file to 16kHz.
+#[tauri::command]
+async fn transcribe_file(
+    state: State<'_, AppState>,
+    path: String,
+    mode: DictationMode,
+) -> Result<TranscriptionCompleteEvent, String> {
+    let validated_path = validate_audio_file_path(std::path::Path::new(&path))?;
+    log::info!(
+        "[TRANSCRIBE_FILE] Transcribing: {}",
+        validated_path.display()
+    );
+
+    let (samples, sample_rate) = audio::load_wav_file(&validated_path)?;
+    if samples.is_empty() || sample_rate == 0 {
+        return Err("Audio file contains no audio".to_string());
+    }
+    if samples.len() / sample_rate as usize > MAX_AUDIO_FILE_SECS {
+        return Err(format!(
+            "Audio file too long (max {} minutes)",
+            MAX_AUDIO_FILE_SECS / 60
+        ));
+    }
+
+    let samples = audio::trim_silence(&samples, sample_rate);
+    if samples.is_empty() {
+        return Err("No speech detected".to_string());
+    }
+
+    let quality = state.with_config(|cfg| cfg.resample_quality)?;
+    let samples_16khz = audio::resample_to_16khz(&samples, sample_rate, quality)?;
+    let wav = encode_samples_to_wav(&samples_16khz, 16000)?;
+
+    process_wav_without_insert(&state, &wav, mode).await
+}
+
+/// Add a workspace root and build its file index.
+/// Re-adding an existing root rebuilds its index. Unless `watch` is false,
+/// the index is kept current with a file watcher. Emits `workspace-index-progress`
+/// while the folder is scanned.
+#[tauri::command]
+async fn add_workspace_root(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    path: String,
+    watch: Option<bool>,
+) -> Result<WorkspaceIndexStatus, String> {
+    use std::path::Path;
+
+    let workspace_path = Path::new(&path);
+
+    if !workspace_path.exists() {
+        return Err(format!("Path does not exist: {}", path));
+    }
+
+    if !workspace_path.is_dir() {
+        return Err(format!("Path is not a directory: {}", path));
+    }
+
+    // Validate path security (canonicalize, check boundaries, block sensitive dirs)
+    let validated_path = validate_workspace_path(workspace_path)?;
+
+    log::info!(
+        "[WORKSPACE] Building index for: {}",
+        validated_path.display()
+    );
+
+    let index_config = state.with_config(|cfg| cfg.index_config.clone())?;
+
+    // Walk off the async runtime so progress events reach the UI during the scan
+    let app_for_progress = app.clone();
+    let build_result = tauri::async_runtime::spawn_blocking(move || {
+        ide::file_index::WorkspaceIndex::build_with_progress(
+            &validated_path,
+            &index_config,
+            |progress| {
+                let _ = app_for_progress.emit("workspace-index-progress", progress);
+            },
+        )
+    })
+    .await
+    .map_err(|e| format!("Index task failed: {}", e))?;
+
+    let index = match build_result {
+        Ok(index) => index,
+        Err(e) => {
+            log::info!("[WORKSPACE] Failed to build index: {}", e);
+            return Err(e);
+        }
+    };
+
+    // Cap the combined file count across roots (a re-added root replaces itself)
+    let other_files: usize = state
+        .get_workspace_indexes()
+        .iter()
+        .filter(|existing| existing.root != index.root)
+        .map(|existing| existing.file_count())
+        .sum();
+    if other_files + index.file_count() > ide::file_index::MAX_TOTAL_FILES {
+        return Err(format!(
+            "Too many files across workspaces ({} already indexed, {} in this folder, limit is {})",
+            other_files,
+            index.file_count(),
+            ide::file_index::MAX_TOTAL_FILES
+        ));
+    }
+
+    log::info!(
+        "[WORKSPACE] Index built: {} files indexed, {} skipped",
+        index.file_count(),
+        index.files_skipped
+    );
+
+    let watcher = if watch.unwrap_or(true) {
+        match index.start_watching(app.clone()) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                log::warn!("[WORKSPACE] File watcher unavailable: {}", e);
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    // Symbol scanning reads every file, so do it off the command thread
+    if ide::IDESettings::default().symbol_tagging_enabled {
+        let index_for_symbols = index.clone();
+        let app_for_symbols = app.clone();
+        std::thread::spawn(move || {
+            let symbols = ide::symbol_index::SymbolIndex::build(&index_for_symbols);
+            log::info!(
+                "[WORKSPACE] Symbol index built: {} symbols in {}",
+                symbols.symbol_count(),
+                symbols.root.display()
+            );
+            app_for_symbols
+                .state::<AppState>()
+                .set_symbol_index(symbols);
+        });
+    }
+
+    state.add_workspace_index(index, watcher);
+
+    Ok(workspace_status(&state.get_workspace_indexes()))
+}
+
+/// Remove a workspace root and its file index
+#[tauri::command]
+fn remove_workspace_root(
+    state: State<'_, AppState>,
+    path: String,
+) -> Result<WorkspaceIndexStatus, String> {
+    // Roots are stored canonicalized; fall back to the raw path if it was deleted
+    let root = std::fs::canonicalize(&path).unwrap_or_else(|_| std::path::PathBuf::from(&path));
+
+    if !state.remove_workspace_index(&root) {
+        return Err(format!("Workspace root not indexed: {}", path));
+    }
+    log::info!("[WORKSPACE] Removed root: {}", root.display());
+
+    Ok(workspace_status(&state.get_workspace_indexes()))
+}
+
+/// Get the current workspace index status
+#[tauri::command]
+fn get_workspace_status(state: State<'_, AppState>) -> WorkspaceIndexStatus {
+    workspace_status(&state.get_workspace_indexes())
+}
+
+/// Clear all workspace indexes
+#[tauri::command]
+fn clear_workspace_index(state: State<'_, AppState>) {
+    state.clear_workspace_indexes();
+    log::info!("[WORKSPACE] Index cleared");
+}
+
+/// Run the IDE transformations on `text` as if it was dictated in `bundle_id`,
+/// for trying out IDE settings. Text for a non-IDE app comes back unchanged.
+#[tauri::command]
+fn preview_ide_transform(
+    state: State<'_, AppState>,
+    text: String,
+    bundle_id: String,
+) -> Result<String, String> {
+    let settings = ide::IDESettings {
+        custom_dictionary: ide::dictionary::load_custom_entries(),
+        case_style_by_language: state.with_config(|cfg| cfg.case_style_by_language.clone())?,
+        ..Default::default()
+    };
+    Ok(ide_transform_preview(
+        &text,
+        &bundle_id,
+        &settings,
+        &state.get_workspace_indexes(),
+        &state.get_symbol_indexes(),
+    ))
+}
+
+fn ide_transform_preview(
+    text: &str,
+    bundle_id: &str,
+    settings: &ide::IDESettings,
+    workspace_indexes: &[ide::file_index::WorkspaceIndex],
+    symbol_indexes: &[ide::symbol_index::SymbolIndex],
+) -> String {
+    // No window to read a title from, so the language is unknown
+    let context = ide::get_ide_context(bundle_id, None);
+    ide::apply_ide_transformations(text, &context, settings, workspace_indexes, symbol_indexes)
+}
+
+fn workspace_status(indexes: &[ide::file_index::WorkspaceIndex]) -> WorkspaceIndexStatus {
+    WorkspaceIndexStatus {
+        indexed: !indexes.is_empty(),
+        roots: indexes
+            .iter()
+            .map(|index| WorkspaceRootStatus {
+                root: index.root.to_string_lossy().to_string(),
+                file_count: index.file_count(),
+                files_skipped: index.files_skipped,
+            })
+            .collect(),
+        file_count: ide::file_index::total_file_count(indexes),
+        files_skipped: indexes.iter().map(|index| index.files_skipped).sum(),
+    }
+}
+
+// ============================================================================
+// REPLACEMENT COMMANDS
+// ============================================================================
+
+#[tauri::command]
+fn get_replacements(
+    state: State<'_, AppState>,
+) -> Result<Vec<replacements::ReplacementRule>, String> {
+    state.with_config(|cfg| cfg.replacements.clone())
+}
+
+#[tauri::command]
+fn add_replacement(
+    state: State<'_, AppState>,
+    find: String,
+    replace: String,
+    whole_word: Option<bool>,
+    case_sensitive: Option<bool>,
+) -> Result<replacements::ReplacementRule, String> {
+    let rule = replacements::add_rule(replacements::ReplacementRule::new(
+        find,
+        replace,
+        whole_word.unwrap_or(true),
+        case_sensitive.unwrap_or(false),
+    ))?;
+    state.reload_replacements()?;
+    Ok(rule)
+}
+
+#[tauri::command]
+fn update_replacement(
+    state: State<'_, AppState>,
+    rule: replacements::ReplacementRule,
+) -> Result<(), String> {
+    replacements::update_rule(rule)?;
+    state.reload_replacements()
+}
+
+#[tauri::command]
+fn remove_replacement(state: State<'_, AppState>, id: String) -> Result<(), String> {
+    replacements::remove_rule(&id)?;
+    state.reload_replacements()
+}
+
+// ============================================================================
+// CUSTOM DICTIONARY COMMANDS
+// ============================================================================
+
+/// Get the user's programming dictionary additions as (spoken, written) pairs
+#[tauri::command]
+fn get_custom_dictionary() -> Vec<(String, String)> {
+    ide::dictionary::load_custom_entries()
+}
+
+/// Add a programming dictionary entry, replacing any with the same spoken form
+#[tauri::command]
+fn add_dictionary_entry(spoken: String, written: String) -> Result<(), String> {
+    ide::dictionary::add_custom_entry(&spoken, &written)
+}
+
+#[tauri::command]
+fn remove_dictionary_entry(spoken: String) -> Result<(), String> {
+    ide::dictionary::remove_custom_entry(&spoken)
+}
+
+// ============================================================================
+// STYLE COMMANDS
+// ============================================================================
+
+/// All styles: built-ins first, then custom styles
+#[tauri::command]
+fn list_styles() -> Vec<styles::Style> {
+    styles::get_all_styles()
+}
+
+#[tauri::command]
+fn create_style(style: styles::Style) -> Result<styles::Style, String> {
+    let style = styles::custom::create_style(style)?;
+    log::info!("[STYLE] Custom style created: {}", style.id);
+    Ok(style)
+}
+
+#[tauri::command]
+fn update_style(style: styles::Style) -> Result<styles::Style, String> {
+    let style = styles::custom::update_style(style)?;
+    log::info!("[STYLE] Custom style updated: {}", style.id);
+    Ok(style)
+}
+
+#[tauri::command]
+fn delete_style(id: String) -> Result<(), String> {
+    styles::custom::delete_style(&id)?;
+    log::info!("[STYLE] Custom style deleted: {}", id);
+    Ok(())
+}
+
+/// Style the frontmost app maps to, including per-app overrides.
+/// Ignores any forced style so settings can preview the automatic mapping.
+/// Detection runs on a blocking thread and only holds the config lock long
+/// enough to copy the overrides, so it never stalls the recording pipeline.
+#[tauri::command]
+async fn get_active_style_for_current_app(app_handle: AppHandle) -> Result<styles::Style, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state: tauri::State<'_, AppState> = app_handle.state();
+        let (app_styles, inference_excluded) = state
+            .with_config(|cfg| (cfg.app_styles.clone(), cfg.style_inference_excluded.clone()))?;
+        Ok(styles::get_current_style(&app_styles, &inference_excluded))
+    })
+    .await
+    .map_err(|e| format!("Style detection task failed: {}", e))?
+}
+
+/// Force a style for dictations regardless of the active app.
+/// Pass None to return to automatic detection.
+#[tauri::command]
+fn set_forced_style(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+    style_id: Option<String>,
+) -> Result<(), String> {
+    let style = match style_id {
+        Some(id) => {
+            Some(styles::get_style_by_id(&id).ok_or_else(|| format!("Unknown style: {}", id))?)
+        }
+        None => None,
+    };
+
+    let message = match style {
+        Some(ref style) => {
+            log::info!("[STYLE] Forcing style: {}", style.id);
+            format!("Style: {}", style.name)
+        }
+        None => {
+            log::info!("[STYLE] Forced style cleared, using automatic detection");
+            "Style: automatic".to_string()
+        }
+    };
+    state.set_forced_style(style);
+    emit_state_change(&app_handle, &state, Some(message));
+    Ok(())
+}
+
+/// Per-app style overrides (bundle_id → style id)
+#[tauri::command]
+fn get_app_style_overrides(
+    state: State<'_, AppState>,
+) -> Result<std::collections::HashMap<String, String>, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    Ok(config.app_styles.clone())
+}
+
+/// Always use `style_id` for the app, ahead of the built-in mappings
+#[tauri::command]
+fn set_app_style_override(
+    state: State<'_, AppState>,
+    bundle_id: String,
+    style_id: String,
+) -> Result<(), String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    config.set_app_style(&bundle_id, Some(&style_id))?;
+    log::info!("[STYLE] Override set: {} -> {}", bundle_id, style_id);
+    Ok(())
+}
+
+#[tauri::command]
+fn clear_app_style_override(state: State<'_, AppState>, bundle_id: String) -> Result<(), String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    config.set_app_style(&bundle_id, None)?;
+    log::info!("[STYLE] Override cleared for {}", bundle_id);
+    Ok(())
+}
+
+/// Restore the built-in enhancement or transform prompt, returning it
+#[tauri::command]
+fn reset_prompt_template(
+    state: State<'_, AppState>,
+    template: config::PromptTemplate,
+) -> Result<String, String> {
+    let mut config = state
+        .config
+        .lock()
+        .map_err(|e| format!("Failed to lock config: {}", e))?;
+    config.reset_prompt_template(template)?;
+    log::info!("[PROMPT] Reset {:?} prompt to default", template);
+    Ok(template.default_prompt().to_string())
+}
+
+// ============================================================================
+// SCRATCH BUFFER COMMANDS
+// ============================================================================
+
+#[tauri::command]
+fn get_scratch_notes() -> Vec<scratch::ScratchNote> {
+    scratch::load_notes()
+}
+
+#[tauri::command]
+fn delete_scratch_note(id: String) -> Result<(), String> {
+    scratch::delete_note(&id)
+}
 
-    // Get home directory
-    let home_dir = dirs::home_dir().ok_or("Could not determine home directory")?;
+#[tauri::command]
+fn clear_scratch_notes() -> Result<(), String> {
+    scratch::clear_notes()
+}
 
-    // Path must be under home directory (security: prevent indexing system dirs)
-    if !canonical.starts_with(&home_dir) {
-        return Err(format!(
-            "Workspace must be within your home directory: {}",
-            home_dir.display()
-        ));
-    }
+// ============================================================================
+// HISTORY COMMANDS
+// ============================================================================
 
-    // Check against blocked directories
-    let relative_to_home = canonical
-        .strip_prefix(&home_dir)
-        .map_err(|_| "Path is not under home directory")?;
+/// Most recent dictations, newest first
+#[tauri::command]
+fn get_history(limit: usize) -> Vec<history::HistoryEntry> {
+    history::recent_entries(limit)
+}
 
-    let path_str = relative_to_home.to_string_lossy();
-    for blocked in BLOCKED_DIRECTORIES {
-        if path_str.starts_with(blocked) || path_str.contains(&format!("/{}/", blocked)) {
-            return Err(format!("Cannot index sensitive directory: {}", blocked));
-        }
-    }
+#[tauri::command]
+fn delete_history_entry(id: String) -> Result<(), String> {
+    history::delete_entry(&id)
+}
 
-    Ok(canonical)
+#[tauri::command]
+fn clear_history() -> Result<(), String> {
+    history::clear_entries()
 }
 
-/// Set the workspace root and build the file index
+/// Insert the most recent dictation from history again at the cursor
 #[tauri::command]
-fn set_workspace_root(
-    state: State<'_, AppState>,
-    path: String,
-) -> Result<WorkspaceIndexStatus, String> {
-    use std::path::Path;
+async fn reinsert_last(app_handle: AppHandle) -> Result<(), String> {
+    tauri::async_runtime::spawn_blocking(move || reinsert_last_entry(&app_handle))
+        .await
+        .map_err(|e| format!("Re-insert task failed: {}", e))?
+}
 
-    let workspace_path = Path::new(&path);
+/// Shared by the `reinsert_last` command and the re-insert hotkey.
+/// Respects the current newline and insertion preferences for the frontmost app.
+fn reinsert_last_entry(app: &AppHandle) -> Result<(), String> {
+    let state: tauri::State<'_, AppState> = app.state();
+    let bundle_id = styles::detection::get_active_app().map(|a| a.bundle_id);
+    let (history_enabled, pre_insert_delay_ms, newline_mode, method) =
+        state.with_config(|cfg| {
+            (
+                cfg.history_enabled,
+                cfg.pre_insert_delay_for_app(bundle_id.as_deref()),
+                cfg.newline_mode,
+                cfg.insertion_method,
+            )
+        })?;
 
-    if !workspace_path.exists() {
-        return Err(format!("Path does not exist: {}", path));
+    if !history_enabled {
+        return Err(
+            "History is turned off. Enable it in Preferences to re-insert past dictations."
+                .to_string(),
+        );
     }
+    let entry = history::recent_entries(1)
+        .pop()
+        .ok_or_else(|| "Nothing to re-insert yet - dictate something first".to_string())?;
 
-    if !workspace_path.is_dir() {
-        return Err(format!("Path is not a directory: {}", path));
+    if !state.get_state().can_start_recording() || state.is_inserting() {
+        return Err("Cannot re-insert while a recording is in progress".to_string());
     }
 
-    // Validate path security (canonicalize, check boundaries, block sensitive dirs)
-    let validated_path = validate_workspace_path(workspace_path)?;
-
     log::info!(
-        "[WORKSPACE] Building index for: {}",
-        validated_path.display()
+        "[REINSERT] Re-inserting last dictation ({} chars)",
+        entry.enhanced_text.len()
+    );
+    state.set_inserting(true);
+    let inserted = insert_text_directly(
+        &state,
+        &entry.enhanced_text,
+        pre_insert_delay_ms,
+        newline_mode,
+        method,
+        bundle_id.as_deref(),
+        false,
     );
+    state.set_inserting(false);
 
-    match ide::file_index::WorkspaceIndex::build(&validated_path) {
-        Ok(index) => {
-            let file_count = index.file_count();
-            let files_skipped = index.files_skipped;
-            state.set_workspace_index(Some(index));
-            log::info!(
-                "[WORKSPACE] Index built: {} files indexed, {} skipped",
-                file_count,
-                files_skipped
-            );
-            Ok(WorkspaceIndexStatus {
-                indexed: true,
-                root: Some(validated_path.to_string_lossy().to_string()),
-                file_count,
-                files_skipped,
-            })
-        }
-        Err(e) => {
-            log::info!("[WORKSPACE] Failed to build index: {}", e);
-            Err(e)
+    inserted.map_err(|e| format!("Could not insert the text into the current app: {}", e))
+}
+
+/// Remove the text the app last inserted: Delete presses for typed text, the
+/// target app's Undo for pasted text. Returns the removed text.
+/// Refused once `UNDO_INSERTION_WINDOW` has passed, since the user may have typed more.
+#[tauri::command]
+async fn undo_last_insertion(app_handle: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let state: tauri::State<'_, AppState> = app_handle.state();
+        if !state.get_state().can_start_recording() || state.is_inserting() {
+            return Err("Cannot undo while a recording is in progress".to_string());
         }
+        state.set_inserting(true);
+        let result = undo_last_insertion_now(&state);
+        state.set_inserting(false);
+        result
+    })
+    .await
+    .map_err(|e| format!("Undo task failed: {}", e))?
+}
+
+/// Shared by the `undo_last_insertion` command and the spoken undo command.
+fn undo_last_insertion_now(state: &AppState) -> Result<String, String> {
+    let last = state
+        .take_last_insertion()
+        .ok_or_else(|| "Nothing to undo".to_string())?;
+
+    if last.inserted_at.elapsed() > UNDO_INSERTION_WINDOW {
+        return Err(format!(
+            "The last insertion is more than {} seconds old",
+            UNDO_INSERTION_WINDOW.as_secs()
+        ));
+    }
+
+    log::info!(
+        "[UNDO] Undoing last insertion ({} chars, {})",
+        last.char_count,
+        if last.pasted { "pasted" } else { "typed" }
+    );
+    let undone = if last.pasted {
+        platform::insertion::undo()
+    } else {
+        platform::insertion::delete_backward(last.char_count)
+    };
+
+    if undone {
+        Ok(last.text)
+    } else {
+        Err("Could not send keys to the current app".to_string())
     }
 }
 
-/// Get the current workspace index status
+// ============================================================================
+// DIAGNOSTICS COMMANDS
+// ============================================================================
+
+/// Write a diagnostics bundle for bug reports and return its folder path.
+/// Contains sanitized recent logs, non-sensitive preferences, permission
+/// status, the selected microphone and workspace index status; never audio
+/// or API keys.
 #[tauri::command]
-fn get_workspace_status(state: State<'_, AppState>) -> WorkspaceIndexStatus {
-    match state.get_workspace_index() {
-        Some(index) => WorkspaceIndexStatus {
-            indexed: true,
-            root: Some(index.root.to_string_lossy().to_string()),
-            file_count: index.file_count(),
-            files_skipped: index.files_skipped,
-        },
-        None => WorkspaceIndexStatus {
-            indexed: false,
-            root: None,
-            file_count: 0,
-            files_skipped: 0,
-        },
+async fn export_diagnostics(
+    app_handle: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<String, String> {
+    let report = serde_json::json!({
+        "app_version": app_handle.package_info().version.to_string(),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "preferences": get_preferences(state.clone()).await?,
+        "permissions": permissions::PermissionStatus::current(),
+        "selected_microphone": permissions::get_selected_microphone_name(),
+        "workspace_index": workspace_status(&state.get_workspace_indexes()),
+    });
+
+    let log_dir = app_handle
+        .path()
+        .app_log_dir()
+        .map_err(|e| format!("Failed to resolve log directory: {}", e))?;
+    let output_dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+
+    let bundle = tauri::async_runtime::spawn_blocking(move || {
+        diagnostics::write_bundle(&output_dir, &log_dir, &report)
+    })
+    .await
+    .map_err(|e| format!("Diagnostics export failed: {}", e))??;
+
+    log::info!("[DIAGNOSTICS] Exported bundle to {}", bundle.display());
+    Ok(bundle.to_string_lossy().to_string())
+}
+
+// ============================================================================
+// API KEY COMMANDS
+// ============================================================================
+
+/// Store the user's own Groq API key (used when the proxy is disabled)
+#[tauri::command]
+fn set_groq_api_key(key: String) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("API key cannot be empty".to_string());
+    }
+    if key.len() > 256 || !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err("Invalid API key format".to_string());
     }
+    auth::storage::store_groq_api_key(key).map_err(|e| e.to_string())
 }
 
-/// Clear the workspace index
 #[tauri::command]
-fn clear_workspace_index(state: State<'_, AppState>) {
-    state.set_workspace_index(None);
-    log::info!("[WORKSPACE] Index cleared");
+fn clear_groq_api_key() -> Result<(), String> {
+    auth::storage::delete_groq_api_key().map_err(|e| e.to_string())
+}
+
+/// Whether a user-provided Groq API key is available (never returns the key itself)
+#[tauri::command]
+fn has_groq_api_key() -> bool {
+    signing::user_api_key().is_some()
+}
+
+/// Store the user's Deepgram API key (used when Deepgram is the transcription provider)
+#[tauri::command]
+fn set_deepgram_api_key(key: String) -> Result<(), String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("API key cannot be empty".to_string());
+    }
+    if key.len() > 256 || !key.chars().all(|c| c.is_ascii_graphic()) {
+        return Err("Invalid API key format".to_string());
+    }
+    auth::storage::store_deepgram_api_key(key).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+fn clear_deepgram_api_key() -> Result<(), String> {
+    auth::storage::delete_deepgram_api_key().map_err(|e| e.to_string())
+}
+
+/// Whether a Deepgram API key is available (never returns the key itself)
+#[tauri::command]
+fn has_deepgram_api_key() -> bool {
+    deepgram::api_key().is_some()
+}
+
+/// Remaining request budget per service, for "slow down" feedback
+#[tauri::command]
+fn get_rate_limit_status() -> Vec<rate_limit::RateLimitInfo> {
+    rate_limit::rate_limit_status()
+}
+
+/// Check that Groq (and Deepgram, if a key is set) are reachable and accept
+/// our credentials
+#[tauri::command]
+async fn check_provider_health() -> Result<health::ProviderHealth, String> {
+    health::check_providers().await
 }
 
 // ============================================================================
@@ -1068,320 +3775,273 @@ fn clear_remembered_email() -> Result<(), String> {
 #[serde(rename_all = "camelCase")]
 pub struct WorkspaceIndexStatus {
     pub indexed: bool,
-    pub root: Option<String>,
+    /// Per-root breakdown
+    pub roots: Vec<WorkspaceRootStatus>,
+    /// Files indexed across all roots
+    pub file_count: usize,
+    pub files_skipped: usize,
+}
+
+/// Index status for a single workspace root
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceRootStatus {
+    pub root: String,
     pub file_count: usize,
     pub files_skipped: usize,
 }
 
 /// Insert text directly at cursor position
-/// Uses AppleScript keystroke for ASCII, clipboard paste for Unicode
-fn insert_text_directly(text: &str) {
+/// Types ASCII text as keystrokes and pastes Unicode via the clipboard, unless
+/// `method` forces one of them.
+/// `pre_insert_delay_ms` gives slow target apps extra time before typing starts.
+/// `newline_mode` decides whether line breaks survive (typed as Return or pasted),
+/// and the `collapse_whitespace` preference whether runs of spaces do.
+/// Successful insertions are recorded so `undo_last_insertion` can remove them.
+/// `sensitive` text (see `redact::RedactMode::SkipEnhancement`) is never logged.
+/// Fails with the platform's reason if the text could not be inserted.
+fn insert_text_directly(
+    state: &AppState,
+    text: &str,
+    pre_insert_delay_ms: u64,
+    newline_mode: insertion::NewlineMode,
+    method: insertion::InsertionMethod,
+    bundle_id: Option<&str>,
+    sensitive: bool,
+) -> Result<(), String> {
     log::info!(
         "[INSERT] insert_text_directly called with {} chars",
         text.len()
     );
+
+    if pre_insert_delay_ms > 0 {
+        log::info!(
+            "[INSERT] Waiting {}ms before inserting",
+            pre_insert_delay_ms
+        );
+        std::thread::sleep(std::time::Duration::from_millis(pre_insert_delay_ms));
+    }
     sentry_breadcrumb(
         "insertion",
         &format!("Starting text insertion ({} chars)", text.len()),
     );
 
-    #[cfg(target_os = "macos")]
-    {
-        // Normalize newlines to spaces - pressing Enter in chat apps sends the message,
-        // which is not the intended behavior for dictation
-        let normalized = text
-            .replace("\r\n", " ")
-            .replace("\r", " ")
-            .replace("\n", " ");
-
-        // Collapse multiple spaces into one
-        let clean_text: String = normalized
-            .split_whitespace()
-            .collect::<Vec<&str>>()
-            .join(" ");
+    // Collapse whitespace (unless turned off), and newlines unless the mode keeps
+    // them for this app - pressing Enter in chat apps sends the message
+    let collapse_whitespace = state
+        .with_config(|cfg| cfg.collapse_whitespace)
+        .unwrap_or(true);
+    let clean_text =
+        insertion::normalize_for_insertion(text, newline_mode, bundle_id, collapse_whitespace);
+    let use_clipboard = method.uses_clipboard(&clean_text);
 
-        log::info!(
-            "[INSERT] Clean text ({} chars): {:?}",
-            clean_text.len(),
-            &clean_text[..clean_text.len().min(50)]
-        );
+    log::info!(
+        "[INSERT] Clean text ({} chars, {:?}, {:?} → {}): {:?}",
+        clean_text.len(),
+        newline_mode,
+        method,
+        if use_clipboard { "paste" } else { "keystroke" },
+        if sensitive {
+            "[REDACTED]".to_string()
+        } else {
+            clean_text.chars().take(50).collect::<String>()
+        }
+    );
 
-        // Check if text contains non-ASCII characters (Unicode)
-        let has_unicode = clean_text.chars().any(|c| !c.is_ascii());
+    // Check if text contains non-ASCII characters (Unicode)
+    let has_unicode = !clean_text.is_ascii();
 
+    if use_clipboard {
+        // For Unicode text (Hindi, Telugu, Tamil, etc.), use clipboard paste
+        // Keystroke insertion doesn't handle non-ASCII characters reliably
+        log::info!(
+            "[INSERT] Using clipboard paste ({})",
+            if has_unicode {
+                "Unicode detected"
+            } else {
+                "forced"
+            }
+        );
+        sentry_breadcrumb(
+            "insertion",
+            &format!(
+                "Using clipboard method ({} chars, {})",
+                clean_text.len(),
+                if has_unicode { "Unicode" } else { "ASCII" }
+            ),
+        );
+    } else {
+        // For ASCII-only text, use keystroke (faster, no clipboard impact)
         if has_unicode {
-            // For Unicode text (Hindi, Telugu, Tamil, etc.), use clipboard paste
-            // AppleScript's keystroke command doesn't handle non-ASCII characters
-            log::info!("[INSERT] Using clipboard paste (Unicode detected)");
-            sentry_breadcrumb(
-                "insertion",
-                &format!(
-                    "Using clipboard method ({} chars, Unicode)",
-                    clean_text.len()
-                ),
+            log::warn!(
+                "[INSERT] Keystroke insertion forced for non-ASCII text, some characters may not be typed"
             );
-            insert_via_clipboard_preserving(&clean_text);
         } else {
-            // For ASCII-only text, use keystroke (faster, no clipboard impact)
             log::info!("[INSERT] Using keystroke (ASCII only)");
-            sentry_breadcrumb(
-                "insertion",
-                &format!("Using keystroke method ({} chars, ASCII)", clean_text.len()),
-            );
-            insert_via_keystroke(&clean_text);
         }
-        sentry_breadcrumb("insertion", "Text insertion completed");
+        sentry_breadcrumb(
+            "insertion",
+            &format!(
+                "Using keystroke method ({} chars, {})",
+                clean_text.len(),
+                if has_unicode { "Unicode" } else { "ASCII" }
+            ),
+        );
     }
-}
 
-/// Escape text for safe inclusion in AppleScript double-quoted strings.
-/// Handles all characters that could break out of the string or cause injection.
-///
-/// SECURITY: This function prevents AppleScript injection by escaping:
-/// - Backslashes (must be first to avoid double-escaping)
-/// - Double quotes (could break out of string)
-/// - Ampersands (AppleScript concatenation operator - could inject code)
-/// - Carriage returns (removed)
-/// - Tabs (converted to AppleScript tab concatenation)
-#[cfg(target_os = "macos")]
-fn escape_applescript_string(text: &str) -> String {
-    text.replace("\\", "\\\\") // Backslash must be first
-        .replace("\"", "\\\"") // Double quotes
-        .replace("&", "\" & \"&\" & \"") // Escape ampersands to prevent injection
-        .replace("\r", "") // Remove carriage returns (handled separately)
-        .replace("\t", "\" & tab & \"") // Tabs as AppleScript concatenation
+    let inserted = platform::insertion::insert_text(&clean_text, use_clipboard);
+    sentry_breadcrumb("insertion", "Text insertion completed");
+    if inserted.is_ok() {
+        state.set_last_insertion(Some(LastInsertion {
+            char_count: clean_text.chars().count(),
+            text: clean_text,
+            pasted: use_clipboard,
+            inserted_at: Instant::now(),
+        }));
+    }
+    inserted
 }
 
-/// Maximum characters per keystroke chunk to prevent buffer issues
-/// Longer texts are split into chunks with small delays between them
-const KEYSTROKE_CHUNK_SIZE: usize = 500;
-
-/// Insert ASCII text using AppleScript keystroke (doesn't touch clipboard)
-/// For long texts, uses chunking with delays to prevent dropped characters.
-#[cfg(target_os = "macos")]
-fn insert_via_keystroke(text: &str) {
-    log::info!(
-        "[KEYSTROKE] Starting keystroke insertion for {} chars",
-        text.len()
-    );
-
-    // For longer texts, use chunked keystroke insertion to prevent buffer issues
-    if text.len() > KEYSTROKE_CHUNK_SIZE {
-        log::info!(
-            "[KEYSTROKE] Chunking text into {} char segments",
-            KEYSTROKE_CHUNK_SIZE
-        );
-        insert_via_keystroke_chunked(text);
+/// Insert text into the target app, or save it to the scratch buffer when there is
+/// no editable target: the scratch hotkey was used, the frontmost app isn't a text
+/// app, or insertion failed. If the dictation was the spoken undo command, the
+/// previous insertion is undone instead.
+fn insert_or_capture_scratch(
+    app: &AppHandle,
+    text: &str,
+    bundle_id: Option<&str>,
+    pre_insert_delay_ms: u64,
+) {
+    let state: tauri::State<'_, AppState> = app.state();
+    if state.take_undo_requested() {
+        state.take_scratch_capture();
+        if let Err(e) = undo_last_insertion_now(&state) {
+            log::warn!("[UNDO] {}", e);
+            emit_error(app, ErrorEvent::undo_failed(&e));
+        }
         return;
     }
 
-    // For short texts, use single keystroke command
-    let escaped_text = escape_applescript_string(text);
-    let script = format!(
-        r#"tell application "System Events"
-    keystroke "{}"
-end tell"#,
-        escaped_text
-    );
-
-    execute_keystroke_script(&script, text.len());
-}
-
-/// Insert text using chunked keystrokes with delays between chunks
-#[cfg(target_os = "macos")]
-fn insert_via_keystroke_chunked(text: &str) {
-    use std::process::Command;
-    use std::thread;
-    use std::time::Duration;
-
-    let chars: Vec<char> = text.chars().collect();
-    let total_chunks = (chars.len() + KEYSTROKE_CHUNK_SIZE - 1) / KEYSTROKE_CHUNK_SIZE;
-
-    log::info!(
-        "[KEYSTROKE] Inserting {} chars in {} chunks",
-        chars.len(),
-        total_chunks
-    );
-
-    for (i, chunk) in chars.chunks(KEYSTROKE_CHUNK_SIZE).enumerate() {
-        let chunk_text: String = chunk.iter().collect();
-        let escaped_text = escape_applescript_string(&chunk_text);
-
-        let script = format!(
-            r#"tell application "System Events"
-    keystroke "{}"
-end tell"#,
-            escaped_text
-        );
-
+    let clipboard_delivered = state.take_clipboard_delivered();
+    let sensitive = state.take_sensitive_transcript();
+    let to_scratch = if state.take_scratch_capture() {
+        log::info!("[SCRATCH] Scratch hotkey used, capturing to scratch buffer");
+        true
+    } else if clipboard_delivered {
+        log::info!("[INSERT] Output mode is clipboard, not typing the result");
+        false
+    } else if scratch::is_non_text_target(bundle_id) {
         log::info!(
-            "[KEYSTROKE] Sending chunk {}/{} ({} chars)",
-            i + 1,
-            total_chunks,
-            chunk_text.len()
+            "[SCRATCH] No editable target in {:?}, capturing to scratch buffer",
+            bundle_id
         );
-
-        let result = Command::new("osascript").arg("-e").arg(&script).output();
-
-        match result {
-            Ok(output) => {
-                let stderr = String::from_utf8_lossy(&output.stderr);
-                if !output.status.success() || !stderr.is_empty() {
-                    if stderr.contains("not allowed")
-                        || stderr.contains("assistive")
-                        || stderr.contains("1002")
-                    {
-                        log::error!("=======================================================");
-                        log::error!("ACCESSIBILITY PERMISSION REQUIRED");
-                        log::error!("Go to: System Settings > Privacy & Security > Accessibility");
-                        log::error!("Add Keyhold.app and ensure it's enabled");
-                        log::error!("Then QUIT and RELAUNCH the app");
-                        log::error!("=======================================================");
-                        sentry_capture_error(
-                            "Accessibility permission denied during keystroke insertion",
-                            None,
-                        );
-                        return; // Stop on permission error
-                    } else if !stderr.is_empty() {
-                        log::error!("[KEYSTROKE] Chunk {} error: {}", i + 1, stderr);
-                        sentry_capture_error(
-                            &format!("Keystroke chunk {} failed", i + 1),
-                            Some(&stderr),
-                        );
-                    }
-                }
+        true
+    } else if !permissions::check_accessibility_permission() {
+        // Revoked since startup (e.g. after a macOS update): typing would
+        // fail silently, so hand the text over instead of losing it
+        !handle_accessibility_lost(app, text)
+    } else {
+        match insert_text_directly(
+            &state,
+            text,
+            pre_insert_delay_ms,
+            state
+                .with_config(|cfg| cfg.newline_mode)
+                .unwrap_or_default(),
+            state
+                .with_config(|cfg| cfg.insertion_method)
+                .unwrap_or_default(),
+            bundle_id,
+            sensitive,
+        ) {
+            Ok(()) => false,
+            // Keystroke insertion is disabled on this machine - hand the text over
+            // via the clipboard instead, and only fall back to scratch if that fails
+            Err(_) if !permissions::is_osascript_available() => {
+                !copy_to_clipboard_fallback(app, text)
             }
             Err(e) => {
-                log::error!("[KEYSTROKE] Failed to execute chunk {}: {}", i + 1, e);
-                sentry_capture_error(
-                    &format!("Failed to execute osascript for chunk {}", i + 1),
-                    Some(&e.to_string()),
-                );
-                return; // Stop on error
+                // Tell the user why (e.g. no xdotool/wtype on Linux) instead of
+                // only logging it
+                log::warn!("[SCRATCH] Insertion failed, capturing to scratch buffer");
+                emit_error(app, ErrorEvent::insertion_failed(&e));
+                true
             }
         }
+    };
 
-        // Small delay between chunks to let the target app process
-        if i < total_chunks - 1 {
-            thread::sleep(Duration::from_millis(20));
+    if to_scratch {
+        match scratch::add_note(text, bundle_id) {
+            Ok(note) => {
+                if let Err(e) = app.emit("scratch-note-added", &note) {
+                    log::error!("Failed to emit scratch note: {}", e);
+                }
+            }
+            Err(e) => log::error!("[SCRATCH] Failed to save scratch note: {}", e),
         }
     }
-
-    log::info!(
-        "[KEYSTROKE] All {} chunks inserted successfully",
-        total_chunks
-    );
 }
 
-/// Execute a keystroke AppleScript and handle the result
-#[cfg(target_os = "macos")]
-fn execute_keystroke_script(script: &str, char_count: usize) {
-    use std::process::Command;
+/// Copy a finished dictation to the clipboard (`output_mode` clipboard/both).
+/// Returns false if the clipboard couldn't be written.
+fn copy_result_to_clipboard(app: &AppHandle, text: &str) -> bool {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
-    log::info!(
-        "[KEYSTROKE] Executing osascript for {} chars...",
-        char_count
-    );
-    let result = Command::new("osascript").arg("-e").arg(script).output();
+    match app.clipboard().write_text(text.to_string()) {
+        Ok(()) => {
+            log::info!("[OUTPUT] Copied result to clipboard");
+            true
+        }
+        Err(e) => {
+            log::error!("[OUTPUT] Failed to write clipboard: {}", e);
+            false
+        }
+    }
+}
 
-    match result {
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            log::info!(
-                "[KEYSTROKE] osascript exit code: {:?}",
-                output.status.code()
+/// Copy text to the clipboard when it can't be typed, and tell the user.
+/// Returns false if the clipboard couldn't be written either.
+fn copy_to_clipboard_fallback(app: &AppHandle, text: &str) -> bool {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    match app.clipboard().write_text(text.to_string()) {
+        Ok(()) => {
+            log::info!("[INSERT] Copied result to clipboard (osascript unavailable)");
+            emit_error(
+                app,
+                ErrorEvent::osascript_unavailable(Some(text.to_string())),
             );
-            if !stdout.is_empty() {
-                log::info!("[KEYSTROKE] osascript stdout: {}", stdout);
-            }
-            if output.status.success() && stderr.is_empty() {
-                log::info!("[KEYSTROKE] Text inserted via keystroke (clipboard untouched)");
-            } else if stderr.contains("not allowed")
-                || stderr.contains("assistive")
-                || stderr.contains("1002")
-            {
-                log::error!("=======================================================");
-                log::error!("ACCESSIBILITY PERMISSION REQUIRED");
-                log::error!("Go to: System Settings > Privacy & Security > Accessibility");
-                log::error!("Add Keyhold.app and ensure it's enabled");
-                log::error!("Then QUIT and RELAUNCH the app");
-                log::error!("=======================================================");
-            } else if !stderr.is_empty() {
-                log::error!("[KEYSTROKE] osascript stderr: {}", stderr);
-            }
+            true
         }
         Err(e) => {
-            log::error!("[KEYSTROKE] Failed to execute osascript: {}", e);
+            log::error!("[INSERT] Failed to write clipboard: {}", e);
+            false
         }
     }
 }
 
-/// Insert text via clipboard, preserving the user's original clipboard contents
-#[cfg(target_os = "macos")]
-fn insert_via_clipboard_preserving(text: &str) {
-    use std::process::Command;
-
-    // Escape text for AppleScript string using robust escaping
-    let escaped_text = escape_applescript_string(text);
-
-    // AppleScript that:
-    // 1. Saves current clipboard
-    // 2. Sets clipboard to our text
-    // 3. Pastes (Cmd+V)
-    // 4. Restores original clipboard after a brief delay
-    let script = format!(
-        r#"
-        -- Save original clipboard
-        set originalClipboard to the clipboard
-
-        -- Set clipboard to our text
-        set the clipboard to "{}"
-
-        -- Small delay to ensure clipboard is ready
-        delay 0.05
-
-        -- Paste using Cmd+V
-        tell application "System Events"
-            keystroke "v" using command down
-        end tell
-
-        -- Delay before restoring (give paste time to complete)
-        delay 0.15
-
-        -- Restore original clipboard
-        try
-            set the clipboard to originalClipboard
-        end try
-        "#,
-        escaped_text
-    );
-
-    let result = Command::new("osascript").arg("-e").arg(&script).output();
+/// Accessibility permission is gone at insertion time: copy the text to the
+/// clipboard, tell the user (with the text attached) and open the settings
+/// pane. Returns false if the clipboard couldn't be written.
+fn handle_accessibility_lost(app: &AppHandle, text: &str) -> bool {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
 
-    match result {
-        Ok(output) => {
-            let stderr = String::from_utf8_lossy(&output.stderr);
-            if output.status.success() && stderr.is_empty() {
-                log::info!("Unicode text inserted via clipboard (original clipboard restored)");
-            } else if stderr.contains("not allowed")
-                || stderr.contains("assistive")
-                || stderr.contains("1002")
-            {
-                log::error!("=======================================================");
-                log::error!("ACCESSIBILITY PERMISSION REQUIRED");
-                log::error!("Go to: System Settings > Privacy & Security > Accessibility");
-                log::error!("Add Keyhold.app and ensure it's enabled");
-                log::error!("Then QUIT and RELAUNCH the app");
-                log::error!("=======================================================");
-            } else if !stderr.is_empty() {
-                log::error!("osascript stderr: {}", stderr);
-            }
-        }
+    log::warn!("[INSERT] Accessibility permission not granted, not typing the result");
+    let copied = match app.clipboard().write_text(text.to_string()) {
+        Ok(()) => true,
         Err(e) => {
-            log::error!("Failed to execute osascript: {}", e);
+            log::error!("[INSERT] Failed to write clipboard: {}", e);
+            false
         }
+    };
+    emit_error(
+        app,
+        ErrorEvent::accessibility_lost(text.to_string(), copied),
+    );
+    if let Err(e) = permissions::open_accessibility_settings() {
+        log::warn!("[INSERT] {}", e);
     }
+    copied
 }
 
 fn hide_overlay(app: &AppHandle) {
@@ -1406,6 +4066,11 @@ fn activate_app_by_bundle_id(bundle_id: &str) {
         return;
     }
 
+    if !permissions::is_osascript_available() {
+        log::warn!("[ACTIVATE] osascript unavailable, skipping activation");
+        return;
+    }
+
     // Use AppleScript to activate the app - this only activates existing instances,
     // unlike `open -b` which can launch new ones
     let script = format!(
@@ -1439,28 +4104,122 @@ fn activate_app_by_bundle_id(_bundle_id: &str) {
     // No-op on non-macOS platforms
 }
 
-/// Position overlay window at center-bottom of the primary monitor with offset from bottom
+/// Monitor the user is working on: the one containing the mouse cursor, which
+/// tracks the active window far more often than the primary monitor does.
+/// Returns None if the cursor position or monitor list can't be read.
+fn monitor_for_active_window(overlay: &tauri::WebviewWindow) -> Option<tauri::Monitor> {
+    let cursor = overlay
+        .cursor_position()
+        .inspect_err(|e| log::warn!("[OVERLAY] Failed to query cursor position: {}", e))
+        .ok()?;
+    let monitors = overlay
+        .available_monitors()
+        .inspect_err(|e| log::warn!("[OVERLAY] Failed to list monitors: {}", e))
+        .ok()?;
+
+    monitors.into_iter().find(|monitor| {
+        let position = monitor.position();
+        let size = monitor.size();
+        monitor_contains_point(
+            (position.x, position.y),
+            (size.width, size.height),
+            (cursor.x, cursor.y),
+        )
+    })
+}
+
+/// Whether a point (physical pixels) lies within a monitor's bounds.
+fn monitor_contains_point(
+    monitor_position: (i32, i32),
+    monitor_size: (u32, u32),
+    point: (f64, f64),
+) -> bool {
+    let (left, top) = (monitor_position.0 as f64, monitor_position.1 as f64);
+    let right = left + monitor_size.0 as f64;
+    let bottom = top + monitor_size.1 as f64;
+    (left..right).contains(&point.0) && (top..bottom).contains(&point.1)
+}
+
+/// Position overlay window at center-bottom of the active monitor with offset from bottom
 fn position_overlay_center_bottom(overlay: &tauri::WebviewWindow, bottom_offset: i32) {
     use tauri::PhysicalPosition;
 
-    // Get the primary monitor
-    if let Ok(Some(monitor)) = overlay.primary_monitor() {
-        let screen_size = monitor.size();
-        let screen_position = monitor.position();
+    // Prefer the monitor with the cursor, then the primary monitor, then the
+    // first available one
+    let monitor = monitor_for_active_window(overlay).or_else(|| {
+        log::warn!("[OVERLAY] Active monitor unknown, falling back to primary monitor");
+        match overlay.primary_monitor() {
+            Ok(Some(monitor)) => Some(monitor),
+            result => {
+                if let Err(e) = result {
+                    log::warn!("[OVERLAY] Failed to query primary monitor: {}", e);
+                }
+                log::warn!("[OVERLAY] No primary monitor, falling back to first available monitor");
+                overlay
+                    .available_monitors()
+                    .ok()
+                    .and_then(|monitors| monitors.into_iter().next())
+            }
+        }
+    });
 
-        // Get overlay window size
-        if let Ok(overlay_size) = overlay.outer_size() {
-            // Calculate center-bottom position
-            let x =
-                screen_position.x + ((screen_size.width as i32 - overlay_size.width as i32) / 2);
-            let y = screen_position.y
-                + (screen_size.height as i32 - overlay_size.height as i32 - bottom_offset);
+    let overlay_size = match overlay.outer_size() {
+        Ok(size) => (size.width, size.height),
+        Err(e) => {
+            log::warn!(
+                "[OVERLAY] Failed to query overlay size: {}, using default",
+                e
+            );
+            (OVERLAY_DEFAULT_WIDTH, OVERLAY_DEFAULT_HEIGHT)
+        }
+    };
 
-            let _ = overlay.set_position(PhysicalPosition::new(x, y));
+    let (x, y) = match monitor {
+        Some(monitor) => {
+            let position = monitor.position();
+            let size = monitor.size();
+            calculate_overlay_position(
+                (position.x, position.y),
+                (size.width, size.height),
+                overlay_size,
+                bottom_offset,
+            )
         }
+        None => {
+            log::warn!("[OVERLAY] No monitors available, using fallback position");
+            OVERLAY_FALLBACK_POSITION
+        }
+    };
+
+    if let Err(e) = overlay.set_position(PhysicalPosition::new(x, y)) {
+        log::warn!("[OVERLAY] Failed to set overlay position: {}", e);
     }
 }
 
+/// Calculate the center-bottom overlay position within a monitor's bounds.
+/// The result is clamped so the overlay always stays fully on that monitor
+/// (or pinned to its top-left corner if the overlay is larger than the monitor).
+fn calculate_overlay_position(
+    monitor_position: (i32, i32),
+    monitor_size: (u32, u32),
+    overlay_size: (u32, u32),
+    bottom_offset: i32,
+) -> (i32, i32) {
+    let (monitor_x, monitor_y) = monitor_position;
+    let monitor_width = monitor_size.0 as i32;
+    let monitor_height = monitor_size.1 as i32;
+    let overlay_width = overlay_size.0 as i32;
+    let overlay_height = overlay_size.1 as i32;
+
+    let x = monitor_x + (monitor_width - overlay_width) / 2;
+    let y = monitor_y + (monitor_height - overlay_height - bottom_offset);
+
+    let max_x = (monitor_x + monitor_width - overlay_width).max(monitor_x);
+    let max_y = (monitor_y + monitor_height - overlay_height).max(monitor_y);
+
+    (x.clamp(monitor_x, max_x), y.clamp(monitor_y, max_y))
+}
+
 // ============================================================================
 // TRAY AND SETUP
 // ============================================================================
@@ -1605,17 +4364,47 @@ fn get_key_code(key: &str) -> Option<tauri_plugin_global_shortcut::Code> {
     }
 }
 
+/// Why a hotkey string couldn't be turned into a Shortcut
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum HotkeyParseError {
+    /// Nothing was entered
+    Empty,
+    /// A part is neither a modifier nor a supported key
+    UnknownKey(String),
+    /// Only modifiers were given
+    NoKey,
+    /// Fn can't be used in global shortcuts
+    FnUnsupported,
+}
+
+impl std::fmt::Display for HotkeyParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            HotkeyParseError::Empty => write!(f, "Enter a hotkey, e.g. Option+Space"),
+            HotkeyParseError::UnknownKey(key) => write!(f, "Unknown key: \"{}\"", key),
+            HotkeyParseError::NoKey => write!(
+                f,
+                "A hotkey needs a key besides the modifiers (e.g. Space or D)"
+            ),
+            HotkeyParseError::FnUnsupported => {
+                write!(f, "The Fn key can't be used in global shortcuts")
+            }
+        }
+    }
+}
+
+impl std::error::Error for HotkeyParseError {}
+
 /// Parse a hotkey string like "Cmd+Shift+D" or "Option+Space" into a Shortcut
-fn parse_hotkey(hotkey: &str) -> Option<Shortcut> {
-    let parts: Vec<&str> = hotkey.split('+').map(|s| s.trim()).collect();
-    if parts.is_empty() {
-        return None;
+fn parse_hotkey(hotkey: &str) -> Result<Shortcut, HotkeyParseError> {
+    if hotkey.trim().is_empty() {
+        return Err(HotkeyParseError::Empty);
     }
 
     let mut modifiers = Modifiers::empty();
     let mut key_code = None;
 
-    for part in parts {
+    for part in hotkey.split('+').map(|s| s.trim()) {
         let lower = part.to_lowercase();
         match lower.as_str() {
             // Modifiers
@@ -1623,23 +4412,18 @@ fn parse_hotkey(hotkey: &str) -> Option<Shortcut> {
             "ctrl" | "control" => modifiers |= Modifiers::CONTROL,
             "alt" | "option" => modifiers |= Modifiers::ALT,
             "shift" => modifiers |= Modifiers::SHIFT,
-            "fn" => {
-                #[cfg(debug_assertions)]
-                log::info!("Warning: Fn key is not supported in global shortcuts, ignoring");
-            }
+            "fn" => return Err(HotkeyParseError::FnUnsupported),
             // Key codes - use lookup function
-            _ => {
-                if let Some(code) = get_key_code(&lower) {
-                    key_code = Some(code);
-                } else {
-                    #[cfg(debug_assertions)]
-                    log::info!("Unknown key: {}", part);
-                }
-            }
+            _ => match get_key_code(&lower) {
+                Some(code) => key_code = Some(code),
+                None => return Err(HotkeyParseError::UnknownKey(part.to_string())),
+            },
         }
     }
 
-    key_code.map(|code| Shortcut::new(Some(modifiers), code))
+    key_code
+        .map(|code| Shortcut::new(Some(modifiers), code))
+        .ok_or(HotkeyParseError::NoKey)
 }
 
 /// Internal function to start recording from shortcut
@@ -1658,12 +4442,7 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
     }
 
     // Fast in-memory auth check (no disk I/O - just atomic bool read)
-    if !auth::is_authenticated_fast() {
-        log::info!("[HOTKEY] Cannot start recording: user not authenticated");
-        if let Some(login) = app_handle.get_webview_window("login") {
-            let _ = login.show();
-            let _ = login.set_focus();
-        }
+    if !check_authenticated(app_handle) {
         return;
     }
 
@@ -1674,6 +4453,10 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
         return;
     }
 
+    if check_api_key_available(app_handle, &state).is_err() {
+        return;
+    }
+
     // =========================================================================
     // INSTANT OVERLAY ARCHITECTURE
     // =========================================================================
@@ -1691,7 +4474,7 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
 
     // 1. Capture active app (fast: ~10-20ms via lsappinfo)
     let active_app_before_overlay = styles::detection::get_active_app();
-    log::info!(
+    log::debug!(
         "[TIMING] get_active_app: {:?} - bundle: {:?}",
         hotkey_start.elapsed(),
         active_app_before_overlay.as_ref().map(|a| &a.bundle_id)
@@ -1705,6 +4488,7 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
     state.set_state(RecordingState::Recording);
     state.set_mode(DictationMode::Dictation);
     state.set_recording_start(Some(std::time::Instant::now()));
+    let session = state.begin_recording_session();
 
     // Show overlay IMMEDIATELY - no blocking operations before this
     if let Some(overlay) = app_handle.get_webview_window("overlay") {
@@ -1712,7 +4496,7 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
         let _ = overlay.show();
     }
     #[cfg(debug_assertions)]
-    log::info!("[TIMING] Hotkey-to-overlay: {:?}", hotkey_start.elapsed());
+    log::debug!("[TIMING] Hotkey-to-overlay: {:?}", hotkey_start.elapsed());
 
     // Emit initial state (Dictation mode)
     emit_state_change(app_handle, &state, Some("Recording...".to_string()));
@@ -1723,40 +4507,58 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
     // Selection detection can take 500ms-1s+ but user speaks for 1-5+ seconds
     // So selection will be ready before recording stops
 
-    let app_handle_for_selection = app_handle.clone();
-    std::thread::spawn(move || {
-        let state: tauri::State<'_, AppState> = app_handle_for_selection.state();
-
-        // Only proceed if we're still recording
-        if state.get_state() != RecordingState::Recording {
-            return;
-        }
+    // Apps the user excluded from Command Mode skip the Accessibility call
+    let command_mode_excluded = state
+        .with_config(|cfg| {
+            cfg.command_mode_excluded_for_app(
+                active_app_before_overlay
+                    .as_ref()
+                    .map(|a| a.bundle_id.as_str()),
+            )
+        })
+        .unwrap_or(false);
+    if command_mode_excluded {
+        log::debug!(
+            "[MODE] Selection detection skipped for {:?}",
+            active_app_before_overlay.as_ref().map(|a| &a.bundle_id)
+        );
+    } else {
+        let app_handle_for_selection = app_handle.clone();
+        std::thread::spawn(move || {
+            let state: tauri::State<'_, AppState> = app_handle_for_selection.state();
 
-        let selection = platform::selection::get_selected_text().ok();
-        #[cfg(debug_assertions)]
-        {
-            let _timing_chars = selection.as_ref().map(|s| s.len()).unwrap_or(0);
-            log::info!(
-                "[TIMING] get_selected_text (async): {} chars",
-                _timing_chars
-            );
-        }
+            // Only proceed if we're still recording
+            if state.get_state() != RecordingState::Recording {
+                return;
+            }
 
-        if let Some(text) = selection {
-            // Check if still recording before switching mode
-            if state.get_state() == RecordingState::Recording {
-                state.set_mode(DictationMode::Command);
-                state.set_selected_text(Some(text));
-                emit_state_change(
-                    &app_handle_for_selection,
-                    &state,
-                    Some("Command Mode".to_string()),
+            let selection = platform::selection::get_selected_text().ok();
+            #[cfg(debug_assertions)]
+            {
+                let _timing_chars = selection.as_ref().map(|s| s.len()).unwrap_or(0);
+                log::debug!(
+                    "[TIMING] get_selected_text (async): {} chars",
+                    _timing_chars
                 );
-                #[cfg(debug_assertions)]
-                log::info!("[MODE] Switched to Command Mode");
             }
-        }
-    });
+
+            if let Some(text) = selection {
+                // Only switch if this recording hasn't been stopped in the meantime
+                if state.apply_selection(session, text) {
+                    emit_state_change(
+                        &app_handle_for_selection,
+                        &state,
+                        Some("Command Mode".to_string()),
+                    );
+                    #[cfg(debug_assertions)]
+                    log::info!("[MODE] Switched to Command Mode");
+                }
+            }
+        });
+    }
+
+    // A new recording replaces whatever was kept for re-transcription
+    state.set_last_audio(None);
 
     // Start audio capture IMMEDIATELY
     let result = {
@@ -1768,7 +4570,25 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
                 return;
             }
         };
-        let selected_mic = permissions::get_selected_microphone_name();
+        let selected_mic = permissions::get_configured_microphone_name();
+        if let Ok((timeout, threshold, quality, mono_channel, meter, gain_db)) =
+            state.with_config(|cfg| {
+                (
+                    cfg.silence_timeout_ms,
+                    cfg.silence_threshold_db,
+                    cfg.resample_quality,
+                    cfg.mono_channel,
+                    cfg.meter_settings(),
+                    cfg.input_gain_db,
+                )
+            })
+        {
+            recorder.set_silence_timeout(timeout, threshold);
+            recorder.set_resample_quality(quality);
+            recorder.set_mono_channel(mono_channel);
+            recorder.set_meter_settings(meter);
+            recorder.set_input_gain(gain_db);
+        }
         recorder.start_recording_with_device(app_handle.clone(), selected_mic)
     };
 
@@ -1778,6 +4598,8 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
         log::info!("Failed to start recording: {}", e);
         return;
     }
+    start_recording_watchdog(app_handle);
+    play_cue(app_handle, audio_cues::Cue::Start);
 
     // =========================================================================
     // PHASE 2: ASYNC CONTEXT PROCESSING (happens while user speaks)
@@ -1801,7 +4623,14 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
 
         // Get style for the active app
         let active_style = match active_app_captured {
-            Some(ref app) => styles::get_style_for_app(app),
+            Some(ref app) => {
+                let (app_styles, inference_excluded) = state
+                    .with_config(|cfg| {
+                        (cfg.app_styles.clone(), cfg.style_inference_excluded.clone())
+                    })
+                    .unwrap_or_default();
+                styles::get_style_for_app(app, &app_styles, &inference_excluded)
+            }
             None => styles::get_default_style(),
         };
 
@@ -1820,6 +4649,19 @@ fn shortcut_start_recording(app_handle: &AppHandle) {
         // Store context in state for use during transcription processing
         state.set_active_style(Some(active_style));
         state.set_active_bundle_id(bundle_id);
+
+        // Show the forced style in the overlay (Command Mode has its own message)
+        if let Some(forced) = state.get_forced_style() {
+            if state.get_state() == RecordingState::Recording
+                && state.get_mode() == DictationMode::Dictation
+            {
+                emit_state_change(
+                    &app_handle_for_context,
+                    &state,
+                    Some(format!("Style: {}", forced.name)),
+                );
+            }
+        }
     });
 }
 
@@ -1845,6 +4687,19 @@ fn shortcut_stop_recording(app_handle: AppHandle) {
         // Capture the bundle_id BEFORE processing clears it
         let bundle_id = state.get_active_bundle_id();
         log::info!("[STOP] Captured bundle_id: {:?}", bundle_id);
+        let (pre_insert_delay_ms, done_display_ms, app_focus_wait_ms) = state
+            .with_config(|cfg| {
+                (
+                    cfg.pre_insert_delay_for_app(bundle_id.as_deref()),
+                    cfg.done_display_ms,
+                    cfg.app_focus_wait_ms,
+                )
+            })
+            .unwrap_or((
+                0,
+                config::DEFAULT_DONE_DISPLAY_MS,
+                config::DEFAULT_APP_FOCUS_WAIT_MS,
+            ));
 
         // Use shared processing logic
         match process_recording_stop(&app_handle_clone, &state).await {
@@ -1864,7 +4719,7 @@ fn shortcut_stop_recording(app_handle: AppHandle) {
                     }
 
                     // Brief delay to show "Done!" state
-                    std::thread::sleep(std::time::Duration::from_millis(DONE_DISPLAY_DELAY_MS));
+                    std::thread::sleep(std::time::Duration::from_millis(done_display_ms));
                     log::info!("[STOP] Hiding overlay");
                     hide_overlay(&app_for_hide);
                     // Reactivate the previous app explicitly
@@ -1875,10 +4730,16 @@ fn shortcut_stop_recording(app_handle: AppHandle) {
                         log::info!("[STOP] No bundle_id to reactivate");
                     }
                     // Wait for the app to regain focus
-                    std::thread::sleep(std::time::Duration::from_millis(APP_FOCUS_WAIT_MS));
+                    std::thread::sleep(std::time::Duration::from_millis(app_focus_wait_ms));
                     log::info!("[STOP] Calling insert_text_directly");
                     // Insert text (this replaces selection in Command Mode, inserts at cursor in Dictation Mode)
-                    insert_text_directly(&final_text);
+                    let insert_start = Instant::now();
+                    insert_or_capture_scratch(
+                        &app_for_hide,
+                        &final_text,
+                        bundle_id.as_deref(),
+                        pre_insert_delay_ms,
+                    );
                     log::info!("[STOP] insert_text_directly completed");
 
                     // Clear inserting flag
@@ -1886,6 +4747,9 @@ fn shortcut_stop_recording(app_handle: AppHandle) {
                         let state: tauri::State<'_, AppState> = app_for_hide.state();
                         state.set_inserting(false);
                         log::info!("[STOP] Set is_inserting=false");
+                        if state.with_config(|cfg| cfg.verbose_timing).unwrap_or(false) {
+                            log::debug!("[TIMING] insert {}ms", insert_start.elapsed().as_millis());
+                        }
                     }
                 });
             }
@@ -1897,6 +4761,54 @@ fn shortcut_stop_recording(app_handle: AppHandle) {
     });
 }
 
+/// Stop the recording once `max_recording_duration` has passed and process what
+/// was captured. Stopping or cancelling disarms the timer, so one left over
+/// from an earlier recording never stops a new one.
+fn start_recording_watchdog(app_handle: &AppHandle) {
+    let state: tauri::State<'_, AppState> = app_handle.state();
+    let max_secs = state
+        .with_config(|cfg| cfg.max_recording_duration)
+        .unwrap_or(config::DEFAULT_MAX_RECORDING_DURATION_SECS);
+    let token = state.arm_recording_watchdog();
+
+    let app_handle = app_handle.clone();
+    tauri::async_runtime::spawn(async move {
+        let max_duration = std::time::Duration::from_secs(max_secs.into());
+        // Paused time doesn't count, so wait until the recording itself is long enough
+        loop {
+            let state: tauri::State<'_, AppState> = app_handle.state();
+            let recorded =
+                std::time::Duration::from_millis(state.get_recording_duration_ms().unwrap_or(0));
+            let wait = if state.get_state() == RecordingState::Paused {
+                WATCHDOG_PAUSED_POLL
+            } else {
+                max_duration.saturating_sub(recorded)
+            };
+            if wait.is_zero() {
+                break;
+            }
+            tokio::select! {
+                _ = token.cancelled() => return,
+                _ = tokio::time::sleep(wait) => {}
+            }
+        }
+
+        let state: tauri::State<'_, AppState> = app_handle.state();
+        if !token.is_cancelled() && state.get_state() == RecordingState::Recording {
+            log::warn!(
+                "[WATCHDOG] Max recording duration ({}s) reached, stopping",
+                max_secs
+            );
+            emit_state_change(
+                &app_handle,
+                &state,
+                Some("Max duration reached".to_string()),
+            );
+            shortcut_stop_recording(app_handle.clone());
+        }
+    });
+}
+
 /// Internal function to toggle recording from shortcut
 fn shortcut_toggle_recording(app_handle: &AppHandle) {
     let state: tauri::State<'_, AppState> = app_handle.state();
@@ -1906,7 +4818,7 @@ fn shortcut_toggle_recording(app_handle: &AppHandle) {
         RecordingState::Idle | RecordingState::Error => {
             shortcut_start_recording(app_handle);
         }
-        RecordingState::Recording => {
+        RecordingState::Recording | RecordingState::Paused => {
             shortcut_stop_recording(app_handle.clone());
         }
         _ => {
@@ -1919,6 +4831,9 @@ fn setup_global_shortcuts(
     app: &App,
     hotkey: &str,
     _mode: &str, // Not used anymore - we read dynamically from config
+    scratch_hotkey: Option<&str>,
+    reinsert_hotkey: Option<&str>,
+    raw_hotkey: Option<&str>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     log::info!("=======================================================");
     log::info!("[STARTUP] Setting up global shortcuts");
@@ -1926,15 +4841,16 @@ fn setup_global_shortcuts(
 
     // Parse the hotkey string
     let shortcut = match parse_hotkey(hotkey) {
-        Some(s) => {
+        Ok(s) => {
             log::info!("[STARTUP] Parsed hotkey successfully: {:?}", s);
             s
         }
-        None => {
+        Err(e) => {
             // Default to Option+Space if parsing fails
-            log::info!(
-                "[STARTUP] WARNING: Failed to parse hotkey '{}', using default Option+Space",
-                hotkey
+            log::warn!(
+                "[STARTUP] Failed to parse hotkey '{}' ({}), using default Option+Space",
+                hotkey,
+                e
             );
             Shortcut::new(
                 Some(Modifiers::ALT),
@@ -1945,6 +4861,45 @@ fn setup_global_shortcuts(
 
     log::info!("[STARTUP] Registering global shortcut: {:?}", shortcut);
 
+    // Optional dedicated hotkey that sends the dictation to the scratch buffer
+    let scratch_shortcut = scratch_hotkey.and_then(|h| {
+        parse_hotkey(h)
+            .map_err(|e| {
+                log::warn!(
+                    "[STARTUP] Failed to parse scratch hotkey '{}' ({}), ignoring",
+                    h,
+                    e
+                )
+            })
+            .ok()
+    });
+
+    // Optional hotkey that types the last dictation from history again
+    let reinsert_shortcut = reinsert_hotkey.and_then(|h| {
+        parse_hotkey(h)
+            .map_err(|e| {
+                log::warn!(
+                    "[STARTUP] Failed to parse re-insert hotkey '{}' ({}), ignoring",
+                    h,
+                    e
+                )
+            })
+            .ok()
+    });
+
+    // Optional second recording hotkey that skips LLM enhancement
+    let raw_shortcut = raw_hotkey.and_then(|h| {
+        parse_hotkey(h)
+            .map_err(|e| {
+                log::warn!(
+                    "[STARTUP] Failed to parse raw hotkey '{}' ({}), ignoring",
+                    h,
+                    e
+                )
+            })
+            .ok()
+    });
+
     // Register the shortcut with key state handling
     // IMPORTANT: Read recording_mode dynamically from config each time,
     // so changes in preferences take effect immediately
@@ -1957,6 +4912,36 @@ fn setup_global_shortcuts(
                     event.state
                 );
 
+                // Re-insert on release so the hotkey's modifiers don't alter the typed text
+                if reinsert_shortcut == Some(*shortcut) {
+                    if event.state == ShortcutState::Released {
+                        let app = app.clone();
+                        std::thread::spawn(move || {
+                            if let Err(e) = reinsert_last_entry(&app) {
+                                log::warn!("[REINSERT] {}", e);
+                            }
+                        });
+                    }
+                    return;
+                }
+
+                // While a recording runs, only the hotkey that started it controls it:
+                // releasing or pressing the other one (main vs raw) must not stop it
+                {
+                    let state: tauri::State<'_, AppState> = app.state();
+                    if !state.get_state().can_start_recording()
+                        && state
+                            .get_session_shortcut()
+                            .is_some_and(|started_by| started_by != *shortcut)
+                    {
+                        log::info!(
+                            "[HOTKEY] Ignoring {:?}: the recording was started by another hotkey",
+                            shortcut
+                        );
+                        return;
+                    }
+                }
+
                 // Read current mode from config (not captured at startup)
                 let is_push_to_talk = {
                     let state: tauri::State<'_, AppState> = app.state();
@@ -1969,6 +4954,15 @@ fn setup_global_shortcuts(
 
                 match event.state {
                     ShortcutState::Pressed => {
+                        // Remember which hotkey started this recording
+                        {
+                            let state: tauri::State<'_, AppState> = app.state();
+                            if state.get_state().can_start_recording() {
+                                state.set_session_shortcut(Some(*shortcut));
+                                state.set_scratch_capture(scratch_shortcut == Some(*shortcut));
+                                state.set_raw_session(state.get_raw_shortcut() == Some(*shortcut));
+                            }
+                        }
                         log::info!(
                             "[HOTKEY] Shortcut PRESSED, mode: {}",
                             if is_push_to_talk {
@@ -2014,6 +5008,7 @@ fn setup_global_shortcuts(
     match app.global_shortcut().register(shortcut) {
         Ok(_) => {
             log::info!("[STARTUP] ✓ Global shortcut registered successfully!");
+            app.state::<AppState>().set_main_shortcut(Some(shortcut));
             log::info!("[STARTUP] Press '{}' to start/stop recording", hotkey);
             log::info!("=======================================================");
         }
@@ -2029,6 +5024,36 @@ fn setup_global_shortcuts(
         }
     }
 
+    if let Some(scratch_shortcut) = scratch_shortcut {
+        match app.global_shortcut().register(scratch_shortcut) {
+            Ok(_) => log::info!(
+                "[STARTUP] ✓ Scratch hotkey registered: {:?}",
+                scratch_shortcut
+            ),
+            Err(e) => log::error!("[STARTUP] ✗ Failed to register scratch hotkey: {}", e),
+        }
+    }
+
+    if let Some(reinsert_shortcut) = reinsert_shortcut {
+        match app.global_shortcut().register(reinsert_shortcut) {
+            Ok(_) => log::info!(
+                "[STARTUP] ✓ Re-insert hotkey registered: {:?}",
+                reinsert_shortcut
+            ),
+            Err(e) => log::error!("[STARTUP] ✗ Failed to register re-insert hotkey: {}", e),
+        }
+    }
+
+    if let Some(raw_shortcut) = raw_shortcut {
+        match app.global_shortcut().register(raw_shortcut) {
+            Ok(_) => {
+                log::info!("[STARTUP] ✓ Raw hotkey registered: {:?}", raw_shortcut);
+                app.state::<AppState>().set_raw_shortcut(Some(raw_shortcut));
+            }
+            Err(e) => log::error!("[STARTUP] ✗ Failed to register raw hotkey: {}", e),
+        }
+    }
+
     Ok(())
 }
 
@@ -2058,6 +5083,10 @@ pub fn run() {
     let config = AppConfig::load();
     let initial_hotkey = config.hotkey.clone();
     let initial_mode = config.recording_mode.clone();
+    let scratch_hotkey = config.scratch_hotkey.clone();
+    let reinsert_hotkey = config.reinsert_hotkey.clone();
+    let raw_hotkey = config.raw_hotkey.clone();
+    http_client::set_request_timeout(config.network_timeout_ms);
 
     log::info!("[STARTUP] Configuration loaded:");
     log::info!("[STARTUP]   Hotkey: '{}'", initial_hotkey);
@@ -2067,53 +5096,59 @@ pub fn run() {
         config.groq_api_key.is_some()
     );
 
+    let log_builder = tauri_plugin_log::Builder::new()
+        .targets([
+            // Log to file in ~/Library/Logs/com.keyhold.app/
+            tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
+                file_name: Some("keyhold".into()),
+            }),
+            // Also log to stdout in debug builds
+            #[cfg(debug_assertions)]
+            tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
+        ])
+        .max_file_size(5_000_000) // 5MB max per log file
+        .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
+        .level(log::LevelFilter::Info);
+    // Module paths start with the lib name; debug covers [TIMING] lines. Debug
+    // lines can carry tokens and dictated text, so they never reach release log files.
+    #[cfg(debug_assertions)]
+    let log_builder = log_builder.level_for("keyhold_lib", log::LevelFilter::Debug);
+
     tauri::Builder::default()
         .plugin(tauri_plugin_sentry::init(&sentry_client))
-        .plugin(
-            tauri_plugin_log::Builder::new()
-                .targets([
-                    // Log to file in ~/Library/Logs/com.keyhold.app/
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::LogDir {
-                        file_name: Some("keyhold".into()),
-                    }),
-                    // Also log to stdout in debug builds
-                    #[cfg(debug_assertions)]
-                    tauri_plugin_log::Target::new(tauri_plugin_log::TargetKind::Stdout),
-                ])
-                .max_file_size(5_000_000) // 5MB max per log file
-                .rotation_strategy(tauri_plugin_log::RotationStrategy::KeepAll)
-                .level(log::LevelFilter::Info)
-                .level_for("keyhold", log::LevelFilter::Debug)
-                .build(),
-        )
+        .plugin(log_builder.build())
         .plugin(tauri_plugin_updater::Builder::new().build())
         .plugin(tauri_plugin_single_instance::init(|app, args, _cwd| {
             // Another instance tried to launch - check if it's a deep link callback
             log::info!("Another instance attempted to start, args: {:?}", args);
 
             // Check if any argument is a deep link URL (OAuth callback)
-            for arg in &args {
-                if arg.starts_with("keyhold://auth/callback") {
-                    log::info!("[AUTH] Deep link callback from second instance: {}", arg);
-                    let app_clone = app.clone();
-                    let url = arg.clone();
-                    tauri::async_runtime::spawn(async move {
-                        match auth::handle_callback(&app_clone, &url).await {
-                            Ok(()) => log::info!("[AUTH] OAuth callback handled successfully"),
-                            Err(e) => {
-                                log::error!("[AUTH] OAuth callback failed: {}", e);
-                                sentry_capture_error(&format!("OAuth callback failed: {}", e), None);
-                            }
-                        }
-                    });
-                    return; // Don't show window, auth flow will handle it
-                }
+            if let Some(url) = args.iter().find(|arg| is_auth_callback(arg)) {
+                log::info!("[AUTH] Deep link callback from second instance");
+                handle_auth_callback(app, url.clone());
+                return; // Don't show window, auth flow will handle it
             }
 
-            // Regular second instance - just focus window
-            if let Some(window) = app.get_webview_window("main") {
+            // Regular second instance - focus the window that matches the current setup state
+            let label = if !auth::is_authenticated_fast() {
+                "login"
+            } else if !permissions::is_onboarding_complete() {
+                "onboarding"
+            } else {
+                "main"
+            };
+            log::info!("[STARTUP] Second instance, surfacing '{}' window", label);
+            if let Some(window) = app.get_webview_window(label) {
                 let _ = window.show();
                 let _ = window.set_focus();
+                // Login and onboarding need a Dock presence, same as on startup
+                #[cfg(target_os = "macos")]
+                if label != "main" {
+                    let _ = app.set_activation_policy(tauri::ActivationPolicy::Regular);
+                }
+                if label == "onboarding" {
+                    let _ = app.emit("start-onboarding", ());
+                }
             }
         }))
         .plugin(tauri_plugin_opener::init())
@@ -2127,25 +5162,80 @@ pub fn run() {
             start_recording,
             stop_recording,
             cancel_recording,
+            pause_recording,
+            resume_recording,
+            process_audio_dry_run,
             toggle_recording,
+            transform_clipboard,
+            reenhance_last,
+            retranscribe_last,
+            play_last_recording,
             show_preferences,
+            show_preferences_tab,
             update_preferences,
             get_preferences,
+            validate_hotkey,
             check_permissions,
+            recheck_permissions,
+            export_diagnostics,
+            start_permission_watcher,
+            stop_permission_watcher,
             get_microphones,
             request_microphone_permission,
             open_accessibility_settings,
             open_microphone_settings,
             request_accessibility_permission,
             set_selected_microphone,
+            test_microphone_level,
             is_onboarding_complete,
             needs_reauthorization,
             complete_onboarding,
             restart_app,
             // Workspace index commands
-            set_workspace_root,
+            add_workspace_root,
+            remove_workspace_root,
             get_workspace_status,
             clear_workspace_index,
+            preview_ide_transform,
+            // Replacement (autocorrect) commands
+            get_replacements,
+            add_replacement,
+            update_replacement,
+            remove_replacement,
+            get_custom_dictionary,
+            add_dictionary_entry,
+            remove_dictionary_entry,
+            // File transcription
+            transcribe_file,
+            // Style commands
+            list_styles,
+            create_style,
+            update_style,
+            delete_style,
+            get_active_style_for_current_app,
+            set_forced_style,
+            get_app_style_overrides,
+            set_app_style_override,
+            clear_app_style_override,
+            reset_prompt_template,
+            // Scratch buffer commands
+            get_scratch_notes,
+            delete_scratch_note,
+            clear_scratch_notes,
+            get_history,
+            delete_history_entry,
+            clear_history,
+            reinsert_last,
+            undo_last_insertion,
+            // API key commands
+            set_groq_api_key,
+            clear_groq_api_key,
+            has_groq_api_key,
+            set_deepgram_api_key,
+            clear_deepgram_api_key,
+            has_deepgram_api_key,
+            check_provider_health,
+            get_rate_limit_status,
             // Authentication commands
             get_auth_state,
             start_auth,
@@ -2165,40 +5255,88 @@ pub fn run() {
             setup_system_tray(app)?;
 
             // Register global shortcut
-            setup_global_shortcuts(app, &initial_hotkey, &initial_mode)?;
+            setup_global_shortcuts(
+                app,
+                &initial_hotkey,
+                &initial_mode,
+                scratch_hotkey.as_deref(),
+                reinsert_hotkey.as_deref(),
+                raw_hotkey.as_deref(),
+            )?;
+
+            // Voice-activity auto-stop: the audio meter emits this after trailing silence
+            let app_handle_for_auto_stop = app.handle().clone();
+            app.listen("auto-stop", move |_| {
+                let state: tauri::State<'_, AppState> = app_handle_for_auto_stop.state();
+                if state.get_state() == RecordingState::Recording {
+                    log::info!("[AUTO_STOP] Trailing silence detected, stopping recording");
+                    shortcut_stop_recording(app_handle_for_auto_stop.clone());
+                }
+            });
+
+            // The microphone disconnected mid-recording and no fallback worked
+            let app_handle_for_device_lost = app.handle().clone();
+            app.listen("audio-device-lost", move |_| {
+                handle_audio_device_lost(&app_handle_for_device_lost);
+            });
 
             // Set up deep-link handler for OAuth callbacks
+            // (Windows and Linux register the URL scheme at runtime)
+            #[cfg(any(windows, target_os = "linux"))]
+            if let Err(e) = app.deep_link().register_all() {
+                log::warn!("[AUTH] Failed to register deep link schemes: {}", e);
+            }
             let app_handle = app.handle().clone();
             app.listen("deep-link://new-url", move |event| {
-                let payload = event.payload();
-                log::info!("[AUTH] Received deep link payload: {}", payload);
-
-                // Parse the JSON array payload to extract the URL
                 // The payload comes as: ["keyhold://auth/callback?code=xxx&state=yyy"]
-                let url: Option<String> = serde_json::from_str::<Vec<String>>(payload)
-                    .ok()
-                    .and_then(|urls| urls.into_iter().next());
-
-                if let Some(url) = url {
-                    log::info!("[AUTH] Extracted URL: {}", url);
-                    if url.starts_with("keyhold://auth/callback") {
-                        let app = app_handle.clone();
-                        tauri::async_runtime::spawn(async move {
-                            match auth::handle_callback(&app, &url).await {
-                                Ok(()) => log::info!("[AUTH] OAuth callback handled successfully"),
-                                Err(e) => {
-                                    log::error!("[AUTH] OAuth callback failed: {}", e);
-                                    sentry_capture_error(&format!("OAuth callback failed: {}", e), None);
-                                }
-                            }
-                        });
-                    }
-                } else {
+                let Ok(urls) = serde_json::from_str::<Vec<String>>(event.payload()) else {
                     log::error!("[AUTH] Failed to parse deep link payload");
+                    return;
+                };
+                if let Some(url) = urls.into_iter().find(|url| is_auth_callback(url)) {
+                    log::info!("[AUTH] Received OAuth callback deep link");
+                    handle_auth_callback(&app_handle, url);
                 }
             });
 
-            // NOTE: Workspace auto-indexing disabled - use set_workspace_root command if needed.
+            // Launched cold by the callback link (the app wasn't running)
+            match app.deep_link().get_current() {
+                Ok(Some(urls)) => {
+                    if let Some(url) = urls
+                        .into_iter()
+                        .map(String::from)
+                        .find(|url| is_auth_callback(url))
+                    {
+                        log::info!("[AUTH] Launched by OAuth callback deep link");
+                        handle_auth_callback(app.handle(), url);
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("[AUTH] Failed to read launch deep link: {}", e),
+            }
+
+            // NOTE: Workspace auto-indexing disabled - use add_workspace_root command if needed.
+
+            // Start the pre-roll buffer (if enabled) so the first word isn't clipped.
+            // Only with mic permission - opening the device would otherwise prompt at launch.
+            {
+                let state: tauri::State<'_, AppState> = app.state();
+                let (preroll_ms, mono_channel) = state
+                    .with_config(|cfg| (cfg.preroll_ms, cfg.mono_channel))
+                    .unwrap_or_default();
+                if preroll_ms > 0 && permissions::check_microphone_permission() == "granted" {
+                    let selected_mic = permissions::get_selected_microphone_name();
+                    if let Err(e) = state
+                        .with_recorder_mut(|recorder| {
+                            recorder.set_mono_channel(mono_channel);
+                            recorder.start_prebuffer(preroll_ms, selected_mic)
+                        })
+                        .and_then(|result| result)
+                    {
+                        log::warn!("[STARTUP] Failed to start pre-roll buffer: {}", e);
+                    }
+                }
+            }
 
             // Hide all windows initially
             if let Some(window) = app.get_webview_window("main") {
@@ -2211,6 +5349,18 @@ pub fn run() {
                 let _ = login.hide();
             }
 
+            // Check osascript once at startup - without it keystroke insertion and app
+            // activation can't work, so results are copied to the clipboard instead
+            let app_handle_for_osascript = app.handle().clone();
+            std::thread::spawn(move || {
+                if !permissions::detect_osascript() {
+                    emit_error(
+                        &app_handle_for_osascript,
+                        ErrorEvent::osascript_unavailable(None),
+                    );
+                }
+            });
+
             // Pre-position overlay at bottom-center (while hidden)
             // This prevents flash when first shown
             if let Some(overlay) = app.get_webview_window("overlay") {
@@ -2293,73 +5443,6 @@ pub fn run() {
 mod tests {
     use super::*;
 
-    // AppleScript tests - only run on macOS where the function is available
-    #[cfg(target_os = "macos")]
-    mod applescript_tests {
-        use super::*;
-
-        #[test]
-        fn test_escape_applescript_string_basic() {
-            assert_eq!(escape_applescript_string("hello world"), "hello world");
-        }
-
-        #[test]
-        fn test_escape_applescript_string_quotes() {
-            assert_eq!(
-                escape_applescript_string(r#"say "hello""#),
-                r#"say \"hello\""#
-            );
-        }
-
-        #[test]
-        fn test_escape_applescript_string_backslash() {
-            assert_eq!(
-                escape_applescript_string(r"path\to\file"),
-                r"path\\to\\file"
-            );
-        }
-
-        #[test]
-        fn test_escape_applescript_string_tabs() {
-            assert_eq!(
-                escape_applescript_string("col1\tcol2"),
-                r#"col1" & tab & "col2"#
-            );
-        }
-
-        #[test]
-        fn test_escape_applescript_string_carriage_return() {
-            assert_eq!(escape_applescript_string("line1\r\nline2"), "line1\nline2");
-        }
-
-        #[test]
-        fn test_escape_applescript_string_complex() {
-            let input = r#"She said "hello\" and left"#;
-            let expected = r#"She said \"hello\\\" and left"#;
-            assert_eq!(escape_applescript_string(input), expected);
-        }
-
-        #[test]
-        fn test_escape_applescript_string_empty() {
-            assert_eq!(escape_applescript_string(""), "");
-        }
-
-        #[test]
-        fn test_escape_applescript_string_unicode() {
-            assert_eq!(escape_applescript_string("Hello 世界 🌍"), "Hello 世界 🌍");
-        }
-
-        #[test]
-        fn test_escape_applescript_string_ampersand() {
-            // Ampersand is escaped using AppleScript concatenation to prevent injection
-            // "foo & bar" becomes "foo " & "&" & " bar" which in the escaped form is:
-            assert_eq!(
-                escape_applescript_string("foo & bar"),
-                r#"foo " & "&" & " bar"#
-            );
-        }
-    }
-
     #[test]
     fn test_validate_workspace_path_home_subdir() {
         // A directory under home should be valid
@@ -2396,6 +5479,77 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_overlay_position_center_bottom() {
+        let (x, y) = calculate_overlay_position((0, 0), (1920, 1080), (500, 80), 300);
+        assert_eq!(x, 710);
+        assert_eq!(y, 700);
+    }
+
+    #[test]
+    fn test_overlay_position_secondary_monitor_offset() {
+        // Monitor to the left of the primary with negative coordinates
+        let (x, y) = calculate_overlay_position((-2560, -200), (2560, 1440), (500, 80), 300);
+        assert_eq!(x, -2560 + 1030);
+        assert_eq!(y, -200 + 1060);
+    }
+
+    #[test]
+    fn test_monitor_contains_point() {
+        // Secondary monitor to the left of the primary, offset upwards
+        let secondary = ((-2560, -200), (2560, 1440));
+        let primary = ((0, 0), (1920, 1080));
+
+        let cursor = (-1200.0, 900.0);
+        assert!(monitor_contains_point(secondary.0, secondary.1, cursor));
+        assert!(!monitor_contains_point(primary.0, primary.1, cursor));
+
+        // Right/bottom edges belong to the neighbouring monitor
+        assert!(!monitor_contains_point(
+            secondary.0,
+            secondary.1,
+            (0.0, 10.0)
+        ));
+        assert!(monitor_contains_point(primary.0, primary.1, (0.0, 10.0)));
+        assert!(!monitor_contains_point(
+            primary.0,
+            primary.1,
+            (100.0, 1080.0)
+        ));
+    }
+
+    #[test]
+    fn test_overlay_position_clamped_on_small_monitor() {
+        // Offset larger than the monitor would push the overlay above the top edge
+        let (x, y) = calculate_overlay_position((0, 0), (800, 200), (500, 80), 300);
+        assert_eq!(x, 150);
+        assert_eq!(y, 0);
+
+        // Overlay wider than the monitor is pinned to the monitor's left edge
+        let (x, _) = calculate_overlay_position((100, 0), (400, 600), (500, 80), 0);
+        assert_eq!(x, 100);
+    }
+
+    #[test]
+    fn test_ide_transform_preview() {
+        let settings = ide::IDESettings::default();
+        assert_eq!(
+            ide_transform_preview(
+                "parse the jason",
+                "com.microsoft.VSCode",
+                &settings,
+                &[],
+                &[]
+            ),
+            "parse the JSON"
+        );
+        // Not an IDE: left alone, like a real dictation
+        assert_eq!(
+            ide_transform_preview("parse the jason", "com.apple.Notes", &settings, &[], &[]),
+            "parse the jason"
+        );
+    }
+
     #[test]
     fn test_blocked_directories_list() {
         // Verify blocked directories list includes critical paths
@@ -2403,4 +5557,184 @@ mod tests {
         assert!(BLOCKED_DIRECTORIES.contains(&".gnupg"));
         assert!(BLOCKED_DIRECTORIES.contains(&".aws"));
     }
+
+    #[tokio::test]
+    async fn test_cancelled_enhancement_inserts_nothing() {
+        let state = AppState::new(config::AppConfig::from_stored(Default::default()));
+        let mut config = RecordingStopConfig::from_state(&state, None, Default::default()).unwrap();
+        config.enhancement_enabled = true;
+        config.enhancement_fallback = config::EnhancementFallback::InsertRaw;
+
+        // `cancel_recording` fired while the LLM request was in flight
+        let cancel = CancellationToken::new();
+        cancel.cancel();
+
+        for mode in [DictationMode::Dictation, DictationMode::Command] {
+            let result = enhance_transcript(
+                None,
+                &state,
+                &config,
+                &cancel,
+                "make this formal",
+                DictationContext {
+                    mode,
+                    selected_text: Some("hey, thanks".to_string()),
+                    active_style: None,
+                },
+            )
+            .await;
+            // Not an enhancement failure: the raw transcript must not be inserted instead
+            assert_eq!(result, Err(http_client::CANCELLED_ERROR.to_string()));
+        }
+        assert!(state.get_last_dictation().is_none());
+    }
+
+    #[test]
+    fn test_stop_config_snapshots_replacements_from_memory() {
+        let rule =
+            replacements::ReplacementRule::new("clawed".into(), "Claude".into(), true, false);
+        let stored = config::StoredPreferences {
+            replacements: Some(vec![rule.clone()]),
+            ..Default::default()
+        };
+        let state = AppState::new(config::AppConfig::from_stored(stored));
+
+        // The preferences file passed in has no rules; the in-memory ones are used
+        let config = RecordingStopConfig::from_state(&state, None, Default::default()).unwrap();
+        assert_eq!(config.replacements, vec![rule]);
+    }
+
+    #[tokio::test]
+    async fn test_retranscribe_uses_selected_provider() {
+        // With a Deepgram key this would send the audio off
+        if deepgram::api_key().is_some() {
+            return;
+        }
+        let stored = config::StoredPreferences {
+            transcription_provider: Some(config::TranscriptionProvider::Deepgram),
+            ..Default::default()
+        };
+        let state = AppState::new(config::AppConfig::from_stored(stored));
+        state.set_last_audio(Some(vec![0.0; 16000]));
+
+        // Reaches the Deepgram client rather than Groq's Whisper
+        let result = retranscribe(&state, Some("en".to_string())).await;
+        assert_eq!(
+            result,
+            Err("No Deepgram API key is set. Add your API key in Preferences.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_selection_from_stopped_recording_is_stale() {
+        use std::sync::{mpsc, Arc};
+
+        let session = Arc::new(RecordingSession::default());
+        let id = session.begin();
+        assert!(session.is_current(id));
+
+        // Selection detection is still running when the recording stops
+        let (detected_tx, detected_rx) = mpsc::channel();
+        let worker = {
+            let session = Arc::clone(&session);
+            std::thread::spawn(move || {
+                detected_rx.recv().unwrap();
+                session.is_current(id)
+            })
+        };
+        session.end();
+        detected_tx.send(()).unwrap();
+        assert!(!worker.join().unwrap(), "late selection must be discarded");
+
+        // A new recording doesn't revive the old id
+        let next = session.begin();
+        assert!(session.is_current(next));
+        assert!(!session.is_current(id));
+    }
+
+    #[test]
+    fn test_parse_hotkey() {
+        use tauri_plugin_global_shortcut::Code;
+
+        assert_eq!(
+            parse_hotkey("Option+Space"),
+            Ok(Shortcut::new(Some(Modifiers::ALT), Code::Space))
+        );
+        assert_eq!(
+            parse_hotkey("cmd + shift + d"),
+            Ok(Shortcut::new(
+                Some(Modifiers::SUPER | Modifiers::SHIFT),
+                Code::KeyD
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parse_hotkey_errors() {
+        assert_eq!(parse_hotkey("  "), Err(HotkeyParseError::Empty));
+        assert_eq!(
+            parse_hotkey("Cmd+Banana"),
+            Err(HotkeyParseError::UnknownKey("Banana".to_string()))
+        );
+        assert_eq!(parse_hotkey("Cmd+Shift"), Err(HotkeyParseError::NoKey));
+        assert_eq!(
+            parse_hotkey("Fn+Space"),
+            Err(HotkeyParseError::FnUnsupported)
+        );
+        assert!(validate_hotkey("Ctrl+".to_string()).is_err());
+    }
+
+    #[test]
+    fn test_shortcut_change() {
+        use tauri_plugin_global_shortcut::Code;
+
+        let option_space = Shortcut::new(Some(Modifiers::ALT), Code::Space);
+        let cmd_shift_d = Shortcut::new(Some(Modifiers::SUPER | Modifiers::SHIFT), Code::KeyD);
+
+        // Nothing registered yet, or a different combo → register the new one
+        assert_eq!(
+            shortcut_change(None, "Option+Space"),
+            Ok(Some(option_space))
+        );
+        assert_eq!(
+            shortcut_change(Some(option_space), "Cmd+Shift+D"),
+            Ok(Some(cmd_shift_d))
+        );
+        // Same combo, however it's spelled → leave the registration alone
+        assert_eq!(shortcut_change(Some(option_space), "alt + space"), Ok(None));
+        // Unparseable → error, nothing is touched
+        assert_eq!(
+            shortcut_change(Some(option_space), "Option"),
+            Err(HotkeyParseError::NoKey)
+        );
+    }
+
+    #[test]
+    fn test_raw_shortcut_change() {
+        use tauri_plugin_global_shortcut::Code;
+
+        let option_r = Shortcut::new(Some(Modifiers::ALT), Code::KeyR);
+
+        // Not in the update, or unchanged → leave the registration alone
+        assert_eq!(raw_shortcut_change(Some(option_r), None), Ok(None));
+        assert_eq!(
+            raw_shortcut_change(Some(option_r), Some("Option+R")),
+            Ok(None)
+        );
+        // New combo → register it
+        assert_eq!(
+            raw_shortcut_change(None, Some("Option+R")),
+            Ok(Some(Some(option_r)))
+        );
+        // Empty string → unregister, unless there's nothing registered
+        assert_eq!(
+            raw_shortcut_change(Some(option_r), Some("")),
+            Ok(Some(None))
+        );
+        assert_eq!(raw_shortcut_change(None, Some("")), Ok(None));
+        assert_eq!(
+            raw_shortcut_change(None, Some("Option")),
+            Err(HotkeyParseError::NoKey)
+        );
+    }
 }
@@ -1,5 +1,6 @@
 use cpal::traits::{DeviceTrait, HostTrait};
 use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 
 #[cfg(target_os = "macos")]
 use std::process::Command;
@@ -75,10 +76,64 @@ fn app_name() -> String {
     "keyhold".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct PermissionStatus {
     pub microphone: String, // "granted", "denied", "undetermined"
     pub accessibility: bool,
+    /// Whether `osascript` can run. When false, keystroke insertion is disabled
+    /// and results are copied to the clipboard instead.
+    #[serde(default = "default_true")]
+    pub osascript_available: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl PermissionStatus {
+    /// Query the current microphone and accessibility permission status
+    pub fn current() -> Self {
+        PermissionStatus {
+            microphone: check_microphone_permission(),
+            accessibility: check_accessibility_permission(),
+            osascript_available: is_osascript_available(),
+        }
+    }
+
+    /// Whether every permission the app needs has been granted
+    pub fn all_granted(&self) -> bool {
+        self.microphone == "granted" && self.accessibility
+    }
+}
+
+/// Cached result of the last `osascript` probe. Assumed available until checked.
+static OSASCRIPT_AVAILABLE: AtomicBool = AtomicBool::new(true);
+
+/// Check whether `osascript` can be executed (it may be missing or blocked on
+/// locked-down/MDM-managed machines) and cache the result.
+/// Runs a no-op script, so it doesn't need Accessibility permission.
+pub fn detect_osascript() -> bool {
+    #[cfg(target_os = "macos")]
+    let available = Command::new("osascript")
+        .arg("-e")
+        .arg("return 1")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    #[cfg(not(target_os = "macos"))]
+    let available = true; // osascript is only used on macOS
+
+    OSASCRIPT_AVAILABLE.store(available, Ordering::SeqCst);
+    if !available {
+        log::error!("[PERMISSIONS] osascript is not available - keystroke insertion disabled");
+    }
+    available
+}
+
+/// Whether `osascript` was available the last time it was checked
+pub fn is_osascript_available() -> bool {
+    OSASCRIPT_AVAILABLE.load(Ordering::SeqCst)
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -86,6 +141,54 @@ pub struct MicrophoneDevice {
     pub id: String,
     pub name: String,
     pub is_default: bool,
+    /// None when the device errored while being probed (capabilities unknown)
+    pub capabilities: Option<DeviceCapabilities>,
+}
+
+/// What an input device can record, as reported by cpal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeviceCapabilities {
+    /// Sample rate the device records at by default (Hz)
+    pub default_sample_rate: u32,
+    /// Highest sample rate any supported config allows (Hz)
+    pub max_sample_rate: u32,
+    /// Channel count of the default config
+    pub channels: u16,
+    /// Supported sample formats ("i16", "f32", ...)
+    pub sample_formats: Vec<String>,
+}
+
+/// Probe an input device's default config and supported configs.
+/// Returns None if either query fails (some devices error when probed).
+fn probe_capabilities(device: &cpal::Device) -> Option<DeviceCapabilities> {
+    let default_config = device
+        .default_input_config()
+        .map_err(|e| log::debug!("Failed to get default input config: {}", e))
+        .ok()?;
+    let supported: Vec<_> = device
+        .supported_input_configs()
+        .map_err(|e| log::debug!("Failed to get supported input configs: {}", e))
+        .ok()?
+        .collect();
+
+    let mut sample_formats: Vec<String> = supported
+        .iter()
+        .map(|config| config.sample_format().to_string())
+        .collect();
+    sample_formats.sort();
+    sample_formats.dedup();
+
+    let default_sample_rate = default_config.sample_rate().0;
+    Some(DeviceCapabilities {
+        default_sample_rate,
+        max_sample_rate: supported
+            .iter()
+            .map(|config| config.max_sample_rate().0)
+            .max()
+            .unwrap_or(default_sample_rate),
+        channels: default_config.channels(),
+        sample_formats,
+    })
 }
 
 /// Check if accessibility permission is granted
@@ -187,10 +290,15 @@ pub fn get_microphone_devices() -> Vec<MicrophoneDevice> {
         for device in input_devices {
             if let Ok(name) = device.name() {
                 let is_default = name == default_name;
+                let capabilities = probe_capabilities(&device);
+                if capabilities.is_none() {
+                    log::warn!("Capabilities unknown for input device '{}'", name);
+                }
                 devices.push(MicrophoneDevice {
                     id: name.clone(), // Use name as ID for stability
                     name,
                     is_default,
+                    capabilities,
                 });
             }
         }
@@ -202,6 +310,7 @@ pub fn get_microphone_devices() -> Vec<MicrophoneDevice> {
             id: "default".to_string(),
             name: "Default Microphone".to_string(),
             is_default: true,
+            capabilities: None,
         });
     }
 
@@ -231,7 +340,7 @@ pub fn request_microphone_permission() -> bool {
                 let stream = device.build_input_stream(
                     &config.into(),
                     |_data: &[f32], _: &cpal::InputCallbackInfo| {},
-                    |err| eprintln!("Stream error: {}", err),
+                    |err| log::error!("Stream error: {}", err),
                     None,
                 );
 
@@ -328,9 +437,9 @@ pub fn set_selected_microphone(device_id: &str) -> Result<(), String> {
     Ok(())
 }
 
-/// Get the selected microphone device name
-/// Returns None if no device is selected, "default" is selected, or device no longer exists
-pub fn get_selected_microphone_name() -> Option<String> {
+/// Get the microphone chosen in settings, whether or not it's connected.
+/// Returns None if no device is selected or "default" is selected
+pub fn get_configured_microphone_name() -> Option<String> {
     let config_dir = dirs::config_dir()?.join(app_name());
     let mic_file = config_dir.join("selected_microphone");
 
@@ -345,12 +454,50 @@ pub fn get_selected_microphone_name() -> Option<String> {
         return None;
     }
 
+    Some(device_name.to_string())
+}
+
+/// Get the selected microphone device name
+/// Returns None if no device is selected, "default" is selected, or device no longer exists
+pub fn get_selected_microphone_name() -> Option<String> {
+    let device_name = get_configured_microphone_name()?;
+
     // Verify the device still exists (handles unplugged devices)
     let devices = get_microphone_devices();
     if devices.iter().any(|d| d.name == device_name) {
-        Some(device_name.to_string())
+        Some(device_name)
     } else {
         // Device no longer available, fall back to default
         None
     }
 }
+
+/// Remember the microphone the last recording started on
+pub fn set_last_used_microphone(device_name: &str) -> Result<(), String> {
+    if get_last_used_microphone().as_deref() == Some(device_name) {
+        return Ok(());
+    }
+
+    let config_dir = dirs::config_dir()
+        .ok_or("Could not find config directory")?
+        .join(app_name());
+
+    std::fs::create_dir_all(&config_dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+    let mic_file = config_dir.join("last_used_microphone");
+    std::fs::write(&mic_file, device_name)
+        .map_err(|e| format!("Failed to write last used microphone: {}", e))?;
+
+    Ok(())
+}
+
+/// Get the microphone the last recording started on
+pub fn get_last_used_microphone() -> Option<String> {
+    let mic_file = dirs::config_dir()?
+        .join(app_name())
+        .join("last_used_microphone");
+    let device_name = std::fs::read_to_string(mic_file).ok()?;
+    let device_name = device_name.trim();
+    (!device_name.is_empty()).then(|| device_name.to_string())
+}
@@ -0,0 +1,346 @@
+//! Linux text insertion via `xdotool` (X11) or `wtype` (Wayland).
+//!
+//! The session type comes from `XDG_SESSION_TYPE`. ASCII text is typed with
+//! the session's typing tool; Unicode text is pasted with Ctrl+V through the
+//! clipboard (`xclip` / `wl-clipboard`), whose original text is restored
+//! afterwards. Text arrives already normalized by `insert_text_directly`.
+
+use std::env;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+/// Display server the session runs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Session {
+    X11,
+    Wayland,
+}
+
+impl Session {
+    /// Session for an `XDG_SESSION_TYPE` value (anything but "wayland" is treated as X11).
+    fn from_session_type(session_type: Option<&str>) -> Self {
+        match session_type {
+            Some(t) if t.eq_ignore_ascii_case("wayland") => Session::Wayland,
+            _ => Session::X11,
+        }
+    }
+
+    fn detect() -> Self {
+        Session::from_session_type(env::var("XDG_SESSION_TYPE").ok().as_deref())
+    }
+}
+
+/// A command-line tool that can type and paste text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Typer {
+    Xdotool,
+    Wtype,
+}
+
+impl Typer {
+    fn binary(self) -> &'static str {
+        match self {
+            Typer::Xdotool => "xdotool",
+            Typer::Wtype => "wtype",
+        }
+    }
+
+    /// Typing tools to try for a session, preferred first. xdotool still
+    /// reaches XWayland apps, and wtype needs a Wayland compositor.
+    fn candidates(session: Session) -> &'static [Typer] {
+        match session {
+            Session::X11 => &[Typer::Xdotool],
+            Session::Wayland => &[Typer::Wtype, Typer::Xdotool],
+        }
+    }
+
+    /// First installed typing tool for the session.
+    fn find(session: Session, is_installed: impl Fn(&str) -> bool) -> Result<Self, String> {
+        Typer::candidates(session)
+            .iter()
+            .copied()
+            .find(|t| is_installed(t.binary()))
+            .ok_or_else(|| missing_tool_error(session))
+    }
+
+    fn type_command(self, text: &str) -> Command {
+        let mut cmd = Command::new(self.binary());
+        match self {
+            Typer::Xdotool => cmd.args(["type", "--clearmodifiers", "--delay", "12", "--", text]),
+            Typer::Wtype => cmd.args(["--", text]),
+        };
+        cmd
+    }
+
+    /// Press Backspace `count` times.
+    fn backspace_command(self, count: usize) -> Command {
+        let mut cmd = Command::new(self.binary());
+        match self {
+            Typer::Xdotool => {
+                let count = count.to_string();
+                cmd.args(["key", "--clearmodifiers", "--repeat", &count, "BackSpace"])
+            }
+            Typer::Wtype => cmd.args(std::iter::repeat_n(["-k", "BackSpace"], count).flatten()),
+        };
+        cmd
+    }
+
+    fn undo_command(self) -> Command {
+        let mut cmd = Command::new(self.binary());
+        match self {
+            Typer::Xdotool => cmd.args(["key", "--clearmodifiers", "ctrl+z"]),
+            Typer::Wtype => cmd.args(["-M", "ctrl", "z", "-m", "ctrl"]),
+        };
+        cmd
+    }
+
+    fn paste_command(self) -> Command {
+        let mut cmd = Command::new(self.binary());
+        match self {
+            Typer::Xdotool => cmd.args(["key", "--clearmodifiers", "ctrl+v"]),
+            Typer::Wtype => cmd.args(["-M", "ctrl", "v", "-m", "ctrl"]),
+        };
+        cmd
+    }
+}
+
+/// Error telling the user which package to install.
+fn missing_tool_error(session: Session) -> String {
+    match session {
+        Session::X11 => "Text insertion on X11 needs xdotool. Install it with your \
+                         package manager (e.g. `sudo apt install xdotool`)."
+            .to_string(),
+        Session::Wayland => "Text insertion on Wayland needs wtype (or xdotool for \
+                             XWayland apps). Install it with your package manager \
+                             (e.g. `sudo apt install wtype`)."
+            .to_string(),
+    }
+}
+
+/// Whether an executable with this name is on PATH.
+fn is_installed(binary: &str) -> bool {
+    env::var_os("PATH")
+        .map(|paths| env::split_paths(&paths).any(|dir| is_executable(&dir.join(binary))))
+        .unwrap_or(false)
+}
+
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .map(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+/// Insert text at the cursor of the focused window, typing it or pasting it.
+/// Fails with a message naming the package to install if no insertion tool is
+/// installed, or with the tool's error if it failed.
+pub fn insert_text(text: &str, use_clipboard: bool) -> Result<(), String> {
+    let session = Session::detect();
+    let result = Typer::find(session, is_installed).and_then(|typer| {
+        if use_clipboard {
+            insert_via_clipboard_preserving(session, typer, text)
+        } else {
+            run(typer.type_command(text))
+        }
+    });
+
+    match &result {
+        Ok(()) => log::info!("[INSERT] Text inserted ({:?} session)", session),
+        Err(e) => {
+            log::error!("[INSERT] {}", e);
+            crate::sentry_capture_error("Linux text insertion failed", Some(e));
+        }
+    }
+    result
+}
+
+/// Press Backspace `count` times in the focused window, removing text that was
+/// just typed. Returns false if no typing tool is installed or it failed.
+pub fn delete_backward(count: usize) -> bool {
+    send_keys(|typer| typer.backspace_command(count))
+}
+
+/// Press Ctrl+Z in the focused window, undoing a paste in one step.
+/// Returns false if no typing tool is installed or it failed.
+pub fn undo() -> bool {
+    send_keys(Typer::undo_command)
+}
+
+/// Run a key command with the session's typing tool.
+fn send_keys(command: impl FnOnce(Typer) -> Command) -> bool {
+    let session = Session::detect();
+    match Typer::find(session, is_installed).and_then(|typer| run(command(typer))) {
+        Ok(()) => true,
+        Err(e) => {
+            log::error!("[INSERT] {}", e);
+            false
+        }
+    }
+}
+
+/// Run a tool to completion, turning a non-zero exit into an error.
+fn run(mut cmd: Command) -> Result<(), String> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let output = cmd
+        .output()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!(
+            "{} failed: {}",
+            program,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ))
+    }
+}
+
+/// Clipboard read and write commands for the session, if the tool is installed.
+fn clipboard_commands(session: Session) -> Option<(Command, Command)> {
+    match session {
+        Session::X11 if is_installed("xclip") => {
+            let mut read = Command::new("xclip");
+            read.args(["-selection", "clipboard", "-o"]);
+            let mut write = Command::new("xclip");
+            write.args(["-selection", "clipboard"]);
+            Some((read, write))
+        }
+        Session::Wayland if is_installed("wl-copy") && is_installed("wl-paste") => {
+            let mut read = Command::new("wl-paste");
+            read.arg("--no-newline");
+            Some((read, Command::new("wl-copy")))
+        }
+        _ => None,
+    }
+}
+
+/// Insert text via clipboard + Ctrl+V, preserving the user's original clipboard text.
+/// Falls back to typing when no clipboard tool is installed.
+fn insert_via_clipboard_preserving(
+    session: Session,
+    typer: Typer,
+    text: &str,
+) -> Result<(), String> {
+    let Some((mut read, write)) = clipboard_commands(session) else {
+        log::warn!(
+            "[INSERT] No clipboard tool for {:?} (install xclip or wl-clipboard), typing instead",
+            session
+        );
+        return run(typer.type_command(text));
+    };
+
+    let original = read
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|o| o.status.success())
+        .map(|o| String::from_utf8_lossy(&o.stdout).to_string());
+
+    write_clipboard(write, text)?;
+
+    // Small delay to ensure clipboard is ready
+    thread::sleep(Duration::from_millis(50));
+    let pasted = run(typer.paste_command());
+    // Delay before restoring (give paste time to complete)
+    thread::sleep(Duration::from_millis(150));
+
+    if let Some(original) = original {
+        if let Some((_, write)) = clipboard_commands(session) {
+            if let Err(e) = write_clipboard(write, &original) {
+                log::warn!("[INSERT] Failed to restore clipboard: {}", e);
+            }
+        }
+    }
+    pasted
+}
+
+/// Pipe text into a clipboard write command.
+fn write_clipboard(mut cmd: Command, text: &str) -> Result<(), String> {
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let mut child = cmd
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(text.as_bytes())
+            .map_err(|e| format!("Failed to write to {}: {}", program, e))?;
+    }
+
+    let status = child
+        .wait()
+        .map_err(|e| format!("Failed to wait for {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} failed to set the clipboard", program))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_from_session_type() {
+        assert_eq!(
+            Session::from_session_type(Some("wayland")),
+            Session::Wayland
+        );
+        assert_eq!(Session::from_session_type(Some("x11")), Session::X11);
+        assert_eq!(Session::from_session_type(Some("tty")), Session::X11);
+        assert_eq!(Session::from_session_type(None), Session::X11);
+    }
+
+    #[test]
+    fn test_typer_prefers_session_tool() {
+        let all = |_: &str| true;
+        assert_eq!(Typer::find(Session::X11, all), Ok(Typer::Xdotool));
+        assert_eq!(Typer::find(Session::Wayland, all), Ok(Typer::Wtype));
+
+        // XWayland fallback when wtype isn't installed
+        let only_xdotool = |b: &str| b == "xdotool";
+        assert_eq!(
+            Typer::find(Session::Wayland, only_xdotool),
+            Ok(Typer::Xdotool)
+        );
+    }
+
+    #[test]
+    fn test_backspace_command_args() {
+        let args = |cmd: Command| -> Vec<String> {
+            cmd.get_args()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(
+            args(Typer::Xdotool.backspace_command(3)),
+            ["key", "--clearmodifiers", "--repeat", "3", "BackSpace"]
+        );
+        assert_eq!(
+            args(Typer::Wtype.backspace_command(2)),
+            ["-k", "BackSpace", "-k", "BackSpace"]
+        );
+    }
+
+    #[test]
+    fn test_missing_tool_error_names_package() {
+        let none = |_: &str| false;
+        assert!(Typer::find(Session::X11, none)
+            .unwrap_err()
+            .contains("xdotool"));
+        assert!(Typer::find(Session::Wayland, none)
+            .unwrap_err()
+            .contains("wtype"));
+
+        // wtype alone doesn't help an X11 session
+        let only_wtype = |b: &str| b == "wtype";
+        assert!(Typer::find(Session::X11, only_wtype).is_err());
+    }
+}
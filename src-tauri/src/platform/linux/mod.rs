@@ -1,6 +1,9 @@
-//! Linux platform stubs for cross-platform compilation.
+//! Linux platform implementations.
 //!
-//! These are placeholder implementations that allow the code to compile
-//! on Linux for testing purposes. The actual functionality is macOS-only.
+//! Text insertion shells out to xdotool/wtype. Selection and the window title
+//! are placeholders that allow the code to compile on Linux; that functionality
+//! is macOS-only.
 
+pub mod insertion;
 pub mod selection;
+pub mod window;
@@ -0,0 +1,8 @@
+//! Linux window stub for cross-platform compilation.
+//!
+//! Reading the frontmost window title is macOS-only.
+
+/// Stub: Always returns None on Linux
+pub fn frontmost_window_title() -> Option<String> {
+    None
+}
@@ -0,0 +1,439 @@
+//! macOS text insertion via AppleScript (System Events).
+//!
+//! ASCII text is typed with `keystroke`; Unicode text is pasted through the
+//! clipboard, whose original contents are restored afterwards.
+
+use crate::permissions;
+
+/// Insert text at the cursor of the frontmost app, typing it or pasting it.
+/// Fails if osascript is unavailable or the script failed.
+pub fn insert_text(text: &str, use_clipboard: bool) -> Result<(), String> {
+    if !permissions::is_osascript_available() {
+        log::warn!("[INSERT] osascript unavailable, skipping keystroke insertion");
+        return Err("osascript is unavailable".to_string());
+    }
+
+    let inserted = if use_clipboard {
+        insert_via_clipboard_preserving(text)
+    } else {
+        insert_via_keystroke(text)
+    };
+    if inserted {
+        Ok(())
+    } else {
+        Err("System Events couldn't type into the app".to_string())
+    }
+}
+
+/// Press Delete (`key code 51`) `count` times in the frontmost app, removing
+/// text that was just typed. Returns false if the script failed.
+pub fn delete_backward(count: usize) -> bool {
+    if !permissions::is_osascript_available() {
+        log::warn!("[INSERT] osascript unavailable, can't send Delete");
+        return false;
+    }
+
+    log::info!("[KEYSTROKE] Deleting {} chars", count);
+    run_system_events_script(&delete_backward_script(count))
+}
+
+/// Press Cmd+Z in the frontmost app, undoing a paste in one step.
+/// Returns false if the script failed.
+pub fn undo() -> bool {
+    if !permissions::is_osascript_available() {
+        log::warn!("[INSERT] osascript unavailable, can't send Cmd+Z");
+        return false;
+    }
+
+    log::info!("[KEYSTROKE] Sending Cmd+Z");
+    run_system_events_script(
+        "tell application \"System Events\"\n    keystroke \"z\" using command down\nend tell",
+    )
+}
+
+/// Build the System Events script that presses Delete `count` times.
+fn delete_backward_script(count: usize) -> String {
+    format!(
+        "tell application \"System Events\"\n    repeat {} times\n        key code 51\n    end repeat\nend tell",
+        count
+    )
+}
+
+/// Run a System Events script that sends keys, logging why it failed if it did.
+fn run_system_events_script(script: &str) -> bool {
+    use std::process::Command;
+
+    match Command::new("osascript").arg("-e").arg(script).output() {
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if output.status.success() && stderr.is_empty() {
+                return true;
+            }
+            if stderr.contains("not allowed")
+                || stderr.contains("assistive")
+                || stderr.contains("1002")
+            {
+                log::error!("[KEYSTROKE] Accessibility permission required to send keys");
+            } else {
+                log::error!("[KEYSTROKE] osascript stderr: {}", stderr);
+            }
+            false
+        }
+        Err(e) => {
+            log::error!("[KEYSTROKE] Failed to execute osascript: {}", e);
+            permissions::detect_osascript();
+            false
+        }
+    }
+}
+
+/// Escape text for safe inclusion in AppleScript double-quoted strings.
+/// Handles all characters that could break out of the string or cause injection.
+///
+/// SECURITY: This function prevents AppleScript injection by escaping:
+/// - Backslashes (must be first to avoid double-escaping)
+/// - Double quotes (could break out of string)
+/// - Ampersands (AppleScript concatenation operator - could inject code)
+/// - Carriage returns (removed)
+/// - Tabs (converted to AppleScript tab concatenation)
+fn escape_applescript_string(text: &str) -> String {
+    text.replace("\\", "\\\\") // Backslash must be first
+        .replace("\"", "\\\"") // Double quotes
+        .replace("&", "\" & \"&\" & \"") // Escape ampersands to prevent injection
+        .replace("\r", "") // Remove carriage returns (handled separately)
+        .replace("\t", "\" & tab & \"") // Tabs as AppleScript concatenation
+}
+
+/// Maximum characters per keystroke chunk to prevent buffer issues
+/// Longer texts are split into chunks with small delays between them
+const KEYSTROKE_CHUNK_SIZE: usize = 500;
+
+/// Insert ASCII text using AppleScript keystroke (doesn't touch clipboard)
+/// For long texts, uses chunking with delays to prevent dropped characters.
+fn insert_via_keystroke(text: &str) -> bool {
+    log::info!(
+        "[KEYSTROKE] Starting keystroke insertion for {} chars",
+        text.len()
+    );
+
+    // For longer texts, use chunked keystroke insertion to prevent buffer issues
+    if text.len() > KEYSTROKE_CHUNK_SIZE {
+        log::info!(
+            "[KEYSTROKE] Chunking text into {} char segments",
+            KEYSTROKE_CHUNK_SIZE
+        );
+        return insert_via_keystroke_chunked(text);
+    }
+
+    // For short texts, use single keystroke command
+    let script = keystroke_script(text);
+
+    execute_keystroke_script(&script, text.len())
+}
+
+/// Build the System Events script that types `text`.
+/// Line breaks are sent as Return (`key code 36`) between keystroke commands.
+fn keystroke_script(text: &str) -> String {
+    let commands: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            if line.is_empty() {
+                String::new()
+            } else {
+                format!("    keystroke \"{}\"\n", escape_applescript_string(line))
+            }
+        })
+        .collect();
+
+    format!(
+        "tell application \"System Events\"\n{}end tell",
+        commands.join("    key code 36\n")
+    )
+}
+
+/// Insert text using chunked keystrokes with delays between chunks
+fn insert_via_keystroke_chunked(text: &str) -> bool {
+    use std::process::Command;
+    use std::thread;
+    use std::time::Duration;
+
+    let chars: Vec<char> = text.chars().collect();
+    let total_chunks = (chars.len() + KEYSTROKE_CHUNK_SIZE - 1) / KEYSTROKE_CHUNK_SIZE;
+
+    log::info!(
+        "[KEYSTROKE] Inserting {} chars in {} chunks",
+        chars.len(),
+        total_chunks
+    );
+    let mut all_succeeded = true;
+
+    for (i, chunk) in chars.chunks(KEYSTROKE_CHUNK_SIZE).enumerate() {
+        let chunk_text: String = chunk.iter().collect();
+        let script = keystroke_script(&chunk_text);
+
+        log::info!(
+            "[KEYSTROKE] Sending chunk {}/{} ({} chars)",
+            i + 1,
+            total_chunks,
+            chunk_text.len()
+        );
+
+        let result = Command::new("osascript").arg("-e").arg(&script).output();
+
+        match result {
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                if !output.status.success() || !stderr.is_empty() {
+                    if stderr.contains("not allowed")
+                        || stderr.contains("assistive")
+                        || stderr.contains("1002")
+                    {
+                        log::error!("=======================================================");
+                        log::error!("ACCESSIBILITY PERMISSION REQUIRED");
+                        log::error!("Go to: System Settings > Privacy & Security > Accessibility");
+                        log::error!("Add Keyhold.app and ensure it's enabled");
+                        log::error!("Then QUIT and RELAUNCH the app");
+                        log::error!("=======================================================");
+                        crate::sentry_capture_error(
+                            "Accessibility permission denied during keystroke insertion",
+                            None,
+                        );
+                        return false; // Stop on permission error
+                    } else if !stderr.is_empty() {
+                        all_succeeded = false;
+                        log::error!("[KEYSTROKE] Chunk {} error: {}", i + 1, stderr);
+                        crate::sentry_capture_error(
+                            &format!("Keystroke chunk {} failed", i + 1),
+                            Some(&stderr),
+                        );
+                    }
+                }
+            }
+            Err(e) => {
+                log::error!("[KEYSTROKE] Failed to execute chunk {}: {}", i + 1, e);
+                permissions::detect_osascript();
+                crate::sentry_capture_error(
+                    &format!("Failed to execute osascript for chunk {}", i + 1),
+                    Some(&e.to_string()),
+                );
+                return false; // Stop on error
+            }
+        }
+
+        // Small delay between chunks to let the target app process
+        if i < total_chunks - 1 {
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    if all_succeeded {
+        log::info!(
+            "[KEYSTROKE] All {} chunks inserted successfully",
+            total_chunks
+        );
+    }
+    all_succeeded
+}
+
+/// Execute a keystroke AppleScript and handle the result
+fn execute_keystroke_script(script: &str, char_count: usize) -> bool {
+    use std::process::Command;
+
+    log::info!(
+        "[KEYSTROKE] Executing osascript for {} chars...",
+        char_count
+    );
+    let result = Command::new("osascript").arg("-e").arg(script).output();
+
+    match result {
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            log::info!(
+                "[KEYSTROKE] osascript exit code: {:?}",
+                output.status.code()
+            );
+            if !stdout.is_empty() {
+                log::info!("[KEYSTROKE] osascript stdout: {}", stdout);
+            }
+            if output.status.success() && stderr.is_empty() {
+                log::info!("[KEYSTROKE] Text inserted via keystroke (clipboard untouched)");
+                return true;
+            } else if stderr.contains("not allowed")
+                || stderr.contains("assistive")
+                || stderr.contains("1002")
+            {
+                log::error!("=======================================================");
+                log::error!("ACCESSIBILITY PERMISSION REQUIRED");
+                log::error!("Go to: System Settings > Privacy & Security > Accessibility");
+                log::error!("Add Keyhold.app and ensure it's enabled");
+                log::error!("Then QUIT and RELAUNCH the app");
+                log::error!("=======================================================");
+            } else if !stderr.is_empty() {
+                log::error!("[KEYSTROKE] osascript stderr: {}", stderr);
+            }
+            false
+        }
+        Err(e) => {
+            log::error!("[KEYSTROKE] Failed to execute osascript: {}", e);
+            permissions::detect_osascript();
+            false
+        }
+    }
+}
+
+/// Insert text via clipboard, preserving the user's original clipboard contents
+fn insert_via_clipboard_preserving(text: &str) -> bool {
+    use std::process::Command;
+
+    // Escape text for AppleScript string using robust escaping
+    let escaped_text = escape_applescript_string(text);
+
+    // AppleScript that:
+    // 1. Saves current clipboard
+    // 2. Sets clipboard to our text
+    // 3. Pastes (Cmd+V)
+    // 4. Restores original clipboard after a brief delay
+    let script = format!(
+        r#"
+        -- Save original clipboard
+        set originalClipboard to the clipboard
+
+        -- Set clipboard to our text
+        set the clipboard to "{}"
+
+        -- Small delay to ensure clipboard is ready
+        delay 0.05
+
+        -- Paste using Cmd+V
+        tell application "System Events"
+            keystroke "v" using command down
+        end tell
+
+        -- Delay before restoring (give paste time to complete)
+        delay 0.15
+
+        -- Restore original clipboard
+        try
+            set the clipboard to originalClipboard
+        end try
+        "#,
+        escaped_text
+    );
+
+    let result = Command::new("osascript").arg("-e").arg(&script).output();
+
+    match result {
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            if output.status.success() && stderr.is_empty() {
+                log::info!("Unicode text inserted via clipboard (original clipboard restored)");
+                return true;
+            } else if stderr.contains("not allowed")
+                || stderr.contains("assistive")
+                || stderr.contains("1002")
+            {
+                log::error!("=======================================================");
+                log::error!("ACCESSIBILITY PERMISSION REQUIRED");
+                log::error!("Go to: System Settings > Privacy & Security > Accessibility");
+                log::error!("Add Keyhold.app and ensure it's enabled");
+                log::error!("Then QUIT and RELAUNCH the app");
+                log::error!("=======================================================");
+            } else if !stderr.is_empty() {
+                log::error!("osascript stderr: {}", stderr);
+            }
+            false
+        }
+        Err(e) => {
+            log::error!("Failed to execute osascript: {}", e);
+            permissions::detect_osascript();
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_applescript_string_basic() {
+        assert_eq!(escape_applescript_string("hello world"), "hello world");
+    }
+
+    #[test]
+    fn test_escape_applescript_string_quotes() {
+        assert_eq!(
+            escape_applescript_string(r#"say "hello""#),
+            r#"say \"hello\""#
+        );
+    }
+
+    #[test]
+    fn test_escape_applescript_string_backslash() {
+        assert_eq!(
+            escape_applescript_string(r"path\to\file"),
+            r"path\\to\\file"
+        );
+    }
+
+    #[test]
+    fn test_escape_applescript_string_tabs() {
+        assert_eq!(
+            escape_applescript_string("col1\tcol2"),
+            r#"col1" & tab & "col2"#
+        );
+    }
+
+    #[test]
+    fn test_escape_applescript_string_carriage_return() {
+        assert_eq!(escape_applescript_string("line1\r\nline2"), "line1\nline2");
+    }
+
+    #[test]
+    fn test_escape_applescript_string_complex() {
+        let input = r#"She said "hello\" and left"#;
+        let expected = r#"She said \"hello\\\" and left"#;
+        assert_eq!(escape_applescript_string(input), expected);
+    }
+
+    #[test]
+    fn test_keystroke_script_sends_return_between_lines() {
+        assert_eq!(
+            keystroke_script("Hi \"Sam\"\n\nThanks"),
+            "tell application \"System Events\"\n    keystroke \"Hi \\\"Sam\\\"\"\n    key code 36\n    key code 36\n    keystroke \"Thanks\"\nend tell"
+        );
+        assert_eq!(
+            keystroke_script("hello"),
+            "tell application \"System Events\"\n    keystroke \"hello\"\nend tell"
+        );
+    }
+
+    #[test]
+    fn test_delete_backward_script() {
+        assert_eq!(
+            delete_backward_script(12),
+            "tell application \"System Events\"\n    repeat 12 times\n        key code 51\n    end repeat\nend tell"
+        );
+    }
+
+    #[test]
+    fn test_escape_applescript_string_empty() {
+        assert_eq!(escape_applescript_string(""), "");
+    }
+
+    #[test]
+    fn test_escape_applescript_string_unicode() {
+        assert_eq!(escape_applescript_string("Hello 世界 🌍"), "Hello 世界 🌍");
+    }
+
+    #[test]
+    fn test_escape_applescript_string_ampersand() {
+        // Ampersand is escaped using AppleScript concatenation to prevent injection
+        // "foo & bar" becomes "foo " & "&" & " bar" which in the escaped form is:
+        assert_eq!(
+            escape_applescript_string("foo & bar"),
+            r#"foo " & "&" & " bar"#
+        );
+    }
+}
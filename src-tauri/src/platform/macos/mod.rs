@@ -1,3 +1,5 @@
 //! macOS-specific platform implementations.
 
+pub mod insertion;
 pub mod selection;
+pub mod window;
@@ -40,14 +40,14 @@ fn is_valid_selection(text: &str) -> bool {
     // Reject VS Code webview panel identifiers
     if text.contains("webview-panel/") || text.contains("webview-") {
         #[cfg(debug_assertions)]
-        println!("[SELECTION] Rejecting VS Code webview identifier");
+        log::debug!("[SELECTION] Rejecting VS Code webview identifier");
         return false;
     }
 
     // Reject paths that look like internal URIs
     if text.starts_with("vscode-") || text.starts_with("file://") {
         #[cfg(debug_assertions)]
-        println!("[SELECTION] Rejecting internal URI");
+        log::debug!("[SELECTION] Rejecting internal URI");
         return false;
     }
 
@@ -56,7 +56,7 @@ fn is_valid_selection(text: &str) -> bool {
     if UUID_PATTERN.is_match(text) && text.len() < 100 {
         // Short text that's mostly a UUID is likely an internal identifier
         #[cfg(debug_assertions)]
-        println!("[SELECTION] Rejecting UUID-like selection");
+        log::debug!("[SELECTION] Rejecting UUID-like selection");
         return false;
     }
 
@@ -96,7 +96,7 @@ pub fn get_selected_text() -> Result<String, SelectionError> {
             } else {
                 // Note: Not logging selected text content to avoid leaking sensitive data
                 #[cfg(debug_assertions)]
-                println!("[SELECTION] Got {} chars", text.len());
+                log::debug!("[SELECTION] Got {} chars", text.len());
                 Ok(text)
             }
         }
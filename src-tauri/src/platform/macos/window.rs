@@ -0,0 +1,34 @@
+//! macOS frontmost window information via System Events (Accessibility).
+
+use crate::permissions;
+use std::process::Command;
+
+/// Title of the frontmost app's front window, e.g. "main.py — project".
+/// None if osascript is unavailable, the app has no window, or the title
+/// couldn't be read (Accessibility permission missing).
+pub fn frontmost_window_title() -> Option<String> {
+    if !permissions::is_osascript_available() {
+        return None;
+    }
+
+    let script = "tell application \"System Events\"\n    tell (first application process whose frontmost is true)\n        get name of front window\n    end tell\nend tell";
+    match Command::new("osascript").arg("-e").arg(script).output() {
+        Ok(output) if output.status.success() => {
+            let title = String::from_utf8_lossy(&output.stdout).trim().to_string();
+            (!title.is_empty() && title != "missing value").then_some(title)
+        }
+        Ok(_output) => {
+            #[cfg(debug_assertions)]
+            log::debug!(
+                "[WINDOW] Couldn't read window title: {}",
+                String::from_utf8_lossy(&_output.stderr).trim()
+            );
+            None
+        }
+        Err(e) => {
+            log::error!("[WINDOW] Failed to execute osascript: {}", e);
+            permissions::detect_osascript();
+            None
+        }
+    }
+}
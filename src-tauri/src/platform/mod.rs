@@ -3,7 +3,9 @@
 //! This module provides platform-specific implementations for:
 //! - Getting selected text (via Accessibility API on macOS)
 //! - Detecting active application
+//! - Reading the frontmost window title (via Accessibility on macOS)
 //! - Replacing selected text
+//! - Inserting text at the cursor (typing or clipboard paste)
 
 #[cfg(target_os = "macos")]
 pub mod macos;
@@ -11,8 +13,15 @@ pub mod macos;
 #[cfg(target_os = "linux")]
 pub mod linux;
 
+#[cfg(target_os = "windows")]
+pub mod windows;
+
 #[cfg(target_os = "macos")]
-pub use macos::selection;
+pub use macos::{insertion, selection, window};
 
 #[cfg(target_os = "linux")]
-pub use linux::selection;
+pub use linux::{insertion, selection, window};
+
+// `self::` - a bare `windows` would be ambiguous with the `windows` crate
+#[cfg(target_os = "windows")]
+pub use self::windows::{insertion, selection, window};
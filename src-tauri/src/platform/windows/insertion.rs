@@ -0,0 +1,253 @@
+//! Windows text insertion via `SendInput`.
+//!
+//! ASCII text is typed as synthesized key events; Unicode text is pasted with
+//! Ctrl+V through the clipboard, whose original text is restored afterwards
+//! (mirroring the macOS clipboard-preserving path).
+
+use std::thread;
+use std::time::Duration;
+use windows::Win32::Foundation::{GlobalFree, HANDLE, HGLOBAL};
+use windows::Win32::System::DataExchange::{
+    CloseClipboard, EmptyClipboard, GetClipboardData, IsClipboardFormatAvailable, OpenClipboard,
+    SetClipboardData,
+};
+use windows::Win32::System::Memory::{GlobalAlloc, GlobalLock, GlobalUnlock, GMEM_MOVEABLE};
+use windows::Win32::System::Ole::CF_UNICODETEXT;
+use windows::Win32::UI::Input::KeyboardAndMouse::{
+    SendInput, INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYBD_EVENT_FLAGS, KEYEVENTF_KEYUP,
+    KEYEVENTF_UNICODE, VIRTUAL_KEY, VK_BACK, VK_CONTROL, VK_RETURN, VK_V, VK_Z,
+};
+
+/// Maximum characters per SendInput batch; longer texts are sent in chunks
+/// with small delays so the target app doesn't drop input
+const KEYSTROKE_CHUNK_SIZE: usize = 500;
+
+/// Insert text at the cursor of the foreground window, typing it or pasting it.
+/// Fails if the input could not be sent.
+pub fn insert_text(text: &str, use_clipboard: bool) -> Result<(), String> {
+    let inserted = if use_clipboard {
+        insert_via_clipboard_preserving(text)
+    } else {
+        insert_via_keystroke(text)
+    };
+    if inserted {
+        Ok(())
+    } else {
+        Err("The input couldn't be sent to the app".to_string())
+    }
+}
+
+/// Press Backspace `count` times in the foreground window, removing text that
+/// was just typed. Returns false if the input could not be sent.
+pub fn delete_backward(count: usize) -> bool {
+    let inputs: Vec<INPUT> = (0..count)
+        .flat_map(|_| {
+            [
+                key_input(VK_BACK, 0, KEYBD_EVENT_FLAGS(0)),
+                key_input(VK_BACK, 0, KEYEVENTF_KEYUP),
+            ]
+        })
+        .collect();
+    log::info!("[KEYSTROKE] Deleting {} chars", count);
+    send_inputs(&inputs)
+}
+
+/// Press Ctrl+Z in the foreground window, undoing a paste in one step.
+/// Returns false if the input could not be sent.
+pub fn undo() -> bool {
+    send_inputs(&[
+        key_input(VK_CONTROL, 0, KEYBD_EVENT_FLAGS(0)),
+        key_input(VK_Z, 0, KEYBD_EVENT_FLAGS(0)),
+        key_input(VK_Z, 0, KEYEVENTF_KEYUP),
+        key_input(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+    ])
+}
+
+fn key_input(vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS) -> INPUT {
+    INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT {
+                wVk: vk,
+                wScan: scan,
+                dwFlags: flags,
+                time: 0,
+                dwExtraInfo: 0,
+            },
+        },
+    }
+}
+
+/// Key down + key up events for one character. Newlines are sent as Return.
+fn char_inputs(c: char) -> Vec<INPUT> {
+    if c == '\n' {
+        return vec![
+            key_input(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)),
+            key_input(VK_RETURN, 0, KEYEVENTF_KEYUP),
+        ];
+    }
+
+    let mut units = [0u16; 2];
+    c.encode_utf16(&mut units)
+        .iter()
+        .flat_map(|&unit| {
+            [
+                key_input(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE),
+                key_input(VIRTUAL_KEY(0), unit, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP),
+            ]
+        })
+        .collect()
+}
+
+/// Send a batch of input events, returning false if any were blocked
+/// (e.g. by UIPI when the target runs elevated).
+fn send_inputs(inputs: &[INPUT]) -> bool {
+    let sent = unsafe { SendInput(inputs, std::mem::size_of::<INPUT>() as i32) };
+    if sent as usize != inputs.len() {
+        log::error!(
+            "[KEYSTROKE] SendInput inserted {}/{} events: {}",
+            sent,
+            inputs.len(),
+            windows::core::Error::from_thread()
+        );
+        return false;
+    }
+    true
+}
+
+/// Type text with SendInput (doesn't touch the clipboard).
+fn insert_via_keystroke(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().collect();
+    let total_chunks = chars.len().div_ceil(KEYSTROKE_CHUNK_SIZE);
+
+    log::info!(
+        "[KEYSTROKE] Inserting {} chars in {} chunks",
+        chars.len(),
+        total_chunks
+    );
+
+    for (i, chunk) in chars.chunks(KEYSTROKE_CHUNK_SIZE).enumerate() {
+        let inputs: Vec<INPUT> = chunk.iter().flat_map(|&c| char_inputs(c)).collect();
+        if !send_inputs(&inputs) {
+            crate::sentry_capture_error(&format!("SendInput chunk {} failed", i + 1), None);
+            return false;
+        }
+
+        // Small delay between chunks to let the target app process
+        if i + 1 < total_chunks {
+            thread::sleep(Duration::from_millis(20));
+        }
+    }
+
+    log::info!("[KEYSTROKE] Text inserted via SendInput (clipboard untouched)");
+    true
+}
+
+/// Insert text via clipboard + Ctrl+V, preserving the user's original clipboard text.
+fn insert_via_clipboard_preserving(text: &str) -> bool {
+    let original = read_clipboard_text();
+
+    if let Err(e) = write_clipboard_text(text) {
+        log::error!("[INSERT] Failed to set clipboard: {}", e);
+        return false;
+    }
+
+    // Small delay to ensure clipboard is ready
+    thread::sleep(Duration::from_millis(50));
+
+    let pasted = send_inputs(&[
+        key_input(VK_CONTROL, 0, KEYBD_EVENT_FLAGS(0)),
+        key_input(VK_V, 0, KEYBD_EVENT_FLAGS(0)),
+        key_input(VK_V, 0, KEYEVENTF_KEYUP),
+        key_input(VK_CONTROL, 0, KEYEVENTF_KEYUP),
+    ]);
+
+    // Delay before restoring (give paste time to complete)
+    thread::sleep(Duration::from_millis(150));
+
+    if let Some(original) = original {
+        if let Err(e) = write_clipboard_text(&original) {
+            log::warn!("[INSERT] Failed to restore clipboard: {}", e);
+        }
+    }
+
+    if pasted {
+        log::info!("Unicode text inserted via clipboard (original clipboard restored)");
+    }
+    pasted
+}
+
+/// Holds the clipboard open; closes it when dropped.
+struct ClipboardGuard;
+
+impl ClipboardGuard {
+    fn open() -> Result<Self, String> {
+        // The clipboard may be briefly held by another app - retry a few times
+        for _ in 0..5 {
+            if unsafe { OpenClipboard(None) }.is_ok() {
+                return Ok(ClipboardGuard);
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Err(format!(
+            "Failed to open clipboard: {}",
+            windows::core::Error::from_thread()
+        ))
+    }
+}
+
+impl Drop for ClipboardGuard {
+    fn drop(&mut self) {
+        let _ = unsafe { CloseClipboard() };
+    }
+}
+
+/// Current clipboard text, or None if it is empty or holds non-text data.
+fn read_clipboard_text() -> Option<String> {
+    let _guard = ClipboardGuard::open().ok()?;
+
+    unsafe {
+        IsClipboardFormatAvailable(CF_UNICODETEXT.0 as u32).ok()?;
+        let handle = GetClipboardData(CF_UNICODETEXT.0 as u32).ok()?;
+        let hglobal = HGLOBAL(handle.0);
+        let ptr = GlobalLock(hglobal) as *const u16;
+        if ptr.is_null() {
+            return None;
+        }
+
+        let mut len = 0;
+        while *ptr.add(len) != 0 {
+            len += 1;
+        }
+        let text = String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len));
+        let _ = GlobalUnlock(hglobal);
+        Some(text)
+    }
+}
+
+/// Replace the clipboard contents with `text`.
+fn write_clipboard_text(text: &str) -> Result<(), String> {
+    let wide: Vec<u16> = text.encode_utf16().chain(std::iter::once(0)).collect();
+    let _guard = ClipboardGuard::open()?;
+
+    unsafe {
+        EmptyClipboard().map_err(|e| format!("Failed to empty clipboard: {}", e))?;
+
+        let hglobal = GlobalAlloc(GMEM_MOVEABLE, wide.len() * std::mem::size_of::<u16>())
+            .map_err(|e| format!("Failed to allocate clipboard memory: {}", e))?;
+        let ptr = GlobalLock(hglobal) as *mut u16;
+        if ptr.is_null() {
+            let _ = GlobalFree(Some(hglobal));
+            return Err("Failed to lock clipboard memory".to_string());
+        }
+        std::ptr::copy_nonoverlapping(wide.as_ptr(), ptr, wide.len());
+        let _ = GlobalUnlock(hglobal);
+
+        // On success the clipboard owns the memory; free it only on failure
+        if let Err(e) = SetClipboardData(CF_UNICODETEXT.0 as u32, Some(HANDLE(hglobal.0))) {
+            let _ = GlobalFree(Some(hglobal));
+            return Err(format!("Failed to set clipboard data: {}", e));
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,9 @@
+//! Windows-specific platform implementations.
+
+pub mod insertion;
+
+// Selection and window titles are not implemented on Windows yet; share the Linux stubs.
+#[path = "../linux/selection.rs"]
+pub mod selection;
+#[path = "../linux/window.rs"]
+pub mod window;
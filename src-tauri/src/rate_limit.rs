@@ -2,6 +2,7 @@
 //!
 //! Implements per-service rate limits using a token bucket algorithm.
 
+use serde::Serialize;
 use std::collections::HashMap;
 use std::sync::Mutex;
 use std::time::{Duration, Instant};
@@ -48,6 +49,16 @@ impl RateLimitConfig {
             min_interval: Duration::from_millis(100),
         }
     }
+
+    /// Provider health checks (settings "test connection" button)
+    /// Kept small so repeated clicks can't eat into the dictation budget
+    pub fn health_check() -> Self {
+        Self {
+            max_requests: 5,
+            window: Duration::from_secs(60),
+            min_interval: Duration::from_secs(2),
+        }
+    }
 }
 
 /// Tracks request history for a single service
@@ -73,8 +84,24 @@ impl ServiceRateState {
         self.requests.retain(|&t| t > cutoff);
     }
 
-    /// Check if a request is allowed and record it if so
-    fn check_and_record(&mut self, config: &RateLimitConfig) -> Result<(), RateLimitError> {
+    /// Check if `count` back-to-back requests are allowed and record them if so
+    fn check_and_record(
+        &mut self,
+        config: &RateLimitConfig,
+        count: u32,
+    ) -> Result<(), RateLimitError> {
+        self.check(config, count)?;
+
+        // Requests are allowed - record them
+        let now = Instant::now();
+        self.requests
+            .extend(std::iter::repeat_n(now, count as usize));
+        self.last_request = Some(now);
+        Ok(())
+    }
+
+    /// Check if `count` requests would be allowed, without recording them
+    fn check(&mut self, config: &RateLimitConfig, count: u32) -> Result<(), RateLimitError> {
         let now = Instant::now();
 
         // Check minimum interval between requests
@@ -92,23 +119,57 @@ impl ServiceRateState {
         self.cleanup(config.window);
 
         // Check window limit
-        if self.requests.len() >= config.max_requests as usize {
-            // Find when the oldest request will expire
-            if let Some(&oldest) = self.requests.first() {
-                let expires_at = oldest + config.window;
-                let wait = expires_at.duration_since(now);
-                return Err(RateLimitError::WindowExceeded {
-                    limit: config.max_requests,
-                    wait_ms: wait.as_millis() as u64,
-                });
-            }
+        let over =
+            (self.requests.len() + count as usize).saturating_sub(config.max_requests as usize);
+        if over > 0 {
+            // Find when enough of the oldest requests will have expired
+            let wait = self
+                .requests
+                .get(over - 1)
+                .map(|&oldest| (oldest + config.window).saturating_duration_since(now))
+                .unwrap_or(config.window);
+            return Err(RateLimitError::WindowExceeded {
+                limit: config.max_requests,
+                wait_ms: wait.as_millis() as u64,
+            });
         }
 
-        // Request is allowed - record it
-        self.requests.push(now);
-        self.last_request = Some(now);
         Ok(())
     }
+
+    /// Remaining budget and time until the oldest request leaves the window
+    fn info(&mut self, service: Service, config: &RateLimitConfig) -> RateLimitInfo {
+        self.cleanup(config.window);
+        let reset_in_ms = self
+            .requests
+            .first()
+            .map(|&oldest| {
+                (oldest + config.window)
+                    .saturating_duration_since(Instant::now())
+                    .as_millis() as u64
+            })
+            .unwrap_or(0);
+        RateLimitInfo {
+            service,
+            max_requests: config.max_requests,
+            remaining: config
+                .max_requests
+                .saturating_sub(self.requests.len() as u32),
+            reset_in_ms,
+        }
+    }
+}
+
+/// Current budget for one service, for "slow down" feedback in the UI
+#[derive(Debug, Clone, Serialize)]
+pub struct RateLimitInfo {
+    pub service: Service,
+    /// Requests allowed per window
+    pub max_requests: u32,
+    /// Requests left in the current window
+    pub remaining: u32,
+    /// Time until the oldest request in the window expires (0 when none)
+    pub reset_in_ms: u64,
 }
 
 /// Rate limit error types
@@ -139,19 +200,50 @@ impl std::fmt::Display for RateLimitError {
 
 impl std::error::Error for RateLimitError {}
 
+impl RateLimitError {
+    /// How long to wait before the request would be allowed
+    pub fn retry_after_ms(&self) -> u64 {
+        match self {
+            RateLimitError::TooFast { wait_ms }
+            | RateLimitError::WindowExceeded { wait_ms, .. } => *wait_ms,
+        }
+    }
+}
+
 /// Service identifiers for rate limiting
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Service {
     WhisperApi,
+    Deepgram,
     Groq,
+    HealthCheck,
 }
 
 impl Service {
+    pub const ALL: [Service; 4] = [
+        Service::WhisperApi,
+        Service::Deepgram,
+        Service::Groq,
+        Service::HealthCheck,
+    ];
+
+    /// Name shown to the user
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Service::WhisperApi => "Groq Whisper",
+            Service::Deepgram => "Deepgram",
+            Service::Groq => "Groq",
+            Service::HealthCheck => "health checks",
+        }
+    }
+
     /// Get the rate limit configuration for this service
     pub fn config(&self) -> RateLimitConfig {
         match self {
-            Service::WhisperApi => RateLimitConfig::transcription(),
+            Service::WhisperApi | Service::Deepgram => RateLimitConfig::transcription(),
             Service::Groq => RateLimitConfig::llm_enhancement(),
+            Service::HealthCheck => RateLimitConfig::health_check(),
         }
     }
 }
@@ -173,6 +265,12 @@ impl RateLimiter {
     ///
     /// Returns Ok(()) if allowed, Err(RateLimitError) if rate limited
     pub fn check(&self, service: Service) -> Result<(), RateLimitError> {
+        self.check_many(service, 1)
+    }
+
+    /// Check that `count` requests to the service are allowed and count them
+    /// all now, so a multi-request job isn't cut off halfway by the limit
+    pub fn check_many(&self, service: Service, count: u32) -> Result<(), RateLimitError> {
         let config = service.config();
         let mut states = self
             .states
@@ -180,7 +278,37 @@ impl RateLimiter {
             .map_err(|_| RateLimitError::TooFast { wait_ms: 100 })?;
 
         let state = states.entry(service).or_insert_with(ServiceRateState::new);
-        state.check_and_record(&config)
+        state.check_and_record(&config, count)
+    }
+
+    /// Check whether a request would be allowed, without counting one
+    pub fn peek(&self, service: Service) -> Result<(), RateLimitError> {
+        let config = service.config();
+        let mut states = self
+            .states
+            .lock()
+            .map_err(|_| RateLimitError::TooFast { wait_ms: 100 })?;
+        match states.get_mut(&service) {
+            Some(state) => state.check(&config, 1),
+            None => Ok(()),
+        }
+    }
+
+    /// Remaining budget for every service
+    pub fn status(&self) -> Vec<RateLimitInfo> {
+        let mut states = match self.states.lock() {
+            Ok(states) => states,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        Service::ALL
+            .iter()
+            .map(|&service| {
+                states
+                    .entry(service)
+                    .or_insert_with(ServiceRateState::new)
+                    .info(service, &service.config())
+            })
+            .collect()
     }
 
     /// Check rate limit and return a user-friendly error string if limited
@@ -209,6 +337,29 @@ pub fn check_rate_limit(service: Service) -> Result<(), String> {
     rate_limiter().check_or_error(service)
 }
 
+/// Reserve `count` requests to a service at once using the global limiter
+pub fn check_rate_limit_many(service: Service, count: u32) -> Result<(), String> {
+    rate_limiter()
+        .check_many(service, count)
+        .map_err(|e| e.to_string())
+}
+
+/// A structured error if the next request to `service` would be rate limited
+/// (nothing is counted)
+pub fn peek_rate_limit(service: Service) -> Result<(), crate::error::AppError> {
+    rate_limiter()
+        .peek(service)
+        .map_err(|e| crate::error::AppError::RateLimitExceeded {
+            service: service.display_name().to_string(),
+            retry_after_ms: Some(e.retry_after_ms()),
+        })
+}
+
+/// Remaining budget for every service using the global limiter
+pub fn rate_limit_status() -> Vec<RateLimitInfo> {
+    rate_limiter().status()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -268,6 +419,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_check_many_reserves_all_slots() {
+        let limiter = RateLimiter::new();
+        let service = Service::WhisperApi;
+        let config = service.config();
+
+        assert!(limiter.check_many(service, 8).is_ok());
+        let status = limiter.status();
+        let whisper = status.iter().find(|info| info.service == service).unwrap();
+        assert_eq!(whisper.remaining, config.max_requests - 8);
+
+        // Not enough budget left for another batch of the same size
+        thread::sleep(config.min_interval);
+        assert!(limiter.check_many(service, 8).is_ok());
+        thread::sleep(config.min_interval);
+        let result = limiter.check_many(service, 8);
+        assert!(
+            matches!(result, Err(RateLimitError::WindowExceeded { wait_ms, .. }) if wait_ms > 0),
+            "Expected WindowExceeded error, got {:?}",
+            result
+        );
+    }
+
     #[test]
     fn test_different_services_independent() {
         let limiter = RateLimiter::new();
@@ -283,6 +457,41 @@ mod tests {
         assert!(matches!(result, Err(RateLimitError::TooFast { .. })));
     }
 
+    #[test]
+    fn test_peek_does_not_record() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.peek(Service::Groq).is_ok());
+        assert!(limiter.peek(Service::Groq).is_ok());
+        assert!(limiter.check(Service::Groq).is_ok());
+
+        // Now inside min_interval
+        let result = limiter.peek(Service::Groq);
+        assert!(matches!(result, Err(RateLimitError::TooFast { .. })));
+        assert!(result.unwrap_err().retry_after_ms() > 0);
+    }
+
+    #[test]
+    fn test_status_reports_remaining_budget() {
+        let limiter = RateLimiter::new();
+        assert!(limiter.check(Service::Deepgram).is_ok());
+
+        let status = limiter.status();
+        assert_eq!(status.len(), Service::ALL.len());
+        let deepgram = status
+            .iter()
+            .find(|info| info.service == Service::Deepgram)
+            .unwrap();
+        assert_eq!(deepgram.remaining, deepgram.max_requests - 1);
+        assert!(deepgram.reset_in_ms > 0);
+
+        let groq = status
+            .iter()
+            .find(|info| info.service == Service::Groq)
+            .unwrap();
+        assert_eq!(groq.remaining, groq.max_requests);
+        assert_eq!(groq.reset_in_ms, 0);
+    }
+
     #[test]
     fn test_rate_limit_error_display() {
         let err = RateLimitError::TooFast { wait_ms: 500 };
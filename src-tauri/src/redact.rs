@@ -0,0 +1,86 @@
+//! Masking of sensitive content in dictated text.
+//!
+//! Transcripts sometimes contain things that shouldn't reach the LLM, the
+//! logs or the history - card numbers, passwords read out loud, pasted API
+//! keys. `RedactMode` controls what the pipeline does when one is detected.
+
+use crate::error::SECRET_PATTERNS;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// What to do with transcripts that look like they contain secrets or card
+/// numbers (see `redact_sensitive_text`). Detection is heuristic: it catches
+/// common shapes, not every secret, and can flag harmless text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactMode {
+    /// Process transcripts as-is
+    #[default]
+    Off,
+    /// Replace the sensitive parts with [REDACTED] before enhancement and
+    /// logging. The inserted text is masked too.
+    Mask,
+    /// Insert the raw transcript without LLM enhancement, and keep it out of
+    /// the logs and history. Command Mode needs the LLM, so it masks instead.
+    SkipEnhancement,
+}
+
+// Shapes that show up in dictated text, checked after SECRET_PATTERNS.
+// SAFETY: unwrap() is safe - these are constant, valid patterns.
+static TRANSCRIPT_PATTERNS: LazyLock<Vec<(Regex, &'static str)>> = LazyLock::new(|| {
+    vec![
+        // Card, account and ID numbers: 9+ digits in a row, or card-style groups of 4
+        (
+            Regex::new(r"\b(?:\d{9,}|\d{4}(?:[ -]\d{4}){2,3}(?:[ -]\d{1,4})?)\b").unwrap(),
+            "[REDACTED]",
+        ),
+        // "my password is hunter2", "PIN: 4321"
+        (
+            Regex::new(r"(?i)\b(password|passcode|passphrase|pin)(\s+is|\s*:)\s+\S+").unwrap(),
+            "$1$2 [REDACTED]",
+        ),
+    ]
+});
+
+/// Mask things in a transcript that look like secrets (API keys, tokens,
+/// passwords) or card and account numbers. Returns None when nothing matched.
+pub fn redact_sensitive_text(text: &str) -> Option<String> {
+    let mut redacted = text.to_string();
+    for (pattern, replacement) in SECRET_PATTERNS.iter().chain(TRANSCRIPT_PATTERNS.iter()) {
+        redacted = pattern.replace_all(&redacted, *replacement).into_owned();
+    }
+    (redacted != text).then_some(redacted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redact_sensitive_text() {
+        assert_eq!(
+            redact_sensitive_text("My card is 4111 1111 1111 1111, expiring in 2027."),
+            Some("My card is [REDACTED], expiring in 2027.".to_string())
+        );
+        assert_eq!(
+            redact_sensitive_text("Account 123456789012 please"),
+            Some("Account [REDACTED] please".to_string())
+        );
+        assert_eq!(
+            redact_sensitive_text("The wifi password is hunter2 okay"),
+            Some("The wifi password is [REDACTED] okay".to_string())
+        );
+        assert_eq!(
+            redact_sensitive_text("Use the key gsk_abc123DEF456ghi789"),
+            Some("Use the key gsk_[REDACTED]".to_string())
+        );
+
+        // Ordinary numbers and words are left alone
+        assert_eq!(
+            redact_sensitive_text("Meet at 10:30 in room 4012 on May 5th 2026"),
+            None
+        );
+        assert_eq!(redact_sensitive_text("Reset your password tomorrow"), None);
+    }
+}
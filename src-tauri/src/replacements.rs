@@ -0,0 +1,315 @@
+//! User-managed find→replace list (autocorrect).
+//!
+//! Applied as the very last step before insertion, after IDE transforms and
+//! punctuation cleanup. This is an escape hatch for stubborn misrecognitions
+//! ("clawed" → "Claude") that the Whisper prompt and IDE dictionary don't fix.
+//!
+//! `@`-tagged filenames produced by the file tagger are never modified.
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+use crate::config::StoredPreferences;
+
+/// Maximum number of replacement rules a user can store
+const MAX_RULES: usize = 500;
+
+/// Maximum length of the find/replace strings (characters)
+const MAX_PATTERN_LENGTH: usize = 200;
+
+/// Matches `@`-tagged filenames (e.g. `@src/main.rs`) so they can be protected.
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static AT_TAG_PATTERN: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"@[^\s]+").unwrap());
+
+/// A single find→replace rule.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    pub id: String,
+    pub find: String,
+    pub replace: String,
+    /// Only match whole words (default), so "cat" doesn't match inside "category"
+    #[serde(default = "default_true")]
+    pub whole_word: bool,
+    /// Match case exactly (default: case-insensitive)
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl ReplacementRule {
+    /// Create a new rule with a random id.
+    pub fn new(find: String, replace: String, whole_word: bool, case_sensitive: bool) -> Self {
+        ReplacementRule {
+            id: generate_rule_id(),
+            find,
+            replace,
+            whole_word,
+            case_sensitive,
+        }
+    }
+
+    /// Validate the rule's find/replace strings.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.find.trim().is_empty() {
+            return Err("Find text cannot be empty".to_string());
+        }
+        if self.find.chars().count() > MAX_PATTERN_LENGTH
+            || self.replace.chars().count() > MAX_PATTERN_LENGTH
+        {
+            return Err(format!(
+                "Replacement text too long (max {} characters)",
+                MAX_PATTERN_LENGTH
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compile the rule into a regex.
+    /// Word boundaries are only added on edges that start/end with a word character,
+    /// since `\b` next to punctuation would never match.
+    fn to_regex(&self) -> Option<Regex> {
+        let escaped = regex::escape(&self.find);
+        let pattern = if self.whole_word {
+            let starts_word = self.find.chars().next().is_some_and(is_word_char);
+            let ends_word = self.find.chars().last().is_some_and(is_word_char);
+            format!(
+                "{}{}{}",
+                if starts_word { r"\b" } else { "" },
+                escaped,
+                if ends_word { r"\b" } else { "" }
+            )
+        } else {
+            escaped
+        };
+
+        RegexBuilder::new(&pattern)
+            .case_insensitive(!self.case_sensitive)
+            .build()
+            .ok()
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+fn generate_rule_id() -> String {
+    use rand::rngs::OsRng;
+    use rand::RngCore;
+
+    format!("{:016x}", OsRng.next_u64())
+}
+
+/// Apply replacement rules to text, in order.
+///
+/// # Examples
+/// ```ignore
+/// let rules = vec![ReplacementRule::new("clawed".into(), "Claude".into(), true, false)];
+/// assert_eq!(apply_replacements("Ask clawed", &rules), "Ask Claude");
+/// ```
+pub fn apply_replacements(text: &str, rules: &[ReplacementRule]) -> String {
+    let mut result = text.to_string();
+
+    for rule in rules {
+        if rule.find.is_empty() {
+            continue;
+        }
+        let Some(regex) = rule.to_regex() else {
+            continue;
+        };
+
+        // Recompute protected spans per rule since earlier rules may shift offsets
+        let protected: Vec<(usize, usize)> = AT_TAG_PATTERN
+            .find_iter(&result)
+            .map(|m| (m.start(), m.end()))
+            .collect();
+
+        result = regex
+            .replace_all(&result, |caps: &regex::Captures| {
+                let m = caps.get(0).expect("group 0 always exists");
+                let in_tag = protected
+                    .iter()
+                    .any(|(start, end)| m.start() < *end && m.end() > *start);
+                if in_tag {
+                    m.as_str().to_string()
+                } else {
+                    rule.replace.clone()
+                }
+            })
+            .to_string();
+    }
+
+    result
+}
+
+/// Load the user's replacement rules from stored preferences.
+pub fn load_rules() -> Vec<ReplacementRule> {
+    StoredPreferences::load().replacements.unwrap_or_default()
+}
+
+/// Persist the user's replacement rules to stored preferences.
+fn save_rules(rules: Vec<ReplacementRule>) -> Result<(), String> {
+    let mut stored = StoredPreferences::load();
+    stored.replacements = Some(rules);
+    stored.save()
+}
+
+/// Add a new rule and persist it.
+pub fn add_rule(rule: ReplacementRule) -> Result<ReplacementRule, String> {
+    rule.validate()?;
+    let mut rules = load_rules();
+    if rules.len() >= MAX_RULES {
+        return Err(format!("Too many replacements (max {})", MAX_RULES));
+    }
+    rules.push(rule.clone());
+    save_rules(rules)?;
+    Ok(rule)
+}
+
+/// Replace an existing rule (matched by id) and persist it.
+pub fn update_rule(rule: ReplacementRule) -> Result<(), String> {
+    rule.validate()?;
+    let mut rules = load_rules();
+    let existing = rules
+        .iter_mut()
+        .find(|r| r.id == rule.id)
+        .ok_or_else(|| format!("Replacement not found: {}", rule.id))?;
+    *existing = rule;
+    save_rules(rules)
+}
+
+/// Remove a rule by id and persist the change.
+pub fn remove_rule(id: &str) -> Result<(), String> {
+    let mut rules = load_rules();
+    let before = rules.len();
+    rules.retain(|r| r.id != id);
+    if rules.len() == before {
+        return Err(format!("Replacement not found: {}", id));
+    }
+    save_rules(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(find: &str, replace: &str) -> ReplacementRule {
+        ReplacementRule::new(find.to_string(), replace.to_string(), true, false)
+    }
+
+    #[test]
+    fn test_basic_replacement() {
+        let rules = vec![rule("clawed", "Claude")];
+        assert_eq!(
+            apply_replacements("Ask clawed now", &rules),
+            "Ask Claude now"
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_by_default() {
+        let rules = vec![rule("clawed", "Claude")];
+        assert_eq!(
+            apply_replacements("Clawed said hi", &rules),
+            "Claude said hi"
+        );
+    }
+
+    #[test]
+    fn test_case_sensitive() {
+        let rules = vec![ReplacementRule::new(
+            "Key hold".to_string(),
+            "Keyhold".to_string(),
+            true,
+            true,
+        )];
+        assert_eq!(
+            apply_replacements("Key hold and key hold", &rules),
+            "Keyhold and key hold"
+        );
+    }
+
+    #[test]
+    fn test_whole_word_skips_partial_words() {
+        let rules = vec![rule("cat", "dog")];
+        assert_eq!(
+            apply_replacements("cat category concat", &rules),
+            "dog category concat"
+        );
+    }
+
+    #[test]
+    fn test_partial_word_when_whole_word_disabled() {
+        let rules = vec![ReplacementRule::new(
+            "colour".to_string(),
+            "color".to_string(),
+            false,
+            false,
+        )];
+        assert_eq!(apply_replacements("colours", &rules), "colors");
+    }
+
+    #[test]
+    fn test_does_not_corrupt_at_tags() {
+        let rules = vec![rule("main", "primary")];
+        assert_eq!(
+            apply_replacements("Check @src/main.rs for the main loop", &rules),
+            "Check @src/main.rs for the primary loop"
+        );
+    }
+
+    #[test]
+    fn test_at_tag_protected_without_word_boundary() {
+        let rules = vec![ReplacementRule::new(
+            "utils".to_string(),
+            "helpers".to_string(),
+            false,
+            false,
+        )];
+        assert_eq!(
+            apply_replacements("Open @utils.ts and the utils folder", &rules),
+            "Open @utils.ts and the helpers folder"
+        );
+    }
+
+    #[test]
+    fn test_punctuation_edges() {
+        let rules = vec![rule("c++", "C++")];
+        assert_eq!(
+            apply_replacements("I write c++ daily", &rules),
+            "I write C++ daily"
+        );
+    }
+
+    #[test]
+    fn test_rules_applied_in_order() {
+        let rules = vec![rule("foo", "bar"), rule("bar", "baz")];
+        assert_eq!(apply_replacements("foo", &rules), "baz");
+    }
+
+    #[test]
+    fn test_regex_characters_are_literal() {
+        let rules = vec![rule("a.b", "x")];
+        assert_eq!(apply_replacements("a.b acb", &rules), "x acb");
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(rule("", "x").validate().is_err());
+        assert!(rule("   ", "x").validate().is_err());
+        assert!(rule(&"a".repeat(201), "x").validate().is_err());
+        assert!(rule("ok", "").validate().is_ok());
+    }
+
+    #[test]
+    fn test_deserialize_defaults() {
+        let json = r#"{"id": "1", "find": "a", "replace": "b"}"#;
+        let rule: ReplacementRule = serde_json::from_str(json).unwrap();
+        assert!(rule.whole_word);
+        assert!(!rule.case_sensitive);
+    }
+}
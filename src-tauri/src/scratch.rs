@@ -0,0 +1,160 @@
+//! Scratch buffer for dictations that have nowhere to go.
+//!
+//! When no editable target is focused (the frontmost app isn't a text app,
+//! insertion fails, or the user fires the dedicated scratch hotkey), the
+//! result is saved here instead of being typed, so the thought isn't lost.
+//!
+//! Notes persist to `scratch.json` in the app's config directory.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Maximum number of notes kept (oldest are dropped first)
+const MAX_NOTES: usize = 200;
+
+/// Apps that never have an editable text field to type into.
+const NON_TEXT_BUNDLE_IDS: &[&str] = &[
+    "com.apple.finder",
+    "com.apple.dock",
+    "com.apple.loginwindow",
+    "com.apple.systempreferences",
+    "com.apple.SystemPreferences",
+    "com.apple.controlcenter",
+    "com.apple.notificationcenterui",
+    "com.apple.ActivityMonitor",
+    "com.apple.Preview",
+    "com.apple.Photos",
+    "com.apple.QuickTimePlayerX",
+    "com.apple.calculator",
+    "com.keyhold.app",
+];
+
+/// A dictation captured to the scratch buffer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScratchNote {
+    pub id: String,
+    pub text: String,
+    /// Unix timestamp in milliseconds
+    pub created_at: u64,
+    /// Bundle ID of the app that was frontmost when the note was captured
+    pub source_app: Option<String>,
+}
+
+/// Whether the given app is known to have no insertable text target.
+/// Unknown apps (and a missing bundle ID) are assumed to accept text.
+pub fn is_non_text_target(bundle_id: Option<&str>) -> bool {
+    bundle_id.is_some_and(|id| NON_TEXT_BUNDLE_IDS.contains(&id))
+}
+
+fn scratch_path() -> Option<PathBuf> {
+    dirs::config_dir().map(|d| d.join("keyhold").join("scratch.json"))
+}
+
+/// Load all scratch notes, newest last.
+pub fn load_notes() -> Vec<ScratchNote> {
+    scratch_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_notes(notes: &[ScratchNote]) -> Result<(), String> {
+    let path = scratch_path().ok_or("Could not find config directory")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(notes)
+        .map_err(|e| format!("Failed to serialize scratch notes: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write scratch notes: {}", e))
+}
+
+/// Append a note to the scratch buffer and persist it.
+pub fn add_note(text: &str, source_app: Option<&str>) -> Result<ScratchNote, String> {
+    let created_at = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+
+    let note = ScratchNote {
+        id: format!("{:x}", created_at),
+        text: text.to_string(),
+        created_at,
+        source_app: source_app.map(|s| s.to_string()),
+    };
+
+    let mut notes = load_notes();
+    notes.push(note.clone());
+    trim_to_limit(&mut notes);
+    save_notes(&notes)?;
+
+    Ok(note)
+}
+
+/// Delete a note by id.
+pub fn delete_note(id: &str) -> Result<(), String> {
+    let mut notes = load_notes();
+    notes.retain(|n| n.id != id);
+    save_notes(&notes)
+}
+
+/// Delete all notes.
+pub fn clear_notes() -> Result<(), String> {
+    save_notes(&[])
+}
+
+/// Drop the oldest notes beyond MAX_NOTES.
+fn trim_to_limit(notes: &mut Vec<ScratchNote>) {
+    if notes.len() > MAX_NOTES {
+        let excess = notes.len() - MAX_NOTES;
+        notes.drain(..excess);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_non_text_targets() {
+        assert!(is_non_text_target(Some("com.apple.finder")));
+        assert!(is_non_text_target(Some("com.keyhold.app")));
+        assert!(!is_non_text_target(Some("com.apple.TextEdit")));
+        assert!(!is_non_text_target(None));
+    }
+
+    #[test]
+    fn test_trim_to_limit_drops_oldest() {
+        let mut notes: Vec<ScratchNote> = (0..MAX_NOTES + 5)
+            .map(|i| ScratchNote {
+                id: i.to_string(),
+                text: format!("note {}", i),
+                created_at: i as u64,
+                source_app: None,
+            })
+            .collect();
+
+        trim_to_limit(&mut notes);
+
+        assert_eq!(notes.len(), MAX_NOTES);
+        assert_eq!(notes[0].id, "5");
+    }
+
+    #[test]
+    fn test_note_serialization() {
+        let note = ScratchNote {
+            id: "1".to_string(),
+            text: "remember the milk".to_string(),
+            created_at: 42,
+            source_app: Some("com.apple.finder".to_string()),
+        };
+        let json = serde_json::to_string(&note).unwrap();
+        assert!(json.contains("\"createdAt\":42"));
+        assert!(json.contains("\"sourceApp\":\"com.apple.finder\""));
+    }
+}
@@ -17,40 +17,61 @@ const HMAC_SECRET: &str = "d8a30062682b1fb12471dfd838779a7b6047e04a54e8ce0d440db
 pub const PROXY_URL_WHISPER: &str = "https://murmur-proxy.anurag-ebc.workers.dev/whisper";
 pub const PROXY_URL_CHAT: &str = "https://murmur-proxy.anurag-ebc.workers.dev/chat";
 
-/// Direct API URLs (for development with local API key)
-#[cfg(debug_assertions)]
+/// Direct API URLs (for development, or when the user disables the proxy)
 pub const DIRECT_API_URL_WHISPER: &str = "https://api.groq.com/openai/v1/audio/transcriptions";
-#[cfg(debug_assertions)]
 pub const DIRECT_API_URL_CHAT: &str = "https://api.groq.com/openai/v1/chat/completions";
 
-/// Get API configuration based on build type.
+/// Get API configuration based on build type and the `use_proxy` preference.
 ///
+/// - **Proxy disabled** (`use_proxy: false`): Always use the direct API with the user's
+///   own key (stored key, then GROQ_API_KEY). Errors if no key is set - never falls
+///   back to the proxy.
 /// - **Debug builds** (`npm run tauri dev`): Use direct API with GROQ_API_KEY from environment
 /// - **Release builds** (`npm run tauri build`): Always use proxy with HMAC auth
 ///
 /// Returns (whisper_url, chat_url, Option<api_key>)
-pub fn get_api_config() -> (&'static str, &'static str, Option<String>) {
+pub fn get_api_config() -> Result<(&'static str, &'static str, Option<String>), String> {
+    let use_proxy = crate::config::StoredPreferences::load()
+        .use_proxy
+        .unwrap_or(true);
+
+    if !use_proxy {
+        let key = user_api_key().ok_or_else(|| {
+            "Proxy is disabled but no Groq API key is set. Add your API key in Preferences."
+                .to_string()
+        })?;
+        return Ok((DIRECT_API_URL_WHISPER, DIRECT_API_URL_CHAT, Some(key)));
+    }
+
     #[cfg(debug_assertions)]
     {
         // Dev mode: use direct API if GROQ_API_KEY is available
         if let Ok(key) = std::env::var("GROQ_API_KEY") {
             if !key.is_empty() {
-                println!("[DEV] Using direct Groq API");
-                return (DIRECT_API_URL_WHISPER, DIRECT_API_URL_CHAT, Some(key));
+                log::info!("[DEV] Using direct Groq API");
+                return Ok((DIRECT_API_URL_WHISPER, DIRECT_API_URL_CHAT, Some(key)));
             }
         }
-        println!("[DEV] No GROQ_API_KEY, falling back to proxy");
-        (PROXY_URL_WHISPER, PROXY_URL_CHAT, None)
+        log::info!("[DEV] No GROQ_API_KEY, falling back to proxy");
+        Ok((PROXY_URL_WHISPER, PROXY_URL_CHAT, None))
     }
 
     #[cfg(not(debug_assertions))]
     {
         // Release mode: always use proxy
-        println!("[PROD] Using proxy");
-        (PROXY_URL_WHISPER, PROXY_URL_CHAT, None)
+        log::info!("[PROD] Using proxy");
+        Ok((PROXY_URL_WHISPER, PROXY_URL_CHAT, None))
     }
 }
 
+/// The user's own Groq API key: the stored key first, then GROQ_API_KEY.
+pub fn user_api_key() -> Option<String> {
+    crate::auth::storage::get_groq_api_key()
+        .ok()
+        .flatten()
+        .or_else(|| std::env::var("GROQ_API_KEY").ok().filter(|k| !k.is_empty()))
+}
+
 /// Generate a cryptographically secure nonce using a CSPRNG.
 /// The nonce is 32 random bytes (256 bits) hex-encoded.
 pub fn generate_nonce() -> String {
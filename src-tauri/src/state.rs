@@ -1,3 +1,4 @@
+use crate::error::AppError;
 use serde::{Deserialize, Serialize};
 
 /// The mode of operation based on whether text is selected
@@ -24,6 +25,8 @@ pub enum RecordingState {
     Idle,
     /// Actively capturing audio from microphone
     Recording,
+    /// Recording on hold: the stream stays open, but audio isn't kept
+    Paused,
     /// Audio captured, sending to Groq Whisper for transcription
     Transcribing,
     /// Transcript received, sending to Groq LLM for enhancement (Dictation Mode)
@@ -48,7 +51,7 @@ impl RecordingState {
 
     /// Check if we can stop recording from current state
     pub fn can_stop_recording(&self) -> bool {
-        matches!(self, RecordingState::Recording)
+        matches!(self, RecordingState::Recording | RecordingState::Paused)
     }
 
     /// Check if we can cancel from current state
@@ -56,6 +59,7 @@ impl RecordingState {
         matches!(
             self,
             RecordingState::Recording
+                | RecordingState::Paused
                 | RecordingState::Transcribing
                 | RecordingState::Enhancing
                 | RecordingState::Transforming
@@ -85,6 +89,31 @@ pub struct AudioLevelEvent {
     pub peak: f32,
 }
 
+/// Stats for the captured audio of one recording, emitted to the frontend
+/// when recording stops (including recordings that turn out to be empty).
+/// Local only - never sent anywhere.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingStatsEvent {
+    /// Length of the captured audio, before silence trimming
+    pub duration_ms: u64,
+    /// RMS level over the whole recording, normalized 0.0 to 1.0
+    pub avg_level: f32,
+    /// Peak level normalized 0.0 to 1.0
+    pub peak_level: f32,
+}
+
+/// Emitted (once per disconnection) when the microphone chosen in settings
+/// isn't connected and a recording starts on another one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MicrophoneFallbackEvent {
+    /// The microphone chosen in settings
+    pub configured_device: String,
+    /// The microphone being recorded from instead
+    pub fallback_device: String,
+}
+
 /// Transcription complete event payload
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -92,6 +121,45 @@ pub struct TranscriptionCompleteEvent {
     pub raw_transcript: String,
     pub enhanced_text: String,
     pub copied_to_clipboard: bool,
+    /// Per-stage timings for this dictation
+    #[serde(default)]
+    pub latency: LatencyReport,
+    /// Transcription confidence (0.0-1.0), if reported by the API
+    #[serde(default)]
+    pub confidence: Option<f32>,
+}
+
+/// Per-stage durations (ms) for a single dictation, from key release to text ready
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LatencyReport {
+    /// How long the user was recording
+    pub capture_ms: u64,
+    /// Stopping the stream, resampling and WAV encoding
+    pub audio_processing_ms: u64,
+    /// Whisper API round trip
+    pub transcribe_ms: u64,
+    /// IDE transformations (dictionary, CLI syntax, file tagging)
+    pub ide_transform_ms: u64,
+    /// LLM enhancement / intent classification / transformation
+    pub llm_ms: u64,
+    /// Total processing time after the recording stopped
+    pub total_ms: u64,
+}
+
+impl LatencyReport {
+    /// One-line human readable summary, e.g. "transcribe 800ms, enhance 400ms"
+    pub fn summary(&self) -> String {
+        format!(
+            "capture {}ms, audio {}ms, transcribe {}ms, ide {}ms, enhance {}ms, total {}ms",
+            self.capture_ms,
+            self.audio_processing_ms,
+            self.transcribe_ms,
+            self.ide_transform_ms,
+            self.llm_ms,
+            self.total_ms
+        )
+    }
 }
 
 /// Error event payload
@@ -102,6 +170,29 @@ pub struct ErrorEvent {
     pub message: String,
     pub recoverable: bool,
     pub fallback_text: Option<String>,
+    /// How long to wait before trying again (rate limiting)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+}
+
+impl From<AppError> for ErrorEvent {
+    fn from(error: AppError) -> Self {
+        let fallback_text = match error {
+            AppError::EnhancementFailed { ref fallback, .. } => fallback.clone(),
+            _ => None,
+        };
+        let retry_after_ms = match error {
+            AppError::RateLimitExceeded { retry_after_ms, .. } => retry_after_ms,
+            _ => None,
+        };
+        ErrorEvent {
+            code: error.code().to_string(),
+            message: error.user_message(),
+            recoverable: true,
+            fallback_text,
+            retry_after_ms,
+        }
+    }
 }
 
 impl ErrorEvent {
@@ -111,6 +202,7 @@ impl ErrorEvent {
             message: "No microphone found. Please connect a microphone.".to_string(),
             recoverable: true,
             fallback_text: None,
+            retry_after_ms: None,
         }
     }
 
@@ -120,6 +212,7 @@ impl ErrorEvent {
             message: "No audio was captured. Please try again.".to_string(),
             recoverable: true,
             fallback_text: None,
+            retry_after_ms: None,
         }
     }
 
@@ -129,6 +222,28 @@ impl ErrorEvent {
             message: format!("Transcription failed: {}", msg),
             recoverable: true,
             fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    /// A long recording was only partly transcribed; the rest is inserted anyway
+    pub fn partial_transcription(msg: &str) -> Self {
+        ErrorEvent {
+            code: "PARTIAL_TRANSCRIPTION".to_string(),
+            message: format!("Only part of the recording was transcribed: {}", msg),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn deepgram_error(msg: &str) -> Self {
+        ErrorEvent {
+            code: "DEEPGRAM_ERROR".to_string(),
+            message: format!("Deepgram transcription failed: {}", msg),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
         }
     }
 
@@ -138,6 +253,135 @@ impl ErrorEvent {
             message: format!("Groq LLM error: {}", msg),
             recoverable: true,
             fallback_text: fallback,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn missing_api_key() -> Self {
+        ErrorEvent {
+            code: "MISSING_API_KEY".to_string(),
+            message:
+                "Proxy is disabled but no Groq API key is set. Add your API key in Preferences."
+                    .to_string(),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn not_authenticated() -> Self {
+        ErrorEvent {
+            code: "NOT_AUTHENTICATED".to_string(),
+            message: "Sign in to start dictating.".to_string(),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn osascript_unavailable(text: Option<String>) -> Self {
+        ErrorEvent {
+            code: "OSASCRIPT_UNAVAILABLE".to_string(),
+            message: if text.is_some() {
+                "Typing into apps is disabled on this Mac. Text was copied to the clipboard."
+                    .to_string()
+            } else {
+                "Typing into apps is disabled on this Mac (osascript unavailable). Results will be copied to the clipboard instead."
+                    .to_string()
+            },
+            recoverable: true,
+            fallback_text: text,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn accessibility_lost(text: String, copied: bool) -> Self {
+        ErrorEvent {
+            code: "ACCESSIBILITY_LOST".to_string(),
+            message: if copied {
+                "Accessibility permission was revoked, so Murmur couldn't type. Your text was copied to the clipboard; re-enable Murmur in System Settings."
+                    .to_string()
+            } else {
+                "Accessibility permission was revoked, so Murmur couldn't type. Re-enable Murmur in System Settings."
+                    .to_string()
+            },
+            recoverable: true,
+            fallback_text: Some(text),
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn low_confidence(confidence: f32, blocked: bool, transcript: String) -> Self {
+        ErrorEvent {
+            code: "LOW_CONFIDENCE".to_string(),
+            message: if blocked {
+                format!(
+                    "Transcription confidence too low ({:.0}%). Text was not inserted.",
+                    confidence * 100.0
+                )
+            } else {
+                format!(
+                    "Low transcription confidence ({:.0}%). Please review the text.",
+                    confidence * 100.0
+                )
+            },
+            recoverable: true,
+            fallback_text: Some(transcript),
+            retry_after_ms: None,
+        }
+    }
+
+    /// Typing into the app failed; the text went to the scratch buffer instead
+    pub fn insertion_failed(reason: &str) -> Self {
+        ErrorEvent {
+            code: "INSERTION_FAILED".to_string(),
+            message: format!(
+                "Couldn't insert the text: {}. It was saved to the scratch buffer.",
+                reason.trim_end_matches('.')
+            ),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn undo_failed(reason: &str) -> Self {
+        ErrorEvent {
+            code: "UNDO_FAILED".to_string(),
+            message: format!("Couldn't undo the last insertion: {}", reason),
+            recoverable: true,
+            fallback_text: None,
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn enhancement_failed(msg: &str, copied: bool, transcript: String) -> Self {
+        ErrorEvent {
+            code: "ENHANCEMENT_FAILED".to_string(),
+            message: if copied {
+                format!(
+                    "Enhancement failed: {}. Nothing was inserted; the raw text was copied to the clipboard.",
+                    msg
+                )
+            } else {
+                format!("Enhancement failed: {}. Nothing was inserted.", msg)
+            },
+            recoverable: true,
+            fallback_text: Some(transcript),
+            retry_after_ms: None,
+        }
+    }
+
+    pub fn selection_too_large(chars: usize, max_chars: usize, original: String) -> Self {
+        ErrorEvent {
+            code: "SELECTION_TOO_LARGE".to_string(),
+            message: format!(
+                "Selection too large to transform ({} characters, limit is {}). Your text was left unchanged.",
+                chars, max_chars
+            ),
+            recoverable: true,
+            fallback_text: Some(original),
+            retry_after_ms: None,
         }
     }
 }
@@ -190,6 +434,10 @@ mod tests {
             serde_json::to_string(&RecordingState::Recording).unwrap(),
             "\"recording\""
         );
+        assert_eq!(
+            serde_json::to_string(&RecordingState::Paused).unwrap(),
+            "\"paused\""
+        );
         assert_eq!(
             serde_json::to_string(&RecordingState::Transcribing).unwrap(),
             "\"transcribing\""
@@ -250,6 +498,14 @@ mod tests {
         assert!(state.can_stop_recording());
     }
 
+    #[test]
+    fn test_can_stop_and_cancel_while_paused() {
+        let state = RecordingState::Paused;
+        assert!(state.can_stop_recording());
+        assert!(state.can_cancel());
+        assert!(!state.can_start_recording());
+    }
+
     #[test]
     fn test_cannot_stop_recording_from_idle() {
         let state = RecordingState::Idle;
@@ -344,6 +600,32 @@ mod tests {
         assert!(json.contains("\"peak\":0.95"));
     }
 
+    #[test]
+    fn test_recording_stats_event_serialization() {
+        let event = RecordingStatsEvent {
+            duration_ms: 2500,
+            avg_level: 0.5,
+            peak_level: 0.875,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"durationMs\":2500"));
+        assert!(json.contains("\"avgLevel\":0.5"));
+        assert!(json.contains("\"peakLevel\":0.875"));
+    }
+
+    #[test]
+    fn test_microphone_fallback_event_serialization() {
+        let event = MicrophoneFallbackEvent {
+            configured_device: "Blue Yeti".to_string(),
+            fallback_device: "MacBook Pro Microphone".to_string(),
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"configuredDevice\":\"Blue Yeti\""));
+        assert!(json.contains("\"fallbackDevice\":\"MacBook Pro Microphone\""));
+    }
+
     // ==================== TranscriptionCompleteEvent Tests ====================
 
     #[test]
@@ -352,12 +634,36 @@ mod tests {
             raw_transcript: "hello world".to_string(),
             enhanced_text: "Hello, world!".to_string(),
             copied_to_clipboard: true,
+            latency: LatencyReport {
+                transcribe_ms: 800,
+                llm_ms: 400,
+                ..Default::default()
+            },
+            confidence: Some(0.9),
         };
 
         let json = serde_json::to_string(&event).unwrap();
         assert!(json.contains("\"rawTranscript\":\"hello world\""));
         assert!(json.contains("\"enhancedText\":\"Hello, world!\""));
         assert!(json.contains("\"copiedToClipboard\":true"));
+        assert!(json.contains("\"transcribeMs\":800"));
+        assert!(json.contains("\"llmMs\":400"));
+    }
+
+    #[test]
+    fn test_latency_report_summary() {
+        let report = LatencyReport {
+            capture_ms: 3000,
+            audio_processing_ms: 150,
+            transcribe_ms: 800,
+            ide_transform_ms: 2,
+            llm_ms: 400,
+            total_ms: 1352,
+        };
+        let summary = report.summary();
+        assert!(summary.contains("transcribe 800ms"));
+        assert!(summary.contains("enhance 400ms"));
+        assert!(summary.contains("total 1352ms"));
     }
 
     // ==================== ErrorEvent Tests ====================
@@ -393,6 +699,92 @@ mod tests {
         assert_eq!(event.fallback_text, Some("fallback".to_string()));
     }
 
+    #[test]
+    fn test_error_event_missing_api_key() {
+        let event = ErrorEvent::missing_api_key();
+        assert_eq!(event.code, "MISSING_API_KEY");
+        assert!(event.message.contains("API key"));
+        assert!(event.fallback_text.is_none());
+    }
+
+    #[test]
+    fn test_error_event_not_authenticated() {
+        let event = ErrorEvent::not_authenticated();
+        assert_eq!(event.code, "NOT_AUTHENTICATED");
+        assert!(event.message.contains("Sign in"));
+        assert!(event.recoverable);
+    }
+
+    #[test]
+    fn test_error_event_osascript_unavailable() {
+        let startup = ErrorEvent::osascript_unavailable(None);
+        assert_eq!(startup.code, "OSASCRIPT_UNAVAILABLE");
+        assert!(startup.fallback_text.is_none());
+
+        let copied = ErrorEvent::osascript_unavailable(Some("hello".to_string()));
+        assert!(copied.message.contains("clipboard"));
+        assert_eq!(copied.fallback_text, Some("hello".to_string()));
+    }
+
+    #[test]
+    fn test_error_event_accessibility_lost() {
+        let copied = ErrorEvent::accessibility_lost("hello".to_string(), true);
+        assert_eq!(copied.code, "ACCESSIBILITY_LOST");
+        assert!(copied.message.contains("clipboard"));
+        assert_eq!(copied.fallback_text, Some("hello".to_string()));
+
+        let not_copied = ErrorEvent::accessibility_lost("hello".to_string(), false);
+        assert!(!not_copied.message.contains("clipboard"));
+        assert_eq!(not_copied.fallback_text, Some("hello".to_string()));
+    }
+
+    #[test]
+    fn test_error_event_low_confidence() {
+        let flagged = ErrorEvent::low_confidence(0.42, false, "raw".to_string());
+        assert_eq!(flagged.code, "LOW_CONFIDENCE");
+        assert!(flagged.message.contains("42%"));
+        assert!(flagged.message.contains("review"));
+
+        let blocked = ErrorEvent::low_confidence(0.42, true, "raw".to_string());
+        assert!(blocked.message.contains("not inserted"));
+        assert_eq!(blocked.fallback_text, Some("raw".to_string()));
+    }
+
+    #[test]
+    fn test_error_event_enhancement_failed() {
+        let cancelled = ErrorEvent::enhancement_failed("timed out", false, "raw".to_string());
+        assert_eq!(cancelled.code, "ENHANCEMENT_FAILED");
+        assert!(cancelled.message.contains("Nothing was inserted"));
+        assert!(!cancelled.message.contains("clipboard"));
+        assert_eq!(cancelled.fallback_text, Some("raw".to_string()));
+
+        let copied = ErrorEvent::enhancement_failed("timed out", true, "raw".to_string());
+        assert!(copied.message.contains("clipboard"));
+    }
+
+    #[test]
+    fn test_error_event_selection_too_large() {
+        let event = ErrorEvent::selection_too_large(20000, 10000, "original".to_string());
+        assert_eq!(event.code, "SELECTION_TOO_LARGE");
+        assert!(event.message.contains("20000"));
+        assert!(event.message.contains("10000"));
+        assert_eq!(event.fallback_text, Some("original".to_string()));
+    }
+
+    #[test]
+    fn test_error_event_from_rate_limit() {
+        let event = ErrorEvent::from(AppError::RateLimitExceeded {
+            service: "Groq Whisper".to_string(),
+            retry_after_ms: Some(1500),
+        });
+        assert_eq!(event.code, "RATE_LIMIT_EXCEEDED");
+        assert!(event.message.contains("Groq Whisper"));
+        assert_eq!(event.retry_after_ms, Some(1500));
+
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("\"retryAfterMs\":1500"));
+    }
+
     #[test]
     fn test_error_event_serialization() {
         let event = ErrorEvent {
@@ -400,6 +792,7 @@ mod tests {
             message: "Test message".to_string(),
             recoverable: false,
             fallback_text: Some("fallback".to_string()),
+            retry_after_ms: None,
         };
 
         let json = serde_json::to_string(&event).unwrap();
@@ -0,0 +1,202 @@
+//! Formatting applied to enhanced text according to a style's `AutoFeatures`.
+//!
+//! The style prompt asks the LLM for the same formatting, but models don't
+//! follow it reliably, so the features are enforced after enhancement.
+
+use super::AutoFeatures;
+use regex::Regex;
+use std::sync::LazyLock;
+
+/// Sentence-ending punctuation followed by whitespace (a boundary between sentences).
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static SENTENCE_BOUNDARY: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[.!?]\s+\S").unwrap());
+
+/// Contractions expanded when a style doesn't allow them.
+/// Ambiguous ones ('s, 'd) are left alone.
+const CONTRACTIONS: &[(&str, &str)] = &[
+    ("can't", "cannot"),
+    ("won't", "will not"),
+    ("don't", "do not"),
+    ("doesn't", "does not"),
+    ("didn't", "did not"),
+    ("isn't", "is not"),
+    ("aren't", "are not"),
+    ("wasn't", "was not"),
+    ("weren't", "were not"),
+    ("haven't", "have not"),
+    ("hasn't", "has not"),
+    ("hadn't", "had not"),
+    ("couldn't", "could not"),
+    ("shouldn't", "should not"),
+    ("wouldn't", "would not"),
+    ("i'm", "I am"),
+    ("you're", "you are"),
+    ("we're", "we are"),
+    ("they're", "they are"),
+    ("i've", "I have"),
+    ("you've", "you have"),
+    ("we've", "we have"),
+    ("they've", "they have"),
+    ("i'll", "I will"),
+    ("you'll", "you will"),
+    ("we'll", "we will"),
+    ("they'll", "they will"),
+];
+
+/// Matches any word that could be a contraction (straight or curly apostrophe).
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static CONTRACTION_WORD: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\b[A-Za-z]+['’][A-Za-z]+\b").unwrap());
+
+/// Apply a style's automatic formatting features to enhanced text.
+pub fn apply_auto_features(text: &str, features: &AutoFeatures) -> String {
+    let mut result = text.trim().to_string();
+
+    if !features.allow_contractions {
+        result = expand_contractions(&result);
+    }
+    if features.remove_periods_single_sentence {
+        result = remove_single_sentence_period(&result);
+    }
+    if features.lowercase_start {
+        result = lowercase_first_letter(&result);
+    }
+
+    result
+}
+
+/// Lowercase the first letter, unless the first word is "I" (or an "I'm"-style
+/// contraction) or looks like an acronym ("API", "JSON").
+fn lowercase_first_letter(text: &str) -> String {
+    let first_word = text.split_whitespace().next().unwrap_or("");
+    let letters: Vec<char> = first_word
+        .chars()
+        .take_while(|c| c.is_alphabetic())
+        .collect();
+
+    let is_pronoun_i = letters == ['I'];
+    let is_acronym = letters.len() > 1 && letters.iter().all(|c| c.is_uppercase());
+    if letters.is_empty() || is_pronoun_i || is_acronym {
+        return text.to_string();
+    }
+
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_lowercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Strip the trailing period when the text is exactly one sentence.
+/// Ellipses and multi-line text are left alone.
+fn remove_single_sentence_period(text: &str) -> String {
+    let is_single_sentence = !text.contains('\n') && !SENTENCE_BOUNDARY.is_match(text);
+    if is_single_sentence && text.ends_with('.') && !text.ends_with("..") {
+        text[..text.len() - 1].to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Expand unambiguous contractions ("don't" → "do not"), keeping a leading capital.
+fn expand_contractions(text: &str) -> String {
+    CONTRACTION_WORD
+        .replace_all(text, |caps: &regex::Captures| {
+            let word = &caps[0];
+            let normalized = word.replace('’', "'").to_lowercase();
+            match CONTRACTIONS.iter().find(|(c, _)| *c == normalized) {
+                Some((_, expanded)) if word.starts_with(|c: char| c.is_uppercase()) => {
+                    let mut chars = expanded.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect())
+                        .unwrap_or_default()
+                }
+                Some((_, expanded)) => expanded.to_string(),
+                None => word.to_string(),
+            }
+        })
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn features(
+        lowercase_start: bool,
+        allow_contractions: bool,
+        remove_periods: bool,
+    ) -> AutoFeatures {
+        AutoFeatures {
+            lowercase_start,
+            allow_contractions,
+            remove_periods_single_sentence: remove_periods,
+        }
+    }
+
+    #[test]
+    fn test_no_features_leaves_text_unchanged() {
+        let text = "Don't forget the meeting. It starts at noon.";
+        assert_eq!(
+            apply_auto_features(text, &features(false, true, false)),
+            text
+        );
+    }
+
+    #[test]
+    fn test_lowercase_start() {
+        let f = features(true, true, false);
+        assert_eq!(apply_auto_features("Sounds good.", &f), "sounds good.");
+        assert_eq!(apply_auto_features("I think so.", &f), "I think so.");
+        assert_eq!(apply_auto_features("I'm on it.", &f), "I'm on it.");
+        assert_eq!(apply_auto_features("API is down.", &f), "API is down.");
+        assert_eq!(apply_auto_features("@main.rs fixed", &f), "@main.rs fixed");
+        assert_eq!(apply_auto_features("", &f), "");
+    }
+
+    #[test]
+    fn test_remove_period_single_sentence() {
+        let f = features(false, true, true);
+        assert_eq!(apply_auto_features("Sounds good.", &f), "Sounds good");
+        assert_eq!(apply_auto_features("Wait for it...", &f), "Wait for it...");
+        assert_eq!(apply_auto_features("Really?", &f), "Really?");
+    }
+
+    #[test]
+    fn test_multi_sentence_keeps_periods() {
+        let f = features(false, true, true);
+        let text = "Sounds good. See you at noon.";
+        assert_eq!(apply_auto_features(text, &f), text);
+        let text = "Are you coming? I'll save a seat.";
+        assert_eq!(apply_auto_features(text, &f), text);
+        let text = "First point.\nSecond point.";
+        assert_eq!(apply_auto_features(text, &f), text);
+    }
+
+    #[test]
+    fn test_expand_contractions() {
+        let f = features(false, false, false);
+        assert_eq!(
+            apply_auto_features("Don't worry, we're fine and it’s done.", &f),
+            "Do not worry, we are fine and it’s done."
+        );
+        assert_eq!(
+            apply_auto_features("I'm sure I can't", &f),
+            "I am sure I cannot"
+        );
+    }
+
+    #[test]
+    fn test_casual_combination() {
+        let f = features(true, true, true);
+        assert_eq!(apply_auto_features("  Sounds good.  ", &f), "sounds good");
+        assert_eq!(
+            apply_auto_features("Sounds good. Don't be late.", &f),
+            "sounds good. Don't be late."
+        );
+
+        let f = features(true, false, true);
+        assert_eq!(apply_auto_features("Don't be late.", &f), "do not be late");
+    }
+}
@@ -9,6 +9,11 @@ pub fn get_default_style() -> Style {
     neutral()
 }
 
+/// Get all built-in styles.
+pub fn get_all_styles() -> Vec<Style> {
+    vec![casual(), professional(), neutral(), technical(), creative()]
+}
+
 /// Get a built-in style by ID.
 pub fn get_style_by_id(id: &str) -> Option<Style> {
     match id {
@@ -143,6 +148,15 @@ mod tests {
         assert!(get_style_by_id("nonexistent").is_none());
     }
 
+    #[test]
+    fn test_get_all_styles_resolve_by_id() {
+        let styles = get_all_styles();
+        assert_eq!(styles.len(), 5);
+        for style in styles {
+            assert_eq!(get_style_by_id(&style.id).unwrap().id, style.id);
+        }
+    }
+
     #[test]
     fn test_default_style_is_neutral() {
         let default = get_default_style();
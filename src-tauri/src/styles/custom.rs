@@ -0,0 +1,172 @@
+//! User-defined styles.
+//!
+//! Custom styles sit alongside the built-ins and can be picked anywhere a
+//! built-in can (per-app overrides, re-enhance). Their ids must not collide
+//! with a built-in style.
+//!
+//! Styles persist as JSON to `custom_styles.json` in the app's data directory.
+
+use super::{builtin, Style};
+use std::fs;
+use std::path::PathBuf;
+
+/// Maximum number of custom styles a user can store
+const MAX_STYLES: usize = 50;
+
+fn styles_path() -> Option<PathBuf> {
+    dirs::data_dir().map(|d| d.join("keyhold").join("custom_styles.json"))
+}
+
+/// Load all custom styles, in creation order.
+pub fn load_styles() -> Vec<Style> {
+    styles_path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_styles(styles: &[Style]) -> Result<(), String> {
+    let path = styles_path().ok_or("Could not find data directory")?;
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+    }
+
+    let content = serde_json::to_string_pretty(styles)
+        .map_err(|e| format!("Failed to serialize custom styles: {}", e))?;
+    fs::write(&path, content).map_err(|e| format!("Failed to write custom styles: {}", e))
+}
+
+/// Get a custom style by ID.
+pub fn get_style_by_id(id: &str) -> Option<Style> {
+    load_styles().into_iter().find(|s| s.id == id)
+}
+
+/// Validate the fields of a style.
+fn validate(style: &Style) -> Result<(), String> {
+    if style.id.trim().is_empty() {
+        return Err("Style id cannot be empty".to_string());
+    }
+    if style.id.trim() != style.id {
+        return Err("Style id cannot start or end with whitespace".to_string());
+    }
+    if style.name.trim().is_empty() {
+        return Err("Style name cannot be empty".to_string());
+    }
+    if style.prompt_modifier.trim().is_empty() {
+        return Err("Style prompt cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+/// Add `style` to `styles`, rejecting ids used by a built-in or existing style.
+fn insert_style(styles: &mut Vec<Style>, style: Style) -> Result<(), String> {
+    validate(&style)?;
+    if builtin::get_style_by_id(&style.id).is_some() {
+        return Err(format!("'{}' is a built-in style", style.id));
+    }
+    if styles.iter().any(|s| s.id == style.id) {
+        return Err(format!("A style with id '{}' already exists", style.id));
+    }
+    if styles.len() >= MAX_STYLES {
+        return Err(format!("Too many custom styles (max {})", MAX_STYLES));
+    }
+    styles.push(style);
+    Ok(())
+}
+
+/// Replace the custom style with the same id.
+fn replace_style(styles: &mut [Style], style: Style) -> Result<(), String> {
+    validate(&style)?;
+    if builtin::get_style_by_id(&style.id).is_some() {
+        return Err("Built-in styles cannot be modified".to_string());
+    }
+    let existing = styles
+        .iter_mut()
+        .find(|s| s.id == style.id)
+        .ok_or_else(|| format!("Unknown style: {}", style.id))?;
+    *existing = style;
+    Ok(())
+}
+
+/// Create a new custom style and persist it.
+pub fn create_style(style: Style) -> Result<Style, String> {
+    let mut styles = load_styles();
+    insert_style(&mut styles, style.clone())?;
+    save_styles(&styles)?;
+    Ok(style)
+}
+
+/// Update an existing custom style (matched by id) and persist it.
+pub fn update_style(style: Style) -> Result<Style, String> {
+    let mut styles = load_styles();
+    replace_style(&mut styles, style.clone())?;
+    save_styles(&styles)?;
+    Ok(style)
+}
+
+/// Delete a custom style by id.
+pub fn delete_style(id: &str) -> Result<(), String> {
+    if builtin::get_style_by_id(id).is_some() {
+        return Err("Built-in styles cannot be deleted".to_string());
+    }
+    let mut styles = load_styles();
+    let before = styles.len();
+    styles.retain(|s| s.id != id);
+    if styles.len() == before {
+        return Err(format!("Unknown style: {}", id));
+    }
+    save_styles(&styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::styles::AutoFeatures;
+
+    fn style(id: &str, prompt: &str) -> Style {
+        Style {
+            id: id.to_string(),
+            name: "Pirate".to_string(),
+            description: String::new(),
+            prompt_modifier: prompt.to_string(),
+            auto_features: AutoFeatures::default(),
+        }
+    }
+
+    #[test]
+    fn test_insert_style_validation() {
+        let mut styles = Vec::new();
+        assert!(insert_style(&mut styles, style("pirate", "Talk like a pirate")).is_ok());
+
+        // Duplicate id, built-in id, empty prompt
+        assert!(insert_style(&mut styles, style("pirate", "Arr"))
+            .unwrap_err()
+            .contains("already exists"));
+        assert!(insert_style(&mut styles, style("casual", "Arr"))
+            .unwrap_err()
+            .contains("built-in"));
+        assert!(insert_style(&mut styles, style("parrot", "  \n"))
+            .unwrap_err()
+            .contains("prompt"));
+        assert!(insert_style(&mut styles, style(" ", "Arr")).is_err());
+
+        assert_eq!(styles.len(), 1);
+    }
+
+    #[test]
+    fn test_replace_style() {
+        let mut styles = vec![style("pirate", "Talk like a pirate")];
+
+        let mut updated = style("pirate", "Talk like a polite pirate");
+        updated.auto_features.lowercase_start = true;
+        assert!(replace_style(&mut styles, updated).is_ok());
+        assert_eq!(styles[0].prompt_modifier, "Talk like a polite pirate");
+        assert!(styles[0].auto_features.lowercase_start);
+
+        assert!(replace_style(&mut styles, style("ninja", "Be quiet")).is_err());
+        assert!(replace_style(&mut styles, style("neutral", "Changed")).is_err());
+        assert!(replace_style(&mut styles, style("pirate", "")).is_err());
+    }
+}
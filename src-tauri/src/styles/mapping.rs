@@ -4,6 +4,7 @@
 //! Includes 100+ app mappings and categorical inference for unknown apps.
 
 use super::{builtin, ActiveApp, Style};
+use std::collections::HashMap;
 
 /// Default app-to-style mappings by bundle ID.
 /// Organized by category for maintainability.
@@ -259,7 +260,18 @@ const DEFAULT_MAPPINGS: &[(&str, &str)] = &[
 /// 1. Exact bundle ID match from hardcoded mappings
 /// 2. Categorical inference from app name keywords
 /// 3. Falls back to neutral style
-pub fn get_style_for_app(app: &ActiveApp) -> Style {
+///
+/// Apps in `inference_excluded` (bundle IDs the user found mis-categorized)
+/// skip the mappings and inference and always get neutral.
+pub fn get_style_for_app(app: &ActiveApp, inference_excluded: &[String]) -> Style {
+    if inference_excluded.contains(&app.bundle_id) {
+        log::info!(
+            "[STYLE] {} is excluded from style inference, using neutral",
+            app.bundle_id
+        );
+        return builtin::get_default_style();
+    }
+
     // Tier 1: Exact bundle ID match
     if let Some(style_id) = get_style_id_for_bundle_id(&app.bundle_id) {
         if let Some(style) = builtin::get_style_by_id(style_id) {
@@ -278,6 +290,19 @@ pub fn get_style_for_app(app: &ActiveApp) -> Style {
     builtin::get_default_style()
 }
 
+/// Get the style for an application, letting the user's per-app overrides
+/// (bundle_id → style id) win over both exact mappings and inference.
+pub fn get_style_for_app_with_overrides(
+    app: &ActiveApp,
+    overrides: &HashMap<String, String>,
+    inference_excluded: &[String],
+) -> Style {
+    overrides
+        .get(&app.bundle_id)
+        .and_then(|style_id| super::get_style_by_id(style_id))
+        .unwrap_or_else(|| get_style_for_app(app, inference_excluded))
+}
+
 /// Get the style ID for a bundle ID from default mappings.
 fn get_style_id_for_bundle_id(bundle_id: &str) -> Option<&'static str> {
     for (bid, style_id) in DEFAULT_MAPPINGS {
@@ -574,7 +599,7 @@ mod tests {
             bundle_id: "com.tinyspeck.slackmacgap".to_string(),
             name: "Slack".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(style.id, "casual");
     }
 
@@ -584,7 +609,7 @@ mod tests {
             bundle_id: "com.apple.mail".to_string(),
             name: "Mail".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(style.id, "professional");
     }
 
@@ -594,7 +619,7 @@ mod tests {
             bundle_id: "com.microsoft.VSCode".to_string(),
             name: "Visual Studio Code".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(style.id, "technical");
     }
 
@@ -604,10 +629,73 @@ mod tests {
             bundle_id: "com.figma.Desktop".to_string(),
             name: "Figma".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(style.id, "creative");
     }
 
+    #[test]
+    fn test_override_wins_over_mapping_and_inference() {
+        let overrides = HashMap::from([
+            (
+                "com.tinyspeck.slackmacgap".to_string(),
+                "professional".to_string(),
+            ),
+            (
+                "com.newstartup.SuperChat".to_string(),
+                "neutral".to_string(),
+            ),
+        ]);
+
+        let slack = ActiveApp {
+            bundle_id: "com.tinyspeck.slackmacgap".to_string(),
+            name: "Slack".to_string(),
+        };
+        assert_eq!(
+            get_style_for_app_with_overrides(&slack, &overrides, &[]).id,
+            "professional"
+        );
+
+        let chat = ActiveApp {
+            bundle_id: "com.newstartup.SuperChat".to_string(),
+            name: "SuperChat Messenger".to_string(),
+        };
+        assert_eq!(
+            get_style_for_app_with_overrides(&chat, &overrides, &[]).id,
+            "neutral"
+        );
+
+        // Apps without an override (or with an unknown style) use the mappings
+        let mail = ActiveApp {
+            bundle_id: "com.apple.mail".to_string(),
+            name: "Mail".to_string(),
+        };
+        let broken = HashMap::from([("com.apple.mail".to_string(), "shouty".to_string())]);
+        assert_eq!(
+            get_style_for_app_with_overrides(&mail, &broken, &[]).id,
+            "professional"
+        );
+    }
+
+    #[test]
+    fn test_inference_excluded_app_is_neutral() {
+        let mailchimp = ActiveApp {
+            bundle_id: "com.mailchimp.desktop".to_string(),
+            name: "MailChimp".to_string(),
+        };
+        assert_eq!(get_style_for_app(&mailchimp, &[]).id, "professional");
+
+        let excluded = vec!["com.mailchimp.desktop".to_string()];
+        assert_eq!(get_style_for_app(&mailchimp, &excluded).id, "neutral");
+
+        // An explicit override still wins over the exclusion
+        let overrides =
+            HashMap::from([("com.mailchimp.desktop".to_string(), "casual".to_string())]);
+        assert_eq!(
+            get_style_for_app_with_overrides(&mailchimp, &overrides, &excluded).id,
+            "casual"
+        );
+    }
+
     // =========================================================================
     // Tier 2: Categorical inference tests
     // =========================================================================
@@ -618,7 +706,7 @@ mod tests {
             bundle_id: "com.newstartup.SuperChat".to_string(),
             name: "SuperChat Messenger".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(style.id, "casual", "Chat app should be inferred as casual");
     }
 
@@ -628,7 +716,7 @@ mod tests {
             bundle_id: "com.newstartup.FastMail".to_string(),
             name: "FastMail Pro".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "professional",
             "Mail app should be inferred as professional"
@@ -641,7 +729,7 @@ mod tests {
             bundle_id: "com.newstartup.UltraTerminal".to_string(),
             name: "Ultra Terminal".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "technical",
             "Terminal app should be inferred as technical"
@@ -654,7 +742,7 @@ mod tests {
             bundle_id: "com.newstartup.CodeEditor".to_string(),
             name: "Super Code Editor".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "technical",
             "Code editor should be inferred as technical"
@@ -667,7 +755,7 @@ mod tests {
             bundle_id: "com.newstartup.DesignTool".to_string(),
             name: "Amazing Designer Pro".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "creative",
             "Design app should be inferred as creative"
@@ -680,7 +768,7 @@ mod tests {
             bundle_id: "com.newstartup.Synthesizer".to_string(),
             name: "Podcast Recording".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "creative",
             "Podcast/music app should be inferred as creative"
@@ -693,7 +781,7 @@ mod tests {
             bundle_id: "com.company.dockerhelper".to_string(),
             name: "Container Manager".to_string(), // Name doesn't have keywords
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "technical",
             "Should infer from 'docker' in bundle ID"
@@ -710,7 +798,7 @@ mod tests {
             bundle_id: "com.unknown.randomapp123".to_string(),
             name: "Random App".to_string(),
         };
-        let style = get_style_for_app(&app);
+        let style = get_style_for_app(&app, &[]);
         assert_eq!(
             style.id, "neutral",
             "Unknown app with no keywords should be neutral"
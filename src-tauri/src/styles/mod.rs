@@ -3,11 +3,16 @@
 //! This module provides automatic style selection based on the active application.
 //! Styles adjust the tone and formatting of dictated text to match context.
 
+mod auto_features;
 pub mod builtin;
+pub mod custom;
 pub mod detection;
 pub mod mapping;
 
+pub use auto_features::apply_auto_features;
+
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// A style definition that controls how dictation is enhanced.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -53,15 +58,36 @@ pub fn get_active_app() -> Option<ActiveApp> {
     detection::get_active_app()
 }
 
+/// Get all styles: built-ins first, then the user's custom styles.
+pub fn get_all_styles() -> Vec<Style> {
+    let mut styles = builtin::get_all_styles();
+    styles.extend(custom::load_styles());
+    styles
+}
+
+/// Get a built-in or custom style by ID.
+pub fn get_style_by_id(id: &str) -> Option<Style> {
+    builtin::get_style_by_id(id).or_else(|| custom::get_style_by_id(id))
+}
+
 /// Get the appropriate style for an application.
-pub fn get_style_for_app(app: &ActiveApp) -> Style {
-    mapping::get_style_for_app(app)
+/// `overrides` are the user's per-app style choices (bundle_id → style id);
+/// apps in `inference_excluded` get neutral unless they have an override.
+pub fn get_style_for_app(
+    app: &ActiveApp,
+    overrides: &HashMap<String, String>,
+    inference_excluded: &[String],
+) -> Style {
+    mapping::get_style_for_app_with_overrides(app, overrides, inference_excluded)
 }
 
 /// Get the style for the currently active application.
-pub fn get_current_style() -> Style {
+pub fn get_current_style(
+    overrides: &HashMap<String, String>,
+    inference_excluded: &[String],
+) -> Style {
     match get_active_app() {
-        Some(app) => get_style_for_app(&app),
+        Some(app) => get_style_for_app(&app, overrides, inference_excluded),
         None => builtin::get_default_style(),
     }
 }
@@ -0,0 +1,759 @@
+//! Deterministic number formatting ("twenty twenty four" ↔ "2024").
+//!
+//! Whisper and the LLM are inconsistent about spelling numbers out, so this
+//! runs after enhancement (and before the replacement list) with fixed rules:
+//!
+//! - `Digits`: spelled-out numbers become digits, except a lone "one"
+//!   ("no one", "one of them") and small ordinals ("first of all")
+//! - `Words`: numbers below 1000, ordinals and years are spelled out;
+//!   decimals, amounts ("$5", "5%"), times, dates and versions are left alone
+//! - `Auto`: digits only where they read better - years, 10 and up, and
+//!   amounts, percentages, times of day and dates ("five percent", "March third")
+//!
+//! Ranges are converted as a whole: "one to five" → "1 to 5", "5-10" → "five to ten".
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// How numbers in dictated text are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberFormat {
+    /// Leave numbers as transcribed
+    #[default]
+    Unchanged,
+    /// Spelled-out numbers become digits
+    Digits,
+    /// Small numbers, ordinals and years are spelled out
+    Words,
+    /// Digits for years, larger numbers, amounts and dates; words otherwise
+    Auto,
+}
+
+/// Words, optionally hyphenated ("twenty-four")
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static WORD_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"[A-Za-z]+(?:-[A-Za-z]+)*").unwrap());
+
+/// Whole numbers with an optional ordinal suffix ("21", "21st")
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static DIGITS_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(\d+)(st|nd|rd|th)?\b").unwrap());
+
+/// Separator between the ends of a digit range ("5-10", "5 – 10")
+/// SAFETY: unwrap() is safe - compile-time constant regex validated during development.
+static RANGE_SEPARATOR: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^ ?[-–] ?$").unwrap());
+
+const UNITS: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const TEENS: [&str; 10] = [
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+const UNIT_ORDINALS: [&str; 10] = [
+    "zeroth", "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth",
+];
+const SCALES: [(&str, u64); 3] = [
+    ("thousand", 1_000),
+    ("million", 1_000_000),
+    ("billion", 1_000_000_000),
+];
+
+/// Words after a number that make it an amount, percentage or time of day
+const AMOUNT_WORDS: &[&str] = &[
+    "percent", "dollar", "dollars", "bucks", "cents", "euro", "euros", "pounds", "rupees", "yen",
+    "am", "pm",
+];
+
+const MONTHS: &[&str] = &[
+    "january",
+    "february",
+    "march",
+    "april",
+    "may",
+    "june",
+    "july",
+    "august",
+    "september",
+    "october",
+    "november",
+    "december",
+];
+
+/// Rewrite the numbers in `text` according to `format`.
+pub fn normalize_numbers(text: &str, format: NumberFormat) -> String {
+    match format {
+        NumberFormat::Unchanged => text.to_string(),
+        NumberFormat::Digits | NumberFormat::Auto => words_to_digits(text, format),
+        NumberFormat::Words => digits_to_words(text),
+    }
+}
+
+// ============================================================================
+// Words → digits
+// ============================================================================
+
+/// One word of a spelled-out number
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum NumWord {
+    /// zero-nine
+    Unit(u64),
+    /// ten-nineteen
+    Teen(u64),
+    /// twenty, thirty, ...
+    Tens(u64),
+    /// Hyphenated tens and unit ("twenty-four")
+    Compound(u64),
+    Hundred,
+    Scale(u64),
+    /// "and", as in "one hundred and five"
+    And,
+    /// "oh", as in "nineteen oh five"
+    Oh,
+}
+
+/// A word of a number and whether it is an ordinal ("fifth")
+type NumToken = (NumWord, bool);
+
+fn classify_simple(word: &str) -> Option<NumToken> {
+    if let Some(i) = UNITS.iter().position(|w| *w == word) {
+        return Some((NumWord::Unit(i as u64), false));
+    }
+    if let Some(i) = UNIT_ORDINALS.iter().position(|w| *w == word) {
+        return Some((NumWord::Unit(i as u64), true));
+    }
+    if let Some(i) = TEENS.iter().position(|w| *w == word) {
+        return Some((NumWord::Teen(10 + i as u64), false));
+    }
+    if let Some(i) = TEENS.iter().position(|w| ordinal_word(w) == word) {
+        return Some((NumWord::Teen(10 + i as u64), true));
+    }
+    if let Some(i) = TENS.iter().position(|w| !w.is_empty() && *w == word) {
+        return Some((NumWord::Tens(10 * i as u64), false));
+    }
+    if let Some(i) = TENS
+        .iter()
+        .position(|w| !w.is_empty() && ordinal_word(w) == word)
+    {
+        return Some((NumWord::Tens(10 * i as u64), true));
+    }
+    match word {
+        "hundred" => return Some((NumWord::Hundred, false)),
+        "hundredth" => return Some((NumWord::Hundred, true)),
+        "and" => return Some((NumWord::And, false)),
+        "oh" => return Some((NumWord::Oh, false)),
+        _ => {}
+    }
+    SCALES.iter().find_map(|(name, value)| {
+        if word == *name {
+            Some((NumWord::Scale(*value), false))
+        } else if word.strip_suffix("th") == Some(name) {
+            Some((NumWord::Scale(*value), true))
+        } else {
+            None
+        }
+    })
+}
+
+fn classify(word: &str) -> Option<NumToken> {
+    let word = word.to_lowercase();
+    match word.split_once('-') {
+        None => classify_simple(&word),
+        Some((tens, unit)) => match (classify_simple(tens)?, classify_simple(unit)?) {
+            ((NumWord::Tens(t), false), (NumWord::Unit(u), ordinal)) if u > 0 => {
+                Some((NumWord::Compound(t + u), ordinal))
+            }
+            _ => None,
+        },
+    }
+}
+
+/// Parse a year said in two parts ("nineteen ninety nine", "twenty twenty-four",
+/// "nineteen oh five"). Returns the year and the number of words used.
+fn parse_year(words: &[NumToken]) -> Option<(u64, usize)> {
+    let century = match words.first()? {
+        (NumWord::Teen(v), false) if *v >= 11 => *v,
+        (NumWord::Tens(20), false) => 20,
+        _ => return None,
+    };
+    let (rest, len) = match words.get(1..)? {
+        [(NumWord::Oh, _), (NumWord::Unit(u), false), ..] if *u > 0 => (*u, 3),
+        [(NumWord::Teen(v), false), ..] | [(NumWord::Compound(v), false), ..] => (*v, 2),
+        [(NumWord::Tens(t), false), (NumWord::Unit(u), false), ..] if *u > 0 => (t + u, 3),
+        [(NumWord::Tens(t), false), ..] => (*t, 2),
+        _ => return None,
+    };
+    // "nineteen twenty thousand" isn't a year
+    if matches!(
+        words.get(len),
+        Some((NumWord::Hundred | NumWord::Scale(_), _))
+    ) {
+        return None;
+    }
+    Some((century * 100 + rest, len))
+}
+
+/// What the previous word of a number was, for validating the next one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Last {
+    None,
+    Unit,
+    Teen,
+    Tens,
+    Hundred,
+    Scale,
+}
+
+/// Parse the longest cardinal or ordinal number at the start of `words`.
+/// Returns the value, the number of words used and whether it is an ordinal.
+fn parse_number(words: &[NumToken]) -> Option<(u64, usize, bool)> {
+    if let Some((NumWord::Unit(0), ordinal)) = words.first() {
+        return Some((0, 1, *ordinal));
+    }
+
+    let mut total = 0;
+    let mut current = 0;
+    let mut last = Last::None;
+    let mut last_scale = u64::MAX;
+    let mut used = 0;
+    let mut ordinal = false;
+
+    for (i, (word, is_ordinal)) in words.iter().enumerate() {
+        let accepts_tens = matches!(last, Last::None | Last::Hundred | Last::Scale);
+        match *word {
+            NumWord::Unit(v) if v > 0 && (accepts_tens || last == Last::Tens) => {
+                current += v;
+                last = Last::Unit;
+            }
+            NumWord::Teen(v) if accepts_tens => {
+                current += v;
+                last = Last::Teen;
+            }
+            NumWord::Tens(v) if accepts_tens => {
+                current += v;
+                last = Last::Tens;
+            }
+            NumWord::Compound(v) if accepts_tens => {
+                current += v;
+                last = Last::Unit;
+            }
+            NumWord::Hundred if (1..100).contains(&current) && last != Last::Scale => {
+                current *= 100;
+                last = Last::Hundred;
+            }
+            NumWord::Scale(s) if current > 0 && s < last_scale && last != Last::Scale => {
+                total += current * s;
+                current = 0;
+                last_scale = s;
+                last = Last::Scale;
+            }
+            NumWord::And if matches!(last, Last::Hundred | Last::Scale) && !is_ordinal => {
+                // Only part of the number if more of it follows
+                match words.get(i + 1) {
+                    Some((NumWord::Unit(v), _)) if *v > 0 => continue,
+                    Some((NumWord::Teen(_) | NumWord::Tens(_) | NumWord::Compound(_), _)) => {
+                        continue
+                    }
+                    _ => break,
+                }
+            }
+            _ => break,
+        }
+        used = i + 1;
+        if *is_ordinal {
+            ordinal = true;
+            break;
+        }
+    }
+
+    (used > 0).then_some((total + current, used, ordinal))
+}
+
+/// A spelled-out number found in the text
+#[derive(Debug)]
+struct Found {
+    start: usize,
+    end: usize,
+    /// Index of the first and one past the last word
+    first_word: usize,
+    end_word: usize,
+    value: u64,
+    ordinal: bool,
+    year: bool,
+}
+
+fn find_numbers(text: &str, words: &[(usize, usize, &str)]) -> Vec<Found> {
+    let tokens: Vec<Option<NumToken>> = words.iter().map(|(_, _, w)| classify(w)).collect();
+    let mut found = Vec::new();
+    let mut i = 0;
+    while i < words.len() {
+        // A run of number words separated only by spaces
+        let mut run_end = i;
+        while run_end < words.len()
+            && tokens[run_end].is_some()
+            && (run_end == i
+                || text[words[run_end - 1].1..words[run_end].0]
+                    .trim()
+                    .is_empty())
+        {
+            run_end += 1;
+        }
+        if run_end == i {
+            i += 1;
+            continue;
+        }
+
+        let run: Vec<NumToken> = tokens[i..run_end].iter().flatten().copied().collect();
+        let mut j = 0;
+        while j < run.len() {
+            let parsed = parse_year(&run[j..])
+                .map(|(value, len)| (value, len, false, true))
+                .or_else(|| {
+                    parse_number(&run[j..])
+                        .map(|(value, len, ordinal)| (value, len, ordinal, false))
+                });
+            match parsed {
+                Some((value, len, ordinal, year)) => {
+                    found.push(Found {
+                        start: words[i + j].0,
+                        end: words[i + j + len - 1].1,
+                        first_word: i + j,
+                        end_word: i + j + len,
+                        value,
+                        ordinal,
+                        year,
+                    });
+                    j += len;
+                }
+                None => j += 1,
+            }
+        }
+        i = run_end;
+    }
+    found
+}
+
+fn lowercase_word(words: &[(usize, usize, &str)], index: usize) -> Option<String> {
+    words.get(index).map(|(_, _, w)| w.to_lowercase())
+}
+
+/// Whether a number should be written with digits, ignoring ranges
+fn wants_digits(number: &Found, words: &[(usize, usize, &str)], format: NumberFormat) -> bool {
+    if number.year {
+        return true;
+    }
+    let previous = number
+        .first_word
+        .checked_sub(1)
+        .and_then(|i| lowercase_word(words, i));
+    let next = lowercase_word(words, number.end_word);
+    let is_month = |w: &Option<String>| w.as_deref().is_some_and(|w| MONTHS.contains(&w));
+
+    // "March third", "third of March"
+    let date = is_month(&previous)
+        || (next.as_deref() == Some("of") && is_month(&lowercase_word(words, number.end_word + 1)));
+    if number.ordinal {
+        return number.value >= 10 || date;
+    }
+    let amount = next.as_deref().is_some_and(|w| AMOUNT_WORDS.contains(&w));
+    match format {
+        NumberFormat::Auto => number.value >= 10 || amount || date,
+        _ => number.value != 1 || number.end_word - number.first_word > 1 || amount || date,
+    }
+}
+
+fn ordinal_suffix(value: u64) -> &'static str {
+    match (value % 10, value % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    }
+}
+
+fn format_digits(number: &Found) -> String {
+    if number.ordinal {
+        return format!("{}{}", number.value, ordinal_suffix(number.value));
+    }
+    let digits = number.value.to_string();
+    if number.year || number.value < 10_000 {
+        return digits;
+    }
+    // Group thousands: 25000 → 25,000
+    let mut groups = Vec::new();
+    let mut rest = digits.as_str();
+    while rest.len() > 3 {
+        let (head, group) = rest.split_at(rest.len() - 3);
+        groups.push(group);
+        rest = head;
+    }
+    groups.push(rest);
+    groups.reverse();
+    groups.join(",")
+}
+
+fn words_to_digits(text: &str, format: NumberFormat) -> String {
+    let words: Vec<(usize, usize, &str)> = WORD_PATTERN
+        .find_iter(text)
+        .map(|m| (m.start(), m.end(), m.as_str()))
+        .collect();
+    let numbers = find_numbers(text, &words);
+
+    let mut convert: Vec<bool> = numbers
+        .iter()
+        .map(|n| wants_digits(n, &words, format))
+        .collect();
+    // Both ends of a range ("one to five") are written the same way
+    for i in 1..numbers.len() {
+        let (a, b) = (&numbers[i - 1], &numbers[i]);
+        let joined = b.first_word == a.end_word + 1
+            && matches!(
+                lowercase_word(&words, a.end_word).as_deref(),
+                Some("to" | "through" | "thru")
+            );
+        if joined && (convert[i - 1] || convert[i]) {
+            convert[i - 1] = true;
+            convert[i] = true;
+        }
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0;
+    for (number, convert) in numbers.iter().zip(convert) {
+        if convert {
+            result.push_str(&text[copied..number.start]);
+            result.push_str(&format_digits(number));
+            copied = number.end;
+        }
+    }
+    result.push_str(&text[copied..]);
+    result
+}
+
+// ============================================================================
+// Digits → words
+// ============================================================================
+
+fn ordinal_word(word: &str) -> String {
+    match word {
+        "one" => "first".to_string(),
+        "two" => "second".to_string(),
+        "three" => "third".to_string(),
+        "five" => "fifth".to_string(),
+        "eight" => "eighth".to_string(),
+        "nine" => "ninth".to_string(),
+        "twelve" => "twelfth".to_string(),
+        _ => match word.strip_suffix('y') {
+            Some(stem) => format!("{}ieth", stem),
+            None => format!("{}th", word),
+        },
+    }
+}
+
+/// Spell out 0-99 ("forty-two")
+fn tens_words(value: u64) -> String {
+    let value = value as usize;
+    match (value / 10, value % 10) {
+        (0, unit) => UNITS[unit].to_string(),
+        (1, unit) => TEENS[unit].to_string(),
+        (tens, 0) => TENS[tens].to_string(),
+        (tens, unit) => format!("{}-{}", TENS[tens], UNITS[unit]),
+    }
+}
+
+/// Spell out 0-999 ("three hundred forty-two")
+fn cardinal_words(value: u64) -> String {
+    match (value / 100, value % 100) {
+        (0, rest) => tens_words(rest),
+        (hundreds, 0) => format!("{} hundred", UNITS[hundreds as usize]),
+        (hundreds, rest) => format!("{} hundred {}", UNITS[hundreds as usize], tens_words(rest)),
+    }
+}
+
+/// Spell out an ordinal below 1000 ("twenty-first")
+fn ordinal_words(value: u64) -> String {
+    let cardinal = cardinal_words(value);
+    let split = cardinal.rfind([' ', '-']).map_or(0, |i| i + 1);
+    format!("{}{}", &cardinal[..split], ordinal_word(&cardinal[split..]))
+}
+
+/// Spell out a year the way it's said ("nineteen oh five", "two thousand eight")
+fn year_words(year: u64) -> String {
+    match (year / 100, year % 100) {
+        (20, 0..=9) => match year % 100 {
+            0 => "two thousand".to_string(),
+            rest => format!("two thousand {}", UNITS[rest as usize]),
+        },
+        (century, 0) => format!("{} hundred", tens_words(century)),
+        (century, rest @ 1..=9) => format!("{} oh {}", tens_words(century), UNITS[rest as usize]),
+        (century, rest) => format!("{} {}", tens_words(century), tens_words(rest)),
+    }
+}
+
+/// Words for a number written with digits, or None to keep the digits
+fn spell_out(digits: &str, suffix: Option<&str>) -> Option<String> {
+    // Leading zeros are codes and IDs ("007")
+    if digits.len() > 1 && digits.starts_with('0') {
+        return None;
+    }
+    let value: u64 = digits.parse().ok()?;
+    match suffix {
+        Some(_) if value < 1000 => Some(ordinal_words(value)),
+        Some(_) => None,
+        None if value < 1000 => Some(cardinal_words(value)),
+        None if (1100..2100).contains(&value) => Some(year_words(value)),
+        None => None,
+    }
+}
+
+/// Whether the digits starting at `start` continue something before them
+/// (a decimal, amount, version, identifier or another number)
+fn attached_before(text: &str, start: usize) -> bool {
+    text[..start]
+        .chars()
+        .next_back()
+        .is_some_and(|c| c.is_alphanumeric() || "._$€£¥#@/:\\,+-–".contains(c))
+}
+
+/// Whether the digits ending at `end` continue into something after them
+/// (a percentage, unit, decimal, time, date or identifier)
+fn attached_after(text: &str, end: usize) -> bool {
+    let mut after = text[end..].chars();
+    let next_is_digit = after.clone().nth(1).is_some_and(|c| c.is_ascii_digit());
+    after.next().is_some_and(|c| {
+        c.is_alphanumeric() || "%_$€£¥/:@°".contains(c) || (".,-–".contains(c) && next_is_digit)
+    })
+}
+
+/// Capitalize `words` when they start a sentence
+fn capitalize_at_sentence_start(text: &str, start: usize, words: String) -> String {
+    let sentence_start = text[..start]
+        .trim_end_matches([' ', '\t'])
+        .chars()
+        .next_back()
+        .is_none_or(|c| matches!(c, '.' | '!' | '?' | '\n'));
+    if !sentence_start {
+        return words;
+    }
+    let mut chars = words.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => words,
+    }
+}
+
+/// Whether two hyphenated numbers read as a range ("3-5", "10-20") rather than
+/// a phone number, part number or code ("555-1234", "4411-2", "12-05")
+fn is_short_range(from: &str, to: &str) -> bool {
+    let leading_zero = |d: &str| d.len() > 1 && d.starts_with('0');
+    if leading_zero(from) || leading_zero(to) {
+        return false;
+    }
+    match (from.parse::<u64>(), to.parse::<u64>()) {
+        (Ok(from), Ok(to)) => from < to && to < 1000,
+        _ => false,
+    }
+}
+
+fn digits_to_words(text: &str) -> String {
+    let matches: Vec<(usize, usize, &str, Option<&str>)> = DIGITS_PATTERN
+        .captures_iter(text)
+        .filter_map(|caps| {
+            let whole = caps.get(0)?;
+            let suffix = caps.get(2).map(|m| m.as_str());
+            Some((whole.start(), whole.end(), caps.get(1)?.as_str(), suffix))
+        })
+        .collect();
+
+    let mut result = String::with_capacity(text.len());
+    let mut copied = 0;
+    let mut i = 0;
+    while i < matches.len() {
+        let (start, end, digits, suffix) = matches[i];
+        let mut replacement = None;
+        let mut replaced_end = end;
+
+        // "5-10" → "five to ten"; other hyphenated groups ("555-1234") stay digits
+        if let Some(&(next_start, next_end, next_digits, None)) = matches.get(i + 1) {
+            let hyphenated = suffix.is_none()
+                && RANGE_SEPARATOR.is_match(&text[end..next_start])
+                && !attached_before(text, start)
+                && !attached_after(text, next_end);
+            if hyphenated {
+                if !is_short_range(digits, next_digits) {
+                    i += 2;
+                    continue;
+                }
+                if let (Some(from), Some(to)) =
+                    (spell_out(digits, None), spell_out(next_digits, None))
+                {
+                    replacement = Some(format!("{} to {}", from, to));
+                    replaced_end = next_end;
+                }
+            }
+        }
+        if replacement.is_none() && !attached_before(text, start) && !attached_after(text, end) {
+            replacement = spell_out(digits, suffix);
+        }
+
+        if let Some(words) = replacement {
+            result.push_str(&text[copied..start]);
+            result.push_str(&capitalize_at_sentence_start(text, start, words));
+            copied = replaced_end;
+            if replaced_end != end {
+                i += 1;
+            }
+        }
+        i += 1;
+    }
+    result.push_str(&text[copied..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(text: &str) -> String {
+        normalize_numbers(text, NumberFormat::Digits)
+    }
+
+    fn words(text: &str) -> String {
+        normalize_numbers(text, NumberFormat::Words)
+    }
+
+    fn auto(text: &str) -> String {
+        normalize_numbers(text, NumberFormat::Auto)
+    }
+
+    #[test]
+    fn test_unchanged() {
+        let text = "twenty twenty four and 5 apples";
+        assert_eq!(normalize_numbers(text, NumberFormat::Unchanged), text);
+    }
+
+    #[test]
+    fn test_cardinals_to_digits() {
+        assert_eq!(digits("I need five apples"), "I need 5 apples");
+        assert_eq!(digits("twenty-four hours"), "24 hours");
+        assert_eq!(digits("Twenty four hours"), "24 hours");
+        assert_eq!(digits("one hundred and five people"), "105 people");
+        assert_eq!(digits("three million two hundred thousand"), "3,200,000");
+        assert_eq!(digits("five and six"), "5 and 6");
+        assert_eq!(
+            digits("no one knows, one of them"),
+            "no one knows, one of them"
+        );
+        assert_eq!(digits("it costs one dollar"), "it costs 1 dollar");
+        assert_eq!(digits("Often the tone is fine"), "Often the tone is fine");
+    }
+
+    #[test]
+    fn test_years_to_digits() {
+        assert_eq!(digits("in twenty twenty four"), "in 2024");
+        assert_eq!(digits("since nineteen ninety-nine"), "since 1999");
+        assert_eq!(digits("nineteen oh five"), "1905");
+        assert_eq!(digits("two thousand and eight"), "2008");
+        assert_eq!(auto("back in twenty ten"), "back in 2010");
+    }
+
+    #[test]
+    fn test_ordinals_to_digits() {
+        assert_eq!(digits("the twenty-first century"), "the 21st century");
+        assert_eq!(digits("her forty second birthday"), "her 42nd birthday");
+        assert_eq!(digits("the eleventh hour"), "the 11th hour");
+        assert_eq!(digits("the one hundredth time"), "the 100th time");
+        // Small ordinals only in dates
+        assert_eq!(digits("first of all"), "first of all");
+        assert_eq!(digits("on March third"), "on March 3rd");
+        assert_eq!(digits("the second of May"), "the 2nd of May");
+    }
+
+    #[test]
+    fn test_ranges_to_digits() {
+        assert_eq!(digits("one to five"), "1 to 5");
+        assert_eq!(auto("five to fifteen minutes"), "5 to 15 minutes");
+        assert_eq!(auto("two through four"), "two through four");
+    }
+
+    #[test]
+    fn test_auto() {
+        assert_eq!(auto("I have three cats"), "I have three cats");
+        assert_eq!(auto("about forty people"), "about 40 people");
+        assert_eq!(auto("five percent"), "5 percent");
+        assert_eq!(auto("twenty dollars"), "20 dollars");
+        assert_eq!(auto("meet at three pm"), "meet at 3 pm");
+        assert_eq!(auto("Keep 5 apples"), "Keep 5 apples");
+    }
+
+    #[test]
+    fn test_cardinals_to_words() {
+        assert_eq!(words("I need 5 apples"), "I need five apples");
+        assert_eq!(words("24 hours"), "Twenty-four hours");
+        assert_eq!(words("page 342."), "page three hundred forty-two.");
+        assert_eq!(words("We have 100 users"), "We have one hundred users");
+        assert_eq!(words("about 25000 people"), "about 25000 people");
+    }
+
+    #[test]
+    fn test_years_to_words() {
+        assert_eq!(words("in 2024"), "in twenty twenty-four");
+        assert_eq!(words("since 1999"), "since nineteen ninety-nine");
+        assert_eq!(words("in 1905"), "in nineteen oh five");
+        assert_eq!(words("in 2008"), "in two thousand eight");
+        assert_eq!(words("in 1900"), "in nineteen hundred");
+    }
+
+    #[test]
+    fn test_ordinals_to_words() {
+        assert_eq!(words("the 21st century"), "the twenty-first century");
+        assert_eq!(words("my 3rd try"), "my third try");
+        assert_eq!(words("the 12th"), "the twelfth");
+        assert_eq!(words("the 40th"), "the fortieth");
+        assert_eq!(words("the 100th"), "the one hundredth");
+    }
+
+    #[test]
+    fn test_ranges_to_words() {
+        assert_eq!(words("pages 5-10"), "pages five to ten");
+        assert_eq!(words("pages 5 – 10"), "pages five to ten");
+        assert_eq!(words("from 5 to 10"), "from five to ten");
+        assert_eq!(words("call 1-800-555"), "call 1-800-555");
+        assert_eq!(words("call 555-1234 now"), "call 555-1234 now");
+        assert_eq!(words("part 4411-2 is out"), "part 4411-2 is out");
+        assert_eq!(words("ID 12-05"), "ID 12-05");
+        assert_eq!(words("pages 10-5"), "pages 10-5");
+    }
+
+    #[test]
+    fn test_words_leaves_attached_numbers() {
+        for text in [
+            "costs $5",
+            "at 5%",
+            "version 2.5",
+            "at 10:30",
+            "on 3/4",
+            "2024-01-05",
+            "agent 007",
+            "the v2 api",
+            "1,500 items",
+            "@file2.rs",
+        ] {
+            assert_eq!(words(text), text);
+        }
+    }
+}
@@ -6,21 +6,40 @@
 //! - **Mixed mode**: Auto-detect among user's spoken languages, romanized output
 
 use crate::http_client;
-use crate::rate_limit::{check_rate_limit, Service};
+use crate::rate_limit::{check_rate_limit, check_rate_limit_many, Service};
 use crate::signing;
 use any_ascii::any_ascii;
-use reqwest::{multipart, Client};
-use serde::Deserialize;
+use reqwest::{multipart, Client, RequestBuilder};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedSender;
 
 const WHISPER_MODEL: &str = "whisper-large-v3-turbo";
 
-/// Response from Groq Whisper API (simple format)
-#[derive(Debug, Deserialize)]
-struct GroqTranscriptionResponse {
-    text: String,
+/// Longest vocabulary prompt sent to Whisper (characters).
+/// Whisper only conditions on the last ~224 tokens of its prompt and silently
+/// drops anything earlier, so a long vocabulary list would push out the
+/// language instructions it's appended to. 600 characters is roughly 150
+/// tokens of English, leaving room for those instructions.
+pub const MAX_VOCABULARY_PROMPT_CHARS: usize = 600;
+
+/// Confident mixed-mode detections of the same new language, in a row, before
+/// it's suggested as a spoken language
+pub const LANGUAGE_SUGGESTION_STREAK: u32 = 3;
+
+/// Script of mixed-mode transcripts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MixedModeOutput {
+    /// Latin letters for everything (Hinglish as "mera naam")
+    #[default]
+    Romanized,
+    /// Keep whatever script Whisper produced (Hinglish as "मेरा naam")
+    NativeScript,
+    /// Native script for the primary (first) spoken language, romanized otherwise
+    Auto,
 }
 
-/// Response from Groq Whisper API (verbose_json format for mixed mode)
+/// Response from Groq Whisper API (verbose_json format)
 #[derive(Debug, Deserialize)]
 struct GroqVerboseResponse {
     text: String,
@@ -37,6 +56,41 @@ struct GroqSegment {
     avg_logprob: f64,
 }
 
+/// A transcript plus the model's confidence in it
+#[derive(Debug, Clone)]
+pub struct Transcription {
+    pub text: String,
+    /// Average segment log probability (closer to 0 is better), if the API reported segments
+    pub avg_logprob: Option<f64>,
+    /// Language code confidently detected in mixed mode that isn't one of the
+    /// user's spoken languages
+    pub new_language: Option<String>,
+}
+
+/// Result of `WhisperApiClient::transcribe_streaming`
+#[derive(Debug, Clone)]
+pub struct StreamingTranscription {
+    pub transcription: Transcription,
+    /// Why the end of the recording is missing (a later piece failed)
+    pub incomplete: Option<String>,
+}
+
+impl Transcription {
+    /// Confidence as a probability in 0.0..=1.0 (exp of the average log probability)
+    pub fn confidence(&self) -> Option<f32> {
+        self.avg_logprob.map(|lp| lp.exp().clamp(0.0, 1.0) as f32)
+    }
+}
+
+/// Average log probability across segments, or None if there are no segments
+fn average_logprob(segments: &[GroqSegment]) -> Option<f64> {
+    if segments.is_empty() {
+        return None;
+    }
+    let sum: f64 = segments.iter().map(|s| s.avg_logprob).sum();
+    Some(sum / segments.len() as f64)
+}
+
 pub struct WhisperApiClient {
     client: &'static Client,
 }
@@ -55,6 +109,8 @@ impl WhisperApiClient {
     /// * `audio_wav` - WAV-encoded audio bytes
     /// * `language` - Language code (e.g., "en-US", "hi", "te") or "mixed" for mixed mode
     /// * `spoken_languages` - List of languages the user speaks (for mixed mode validation)
+    /// * `vocabulary` - Names and jargon to bias recognition towards, appended to the prompt
+    /// * `mixed_output` - Whether mixed-mode transcripts are romanized
     ///
     /// Two modes:
     /// - **Native mode** (specific language): Strict transcription in that language's native script
@@ -64,28 +120,124 @@ impl WhisperApiClient {
         audio_wav: &[u8],
         language: &str,
         spoken_languages: &[String],
-    ) -> Result<String, String> {
+        vocabulary: Option<&str>,
+        mixed_output: MixedModeOutput,
+    ) -> Result<Transcription, String> {
         // Check rate limit before making API call
         check_rate_limit(Service::WhisperApi)?;
 
-        println!(
+        self.transcribe_unlimited(
+            audio_wav,
+            language,
+            spoken_languages,
+            vocabulary,
+            mixed_output,
+        )
+        .await
+    }
+
+    /// `transcribe` without the rate limit check, for requests already counted
+    async fn transcribe_unlimited(
+        &self,
+        audio_wav: &[u8],
+        language: &str,
+        spoken_languages: &[String],
+        vocabulary: Option<&str>,
+        mixed_output: MixedModeOutput,
+    ) -> Result<Transcription, String> {
+        log::info!(
             "Sending audio to Groq Whisper API ({} bytes)...",
             audio_wav.len()
         );
 
-        let (api_url, api_key) = self.get_api_config();
+        let (api_url, api_key) = self.get_api_config()?;
+        let auth = match api_key {
+            Some(key) => RequestAuth::ApiKey(key),
+            None => RequestAuth::Proxy {
+                access_token: crate::auth::access_token_for_proxy().await?,
+            },
+        };
 
         // Handle "auto" as mixed mode (legacy support for old stored preferences)
         let is_mixed_mode = language == "mixed" || language == "auto";
         let lang_code = language.split('-').next().unwrap_or(language);
 
         if is_mixed_mode {
-            self.transcribe_mixed_mode(audio_wav, &api_url, api_key.as_deref(), spoken_languages)
-                .await
+            self.transcribe_mixed_mode(
+                audio_wav,
+                &api_url,
+                &auth,
+                spoken_languages,
+                vocabulary,
+                mixed_output,
+            )
+            .await
         } else {
-            self.transcribe_native_mode(audio_wav, &api_url, api_key.as_deref(), lang_code)
+            self.transcribe_native_mode(audio_wav, &api_url, &auth, lang_code, vocabulary)
+                .await
+        }
+    }
+
+    /// Transcribe a long recording piece by piece, sending the text so far to
+    /// `partials` after each piece so it can be shown while the rest is
+    /// still being transcribed.
+    ///
+    /// `chunks` are WAV-encoded consecutive pieces of one recording (see
+    /// `audio::split_at_pauses`). Returns the combined transcription; the
+    /// confidence is the average over the pieces.
+    ///
+    /// All pieces are counted against the rate limit up front, so a long
+    /// dictation can't be cut off halfway by it. If a later piece fails, the
+    /// text transcribed so far is returned with the error in `incomplete`.
+    pub async fn transcribe_streaming(
+        &self,
+        chunks: &[Vec<u8>],
+        language: &str,
+        spoken_languages: &[String],
+        vocabulary: Option<&str>,
+        mixed_output: MixedModeOutput,
+        partials: &UnboundedSender<String>,
+    ) -> Result<StreamingTranscription, String> {
+        log::info!("Transcribing {} chunks with partial results", chunks.len());
+        check_rate_limit_many(Service::WhisperApi, chunks.len() as u32)?;
+
+        let mut pieces = Vec::with_capacity(chunks.len());
+        let mut text = String::new();
+        for (index, chunk) in chunks.iter().enumerate() {
+            let piece = match self
+                .transcribe_unlimited(chunk, language, spoken_languages, vocabulary, mixed_output)
                 .await
+            {
+                Ok(piece) => piece,
+                Err(e) if !text.is_empty() => {
+                    log::warn!(
+                        "Piece {} of {} failed, keeping the text so far: {}",
+                        index + 1,
+                        chunks.len(),
+                        e
+                    );
+                    return Ok(StreamingTranscription {
+                        transcription: combine_transcriptions(text, &pieces),
+                        incomplete: Some(e),
+                    });
+                }
+                Err(e) => return Err(e),
+            };
+            if !piece.text.is_empty() {
+                if !text.is_empty() {
+                    text.push(' ');
+                }
+                text.push_str(&piece.text);
+                // The receiver is gone if the dictation was cancelled
+                let _ = partials.send(text.clone());
+            }
+            pieces.push(piece);
         }
+
+        Ok(StreamingTranscription {
+            transcription: combine_transcriptions(text, &pieces),
+            incomplete: None,
+        })
     }
 
     /// Native mode: Strict single-language transcription
@@ -94,20 +246,11 @@ impl WhisperApiClient {
         &self,
         audio_wav: &[u8],
         api_url: &str,
-        api_key: Option<&str>,
+        auth: &RequestAuth,
         lang_code: &str,
-    ) -> Result<String, String> {
-        println!("Native mode: strict {} transcription", lang_code);
-
-        let file_part = multipart::Part::bytes(audio_wav.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| format!("Failed to create file part: {}", e))?;
-
-        let mut form = multipart::Form::new()
-            .part("file", file_part)
-            .text("model", WHISPER_MODEL)
-            .text("language", lang_code.to_string());
+        vocabulary: Option<&str>,
+    ) -> Result<Transcription, String> {
+        log::info!("Native mode: strict {} transcription", lang_code);
 
         // Add language-specific prompts for non-English to ensure native script output
         let prompt = match lang_code {
@@ -122,29 +265,20 @@ impl WhisperApiClient {
             "ko" => Some("한국어로 전사해 주세요."),
             _ => None,
         };
-
-        if let Some(p) = prompt {
-            form = form.text("prompt", p);
-        }
-
-        let mut request = self.client.post(api_url).multipart(form);
-
-        // Add Authorization header only for direct API (dev mode)
-        // Add HMAC signature for proxy mode
-        if let Some(key) = api_key {
-            request = request.header("Authorization", format!("Bearer {}", key));
-        } else {
-            // Production mode: add HMAC signature for proxy authentication
-            // Sign the audio bytes as the request payload
-            let (timestamp, nonce, signature) = signing::sign_request(audio_wav);
-            request = request
-                .header("X-Murmur-Timestamp", timestamp)
-                .header("X-Murmur-Nonce", nonce)
-                .header("X-Murmur-Signature", signature);
-        }
-
-        let response = request
-            .send()
+        let prompt = append_vocabulary(prompt.unwrap_or_default(), vocabulary);
+
+        let response =
+            http_client::send_with_retry(&http_client::TRANSCRIPTION_RETRY, "WHISPER", || {
+                let mut form = multipart::Form::new()
+                    .part("file", audio_part(audio_wav)?)
+                    .text("model", WHISPER_MODEL)
+                    .text("response_format", "verbose_json")
+                    .text("language", lang_code.to_string());
+                if !prompt.is_empty() {
+                    form = form.text("prompt", prompt.clone());
+                }
+                Ok(self.build_request(api_url, auth, audio_wav, form))
+            })
             .await
             .map_err(|e| format!("Failed to send request to Groq: {}", e))?;
 
@@ -154,18 +288,28 @@ impl WhisperApiClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Groq API error ({}): {}", status, error_text));
+            return Err(api_error(status, &error_text, auth));
         }
 
-        let result: GroqTranscriptionResponse = response
+        let result: GroqVerboseResponse = response
             .json()
             .await
             .map_err(|e| format!("Failed to parse Groq response: {}", e))?;
 
         let transcript = result.text.trim().to_string();
-        println!("Native mode transcript ({}): {}", lang_code, transcript);
+        let avg_logprob = average_logprob(&result.segments);
+        log::debug!(
+            "Native mode transcript ({}, avg_logprob={:?}): {}",
+            lang_code,
+            avg_logprob,
+            transcript
+        );
 
-        Ok(transcript)
+        Ok(Transcription {
+            text: transcript,
+            avg_logprob,
+            new_language: None,
+        })
     }
 
     /// Mixed mode: Auto-detect among user's spoken languages with romanized output
@@ -174,15 +318,12 @@ impl WhisperApiClient {
         &self,
         audio_wav: &[u8],
         api_url: &str,
-        api_key: Option<&str>,
+        auth: &RequestAuth,
         spoken_languages: &[String],
-    ) -> Result<String, String> {
-        println!("Mixed mode: detecting among {:?}", spoken_languages);
-
-        let file_part = multipart::Part::bytes(audio_wav.to_vec())
-            .file_name("audio.wav")
-            .mime_str("audio/wav")
-            .map_err(|e| format!("Failed to create file part: {}", e))?;
+        vocabulary: Option<&str>,
+        mixed_output: MixedModeOutput,
+    ) -> Result<Transcription, String> {
+        log::info!("Mixed mode: detecting among {:?}", spoken_languages);
 
         // Build prompt listing user's spoken languages
         let lang_names = spoken_languages
@@ -195,32 +336,18 @@ impl WhisperApiClient {
             "This speaker uses these languages: {}. TRANSCRIBE (do NOT translate). Output the exact words spoken in the original language. Never convert one language to another.",
             lang_names
         );
-
-        let form = multipart::Form::new()
-            .part("file", file_part)
-            .text("model", WHISPER_MODEL)
-            .text("response_format", "verbose_json")
-            .text("prompt", prompt);
-        // Note: Not setting "language" parameter - let Whisper auto-detect
-
-        let mut request = self.client.post(api_url).multipart(form);
-
-        // Add Authorization header only for direct API (dev mode)
-        // Add HMAC signature for proxy mode
-        if let Some(key) = api_key {
-            request = request.header("Authorization", format!("Bearer {}", key));
-        } else {
-            // Production mode: add HMAC signature for proxy authentication
-            // Sign the audio bytes as the request payload
-            let (timestamp, nonce, signature) = signing::sign_request(audio_wav);
-            request = request
-                .header("X-Murmur-Timestamp", timestamp)
-                .header("X-Murmur-Nonce", nonce)
-                .header("X-Murmur-Signature", signature);
-        }
-
-        let response = request
-            .send()
+        let prompt = append_vocabulary(&prompt, vocabulary);
+
+        let response =
+            http_client::send_with_retry(&http_client::TRANSCRIPTION_RETRY, "WHISPER", || {
+                // Note: Not setting "language" parameter - let Whisper auto-detect
+                let form = multipart::Form::new()
+                    .part("file", audio_part(audio_wav)?)
+                    .text("model", WHISPER_MODEL)
+                    .text("response_format", "verbose_json")
+                    .text("prompt", prompt.clone());
+                Ok(self.build_request(api_url, auth, audio_wav, form))
+            })
             .await
             .map_err(|e| format!("Failed to send request to Groq: {}", e))?;
 
@@ -230,7 +357,7 @@ impl WhisperApiClient {
                 .text()
                 .await
                 .unwrap_or_else(|_| "Unknown error".to_string());
-            return Err(format!("Groq API error ({}): {}", status, error_text));
+            return Err(api_error(status, &error_text, auth));
         }
 
         let result: GroqVerboseResponse = response
@@ -241,20 +368,18 @@ impl WhisperApiClient {
         let detected_lang = result.language.as_deref().unwrap_or("unknown");
 
         // Calculate average confidence from segments
-        let avg_confidence = if !result.segments.is_empty() {
-            let sum: f64 = result.segments.iter().map(|s| s.avg_logprob).sum();
-            sum / result.segments.len() as f64
-        } else {
-            -1.0 // Unknown confidence
-        };
+        let avg_logprob = average_logprob(&result.segments);
+        let avg_confidence = avg_logprob.unwrap_or(-1.0); // -1.0 = unknown confidence
 
         // Convert log probability to confidence (higher is better, 0 is perfect)
         // avg_logprob > -0.3 is generally good confidence (~74% probability)
         let is_confident = avg_confidence > -0.3;
 
-        println!(
+        log::info!(
             "Mixed mode: detected '{}', avg_logprob={:.3}, confident={}",
-            detected_lang, avg_confidence, is_confident
+            detected_lang,
+            avg_confidence,
+            is_confident
         );
 
         // Validate detected language is in user's spoken languages
@@ -263,30 +388,199 @@ impl WhisperApiClient {
             .iter()
             .any(|l| l.split('-').next().unwrap_or(l) == detected_code);
 
-        if !is_valid_lang && is_confident {
-            println!(
-                "Warning: Detected '{}' not in user's languages {:?}, but high confidence",
-                detected_lang, spoken_languages
+        let new_language = if !is_valid_lang && is_confident && detected_code != "unknown" {
+            log::warn!(
+                "Detected '{}' not in user's languages {:?}, but high confidence",
+                detected_lang,
+                spoken_languages
             );
             // Still use the transcript, user might be using a new language
-        }
+            Some(detected_code.to_string())
+        } else {
+            None
+        };
 
         let transcript = result.text.trim().to_string();
-        println!("Mixed mode raw transcript: {}", transcript);
+        log::debug!("Mixed mode raw transcript: {}", transcript);
+
+        // Romanize code-switched output (Hinglish, Tenglish, etc.) unless the
+        // user prefers native script
+        let text = if should_romanize(mixed_output, detected_code, spoken_languages) {
+            let romanized = romanize_transcript(&transcript);
+            log::debug!("Mixed mode romanized: {}", romanized);
+            romanized
+        } else {
+            transcript
+        };
 
-        // Always romanize in mixed mode for code-switched output (Hinglish, Tenglish, etc.)
-        let romanized = romanize_transcript(&transcript);
-        println!("Mixed mode romanized: {}", romanized);
+        Ok(Transcription {
+            text,
+            avg_logprob,
+            new_language,
+        })
+    }
 
-        Ok(romanized)
+    /// Build a transcription request with the right authentication.
+    /// Called once per attempt so each retry gets a fresh signature.
+    fn build_request(
+        &self,
+        api_url: &str,
+        auth: &RequestAuth,
+        audio_wav: &[u8],
+        form: multipart::Form,
+    ) -> RequestBuilder {
+        let request = self.client.post(api_url).multipart(form);
+
+        match auth {
+            // Direct API (user's key, or dev mode)
+            RequestAuth::ApiKey(key) => request.header("Authorization", format!("Bearer {}", key)),
+            RequestAuth::Proxy { access_token } => {
+                // Production mode: HMAC signature for proxy authentication (signing
+                // the audio bytes as the payload), plus the user's token so the
+                // proxy can rate limit and check entitlement per user
+                let (timestamp, nonce, signature) = signing::sign_request(audio_wav);
+                request
+                    .header("Authorization", format!("Bearer {}", access_token))
+                    .header("X-Murmur-Timestamp", timestamp)
+                    .header("X-Murmur-Nonce", nonce)
+                    .header("X-Murmur-Signature", signature)
+            }
+        }
     }
 
     /// Get the API URL and optional API key based on build type.
     /// - Debug builds: use direct API with GROQ_API_KEY
     /// - Release builds: always use proxy
-    fn get_api_config(&self) -> (String, Option<String>) {
-        let (whisper_url, _, api_key) = signing::get_api_config();
-        (whisper_url.to_string(), api_key)
+    fn get_api_config(&self) -> Result<(String, Option<String>), String> {
+        let (whisper_url, _, api_key) = signing::get_api_config()?;
+        Ok((whisper_url.to_string(), api_key))
+    }
+}
+
+/// How transcription requests authenticate
+enum RequestAuth {
+    /// Direct Groq API with the user's (or dev) API key
+    ApiKey(String),
+    /// The Murmur proxy: HMAC-signed, with the signed-in user's access token
+    Proxy { access_token: String },
+}
+
+/// Error `code` the proxy returns when it rejects the user's access token
+const PROXY_LOGIN_REQUIRED_CODE: &str = "login_required";
+
+/// Error for a failed transcription response. Only the proxy rejecting the
+/// user's token means they need to sign in again; other 401s (a bad request
+/// signature, clock skew) are reported as they are.
+fn api_error(status: reqwest::StatusCode, error_text: &str, auth: &RequestAuth) -> String {
+    let login_required = matches!(auth, RequestAuth::Proxy { .. })
+        && status == reqwest::StatusCode::UNAUTHORIZED
+        && serde_json::from_str::<serde_json::Value>(error_text)
+            .ok()
+            .and_then(|body| body.get("code")?.as_str().map(str::to_string))
+            .is_some_and(|code| code == PROXY_LOGIN_REQUIRED_CODE);
+    if login_required {
+        return crate::auth::LOGIN_REQUIRED_ERROR.to_string();
+    }
+    format!("Groq API error ({}): {}", status, error_text)
+}
+
+/// Tracks consecutive mixed-mode detections of a language the user hasn't
+/// listed, so a language they keep speaking can be suggested once.
+#[derive(Debug, Default)]
+pub struct LanguageStreak {
+    language: Option<String>,
+    count: u32,
+}
+
+impl LanguageStreak {
+    /// Record one transcription's `new_language`. Returns the language when
+    /// it has just been detected LANGUAGE_SUGGESTION_STREAK times in a row;
+    /// a longer streak doesn't suggest it again.
+    pub fn record(&mut self, new_language: Option<&str>) -> Option<String> {
+        let Some(language) = new_language else {
+            self.reset();
+            return None;
+        };
+
+        if self.language.as_deref() == Some(language) {
+            self.count = self.count.saturating_add(1);
+        } else {
+            self.language = Some(language.to_string());
+            self.count = 1;
+        }
+
+        (self.count == LANGUAGE_SUGGESTION_STREAK).then(|| language.to_string())
+    }
+
+    fn reset(&mut self) {
+        self.language = None;
+        self.count = 0;
+    }
+}
+
+/// Append the user's vocabulary to a Whisper prompt, capped at
+/// MAX_VOCABULARY_PROMPT_CHARS so it can't crowd out `base`.
+fn append_vocabulary(base: &str, vocabulary: Option<&str>) -> String {
+    let vocabulary = vocabulary.map(str::trim).unwrap_or_default();
+    if vocabulary.is_empty() {
+        return base.to_string();
+    }
+
+    let vocabulary = match vocabulary.char_indices().nth(MAX_VOCABULARY_PROMPT_CHARS) {
+        Some((end, _)) => {
+            log::debug!(
+                "Vocabulary prompt truncated to {} characters",
+                MAX_VOCABULARY_PROMPT_CHARS
+            );
+            &vocabulary[..end]
+        }
+        None => vocabulary,
+    };
+
+    if base.is_empty() {
+        vocabulary.to_string()
+    } else {
+        format!("{} {}", base, vocabulary)
+    }
+}
+
+/// WAV file part for a transcription request.
+fn audio_part(audio_wav: &[u8]) -> Result<multipart::Part, String> {
+    multipart::Part::bytes(audio_wav.to_vec())
+        .file_name("audio.wav")
+        .mime_str("audio/wav")
+        .map_err(|e| format!("Failed to create file part: {}", e))
+}
+
+/// One transcription from consecutive pieces: `text` joined by the caller,
+/// the mean log probability of the pieces that have one, and the first new
+/// language detected
+fn combine_transcriptions(text: String, pieces: &[Transcription]) -> Transcription {
+    let logprobs: Vec<f64> = pieces.iter().filter_map(|p| p.avg_logprob).collect();
+    let avg_logprob =
+        (!logprobs.is_empty()).then(|| logprobs.iter().sum::<f64>() / logprobs.len() as f64);
+    Transcription {
+        text,
+        avg_logprob,
+        new_language: pieces.iter().find_map(|p| p.new_language.clone()),
+    }
+}
+
+/// Whether a mixed-mode transcript in `detected_code` gets romanized
+fn should_romanize(
+    output: MixedModeOutput,
+    detected_code: &str,
+    spoken_languages: &[String],
+) -> bool {
+    match output {
+        MixedModeOutput::Romanized => true,
+        MixedModeOutput::NativeScript => false,
+        MixedModeOutput::Auto => {
+            let primary = spoken_languages
+                .first()
+                .map(|l| l.split('-').next().unwrap_or(l));
+            primary != Some(detected_code)
+        }
     }
 }
 
@@ -403,6 +697,17 @@ fn init_language_maps() -> (
     (code_to_name, name_to_code)
 }
 
+/// Check whether a language setting is supported: a known language code
+/// (optionally with a region, e.g. "en-US") or "mixed"/"auto"
+pub fn is_supported_language(language: &str) -> bool {
+    if language == "mixed" || language == "auto" {
+        return true;
+    }
+    let map = LANGUAGE_CODE_TO_NAME.get_or_init(|| init_language_maps().0);
+    let base_code = language.split('-').next().unwrap_or(language);
+    map.contains_key(base_code)
+}
+
 /// Convert language code to full language name (for prompts) - O(1) lookup
 fn language_code_to_name(code: &str) -> &'static str {
     let map = LANGUAGE_CODE_TO_NAME.get_or_init(|| init_language_maps().0);
@@ -417,3 +722,188 @@ fn language_name_to_code(name: &str) -> &'static str {
     let lowercase = name.to_lowercase();
     map.get(lowercase.as_str()).copied().unwrap_or("unknown")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(avg_logprob: f64) -> GroqSegment {
+        GroqSegment {
+            text: String::new(),
+            avg_logprob,
+        }
+    }
+
+    #[test]
+    fn test_average_logprob() {
+        assert_eq!(average_logprob(&[]), None);
+        let avg = average_logprob(&[segment(-0.2), segment(-0.4)]).unwrap();
+        assert!((avg - -0.3).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_transcription_confidence() {
+        let perfect = Transcription {
+            text: "hi".to_string(),
+            avg_logprob: Some(0.0),
+            new_language: None,
+        };
+        assert_eq!(perfect.confidence(), Some(1.0));
+
+        let low = Transcription {
+            text: "hi".to_string(),
+            avg_logprob: Some(-2.0),
+            new_language: None,
+        };
+        assert!(low.confidence().unwrap() < 0.2);
+
+        let unknown = Transcription {
+            text: "hi".to_string(),
+            avg_logprob: None,
+            new_language: None,
+        };
+        assert_eq!(unknown.confidence(), None);
+    }
+
+    /// Mixed-mode text for a transcript, as `transcribe_mixed_mode` produces it
+    fn mixed_text(output: MixedModeOutput, transcript: &str, detected: &str) -> String {
+        let spoken = vec!["hi".to_string(), "te".to_string(), "en".to_string()];
+        if should_romanize(output, detected, &spoken) {
+            romanize_transcript(transcript)
+        } else {
+            transcript.to_string()
+        }
+    }
+
+    #[test]
+    fn test_mixed_mode_output() {
+        let hindi = "मेरा नाम Anurag है";
+        let telugu = "నా పేరు Anurag";
+
+        let romanized = mixed_text(MixedModeOutput::Romanized, hindi, "hi");
+        assert!(romanized.is_ascii());
+        assert!(romanized.contains("Anurag"));
+        assert!(mixed_text(MixedModeOutput::Romanized, telugu, "te").is_ascii());
+
+        assert_eq!(
+            mixed_text(MixedModeOutput::NativeScript, hindi, "hi"),
+            hindi
+        );
+        assert_eq!(
+            mixed_text(MixedModeOutput::NativeScript, telugu, "te"),
+            telugu
+        );
+
+        // Auto keeps the primary language's script and romanizes the others
+        assert_eq!(mixed_text(MixedModeOutput::Auto, hindi, "hi"), hindi);
+        assert!(mixed_text(MixedModeOutput::Auto, telugu, "te").is_ascii());
+    }
+
+    #[test]
+    fn test_should_romanize_auto_with_region() {
+        let spoken = vec!["hi-IN".to_string()];
+        assert!(!should_romanize(MixedModeOutput::Auto, "hi", &spoken));
+        assert!(should_romanize(MixedModeOutput::Auto, "hi", &[]));
+    }
+
+    #[test]
+    fn test_language_streak() {
+        let mut streak = LanguageStreak::default();
+        assert_eq!(streak.record(Some("es")), None);
+        assert_eq!(streak.record(Some("es")), None);
+        assert_eq!(streak.record(Some("es")), Some("es".to_string()));
+        // Suggested once per streak
+        assert_eq!(streak.record(Some("es")), None);
+
+        // A different language or a miss starts over
+        assert_eq!(streak.record(Some("fr")), None);
+        assert_eq!(streak.record(Some("fr")), None);
+        assert_eq!(streak.record(None), None);
+        assert_eq!(streak.record(Some("fr")), None);
+        assert_eq!(streak.record(Some("fr")), None);
+        assert_eq!(streak.record(Some("fr")), Some("fr".to_string()));
+    }
+
+    #[test]
+    fn test_append_vocabulary() {
+        assert_eq!(append_vocabulary("Base.", None), "Base.");
+        assert_eq!(append_vocabulary("Base.", Some("  ")), "Base.");
+        assert_eq!(
+            append_vocabulary("Base.", Some("Kubernetes, Anurag")),
+            "Base. Kubernetes, Anurag"
+        );
+        assert_eq!(append_vocabulary("", Some("Keyhold")), "Keyhold");
+
+        let long = "ä".repeat(MAX_VOCABULARY_PROMPT_CHARS + 50);
+        let prompt = append_vocabulary("", Some(&long));
+        assert_eq!(prompt.chars().count(), MAX_VOCABULARY_PROMPT_CHARS);
+    }
+
+    #[test]
+    fn test_combine_transcriptions() {
+        let pieces = [
+            Transcription {
+                text: "Hello".to_string(),
+                avg_logprob: Some(-0.2),
+                new_language: None,
+            },
+            Transcription {
+                text: "world".to_string(),
+                avg_logprob: None,
+                new_language: Some("es".to_string()),
+            },
+            Transcription {
+                text: "again".to_string(),
+                avg_logprob: Some(-0.4),
+                new_language: Some("fr".to_string()),
+            },
+        ];
+        let combined = combine_transcriptions("Hello world again".to_string(), &pieces);
+        assert_eq!(combined.text, "Hello world again");
+        assert!((combined.avg_logprob.unwrap() - -0.3).abs() < 1e-9);
+        assert_eq!(combined.new_language, Some("es".to_string()));
+
+        assert_eq!(combine_transcriptions(String::new(), &[]).avg_logprob, None);
+    }
+
+    #[test]
+    fn test_api_error_asks_proxy_users_to_sign_in() {
+        let proxy = RequestAuth::Proxy {
+            access_token: "token".to_string(),
+        };
+        let direct = RequestAuth::ApiKey("gsk_key".to_string());
+        let unauthorized = reqwest::StatusCode::UNAUTHORIZED;
+
+        assert_eq!(
+            api_error(
+                unauthorized,
+                r#"{"error":"Login required","code":"login_required"}"#,
+                &proxy
+            ),
+            crate::auth::LOGIN_REQUIRED_ERROR
+        );
+        // A failed request signature isn't a session problem
+        assert!(api_error(
+            unauthorized,
+            r#"{"error":"Unauthorized","code":"invalid_signature"}"#,
+            &proxy
+        )
+        .contains("(401"));
+        assert!(api_error(unauthorized, "not json", &proxy).contains("(401"));
+        assert!(api_error(
+            unauthorized,
+            r#"{"error":"Login required","code":"login_required"}"#,
+            &direct
+        )
+        .contains("(401"));
+        assert!(api_error(reqwest::StatusCode::BAD_GATEWAY, "down", &proxy).contains("(502"));
+    }
+
+    #[test]
+    fn test_is_supported_language() {
+        assert!(is_supported_language("en-US"));
+        assert!(is_supported_language("hi"));
+        assert!(is_supported_language("mixed"));
+        assert!(!is_supported_language("xx"));
+    }
+}
@@ -9,7 +9,7 @@ import {
 import { Label } from "@/components/ui/label";
 import { Mic } from "lucide-react";
 import { tauriCommands } from "@/lib/tauri";
-import type { MicrophoneDevice } from "@/types";
+import { microphoneLabel, type MicrophoneDevice } from "@/types";
 
 interface MicrophoneSelectorProps {
   value: string;
@@ -49,7 +49,7 @@ export function MicrophoneSelector({ value, onChange }: MicrophoneSelectorProps)
           <SelectItem value="default">Default Microphone</SelectItem>
           {devices.map((device) => (
             <SelectItem key={device.id} value={device.id}>
-              {device.name}
+              {microphoneLabel(device)}
             </SelectItem>
           ))}
         </SelectContent>
@@ -24,7 +24,7 @@ export function OverlayPill({
   onStop,
   onCancel,
 }: OverlayPillProps) {
-  const canStop = state === "recording";
+  const canStop = state === "recording" || state === "paused";
   const isCommandMode = mode === "command";
 
   // Determine dot state: use "command" (blue) for Command Mode recording
@@ -143,6 +143,7 @@ export function OverlayWindow() {
   const isCommandMode = overlayState.mode === "command";
   const statusMessage =
     overlayState.state === "recording" ? (isCommandMode ? "Command Mode" : "Recording...") :
+    overlayState.state === "paused" ? "Paused" :
     overlayState.state === "transcribing" ? (overlayState.message || "Transcribing...") :
     overlayState.state === "enhancing" ? (overlayState.message || "Enhancing...") :
     overlayState.state === "transforming" ? (overlayState.message || "Transforming...") :
@@ -10,7 +10,7 @@ import {
 import { cn } from "@/lib/utils";
 import { tauriCommands, tauriEvents } from "@/lib/tauri";
 import { Mic, Activity, Lightbulb, Check } from "lucide-react";
-import type { Preferences, MicrophoneDevice } from "@/types";
+import { microphoneLabel, type Preferences, type MicrophoneDevice } from "@/types";
 
 interface AudioTabProps {
   preferences: Preferences;
@@ -76,7 +76,7 @@ export function AudioTab({ preferences, onUpdate }: AudioTabProps) {
                 <SelectItem value="default">System Default</SelectItem>
                 {devices.map((device) => (
                   <SelectItem key={device.id} value={device.id}>
-                    {device.name}
+                    {microphoneLabel(device)}
                   </SelectItem>
                 ))}
               </SelectContent>
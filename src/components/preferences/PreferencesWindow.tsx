@@ -8,6 +8,9 @@ import { tauriEvents, tauriWindow } from "@/lib/tauri";
 import { Settings, Volume2, Sparkles } from "lucide-react";
 import gsap from "gsap";
 
+// Tabs this window has; other navigate-preferences targets land on the current tab
+const PREFERENCES_TABS = ["general", "audio"];
+
 export function PreferencesWindow() {
   const {
     preferences,
@@ -89,6 +92,19 @@ export function PreferencesWindow() {
     };
   }, []);
 
+  // Jump to a tab when the backend asks (e.g. from an error message)
+  useEffect(() => {
+    const unlistenPromise = tauriEvents.onNavigatePreferences((tab) => {
+      if (PREFERENCES_TABS.includes(tab)) {
+        setActiveTab(tab);
+      }
+    });
+
+    return () => {
+      unlistenPromise.then((fn) => fn());
+    };
+  }, []);
+
   if (isLoading) {
     return (
       <div className="flex h-screen items-center justify-center glass-window">
@@ -48,6 +48,7 @@ export const tauriCommands = {
 
   // Window
   showPreferences: () => invoke<void>("show_preferences"),
+  showPreferencesTab: (tab: string) => invoke<void>("show_preferences_tab", { tab }),
 
   // Authentication
   getAuthState: () => invoke<AuthState>("get_auth_state"),
@@ -87,6 +88,10 @@ export const tauriEvents = {
   // Authentication state changes
   onAuthStateChanged: (callback: (state: AuthState) => void): Promise<UnlistenFn> =>
     listen<AuthState>("auth-state-changed", (e) => callback(e.payload)),
+
+  // Open a specific preferences tab
+  onNavigatePreferences: (callback: (tab: string) => void): Promise<UnlistenFn> =>
+    listen<string>("navigate-preferences", (e) => callback(e.payload)),
 };
 
 // ============================================================================
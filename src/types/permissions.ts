@@ -5,12 +5,30 @@ export interface PermissionStatus {
   accessibility: boolean;
 }
 
+export interface DeviceCapabilities {
+  default_sample_rate: number;
+  max_sample_rate: number;
+  channels: number;
+  sample_formats: string[];
+}
+
 export interface MicrophoneDevice {
   id: string;
   name: string;
   is_default: boolean;
+  capabilities: DeviceCapabilities | null; // null when the device couldn't be probed
 }
 
+// Picker label, e.g. "USB Mic (48 kHz, 2 ch)"
+export const microphoneLabel = (device: MicrophoneDevice): string => {
+  const caps = device.capabilities;
+  if (!caps) {
+    return `${device.name} (capabilities unknown)`;
+  }
+  const khz = caps.max_sample_rate / 1000;
+  return `${device.name} (${Number.isInteger(khz) ? khz : khz.toFixed(1)} kHz, ${caps.channels} ch)`;
+};
+
 export const DEFAULT_PERMISSION_STATUS: PermissionStatus = {
   microphone: "undetermined",
   accessibility: false,
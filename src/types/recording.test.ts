@@ -86,6 +86,7 @@ describe('Recording State Helpers', () => {
       const allStates: RecordingState[] = [
         'idle',
         'recording',
+        'paused',
         'transcribing',
         'enhancing',
         'transforming',
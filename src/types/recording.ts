@@ -1,6 +1,7 @@
 export type RecordingState =
   | "idle"
   | "recording"
+  | "paused"
   | "transcribing"
   | "enhancing"
   | "transforming"
@@ -36,7 +37,7 @@ export const canStartRecording = (state: RecordingState): boolean => {
 };
 
 export const canStopRecording = (state: RecordingState): boolean => {
-  return state === "recording";
+  return state === "recording" || state === "paused";
 };
 
 export const isProcessing = (state: RecordingState): boolean => {